@@ -3,18 +3,20 @@ use std::{env, fs::File, path::PathBuf, process, str::FromStr};
 use clap::{ArgAction, CommandFactory, Parser, Subcommand};
 use clap_complete::{Generator, Shell};
 use hiro_system_kit::{self, Logger};
+use serde_json::json;
 use solana_keypair::Keypair;
 use solana_pubkey::Pubkey;
 use solana_signer::{EncodableKey, Signer};
 use surfpool_mcp::McpOptions;
 use surfpool_types::{
     CHANGE_TO_DEFAULT_STUDIO_PORT_ONCE_SUPERVISOR_MERGED, DEFAULT_NETWORK_HOST, DEFAULT_RPC_PORT,
-    DEFAULT_SLOT_TIME_MS, DEFAULT_WS_PORT, RpcConfig, SimnetConfig, StudioConfig, SubgraphConfig,
-    SurfpoolConfig,
+    DEFAULT_SLOT_TIME_MS, DEFAULT_SYNTHETIC_VALIDATORS, DEFAULT_WS_PORT, JITO_TIP_ACCOUNTS,
+    RemoteCacheConfig, RemoteTrafficMode, RpcConfig, SimnetConfig, StudioConfig, SubgraphConfig,
+    SurfpoolConfig, UiReplayedTransaction,
 };
 use txtx_cloud::LoginCommand;
 use txtx_core::manifest::WorkspaceManifest;
-use txtx_gql::kit::helpers::fs::FileLocation;
+use txtx_gql::kit::{helpers::fs::FileLocation, reqwest};
 
 use crate::{cloud::CloudStartCommand, runbook::handle_execute_runbook_command};
 
@@ -35,7 +37,8 @@ pub const DEFAULT_CLOUD_URL: &str = "https://cloud.txtx.run";
 pub const DEFAULT_SVM_GQL_URL: &str = "https://svm-cloud.gql.txtx.run/v1/graphql";
 pub const DEFAULT_SVM_CLOUD_API_URL: &str = "https://svm-cloud-api.txtx.run/v1/surfnets";
 pub const DEFAULT_RUNBOOK: &str = "deployment";
-pub const DEFAULT_AIRDROP_AMOUNT: &str = "10000000000000";
+pub const DEFAULT_SURFNET_RPC_URL: &str = "http://127.0.0.1:8899";
+pub const DEFAULT_AIRDROP_AMOUNT: u64 = 10_000_000_000_000;
 
 lazy_static::lazy_static! {
     pub static ref DEFAULT_SOLANA_KEYPAIR_PATH: String = {
@@ -121,6 +124,9 @@ enum Command {
     /// Start MCP server
     #[clap(name = "mcp", bin_name = "mcp")]
     Mcp,
+    /// Replay a transaction of the datasource network on a running surfnet
+    #[clap(name = "replay", bin_name = "replay")]
+    Replay(ReplayTransaction),
 }
 
 #[derive(Parser, PartialEq, Clone, Debug)]
@@ -141,9 +147,15 @@ pub struct StartSimnet {
     /// Set the Simnet host address
     #[arg(long = "host", short = 'o', default_value = DEFAULT_NETWORK_HOST)]
     pub network_host: String,
-    /// Set the slot time
-    #[arg(long = "slot-time", short = 't', default_value_t = DEFAULT_SLOT_TIME_MS)]
-    pub slot_time: u64,
+    /// Set the slot time, in milliseconds (default: 400)
+    #[arg(long = "slot-time", short = 't')]
+    pub slot_time: Option<u64>,
+    /// Set the number of synthetic validators, holding stake and voting on every slot (default: 1)
+    #[arg(long = "validators")]
+    pub validators: Option<usize>,
+    /// Set an account that bundles must tip, replacing the Jito tip accounts (can be repeated)
+    #[arg(long = "tip-account", value_parser = Pubkey::from_str)]
+    pub tip_accounts: Vec<Pubkey>,
     /// Set a datasource RPC URL (cannot be used with --network). Can also be set via SURFPOOL_DATASOURCE_RPC_URL.
     #[arg(long = "rpc-url", short = 'u', conflicts_with = "network")]
     pub rpc_url: Option<String>,
@@ -165,10 +177,10 @@ pub struct StartSimnet {
     /// List of pubkeys to airdrop
     #[arg(long = "airdrop", short = 'a')]
     pub airdrop_addresses: Vec<String>,
-    /// Quantity of tokens to airdrop
-    #[arg(long = "airdrop-amount", short = 'q', default_value = DEFAULT_AIRDROP_AMOUNT)]
-    pub airdrop_token_amount: u64,
-    /// List of keypair paths to airdrop
+    /// Quantity of tokens to airdrop (default: 10000000000000)
+    #[arg(long = "airdrop-amount", short = 'q')]
+    pub airdrop_token_amount: Option<u64>,
+    /// List of keypair paths to airdrop. These keypairs also sign the transactions of `surfnet_sendInstruction`
     #[arg(long = "airdrop-keypair-path", short = 'k', default_value = DEFAULT_SOLANA_KEYPAIR_PATH.as_str())]
     pub airdrop_keypair_path: Vec<String>,
     /// Disable explorer (default: false)
@@ -177,10 +189,13 @@ pub struct StartSimnet {
     /// Watch programs (default: false)
     #[clap(long = "watch", action=ArgAction::SetTrue)]
     pub watch: bool,
+    /// Swap the bytecode of programs rebuilt in target/deploy directly in the surfnet, without redeploying them (default: false)
+    #[clap(long = "hot-reload", action=ArgAction::SetTrue, conflicts_with = "watch")]
+    pub hot_reload: bool,
     /// List of geyser plugins to load
     #[arg(long = "geyser-plugin-config", short = 'g')]
     pub plugin_config_path: Vec<String>,
-    /// Path to subgraph's sqlite database (default: :memory:)
+    /// Path to subgraph's sqlite database, or url of a postgres database (default: :memory:)
     #[arg(long = "subgraph-database-path", short = 'd')]
     pub subgraph_database_path: Option<String>,
     /// Disable Studio (default: true)
@@ -192,6 +207,27 @@ pub struct StartSimnet {
     /// Start surfpool without a remote RPC client to simulate an offline environment (default: false)
     #[clap(long = "offline", action=ArgAction::SetTrue)]
     pub offline: bool,
+    /// Path to a state dump (written with the surfnet_dumpState cheatcode) to load on startup
+    #[arg(long = "load-state")]
+    pub state_dump_path: Option<String>,
+    /// Directory the surfnet_dumpState cheatcode writes state dumps to (the cheatcode is disabled if not set)
+    #[arg(long = "state-dir")]
+    pub state_dump_dir: Option<String>,
+    /// Cache the accounts fetched from the datasource RPC for this many seconds
+    #[arg(long = "remote-cache-ttl")]
+    pub remote_cache_ttl: Option<u64>,
+    /// Persist the accounts fetched from the datasource RPC to this file, to reuse them across runs
+    #[arg(long = "remote-cache-path")]
+    pub remote_cache_path: Option<String>,
+    /// Fork the datasource at this slot: accounts modified since then are refused (cannot be used with --offline)
+    #[arg(long = "fork-slot", conflicts_with = "offline")]
+    pub fork_slot: Option<u64>,
+    /// Record every response of the datasource RPC to this fixture file
+    #[arg(long = "record", conflicts_with_all = ["offline", "replay_path"])]
+    pub record_path: Option<String>,
+    /// Serve the datasource RPC responses from this fixture file, without network access
+    #[arg(long = "replay", conflicts_with_all = ["offline", "record_path"])]
+    pub replay_path: Option<String>,
 }
 
 #[derive(clap::ValueEnum, PartialEq, Clone, Debug)]
@@ -256,33 +292,81 @@ impl StartSimnet {
         }
     }
 
+    /// The datasource RPC URL set with `--network`, `--rpc-url` or `SURFPOOL_DATASOURCE_RPC_URL`, if any.
+    pub fn datasource_rpc_url(&self) -> Option<String> {
+        match &self.network {
+            Some(NetworkType::Mainnet) => Some(DEFAULT_RPC_URL.to_string()),
+            Some(NetworkType::Devnet) => Some(DEVNET_RPC_URL.to_string()),
+            Some(NetworkType::Testnet) => Some(TESTNET_RPC_URL.to_string()),
+            None => match self.rpc_url {
+                Some(ref rpc_url) => Some(rpc_url.clone()),
+                None => env::var("SURFPOOL_DATASOURCE_RPC_URL").ok(),
+            },
+        }
+    }
+
     pub fn simnet_config(&self, airdrop_addresses: Vec<Pubkey>) -> SimnetConfig {
         let remote_rpc_url = if !self.offline {
-            Some(match &self.network {
-                Some(NetworkType::Mainnet) => DEFAULT_RPC_URL.to_string(),
-                Some(NetworkType::Devnet) => DEVNET_RPC_URL.to_string(),
-                Some(NetworkType::Testnet) => TESTNET_RPC_URL.to_string(),
-                None => match self.rpc_url {
-                    Some(ref rpc_url) => rpc_url.clone(),
-                    None => match env::var("SURFPOOL_DATASOURCE_RPC_URL") {
-                        Ok(value) => value,
-                        _ => DEFAULT_RPC_URL.to_string(),
-                    },
-                },
-            })
+            Some(
+                self.datasource_rpc_url()
+                    .unwrap_or(DEFAULT_RPC_URL.to_string()),
+            )
         } else {
             None
         };
 
         SimnetConfig {
+            name: None,
+            rpc: None,
             remote_rpc_url,
-            slot_time: self.slot_time,
+            slot_time: self.slot_time.unwrap_or(DEFAULT_SLOT_TIME_MS),
             block_production_mode: surfpool_types::BlockProductionMode::Clock,
             airdrop_addresses,
-            airdrop_token_amount: self.airdrop_token_amount,
+            airdrop_token_amount: self.airdrop_token_amount.unwrap_or(DEFAULT_AIRDROP_AMOUNT),
             expiry: None,
             offline_mode: self.offline,
+            state_dump_path: self.state_dump_path.as_deref().map(resolve_path),
+            state_dump_dir: self.state_dump_dir.as_deref().map(resolve_path),
+            remote_cache: self.remote_cache_config(),
+            fork_slot: self.fork_slot,
+            remote_traffic: self.remote_traffic_mode(),
+            program_deployments: vec![],
+            preloaded_accounts: vec![],
+            token_accounts: vec![],
+            cloned_programs: vec![],
+            idl_paths: vec![],
+            validators: self.validators.unwrap_or(DEFAULT_SYNTHETIC_VALIDATORS),
+            tip_accounts: if self.tip_accounts.is_empty() {
+                JITO_TIP_ACCOUNTS.to_vec()
+            } else {
+                self.tip_accounts.clone()
+            },
+            signer_keypair_paths: self
+                .airdrop_keypair_path
+                .iter()
+                .map(|path| resolve_path(path))
+                .collect(),
+        }
+    }
+
+    pub fn remote_traffic_mode(&self) -> Option<RemoteTrafficMode> {
+        match (&self.record_path, &self.replay_path) {
+            (Some(path), _) => Some(RemoteTrafficMode::Record(resolve_path(path))),
+            (None, Some(path)) => Some(RemoteTrafficMode::Replay(resolve_path(path))),
+            (None, None) => None,
+        }
+    }
+
+    /// The remote account cache is enabled as soon as a TTL or a path is provided.
+    /// Persisted entries never expire if no TTL is provided.
+    pub fn remote_cache_config(&self) -> Option<RemoteCacheConfig> {
+        if self.remote_cache_ttl.is_none() && self.remote_cache_path.is_none() {
+            return None;
         }
+        Some(RemoteCacheConfig {
+            ttl: self.remote_cache_ttl,
+            path: self.remote_cache_path.as_deref().map(resolve_path),
+        })
     }
 
     pub fn subgraph_config(&self) -> SubgraphConfig {
@@ -320,6 +404,18 @@ pub struct ListRunbooks {
     pub manifest_path: String,
 }
 
+#[derive(Parser, PartialEq, Clone, Debug)]
+pub struct ReplayTransaction {
+    /// Signature of the transaction to replay
+    pub signature: String,
+    /// RPC URL of the running surfnet
+    #[arg(long = "rpc-url", short = 'u', default_value = DEFAULT_SURFNET_RPC_URL)]
+    pub rpc_url: String,
+    /// Tag of the profile result
+    #[arg(long = "tag", short = 't')]
+    pub tag: Option<String>,
+}
+
 #[derive(Subcommand, PartialEq, Clone, Debug)]
 pub enum CloudCommand {
     /// Login to the Txtx Cloud
@@ -445,7 +541,66 @@ async fn handle_command(opts: Opts, ctx: &Context) -> Result<(), String> {
         Command::List(cmd) => handle_list_command(cmd, ctx).await,
         Command::Cloud(cmd) => handle_cloud_commands(cmd).await,
         Command::Mcp => handle_mcp_command(ctx).await,
+        Command::Replay(cmd) => handle_replay_command(cmd).await,
+    }
+}
+
+async fn handle_replay_command(cmd: ReplayTransaction) -> Result<(), String> {
+    let request = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "surfnet_replayTransaction",
+        "params": [cmd.signature, cmd.tag],
+    });
+    let response = reqwest::Client::new()
+        .post(&cmd.rpc_url)
+        .json(&request)
+        .send()
+        .await
+        .map_err(|e| format!("failed to send request to surfnet {}: {e}", cmd.rpc_url))?
+        .json::<serde_json::Value>()
+        .await
+        .map_err(|e| format!("failed to parse response: {e}"))?;
+    if let Some(error) = response.get("error") {
+        return Err(format!("failed to replay transaction: {error}"));
     }
+    let replayed: UiReplayedTransaction =
+        serde_json::from_value(response["result"]["value"].clone())
+            .map_err(|e| format!("failed to parse response: {e}"))?;
+
+    let onchain = &replayed.onchain_result;
+    let local = &replayed.profile.transaction_profile;
+    println!(
+        "{} slot {}, {} compute units, {}",
+        yellow!("On-chain:"),
+        onchain.slot,
+        onchain
+            .compute_units_consumed
+            .map(|cu| cu.to_string())
+            .unwrap_or("?".into()),
+        match &onchain.err {
+            Some(err) => red!(format!("failed: {err}")),
+            None => green!("succeeded"),
+        }
+    );
+    println!(
+        "{} {} compute units, {}",
+        yellow!("Replayed:"),
+        local.compute_units_consumed,
+        match &local.error_message {
+            Some(err) => red!(format!("failed: {err}")),
+            None => green!("succeeded"),
+        }
+    );
+    for log in local.log_messages.iter().flatten() {
+        println!("  {log}");
+    }
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&replayed.profile)
+            .map_err(|e| format!("failed to serialize profile: {e}"))?
+    );
+    Ok(())
 }
 
 fn generate_completion_helpers(cmd: &Completions) -> Result<(), String> {
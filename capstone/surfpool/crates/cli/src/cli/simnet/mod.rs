@@ -1,12 +1,13 @@
 use std::{
-    path::Path,
+    collections::HashMap,
+    path::{Path, PathBuf},
     sync::{
         Arc,
         atomic::{AtomicBool, Ordering},
-        mpsc,
+        mpsc::{self, RecvTimeoutError},
     },
     thread::sleep,
-    time::Duration,
+    time::{Duration, Instant, SystemTime},
 };
 
 use crossbeam::channel::{Select, Sender};
@@ -16,9 +17,12 @@ use notify::{
 };
 use serde::{Deserialize, Serialize};
 use solana_keypair::Keypair;
-use solana_signer::Signer;
+use solana_pubkey::Pubkey;
+use solana_signer::{EncodableKey, Signer};
 use surfpool_core::{start_local_surfnet, surfnet::svm::SurfnetSvm};
-use surfpool_types::{SanitizedConfig, SimnetEvent, SubgraphEvent};
+use surfpool_types::{
+    SanitizedConfig, SimnetCommand, SimnetEvent, SubgraphCommand, SubgraphEvent, SurfpoolConfig,
+};
 use txtx_core::kit::{
     channel::Receiver, futures::future::join_all, helpers::fs::FileLocation,
     types::frontend::BlockEvent,
@@ -28,9 +32,13 @@ use txtx_gql::kit::reqwest;
 use super::{Context, DEFAULT_CLOUD_URL, ExecuteRunbook, StartSimnet};
 use crate::{
     http::start_subgraph_and_explorer_server,
+    manifest::SurfpoolManifest,
     runbook::execute_runbook,
     scaffold::{detect_program_frameworks, scaffold_iac_layout},
-    tui::{self, simnet::DisplayedUrl},
+    tui::{
+        self,
+        simnet::{DisplayedSurfnet, DisplayedUrl},
+    },
 };
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -62,7 +70,13 @@ pub async fn handle_start_local_surfnet_command(
     };
 
     // Build config
-    let config = cmd.surfpool_config(airdrop_addresses);
+    let mut config = cmd.surfpool_config(airdrop_addresses);
+    if let Some(manifest) = SurfpoolManifest::from_path(&cmd.manifest_path)? {
+        manifest.apply_to(cmd, &mut config.simnets[0])?;
+        config
+            .simnets
+            .extend(manifest.additional_simnet_configs(&config.rpc)?);
+    }
 
     let studio_binding_address = config.studio.get_studio_base_url();
     let rpc_url = format!("http://{}", config.rpc.get_rpc_base_url());
@@ -112,6 +126,26 @@ pub async fn handle_start_local_surfnet_command(
         }
     };
 
+    // Additional surfnets run on their own threads, and relay their logs to the main surfnet
+    let mut additional_surfnets = vec![];
+    let mut additional_surfnets_commands_tx = vec![];
+    for index in 1..config.simnets.len() {
+        let (displayed_surfnet, commands_tx) = match start_additional_surfnet(
+            &config,
+            index,
+            &subgraph_commands_tx,
+            &simnet_events_tx,
+        ) {
+            Ok(surfnet) => surfnet,
+            Err(e) => {
+                terminate_surfnets(&additional_surfnets_commands_tx);
+                return Err(e);
+            }
+        };
+        additional_surfnets.push(displayed_surfnet);
+        additional_surfnets_commands_tx.push(commands_tx);
+    }
+
     let ctx_copy = ctx.clone();
     let simnet_commands_tx_copy = simnet_commands_tx.clone();
     let config_copy = config.clone();
@@ -137,8 +171,14 @@ pub async fn handle_start_local_surfnet_command(
 
     loop {
         match simnet_events_rx.recv() {
-            Ok(SimnetEvent::Aborted(error)) => return Err(error),
-            Ok(SimnetEvent::Shutdown) => return Ok(()),
+            Ok(SimnetEvent::Aborted(error)) => {
+                terminate_surfnets(&additional_surfnets_commands_tx);
+                return Err(error);
+            }
+            Ok(SimnetEvent::Shutdown) => {
+                terminate_surfnets(&additional_surfnets_commands_tx);
+                return Ok(());
+            }
             Ok(SimnetEvent::Connected(_)) => break,
             Ok(SimnetEvent::Ready) => break,
             _other => continue,
@@ -149,6 +189,14 @@ pub async fn handle_start_local_surfnet_command(
         let _ = simnet_events_tx.send(SimnetEvent::warn(error));
     }
 
+    if cmd.hot_reload {
+        if let Err(e) = watch_and_reload_programs(cmd, &simnet_commands_tx, &simnet_events_tx) {
+            let _ = simnet_events_tx.send(SimnetEvent::warn(format!(
+                "Unable to watch programs for hot reload: {e}"
+            )));
+        }
+    }
+
     let mut deploy_progress_rx = vec![];
     if !cmd.no_deploy {
         match write_and_execute_iac(cmd, &simnet_events_tx).await {
@@ -183,14 +231,14 @@ pub async fn handle_start_local_surfnet_command(
     };
 
     // Start frontend - kept on main thread
-    if cmd.no_tui {
+    let frontend_result = if cmd.no_tui {
         log_events(
             simnet_events_rx,
             subgraph_events_rx,
             cmd.debug,
             deploy_progress_rx,
             ctx,
-        )?;
+        )
     } else {
         tui::simnet::start_app(
             simnet_events_rx,
@@ -198,16 +246,122 @@ pub async fn handle_start_local_surfnet_command(
             cmd.debug,
             deploy_progress_rx,
             displayed_url,
+            additional_surfnets,
             breaker,
         )
-        .map_err(|e| format!("{}", e))?;
-    }
+        .map_err(|e| format!("{}", e))
+    };
+    terminate_surfnets(&additional_surfnets_commands_tx);
+    frontend_result?;
     if let Some(explorer_handle) = explorer_handle {
         let _ = explorer_handle.stop(true).await;
     }
     Ok(())
 }
 
+/// Starts the surfnet at `index` in the configuration with its own SVM, relaying its events to
+/// `main_events_tx`.
+///
+/// Returns the sender of its commands, used to terminate it along with the main surfnet.
+fn start_additional_surfnet(
+    config: &SurfpoolConfig,
+    index: usize,
+    subgraph_commands_tx: &Sender<SubgraphCommand>,
+    main_events_tx: &Sender<SimnetEvent>,
+) -> Result<(DisplayedSurfnet, Sender<SimnetCommand>), String> {
+    let surfnet_config = config
+        .surfnet_config(index)
+        .ok_or_else(|| format!("no surfnet configured at index {index}"))?;
+    let name = surfnet_config.simnets[0]
+        .name
+        .clone()
+        .unwrap_or_else(|| format!("surfnet-{index}"));
+    let displayed_surfnet = DisplayedSurfnet {
+        name: name.clone(),
+        rpc_url: format!("http://{}", surfnet_config.rpc.get_rpc_base_url()),
+        ws_url: format!("ws://{}", surfnet_config.rpc.get_ws_base_url()),
+    };
+
+    let (surfnet_svm, simnet_events_rx, geyser_events_rx) = SurfnetSvm::new();
+    let (simnet_commands_tx, simnet_commands_rx) = crossbeam::channel::unbounded();
+    let subgraph_commands_tx = subgraph_commands_tx.clone();
+    let events_tx = main_events_tx.clone();
+    let surfnet_commands_tx = simnet_commands_tx.clone();
+    let _handle = hiro_system_kit::thread_named(&format!("simnet {name}"))
+        .spawn(move || {
+            let future = start_local_surfnet(
+                surfnet_svm,
+                surfnet_config,
+                subgraph_commands_tx,
+                surfnet_commands_tx,
+                simnet_commands_rx,
+                geyser_events_rx,
+            );
+            if let Err(e) = hiro_system_kit::nestable_block_on(future) {
+                let _ = events_tx.send(SimnetEvent::error(format!(
+                    "[{name}] Surfnet exited with error: {e}"
+                )));
+            }
+        })
+        .map_err(|e| format!("{}", e))?;
+
+    let relayed_surfnet = displayed_surfnet.clone();
+    let events_tx = main_events_tx.clone();
+    let _handle =
+        hiro_system_kit::thread_named(&format!("simnet {} events", displayed_surfnet.name))
+            .spawn(move || relay_surfnet_events(relayed_surfnet, simnet_events_rx, events_tx))
+            .map_err(|e| format!("{}", e))?;
+
+    Ok((displayed_surfnet, simnet_commands_tx))
+}
+
+/// Terminates the additional surfnets, which are not tied to the frontend of the main surfnet.
+fn terminate_surfnets(commands_txs: &[Sender<SimnetCommand>]) {
+    for commands_tx in commands_txs {
+        let _ = commands_tx.send(SimnetCommand::Terminate(None));
+    }
+}
+
+/// Forwards the logs and processed transactions of an additional surfnet to the events of the main
+/// surfnet, prefixed with the name of the surfnet.
+fn relay_surfnet_events(
+    surfnet: DisplayedSurfnet,
+    events_rx: Receiver<SimnetEvent>,
+    events_tx: Sender<SimnetEvent>,
+) {
+    let prefix = |log: String| format!("[{}] {}", surfnet.name, log);
+    while let Ok(event) = events_rx.recv() {
+        let relayed_event = match event {
+            SimnetEvent::Ready => SimnetEvent::info(prefix(format!(
+                "Surfnet up and running (RPC: {}, WS: {})",
+                surfnet.rpc_url, surfnet.ws_url
+            ))),
+            SimnetEvent::InfoLog(dt, log) => SimnetEvent::InfoLog(dt, prefix(log)),
+            SimnetEvent::WarnLog(dt, log) => SimnetEvent::WarnLog(dt, prefix(log)),
+            SimnetEvent::ErrorLog(dt, log) => SimnetEvent::ErrorLog(dt, prefix(log)),
+            SimnetEvent::DebugLog(dt, log) => SimnetEvent::DebugLog(dt, prefix(log)),
+            SimnetEvent::TransactionProcessed(dt, meta, Some(err)) => SimnetEvent::ErrorLog(
+                dt,
+                prefix(format!("Failed processing tx {}: {}", meta.signature, err)),
+            ),
+            SimnetEvent::TransactionProcessed(dt, meta, None) => {
+                SimnetEvent::InfoLog(dt, prefix(format!("Processed tx {}", meta.signature)))
+            }
+            SimnetEvent::AnchorEventEmitted { timestamp, .. } => {
+                SimnetEvent::InfoLog(timestamp, prefix(event.anchor_event_msg()))
+            }
+            SimnetEvent::Aborted(error) => {
+                SimnetEvent::error(prefix(format!("Surfnet aborted: {error}")))
+            }
+            SimnetEvent::Shutdown => break,
+            _ => continue,
+        };
+        if events_tx.send(relayed_event).is_err() {
+            break;
+        }
+    }
+}
+
 fn log_events(
     simnet_events_rx: Receiver<SimnetEvent>,
     subgraph_events_rx: Receiver<SubgraphEvent>,
@@ -313,6 +467,9 @@ fn log_events(
                             result.transaction_profile.compute_units_consumed
                         );
                     }
+                    SimnetEvent::AnchorEventEmitted { .. } => {
+                        info!(ctx.expect_logger(), "{}", event.anchor_event_msg());
+                    }
                     SimnetEvent::RunbookStarted(runbook_id) => {
                         deployment_completed = false;
                         info!(
@@ -489,3 +646,127 @@ async fn write_and_execute_iac(
     }
     Ok(progress_rx)
 }
+
+/// Delay without any write to a rebuilt program after which it is reloaded, so that a program
+/// still being written by the build is not deployed.
+const PROGRAM_RELOAD_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Watches `target/deploy` and swaps the bytecode of the programs rebuilt there in place, at the
+/// address of their `<program>-keypair.json`.
+///
+/// Unlike `--watch`, no deployment transaction is sent: the program accounts are rewritten, so the
+/// accounts owned by the program are kept.
+fn watch_and_reload_programs(
+    cmd: &StartSimnet,
+    simnet_commands_tx: &Sender<SimnetCommand>,
+    simnet_events_tx: &Sender<SimnetEvent>,
+) -> Result<(), String> {
+    let mut target_path =
+        FileLocation::from_path_string(&cmd.manifest_path)?.get_parent_location()?;
+    target_path.append_path("target")?;
+    target_path.append_path("deploy")?;
+    let target_path = PathBuf::from(target_path.to_string());
+    if !target_path.is_dir() {
+        return Err(format!("{} is not a directory", target_path.display()));
+    }
+
+    let simnet_commands_tx = simnet_commands_tx.clone();
+    let simnet_events_tx = simnet_events_tx.clone();
+    let _handle = hiro_system_kit::thread_named("Hot Reload Programs")
+        .spawn(move || {
+            let (tx, rx) = mpsc::channel::<NotifyResult<Event>>();
+            let mut watcher = notify::recommended_watcher(tx).map_err(|e| e.to_string())?;
+            watcher
+                .watch(&target_path, RecursiveMode::NonRecursive)
+                .map_err(|e| e.to_string())?;
+            let _ = simnet_events_tx.send(SimnetEvent::info(format!(
+                "Hot reloading programs rebuilt in {}",
+                target_path.display()
+            )));
+            // Programs being written, with the time of their latest write and their size and
+            // modification time at this point
+            let mut pending_programs = HashMap::new();
+            loop {
+                let paths = match rx.recv_timeout(PROGRAM_RELOAD_DEBOUNCE) {
+                    // Disregard any event that would not create or modify a .so file
+                    Ok(Ok(Event {
+                        kind: EventKind::Modify(ModifyKind::Data(_)),
+                        paths,
+                        attrs: _,
+                    }))
+                    | Ok(Ok(Event {
+                        kind: EventKind::Create(CreateKind::File),
+                        paths,
+                        attrs: _,
+                    })) => paths,
+                    Ok(_) | Err(RecvTimeoutError::Timeout) => vec![],
+                    Err(RecvTimeoutError::Disconnected) => break,
+                };
+                for path in paths {
+                    if path.extension().is_some_and(|ext| ext == "so") {
+                        let stamp = program_file_stamp(&path);
+                        pending_programs.insert(path, (Instant::now(), stamp));
+                    }
+                }
+
+                // A program is reloaded once it has not been written to for a while
+                let mut stable_programs = vec![];
+                pending_programs.retain(|program_path, (written_at, stamp)| {
+                    if written_at.elapsed() < PROGRAM_RELOAD_DEBOUNCE {
+                        return true;
+                    }
+                    let current_stamp = program_file_stamp(program_path);
+                    if current_stamp != *stamp {
+                        *written_at = Instant::now();
+                        *stamp = current_stamp;
+                        return true;
+                    }
+                    stable_programs.push(program_path.clone());
+                    false
+                });
+
+                for program_path in stable_programs {
+                    match read_rebuilt_program(&program_path) {
+                        Ok((program_id, elf)) => {
+                            let _ = simnet_events_tx.send(SimnetEvent::info(format!(
+                                "Reloading program {} from {}",
+                                program_id,
+                                program_path.display()
+                            )));
+                            let _ = simnet_commands_tx
+                                .send(SimnetCommand::ReloadProgram(program_id, elf));
+                        }
+                        Err(e) => {
+                            let _ = simnet_events_tx.send(SimnetEvent::warn(format!(
+                                "Unable to reload {}: {}",
+                                program_path.display(),
+                                e
+                            )));
+                        }
+                    }
+                }
+            }
+            Ok::<(), String>(())
+        })
+        .map_err(|e| format!("Thread to watch programs exited: {}", e))?;
+    Ok(())
+}
+
+/// Returns the size and modification time of a program file, used to detect that it is still being written.
+fn program_file_stamp(program_path: &Path) -> Option<(u64, SystemTime)> {
+    let metadata = std::fs::metadata(program_path).ok()?;
+    Some((metadata.len(), metadata.modified().ok()?))
+}
+
+/// Reads a program rebuilt in `target/deploy`, along with the address of its keypair.
+fn read_rebuilt_program(program_path: &Path) -> Result<(Pubkey, Vec<u8>), String> {
+    let Some(program_name) = program_path.file_stem() else {
+        return Err("invalid program path".to_string());
+    };
+    let keypair_path =
+        program_path.with_file_name(format!("{}-keypair.json", program_name.to_string_lossy()));
+    let keypair = Keypair::read_from_file(&keypair_path)
+        .map_err(|e| format!("unable to read {}: {}", keypair_path.display(), e))?;
+    let elf = std::fs::read(program_path).map_err(|e| e.to_string())?;
+    Ok((keypair.pubkey(), elf))
+}
@@ -1,7 +1,10 @@
 #![allow(unused_imports, unused_variables)]
 
 use std::{
-    collections::HashMap, error::Error as StdError, sync::RwLock, thread::JoinHandle,
+    collections::HashMap,
+    error::Error as StdError,
+    sync::{Arc, RwLock},
+    thread::JoinHandle,
     time::Duration,
 };
 
@@ -24,7 +27,10 @@ use surfpool_gql::{
     db::schema::collections,
     new_dynamic_schema,
     query::{CollectionMetadataMap, Dataloader, DataloaderContext, SqlStore},
-    types::{CollectionEntry, CollectionEntryData, collections::CollectionMetadata},
+    types::{
+        CollectionEntry, CollectionEntryData, CollectionEntryDataUpdate,
+        collections::CollectionMetadata,
+    },
 };
 use surfpool_studio_ui::serve_studio_static_files;
 use surfpool_types::{
@@ -47,11 +53,11 @@ pub async fn start_subgraph_and_explorer_server(
     subgraph_commands_rx: Receiver<SubgraphCommand>,
     ctx: &Context,
 ) -> Result<(ServerHandle, JoinHandle<Result<(), String>>), Box<dyn StdError>> {
-    let context = DataloaderContext {
-        pool: SqlStore::new(subgraph_database_path).pool,
-    };
+    let context = DataloaderContext::new(SqlStore::new(subgraph_database_path)?.pool);
     let schema_datasource = CollectionMetadataMap::new();
-    let schema = RwLock::new(Some(new_dynamic_schema(schema_datasource.clone())));
+    let schema = RwLock::new(Some(Arc::new(new_dynamic_schema(
+        schema_datasource.clone(),
+    ))));
     let schema_wrapped = Data::new(schema);
     let context_wrapped = Data::new(RwLock::new(context));
     let config_wrapped = Data::new(RwLock::new(config.clone()));
@@ -151,7 +157,7 @@ async fn dist(path: web::Path<String>) -> impl Responder {
 async fn post_graphql(
     req: HttpRequest,
     payload: web::Payload,
-    schema: Data<RwLock<Option<DynamicSchema>>>,
+    schema: Data<RwLock<Option<Arc<DynamicSchema>>>>,
     context: Data<RwLock<DataloaderContext>>,
 ) -> Result<HttpResponse, Error> {
     let context = context
@@ -171,7 +177,7 @@ async fn post_graphql(
 async fn get_graphql(
     req: HttpRequest,
     payload: web::Payload,
-    schema: Data<RwLock<Option<DynamicSchema>>>,
+    schema: Data<RwLock<Option<Arc<DynamicSchema>>>>,
     context: Data<RwLock<DataloaderContext>>,
 ) -> Result<HttpResponse, Error> {
     let context = context
@@ -191,16 +197,24 @@ async fn get_graphql(
 async fn subscriptions(
     req: HttpRequest,
     stream: web::Payload,
-    schema: Data<DynamicSchema>,
+    schema: Data<RwLock<Option<Arc<DynamicSchema>>>>,
     context: Data<RwLock<DataloaderContext>>,
 ) -> Result<HttpResponse, Error> {
     let context = context
         .read()
-        .map_err(|_| actix_web::error::ErrorInternalServerError("Failed to read context"))?;
+        .map_err(|_| actix_web::error::ErrorInternalServerError("Failed to read context"))?
+        .clone();
+    // Subscriptions keep using the schema they were opened with, even if collections are added later on.
+    let schema = schema
+        .read()
+        .map_err(|_| actix_web::error::ErrorInternalServerError("Failed to read schema"))?
+        .clone()
+        .ok_or(actix_web::error::ErrorInternalServerError(
+            "Missing expected schema",
+        ))?;
     let config = ConnectionConfig::new(context);
     let config = config.with_keep_alive_interval(Duration::from_secs(15));
-    unimplemented!()
-    // subscriptions::ws_handler(req, stream, schema.into_inner(), config).await
+    subscriptions::ws_handler(req, stream, schema, config).await
 }
 
 async fn graphiql() -> Result<HttpResponse, Error> {
@@ -211,7 +225,7 @@ fn start_subgraph_runloop(
     subgraph_events_tx: Sender<SubgraphEvent>,
     subgraph_commands_rx: Receiver<SubgraphCommand>,
     gql_context: Data<RwLock<DataloaderContext>>,
-    gql_schema: Data<RwLock<Option<DynamicSchema>>>,
+    gql_schema: Data<RwLock<Option<Arc<DynamicSchema>>>>,
     mut collections_map: CollectionMetadataMap,
     config: SanitizedConfig,
     ctx: &Context,
@@ -255,7 +269,9 @@ fn start_subgraph_runloop(
                                     error!(ctx.expect_logger(), "{}", e);
                                 }
                                 collections_map.add_collection(metadata);
-                                gql_schema.replace(new_dynamic_schema(collections_map.clone()));
+                                gql_schema.replace(Arc::new(new_dynamic_schema(
+                                    collections_map.clone(),
+                                )));
 
                                 let console_url = format!("{}/subgraphs", config.studio_url.clone());
                                 let _ = sender.send(console_url);
@@ -299,13 +315,20 @@ fn start_subgraph_runloop(
 
                                 if let Err(e) = gql_context
                                     .pool
-                                    .insert_entries_into_collection(entries, &metadata)
+                                    .insert_entries_into_collection(entries.clone(), &metadata)
                                 {
                                     let _ = subgraph_events_tx.send(SubgraphEvent::error(format!(
                                         "{err_ctx}: {e}"
                                     )));
                                     continue;
                                 }
+
+                                // Sending only fails when no subscription is open
+                                for entry in entries.iter() {
+                                    let _ = gql_context.entries_broadcaster.send(
+                                        CollectionEntryDataUpdate::new(&metadata.name, entry),
+                                    );
+                                }
                             }
                             DataIndexingCommand::ProcessCollection(_uuid) => {}
                         },
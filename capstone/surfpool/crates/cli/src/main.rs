@@ -6,8 +6,8 @@ mod cloud;
 extern crate hiro_system_kit;
 
 mod cli;
-// mod manifest;
 mod http;
+mod manifest;
 mod runbook;
 mod scaffold;
 mod tui;
@@ -0,0 +1,582 @@
+use std::{
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use serde::Deserialize;
+use solana_pubkey::Pubkey;
+use surfpool_types::{
+    AccountFileFormat, AccountPreload, BlockProductionMode, ProgramClone, ProgramDeployment,
+    RpcConfig, SimnetConfig, TokenAccountPreset,
+    types::{SetSomeAccount, TokenAccountUpdate},
+};
+
+use crate::cli::{StartSimnet, resolve_path};
+
+/// The content of a `Surfpool.toml` manifest.
+///
+/// The `[surfnet]` section declares the startup state of the surfnet. Flags passed to `surfpool start`
+/// take precedence over the manifest, and relative paths are resolved against the manifest directory.
+///
+/// ```toml
+/// [surfnet]
+/// slot_time = 400
+/// validators = 3
+/// block_production_mode = "transaction"
+/// rpc_url = "https://api.mainnet-beta.solana.com"
+/// airdrop_addresses = ["..."]
+/// airdrop_amount = 10000000000000
+/// # Replaces the Jito tip accounts that bundles must tip
+/// tip_accounts = ["..."]
+/// idls = ["target/idl/my_program.json"]
+///
+/// # `solana account <ADDRESS> --output json` output
+/// [[surfnet.accounts]]
+/// path = "fixtures/pool.json"
+///
+/// # base64 encoded account data
+/// [[surfnet.accounts]]
+/// pubkey = "..."
+/// path = "fixtures/oracle.b64"
+/// owner = "..."
+///
+/// [[surfnet.token_accounts]]
+/// owner = "..."
+/// mint = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"
+/// amount = 1000000000
+///
+/// [[surfnet.cloned_programs]]
+/// source = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+/// destination = "..."
+///
+/// [[surfnet.programs]]
+/// program_id = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+/// path = "target/deploy/patched_token.so"
+/// # Optional, the upgrade authority of the forked program is kept if omitted
+/// authority = "..."
+///
+/// # Additional surfnets, started alongside the main one. They accept the same keys as `[surfnet]`,
+/// # but don't inherit its values: the keys left out take their default value
+/// [[surfnets]]
+/// name = "devnet"
+/// port = 8901
+/// ws_port = 8902
+/// rpc_url = "https://api.devnet.solana.com"
+/// ```
+#[derive(Debug, Default, Deserialize)]
+pub struct SurfpoolManifest {
+    #[serde(default)]
+    pub surfnet: SurfnetManifest,
+    /// Additional surfnets, each bound to its own ports and configured independently of `[surfnet]`
+    #[serde(default)]
+    pub surfnets: Vec<SurfnetManifest>,
+    /// The directory containing the manifest, that relative paths are resolved against
+    #[serde(skip)]
+    pub base_dir: PathBuf,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SurfnetManifest {
+    /// The name of an additional surfnet, displayed along with its logs
+    pub name: Option<String>,
+    /// The RPC port of an additional surfnet
+    pub port: Option<u16>,
+    /// The WebSocket port of an additional surfnet; the RPC port + 1 by default
+    pub ws_port: Option<u16>,
+    /// The slot time, in milliseconds
+    pub slot_time: Option<u64>,
+    /// The number of synthetic validators
+    pub validators: Option<usize>,
+    /// One of `clock`, `transaction` or `manual`
+    pub block_production_mode: Option<String>,
+    /// The datasource RPC URL
+    pub rpc_url: Option<String>,
+    /// Start without a datasource
+    pub offline: Option<bool>,
+    #[serde(default)]
+    pub airdrop_addresses: Vec<String>,
+    pub airdrop_amount: Option<u64>,
+    /// The accounts that bundles must tip, instead of the Jito tip accounts
+    #[serde(default)]
+    pub tip_accounts: Vec<String>,
+    /// Accounts written from local files on startup
+    #[serde(default)]
+    pub accounts: Vec<AccountManifest>,
+    /// Token accounts set on startup
+    #[serde(default)]
+    pub token_accounts: Vec<TokenAccountManifest>,
+    /// Programs cloned on startup
+    #[serde(default)]
+    pub cloned_programs: Vec<ProgramCloneManifest>,
+    /// Programs deployed from local ELFs on startup, replacing the programs at these addresses
+    #[serde(default)]
+    pub programs: Vec<ProgramManifest>,
+    /// Paths of the Anchor IDLs registered on startup
+    #[serde(default)]
+    pub idls: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AccountManifest {
+    /// Required for base64 files; JSON files provide their own address
+    pub pubkey: Option<String>,
+    pub path: String,
+    /// Providing an owner marks the file as base64 encoded account data
+    pub owner: Option<String>,
+    pub lamports: Option<u64>,
+    #[serde(default)]
+    pub executable: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TokenAccountManifest {
+    pub owner: String,
+    pub mint: String,
+    pub token_program: Option<String>,
+    pub amount: Option<u64>,
+    pub delegate: Option<String>,
+    pub state: Option<String>,
+    pub delegated_amount: Option<u64>,
+    pub close_authority: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProgramCloneManifest {
+    pub source: String,
+    pub destination: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProgramManifest {
+    pub program_id: String,
+    pub path: String,
+    pub authority: Option<String>,
+}
+
+impl SurfpoolManifest {
+    /// Loads the manifest at `manifest_path`, returning `None` if there is no such file.
+    pub fn from_path(manifest_path: &str) -> Result<Option<Self>, String> {
+        let path = resolve_path(manifest_path);
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(format!("unable to read {}: {}", path.display(), e)),
+        };
+        let mut manifest: SurfpoolManifest = toml::from_str(&content)
+            .map_err(|e| format!("unable to parse {}: {}", path.display(), e))?;
+        manifest.base_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        Ok(Some(manifest))
+    }
+
+    /// Applies the `[surfnet]` section of the manifest to a simnet configuration built from `cmd`,
+    /// keeping the values explicitly set with flags.
+    pub fn apply_to(
+        &self,
+        cmd: &StartSimnet,
+        simnet_config: &mut SimnetConfig,
+    ) -> Result<(), String> {
+        self.apply_surfnet(&self.surfnet, Some(cmd), simnet_config)
+    }
+
+    /// The configurations of the additional surfnets declared with `[[surfnets]]`, bound to the host
+    /// of the main surfnet, whose RPC configuration is `main_rpc`.
+    ///
+    /// Neither flags nor the `[surfnet]` section apply to them: each one starts from the default
+    /// configuration. An error is returned if two surfnets share a port.
+    pub fn additional_simnet_configs(
+        &self,
+        main_rpc: &RpcConfig,
+    ) -> Result<Vec<SimnetConfig>, String> {
+        let mut used_ports = vec![
+            (main_rpc.bind_port, "main".to_string()),
+            (main_rpc.ws_port, "main".to_string()),
+        ];
+        let mut simnet_configs = vec![];
+        for (i, surfnet) in self.surfnets.iter().enumerate() {
+            let name = surfnet
+                .name
+                .clone()
+                .unwrap_or_else(|| format!("surfnet-{}", i + 1));
+            let Some(bind_port) = surfnet.port else {
+                return Err(format!("missing port for surfnet '{name}'"));
+            };
+            let ws_port = match surfnet.ws_port {
+                Some(ws_port) => ws_port,
+                None => bind_port.checked_add(1).ok_or_else(|| {
+                    format!(
+                        "missing ws_port for surfnet '{name}': port {bind_port} is the last one"
+                    )
+                })?,
+            };
+            for port in [bind_port, ws_port] {
+                if let Some((_, other)) = used_ports.iter().find(|(used, _)| *used == port) {
+                    return Err(format!(
+                        "port {port} of surfnet '{name}' is already used by surfnet '{other}'"
+                    ));
+                }
+                used_ports.push((port, name.clone()));
+            }
+            let mut simnet_config = SimnetConfig {
+                name: Some(name),
+                rpc: Some(RpcConfig {
+                    bind_host: main_rpc.bind_host.clone(),
+                    bind_port,
+                    ws_port,
+                }),
+                ..SimnetConfig::default()
+            };
+            self.apply_surfnet(surfnet, None, &mut simnet_config)?;
+            simnet_configs.push(simnet_config);
+        }
+        Ok(simnet_configs)
+    }
+
+    fn apply_surfnet(
+        &self,
+        surfnet: &SurfnetManifest,
+        cmd: Option<&StartSimnet>,
+        simnet_config: &mut SimnetConfig,
+    ) -> Result<(), String> {
+        let cmd_slot_time = cmd.and_then(|cmd| cmd.slot_time);
+        let cmd_validators = cmd.and_then(|cmd| cmd.validators);
+        let cmd_offline = cmd.is_some_and(|cmd| cmd.offline);
+        let cmd_rpc_url = cmd.and_then(|cmd| cmd.datasource_rpc_url());
+        let cmd_airdrop_amount = cmd.and_then(|cmd| cmd.airdrop_token_amount);
+        let cmd_tip_accounts = cmd.is_some_and(|cmd| !cmd.tip_accounts.is_empty());
+
+        if let (None, Some(slot_time)) = (cmd_slot_time, surfnet.slot_time) {
+            simnet_config.slot_time = slot_time;
+        }
+        if let (None, Some(validators)) = (cmd_validators, surfnet.validators) {
+            simnet_config.validators = validators;
+        }
+        if let Some(mode) = &surfnet.block_production_mode {
+            simnet_config.block_production_mode = parse_block_production_mode(mode)?;
+        }
+        // `--offline`, `--network` and `--rpc-url` all select the datasource
+        if !cmd_offline && cmd_rpc_url.is_none() {
+            if surfnet.offline == Some(true) {
+                simnet_config.offline_mode = true;
+                simnet_config.remote_rpc_url = None;
+            } else if let Some(rpc_url) = &surfnet.rpc_url {
+                simnet_config.remote_rpc_url = Some(rpc_url.clone());
+            }
+        }
+
+        for address in surfnet.airdrop_addresses.iter() {
+            simnet_config
+                .airdrop_addresses
+                .push(parse_pubkey("airdrop address", address)?);
+        }
+        if let (None, Some(amount)) = (cmd_airdrop_amount, surfnet.airdrop_amount) {
+            simnet_config.airdrop_token_amount = amount;
+        }
+        if !cmd_tip_accounts && !surfnet.tip_accounts.is_empty() {
+            simnet_config.tip_accounts = surfnet
+                .tip_accounts
+                .iter()
+                .map(|address| parse_pubkey("tip account", address))
+                .collect::<Result<_, _>>()?;
+        }
+
+        for account in surfnet.accounts.iter() {
+            simnet_config
+                .preloaded_accounts
+                .push(self.account_preload(account)?);
+        }
+        for token_account in surfnet.token_accounts.iter() {
+            simnet_config
+                .token_accounts
+                .push(token_account_preset(token_account)?);
+        }
+        for clone in surfnet.cloned_programs.iter() {
+            simnet_config.cloned_programs.push(ProgramClone {
+                source: parse_pubkey("source", &clone.source)?,
+                destination: parse_pubkey("destination", &clone.destination)?,
+            });
+        }
+        for program in surfnet.programs.iter() {
+            simnet_config
+                .program_deployments
+                .push(self.program_deployment(program)?);
+        }
+        for idl in surfnet.idls.iter() {
+            simnet_config.idl_paths.push(self.resolve(idl));
+        }
+        Ok(())
+    }
+
+    fn account_preload(&self, account: &AccountManifest) -> Result<AccountPreload, String> {
+        let pubkey = account
+            .pubkey
+            .as_deref()
+            .map(|pubkey| parse_pubkey("pubkey", pubkey))
+            .transpose()?;
+        let format = match &account.owner {
+            Some(owner) => AccountFileFormat::Base64 {
+                owner: parse_pubkey("owner", owner)?,
+                lamports: account.lamports,
+                executable: account.executable,
+            },
+            None => AccountFileFormat::Json,
+        };
+        Ok(AccountPreload {
+            pubkey,
+            path: self.resolve(&account.path),
+            format,
+        })
+    }
+
+    fn program_deployment(&self, program: &ProgramManifest) -> Result<ProgramDeployment, String> {
+        let authority = program
+            .authority
+            .as_deref()
+            .map(|authority| parse_pubkey("authority", authority))
+            .transpose()?;
+        Ok(ProgramDeployment {
+            program_id: parse_pubkey("program_id", &program.program_id)?,
+            path: self.resolve(&program.path),
+            authority,
+        })
+    }
+
+    fn resolve(&self, path: &str) -> PathBuf {
+        let path = resolve_path(path);
+        if path.is_absolute() {
+            path
+        } else {
+            self.base_dir.join(path)
+        }
+    }
+}
+
+fn token_account_preset(
+    token_account: &TokenAccountManifest,
+) -> Result<TokenAccountPreset, String> {
+    let token_program = token_account
+        .token_program
+        .as_deref()
+        .map(|token_program| parse_pubkey("token_program", token_program))
+        .transpose()?;
+    Ok(TokenAccountPreset {
+        owner: parse_pubkey("owner", &token_account.owner)?,
+        mint: parse_pubkey("mint", &token_account.mint)?,
+        token_program,
+        update: TokenAccountUpdate {
+            amount: token_account.amount,
+            delegate: token_account.delegate.clone().map(SetSomeAccount::Account),
+            state: token_account.state.clone(),
+            delegated_amount: token_account.delegated_amount,
+            close_authority: token_account
+                .close_authority
+                .clone()
+                .map(SetSomeAccount::Account),
+        },
+    })
+}
+
+fn parse_block_production_mode(mode: &str) -> Result<BlockProductionMode, String> {
+    match mode.to_lowercase().as_str() {
+        "clock" => Ok(BlockProductionMode::Clock),
+        "transaction" => Ok(BlockProductionMode::Transaction),
+        "manual" => Ok(BlockProductionMode::Manual),
+        _ => Err(format!(
+            "invalid block_production_mode '{mode}': expected clock, transaction or manual"
+        )),
+    }
+}
+
+fn parse_pubkey(field: &str, value: &str) -> Result<Pubkey, String> {
+    Pubkey::from_str(value).map_err(|e| format!("invalid {field} '{value}': {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+
+    /// Applies the `[surfnet]` section of `content` to the configuration built from the `args` flags.
+    fn apply(content: &str, args: &[&str]) -> Result<SimnetConfig, String> {
+        let cmd = StartSimnet::parse_from(["start"].iter().chain(args));
+        let mut simnet_config = cmd.simnet_config(vec![]);
+        manifest(content).apply_to(&cmd, &mut simnet_config)?;
+        Ok(simnet_config)
+    }
+
+    fn main_rpc() -> RpcConfig {
+        RpcConfig {
+            bind_host: "127.0.0.1".to_string(),
+            bind_port: 8899,
+            ws_port: 8900,
+        }
+    }
+
+    fn manifest(content: &str) -> SurfpoolManifest {
+        toml::from_str(content).unwrap()
+    }
+
+    #[test]
+    fn test_additional_simnet_configs_ports() {
+        let simnet_configs = manifest(
+            r#"
+            [[surfnets]]
+            name = "devnet"
+            port = 8901
+
+            [[surfnets]]
+            port = 9000
+            ws_port = 9100
+            "#,
+        )
+        .additional_simnet_configs(&main_rpc())
+        .unwrap();
+        let ports = simnet_configs
+            .iter()
+            .map(|config| {
+                let rpc = config.rpc.as_ref().unwrap();
+                (config.name.clone().unwrap(), rpc.bind_port, rpc.ws_port)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ports,
+            vec![
+                ("devnet".to_string(), 8901, 8902),
+                ("surfnet-2".to_string(), 9000, 9100)
+            ]
+        );
+
+        // The WebSocket port can't default past the last port
+        let error = manifest("[[surfnets]]\nport = 65535")
+            .additional_simnet_configs(&main_rpc())
+            .unwrap_err();
+        assert!(error.contains("missing ws_port"), "{error}");
+
+        let error = manifest("[[surfnets]]\nport = 8900")
+            .additional_simnet_configs(&main_rpc())
+            .unwrap_err();
+        assert_eq!(
+            error,
+            "port 8900 of surfnet 'surfnet-1' is already used by surfnet 'main'"
+        );
+
+        let error = manifest(
+            r#"
+            [[surfnets]]
+            name = "devnet"
+            port = 8901
+
+            [[surfnets]]
+            name = "testnet"
+            port = 8902
+            "#,
+        )
+        .additional_simnet_configs(&main_rpc())
+        .unwrap_err();
+        assert_eq!(
+            error,
+            "port 8902 of surfnet 'testnet' is already used by surfnet 'devnet'"
+        );
+    }
+
+    #[test]
+    fn test_additional_simnet_configs_do_not_inherit_surfnet() {
+        let simnet_configs = manifest(
+            r#"
+            [surfnet]
+            slot_time = 100
+            validators = 3
+
+            [[surfnets]]
+            port = 8901
+            validators = 2
+            "#,
+        )
+        .additional_simnet_configs(&main_rpc())
+        .unwrap();
+        assert_eq!(
+            simnet_configs[0].slot_time,
+            SimnetConfig::default().slot_time
+        );
+        assert_eq!(simnet_configs[0].validators, 2);
+    }
+
+    #[test]
+    fn test_apply_to() {
+        let content = r#"
+            [surfnet]
+            slot_time = 100
+            validators = 3
+            block_production_mode = "Manual"
+            rpc_url = "https://api.devnet.solana.com"
+            airdrop_amount = 42
+            "#;
+        let simnet_config = apply(content, &[]).unwrap();
+        assert_eq!(simnet_config.slot_time, 100);
+        assert_eq!(simnet_config.validators, 3);
+        assert_eq!(
+            simnet_config.block_production_mode,
+            BlockProductionMode::Manual
+        );
+        assert_eq!(
+            simnet_config.remote_rpc_url.as_deref(),
+            Some("https://api.devnet.solana.com")
+        );
+        assert_eq!(simnet_config.airdrop_token_amount, 42);
+
+        // Flags take precedence over the manifest
+        let simnet_config = apply(
+            content,
+            &["--slot-time", "200", "--rpc-url", "http://localhost:9999"],
+        )
+        .unwrap();
+        assert_eq!(simnet_config.slot_time, 200);
+        assert_eq!(
+            simnet_config.remote_rpc_url.as_deref(),
+            Some("http://localhost:9999")
+        );
+        let simnet_config = apply(content, &["--offline"]).unwrap();
+        assert!(simnet_config.offline_mode);
+        assert_eq!(simnet_config.remote_rpc_url, None);
+
+        let content = "[surfnet]\noffline = true";
+        let simnet_config = apply(content, &[]).unwrap();
+        assert!(simnet_config.offline_mode);
+        assert_eq!(simnet_config.remote_rpc_url, None);
+        let simnet_config = apply(content, &["--rpc-url", "http://localhost:9999"]).unwrap();
+        assert!(!simnet_config.offline_mode);
+        assert_eq!(
+            simnet_config.remote_rpc_url.as_deref(),
+            Some("http://localhost:9999")
+        );
+
+        let error = apply("[surfnet]\nairdrop_addresses = [\"nope\"]", &[]).unwrap_err();
+        assert!(
+            error.starts_with("invalid airdrop address 'nope'"),
+            "{error}"
+        );
+    }
+
+    #[test]
+    fn test_parse_block_production_mode() {
+        assert_eq!(
+            parse_block_production_mode("clock"),
+            Ok(BlockProductionMode::Clock)
+        );
+        assert_eq!(
+            parse_block_production_mode("Transaction"),
+            Ok(BlockProductionMode::Transaction)
+        );
+        assert_eq!(
+            parse_block_production_mode("MANUAL"),
+            Ok(BlockProductionMode::Manual)
+        );
+        assert_eq!(
+            parse_block_production_mode("slot"),
+            Err(
+                "invalid block_production_mode 'slot': expected clock, transaction or manual"
+                    .to_string()
+            )
+        );
+    }
+}
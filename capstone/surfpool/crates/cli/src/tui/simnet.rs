@@ -93,6 +93,7 @@ struct App {
     deploy_progress_rx: Vec<Receiver<BlockEvent>>,
     status_bar_message: Option<String>,
     displayed_url: DisplayedUrl,
+    additional_surfnets: Vec<DisplayedSurfnet>,
     breaker: Option<Keypair>,
     paused: bool,
     blink_state: bool,
@@ -106,6 +107,7 @@ impl App {
         include_debug_logs: bool,
         deploy_progress_rx: Vec<Receiver<BlockEvent>>,
         displayed_url: DisplayedUrl,
+        additional_surfnets: Vec<DisplayedSurfnet>,
         breaker: Option<Keypair>,
     ) -> App {
         let palette = palette::tailwind::EMERALD;
@@ -155,6 +157,7 @@ impl App {
             deploy_progress_rx,
             status_bar_message: None,
             displayed_url,
+            additional_surfnets,
             breaker,
             paused: false,
             blink_state: false,
@@ -216,12 +219,21 @@ pub enum DisplayedUrl {
     Datasource(SanitizedConfig),
 }
 
+/// A surfnet started alongside the main one, whose logs are relayed to the main surfnet.
+#[derive(Clone, Debug)]
+pub struct DisplayedSurfnet {
+    pub name: String,
+    pub rpc_url: String,
+    pub ws_url: String,
+}
+
 pub fn start_app(
     simnet_events_rx: Receiver<SimnetEvent>,
     simnet_commands_tx: Sender<SimnetCommand>,
     include_debug_logs: bool,
     deploy_progress_rx: Vec<Receiver<BlockEvent>>,
     displayed_url: DisplayedUrl,
+    additional_surfnets: Vec<DisplayedSurfnet>,
     breaker: Option<Keypair>,
 ) -> Result<(), Box<dyn Error>> {
     // setup terminal
@@ -238,6 +250,7 @@ pub fn start_app(
         include_debug_logs,
         deploy_progress_rx,
         displayed_url,
+        additional_surfnets,
         breaker,
     );
     let res = run_app(&mut terminal, app);
@@ -393,6 +406,13 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> io::Result<(
                                 );
                                 new_events.push((EventType::Info, *timestamp, msg));
                             }
+                            SimnetEvent::AnchorEventEmitted { timestamp, .. } => {
+                                new_events.push((
+                                    EventType::Info,
+                                    *timestamp,
+                                    event.anchor_event_msg(),
+                                ));
+                            }
                             SimnetEvent::RunbookStarted(runbook_id) => {
                                 deployment_completed = false;
                                 new_events.push((
@@ -525,7 +545,7 @@ fn run_app<B: Backend>(terminal: &mut Terminal<B>, mut app: App) -> io::Result<(
 
 fn ui(f: &mut Frame, app: &mut App) {
     let rects = Layout::vertical([
-        Constraint::Length(8),
+        Constraint::Length(8 + app.additional_surfnets.len() as u16),
         Constraint::Min(5),
         Constraint::Length(3),
     ])
@@ -616,6 +636,7 @@ fn render_stats(f: &mut Frame, app: &mut App, area: Rect) {
                     Span::styled(datasource_url, app.colors.white),
                 ]));
             }
+            lines.extend(additional_surfnet_lines(app));
             lines.push(Line::from(vec![Span::styled("۬-", app.colors.light_gray)]));
             lines.push(Line::from(vec![
                 Span::styled("۬", app.colors.white),
@@ -628,28 +649,41 @@ fn render_stats(f: &mut Frame, app: &mut App, area: Rect) {
             lines
         }
         DisplayedUrl::Studio(ref config) => {
-            vec![
-                Line::from(vec![
-                    Span::styled("۬", app.colors.white),
-                    Span::styled("Explorer  ", app.colors.light_gray),
-                    Span::styled(&config.studio_url, app.colors.white),
-                ]),
-                Line::from(vec![Span::styled("۬-", app.colors.light_gray)]),
-                Line::from(vec![
-                    Span::styled("۬", app.colors.white),
-                    Span::styled(
-                        format!("{} ", app.successful_transactions),
-                        app.colors.accent,
-                    ),
-                    Span::styled("transactions processed", app.colors.white),
-                ]),
-            ]
+            let mut lines = vec![Line::from(vec![
+                Span::styled("۬", app.colors.white),
+                Span::styled("Explorer  ", app.colors.light_gray),
+                Span::styled(&config.studio_url, app.colors.white),
+            ])];
+            lines.extend(additional_surfnet_lines(app));
+            lines.push(Line::from(vec![Span::styled("۬-", app.colors.light_gray)]));
+            lines.push(Line::from(vec![
+                Span::styled("۬", app.colors.white),
+                Span::styled(
+                    format!("{} ", app.successful_transactions),
+                    app.colors.accent,
+                ),
+                Span::styled("transactions processed", app.colors.white),
+            ]));
+            lines
         }
     };
     let title = Paragraph::new(infos);
     f.render_widget(title.style(app.colors.white), area);
 }
 
+fn additional_surfnet_lines(app: &App) -> Vec<Line> {
+    app.additional_surfnets
+        .iter()
+        .map(|surfnet| {
+            Line::from(vec![
+                Span::styled("۬", app.colors.white),
+                Span::styled(format!("{:<9.9} ", surfnet.name), app.colors.light_gray),
+                Span::styled(surfnet.rpc_url.clone(), app.colors.white),
+            ])
+        })
+        .collect()
+}
+
 fn render_slots(f: &mut Frame, app: &mut App, area: Rect) {
     if area.height == 0 {
         return;
@@ -7,6 +7,7 @@ use serde_json::json;
 use solana_client::{client_error::ClientError, rpc_request::TokenAccountsFilter};
 use solana_pubkey::Pubkey;
 use solana_sdk::slot_history::Slot;
+use solana_signature::Signature;
 use solana_transaction_status::EncodeError;
 
 pub type SurfpoolResult<T> = std::result::Result<T, SurfpoolError>;
@@ -126,6 +127,26 @@ impl SurfpoolError {
         Self(error)
     }
 
+    pub fn get_transaction<T>(signature: Signature, e: T) -> Self
+    where
+        T: ToString,
+    {
+        let mut error = Error::internal_error();
+        error.data = Some(json!(format!(
+            "Failed to fetch transaction {} from remote: {}",
+            signature,
+            e.to_string()
+        )));
+        Self(error)
+    }
+
+    pub fn remote_client_required() -> Self {
+        let mut error = Error::invalid_request();
+        error.message =
+            "This method requires a datasource RPC, but surfpool is running offline".into();
+        Self(error)
+    }
+
     pub fn get_token_accounts<T>(owner: Pubkey, filter: &TokenAccountsFilter, e: T) -> Self
     where
         T: ToString,
@@ -401,6 +422,65 @@ impl SurfpoolError {
         Self(error)
     }
 
+    pub fn invalid_fork_slot(fork_slot: Slot, e: ClientError) -> Self {
+        let mut error = Error::internal_error();
+        error.message = format!("Unable to fork the datasource at slot {fork_slot}");
+        error.data = Some(json!(e.to_string()));
+        Self(error)
+    }
+
+    pub fn account_modified_after_fork_slot(pubkey: Pubkey, fork_slot: Slot, slot: Slot) -> Self {
+        let mut error = Error::internal_error();
+        error.message = format!(
+            "Account {pubkey} cannot be served at fork slot {fork_slot}: it was modified at slot {slot}"
+        );
+        Self(error)
+    }
+
+    pub fn too_many_transactions_after_fork_slot(
+        pubkey: Pubkey,
+        fork_slot: Slot,
+        limit: usize,
+    ) -> Self {
+        let mut error = Error::internal_error();
+        error.message = format!(
+            "Account {pubkey} cannot be served at fork slot {fork_slot}: more than {limit} transactions referenced it since"
+        );
+        Self(error)
+    }
+
+    pub fn account_modified_after_transaction(
+        pubkey: Pubkey,
+        signature: Signature,
+        slot: Slot,
+    ) -> Self {
+        let mut error = Error::internal_error();
+        error.message = format!(
+            "Transaction {signature} cannot be replayed: account {pubkey} was modified since, at slot {slot}"
+        );
+        Self(error)
+    }
+
+    pub fn too_many_transactions_after_transaction(
+        pubkey: Pubkey,
+        signature: Signature,
+        limit: usize,
+    ) -> Self {
+        let mut error = Error::internal_error();
+        error.message = format!(
+            "Transaction {signature} cannot be replayed: more than {limit} transactions referenced account {pubkey} since"
+        );
+        Self(error)
+    }
+
+    pub fn unrecoverable_account_state(pubkey: Pubkey, signature: Signature) -> Self {
+        let mut error = Error::internal_error();
+        error.message = format!(
+            "Transaction {signature} cannot be replayed: the state of account {pubkey} before its execution cannot be recovered"
+        );
+        Self(error)
+    }
+
     pub fn token_mint_not_found(mint: Pubkey) -> Self {
         let mut error = Error::internal_error();
         error.message = format!("Token mint {mint} not found");
@@ -440,6 +520,187 @@ impl SurfpoolError {
         Self(error)
     }
 
+    pub fn remote_cache<P, T>(path: P, e: T) -> Self
+    where
+        P: Display,
+        T: ToString,
+    {
+        let mut error = Error::internal_error();
+        error.data = Some(json!(format!(
+            "Failed to access remote account cache {path}: {}",
+            e.to_string()
+        )));
+        Self(error)
+    }
+
+    pub fn remote_traffic_fixture<P, T>(path: P, e: T) -> Self
+    where
+        P: Display,
+        T: ToString,
+    {
+        let mut error = Error::internal_error();
+        error.data = Some(json!(format!(
+            "Failed to access remote traffic fixture {path}: {}",
+            e.to_string()
+        )));
+        Self(error)
+    }
+
+    pub fn account_file<P, T>(path: P, e: T) -> Self
+    where
+        P: Display,
+        T: ToString,
+    {
+        let mut error = Error::internal_error();
+        error.data = Some(json!(format!(
+            "Failed to read account file {path}: {}",
+            e.to_string()
+        )));
+        Self(error)
+    }
+
+    pub fn idl_file<P, T>(path: P, e: T) -> Self
+    where
+        P: Display,
+        T: ToString,
+    {
+        let mut error = Error::internal_error();
+        error.data = Some(json!(format!(
+            "Failed to read IDL file {path}: {}",
+            e.to_string()
+        )));
+        Self(error)
+    }
+
+    pub fn invalid_program_elf<T>(program_id: &Pubkey, e: T) -> Self
+    where
+        T: ToString,
+    {
+        let mut error = Error::invalid_params(format!("Invalid program ELF for {program_id}"));
+        error.data = Some(json!(e.to_string()));
+        Self(error)
+    }
+
+    pub fn program_file<P, T>(path: P, e: T) -> Self
+    where
+        P: Display,
+        T: ToString,
+    {
+        let mut error = Error::internal_error();
+        error.data = Some(json!(format!(
+            "Failed to read program file {path}: {}",
+            e.to_string()
+        )));
+        Self(error)
+    }
+
+    pub fn state_dump<P, T>(path: P, e: T) -> Self
+    where
+        P: Display,
+        T: ToString,
+    {
+        let mut error = Error::internal_error();
+        error.data = Some(json!(format!(
+            "Failed to access state dump {path}: {}",
+            e.to_string()
+        )));
+        Self(error)
+    }
+
+    pub fn state_dumps_disabled() -> Self {
+        Self(Error::invalid_params(
+            "State dumps are disabled: start the surfnet with --state-dir to enable them",
+        ))
+    }
+
+    pub fn invalid_state_dump_file_name(name: &str) -> Self {
+        Self(Error::invalid_params(format!(
+            "Invalid state dump file name {name:?}: only ASCII alphanumerics, '-', '_' and '.' are allowed"
+        )))
+    }
+
+    pub fn invalid_state_dump_chain_tip(index: u64, hash: &str) -> Self {
+        Self(Error::invalid_params(format!(
+            "Invalid state dump: block {index} can't be restored with blockhash {hash}"
+        )))
+    }
+
+    pub fn snapshot_not_found<S>(snapshot_id: S) -> Self
+    where
+        S: Display,
+    {
+        let error = Error::invalid_params(format!("Snapshot {snapshot_id} not found"));
+        Self(error)
+    }
+
+    pub fn too_many_snapshots(max_snapshots: usize) -> Self {
+        Self(Error::invalid_params(format!(
+            "Unable to take a snapshot: {max_snapshots} snapshots are already kept, delete some with surfnet_deleteSnapshot"
+        )))
+    }
+
+    pub fn no_snapshot_before(slot: Slot) -> Self {
+        Self(Error::invalid_params(format!(
+            "No snapshot was taken before slot {slot}"
+        )))
+    }
+
+    pub fn invalid_slot_range(start_slot: Slot, end_slot: Slot) -> Self {
+        Self(Error::invalid_params(format!(
+            "Start slot {start_slot} is after end slot {end_slot}"
+        )))
+    }
+
+    pub fn account_history_truncated(pubkey: Pubkey, oldest_slot: Slot, start_slot: Slot) -> Self {
+        Self(Error::invalid_params(format!(
+            "The history of account {pubkey} only goes back to slot {oldest_slot}, unable to diff it from slot {start_slot}"
+        )))
+    }
+
+    pub fn invalid_bundle<D>(reason: D) -> Self
+    where
+        D: Display,
+    {
+        Self(Error::invalid_params(format!("Invalid bundle: {reason}")))
+    }
+
+    pub fn idl_not_found(program_id: Pubkey) -> Self {
+        Self(Error::invalid_params(format!(
+            "No IDL registered for program {program_id}"
+        )))
+    }
+
+    pub fn idl_instruction_not_found(program_id: Pubkey, instruction: &str) -> Self {
+        Self(Error::invalid_params(format!(
+            "Instruction {instruction} not found in the IDL of program {program_id}"
+        )))
+    }
+
+    pub fn invalid_instruction<D>(instruction: &str, reason: D) -> Self
+    where
+        D: Display,
+    {
+        Self(Error::invalid_params(format!(
+            "Unable to build instruction {instruction}: {reason}"
+        )))
+    }
+
+    pub fn plugin_manager<D>(data: D) -> Self
+    where
+        D: Display,
+    {
+        let mut error = Error::internal_error();
+        error.message = "Plugin manager error".to_string();
+        error.data = Some(json!(data.to_string()));
+        Self(error)
+    }
+
+    pub fn plugin_manager_unavailable() -> Self {
+        let mut error = Error::internal_error();
+        error.message = "Plugin manager did not respond".to_string();
+        Self(error)
+    }
+
     pub(crate) fn expected_profile_not_found(key: &surfpool_types::UuidOrSignature) -> Self {
         let mut error = Error::internal_error();
         error.message = format!("Expected profile not found for key {key}");
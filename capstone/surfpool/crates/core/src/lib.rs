@@ -17,6 +17,8 @@ pub mod runloops;
 pub mod surfnet;
 pub mod types;
 
+use std::path::PathBuf;
+
 use crossbeam_channel::{Receiver, Sender};
 pub use jsonrpc_core;
 pub use jsonrpc_http_server;
@@ -27,6 +29,10 @@ use surfpool_types::{SimnetCommand, SubgraphCommand, SurfpoolConfig};
 use txtx_addon_network_svm_types::subgraph::PluginConfig;
 use uuid::Uuid;
 
+/// Starts the first surfnet of `config`.
+///
+/// Several surfnets are started by calling this function with a distinct [SurfnetSvm] for each of
+/// them, along with the configuration returned by [SurfpoolConfig::surfnet_config].
 pub async fn start_local_surfnet(
     surfnet_svm: SurfnetSvm,
     config: SurfpoolConfig,
@@ -50,6 +56,14 @@ pub async fn start_local_surfnet(
 #[derive(Debug)]
 pub enum PluginManagerCommand {
     LoadConfig(Uuid, PluginConfig, Sender<String>),
+    /// Loads a Geyser plugin from the path of its manifest, replying with the name of the plugin.
+    LoadGeyserPlugin(PathBuf, Sender<Result<String, String>>),
+    /// Unloads a Geyser plugin by name, or a subgraph by uuid.
+    UnloadPlugin(String, Sender<Result<(), String>>),
+    /// Loads a Geyser plugin again from the path of its manifest, replacing the plugin loaded by name.
+    ReloadPlugin(String, PathBuf, Sender<Result<(), String>>),
+    /// Lists the names of the loaded Geyser plugins, followed by the uuids of the loaded subgraphs.
+    ListPlugins(Sender<Vec<String>>),
 }
 
 #[cfg(test)]
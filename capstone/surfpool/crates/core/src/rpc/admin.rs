@@ -1,9 +1,11 @@
 use std::{
     collections::HashMap,
     net::SocketAddr,
+    path::PathBuf,
     time::{Duration, SystemTime},
 };
 
+use crossbeam_channel::Receiver;
 use jsonrpc_core::{BoxFuture, Result};
 use jsonrpc_derive::rpc;
 use solana_client::{rpc_config::RpcAccountIndex, rpc_custom_error::RpcCustomError};
@@ -12,8 +14,19 @@ use surfpool_types::SimnetCommand;
 use txtx_addon_network_svm_types::subgraph::PluginConfig;
 use uuid::Uuid;
 
-use super::{RunloopContext, not_implemented_err, not_implemented_err_async};
-use crate::PluginManagerCommand;
+use super::{RunloopContext, not_implemented_err};
+use crate::{PluginManagerCommand, error::SurfpoolError};
+
+/// The kind of plugin loaded by `loadPlugin`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PluginKind {
+    /// A subgraph, configured inline with its JSON configuration
+    #[default]
+    Subgraph,
+    /// A Geyser plugin, loaded from the path to its manifest
+    Geyser,
+}
 
 #[rpc]
 pub trait AdminRpc {
@@ -49,6 +62,38 @@ pub trait AdminRpc {
     #[rpc(meta, name = "exit")]
     fn exit(&self, meta: Self::Metadata) -> Result<()>;
 
+    /// Reloads a Geyser plugin from its manifest.
+    ///
+    /// This administrative endpoint is used to hot-swap a Geyser plugin without restarting
+    /// the surfnet, typically after rebuilding its dynamic library.
+    ///
+    /// ## Parameters
+    /// - `name`: The name of the plugin to reload, as returned by `listPlugins`.
+    /// - `config_file`: Path to the manifest of the plugin, a json5 file holding the `name`
+    ///   of the plugin and the `libpath` of its dynamic library.
+    ///
+    /// ## Returns
+    /// - [`BoxFuture<Result<()>>`] — A future resolving to a unit result on success, or an error if
+    ///   reloading fails.
+    ///
+    /// ## Example Request (JSON-RPC)
+    /// ```json
+    /// {
+    ///   "jsonrpc": "2.0",
+    ///   "id": 101,
+    ///   "method": "reloadPlugin",
+    ///   "params": ["hello-geyser", "/etc/plugins/hello-geyser.json"]
+    /// }
+    /// ```
+    ///
+    /// # Notes
+    /// - The library is loaded again before the plugin is unloaded, so a failed reload leaves
+    ///   the previous plugin loaded.
+    /// - The manifest must declare the same plugin name.
+    /// - Subgraphs can't be reloaded: unload them and register them again instead.
+    ///
+    /// # Security
+    /// Ensure only trusted clients can invoke this method. Use metadata-based access control to limit exposure.
     #[rpc(meta, name = "reloadPlugin")]
     fn reload_plugin(
         &self,
@@ -57,52 +102,49 @@ pub trait AdminRpc {
         config_file: String,
     ) -> BoxFuture<Result<()>>;
 
-    /// Reloads a runtime plugin with new configuration.
-    ///
-    /// This administrative endpoint is used to dynamically reload a plugin without restarting
-    /// the entire RPC server or validator. It is useful for applying updated configurations
-    /// to a plugin that supports hot-reloading.
+    /// Unloads a Geyser plugin or a subgraph from the runtime.
     ///
     /// ## Parameters
-    /// - `name`: The identifier of the plugin to reload.
-    /// - `config_file`: Path to the new configuration file to load for the plugin.
+    /// - `name`: The name of the Geyser plugin, or the uuid of the subgraph, as returned by `listPlugins`.
     ///
     /// ## Returns
     /// - [`BoxFuture<Result<()>>`] — A future resolving to a unit result on success, or an error if
-    ///   reloading fails.
+    ///   no such plugin is loaded.
     ///
     /// ## Example Request (JSON-RPC)
     /// ```json
     /// {
     ///   "jsonrpc": "2.0",
-    ///   "id": 101,
-    ///   "method": "reloadPlugin",
-    ///   "params": ["myPlugin", "/etc/plugins/my_plugin_config.toml"]
+    ///   "id": 104,
+    ///   "method": "unloadPlugin",
+    ///   "params": ["hello-geyser"]
     /// }
     /// ```
     ///
     /// # Notes
-    /// - The plugin must support reloading in order for this to succeed.
-    /// - A failed reload will leave the plugin in its previous state.
-    /// - This method is intended for administrators and should be properly secured.
+    /// - Unloading a subgraph stops its indexing, but keeps the entries already indexed.
     ///
     /// # Security
     /// Ensure only trusted clients can invoke this method. Use metadata-based access control to limit exposure.
     #[rpc(meta, name = "unloadPlugin")]
     fn unload_plugin(&self, meta: Self::Metadata, name: String) -> BoxFuture<Result<()>>;
 
-    /// Dynamically loads a new plugin into the runtime from a configuration file.
+    /// Dynamically loads a new plugin into the runtime.
     ///
     /// This administrative endpoint is used to add a new plugin to the system at runtime,
     /// based on the configuration provided. It enables extensibility without restarting
-    /// the validator or RPC server.
+    /// the surfnet.
     ///
     /// ## Parameters
-    /// - `config_file`: Path to the plugin's configuration file, which defines its behavior and settings.
+    /// - `config_file`: Either the JSON configuration of a subgraph, or the path to the manifest
+    ///   of a Geyser plugin, a json5 file holding the `name` of the plugin and the `libpath`
+    ///   of its dynamic library.
+    /// - `plugin_kind` (optional): `"subgraph"` (default) or `"geyser"`, telling how `config_file`
+    ///   is read.
     ///
     /// ## Returns
-    /// - [`BoxFuture<Result<String>>`] — A future resolving to the name or identifier of the loaded plugin,
-    ///   or an error if the plugin could not be loaded.
+    /// - [`BoxFuture<Result<String>>`] — A future resolving to the endpoint of the subgraph, or
+    ///   to the name of the Geyser plugin, or an error if the plugin could not be loaded.
     ///
     /// ## Example Request (JSON-RPC)
     /// ```json
@@ -110,27 +152,32 @@ pub trait AdminRpc {
     ///   "jsonrpc": "2.0",
     ///   "id": 102,
     ///   "method": "loadPlugin",
-    ///   "params": ["/etc/plugins/my_plugin_config.toml"]
+    ///   "params": ["/etc/plugins/hello-geyser.json", "geyser"]
     /// }
     /// ```
     ///
     /// # Notes
-    /// - The plugin system must be initialized and support runtime loading.
-    /// - The config file should be well-formed and point to a valid plugin implementation.
-    /// - Duplicate plugin names may lead to conflicts or errors.
+    /// - Geyser plugins require surfpool to be built with the `geyser-plugin` feature.
+    /// - Loading a Geyser plugin with the name of an already loaded plugin fails.
     ///
     /// # Security
     /// This method should be restricted to administrators only. Validate inputs and use access control.
     #[rpc(meta, name = "loadPlugin")]
-    fn load_plugin(&self, meta: Self::Metadata, config_file: String) -> BoxFuture<Result<String>>;
+    fn load_plugin(
+        &self,
+        meta: Self::Metadata,
+        config_file: String,
+        plugin_kind: Option<PluginKind>,
+    ) -> BoxFuture<Result<String>>;
 
-    /// Returns a list of all currently loaded plugin names.
+    /// Returns a list of all currently loaded plugins.
     ///
     /// This administrative RPC method is used to inspect which plugins have been successfully
     /// loaded into the runtime. It can be useful for debugging or operational monitoring.
     ///
     /// ## Returns
-    /// - `Vec<String>` — A list of plugin names currently active in the system.
+    /// - `Vec<String>` — The names of the loaded Geyser plugins, followed by the uuids of the
+    ///   loaded subgraphs.
     ///
     /// ## Example Request (JSON-RPC)
     /// ```json
@@ -146,7 +193,7 @@ pub trait AdminRpc {
     /// ```json
     /// {
     ///   "jsonrpc": "2.0",
-    ///   "result": ["tx_filter", "custom_logger"],
+    ///   "result": ["hello-geyser", "3f1c4b0e-0a4e-4c3e-9a52-6c1f0e8d2b11"],
     ///   "id": 103
     /// }
     /// ```
@@ -749,6 +796,17 @@ pub trait AdminRpc {
     ) -> Result<()>;
 }
 
+const PLUGIN_MANAGER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Waits for the reply of the plugin manager to a command.
+fn plugin_manager_response<T>(rx: Receiver<std::result::Result<T, String>>) -> Result<T> {
+    match rx.recv_timeout(PLUGIN_MANAGER_TIMEOUT) {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(e)) => Err(SurfpoolError::plugin_manager(e).into()),
+        Err(_) => Err(SurfpoolError::plugin_manager_unavailable().into()),
+    }
+}
+
 pub struct SurfpoolAdminRpc;
 impl AdminRpc for SurfpoolAdminRpc {
     type Metadata = Option<RunloopContext>;
@@ -770,18 +828,61 @@ impl AdminRpc for SurfpoolAdminRpc {
 
     fn reload_plugin(
         &self,
-        _meta: Self::Metadata,
-        _name: String,
-        _config_file: String,
+        meta: Self::Metadata,
+        name: String,
+        config_file: String,
     ) -> BoxFuture<Result<()>> {
-        not_implemented_err_async("reload_plugin")
+        let Some(ctx) = meta else {
+            return SurfpoolError::missing_context().into();
+        };
+        let (tx, rx) = crossbeam_channel::bounded(1);
+        let _ = ctx
+            .plugin_manager_commands_tx
+            .send(PluginManagerCommand::ReloadPlugin(
+                name,
+                PathBuf::from(config_file),
+                tx,
+            ));
+
+        let result = plugin_manager_response(rx);
+        Box::pin(async move { result })
     }
 
-    fn unload_plugin(&self, _meta: Self::Metadata, _name: String) -> BoxFuture<Result<()>> {
-        not_implemented_err_async("unload_plugin")
+    fn unload_plugin(&self, meta: Self::Metadata, name: String) -> BoxFuture<Result<()>> {
+        let Some(ctx) = meta else {
+            return SurfpoolError::missing_context().into();
+        };
+        let (tx, rx) = crossbeam_channel::bounded(1);
+        let _ = ctx
+            .plugin_manager_commands_tx
+            .send(PluginManagerCommand::UnloadPlugin(name, tx));
+
+        let result = plugin_manager_response(rx);
+        Box::pin(async move { result })
     }
 
-    fn load_plugin(&self, meta: Self::Metadata, config_file: String) -> BoxFuture<Result<String>> {
+    fn load_plugin(
+        &self,
+        meta: Self::Metadata,
+        config_file: String,
+        plugin_kind: Option<PluginKind>,
+    ) -> BoxFuture<Result<String>> {
+        if plugin_kind.unwrap_or_default() == PluginKind::Geyser {
+            let Some(ctx) = meta else {
+                return SurfpoolError::missing_context().into();
+            };
+            let (tx, rx) = crossbeam_channel::bounded(1);
+            let _ = ctx
+                .plugin_manager_commands_tx
+                .send(PluginManagerCommand::LoadGeyserPlugin(
+                    PathBuf::from(config_file),
+                    tx,
+                ));
+
+            let result = plugin_manager_response(rx);
+            return Box::pin(async move { result });
+        }
+
         let config = match serde_json::from_str::<PluginConfig>(&config_file)
             .map_err(|e| format!("failed to deserialize plugin config: {e}"))
         {
@@ -795,14 +896,25 @@ impl AdminRpc for SurfpoolAdminRpc {
             .plugin_manager_commands_tx
             .send(PluginManagerCommand::LoadConfig(uuid, config, tx));
 
-        let Ok(endpoint_url) = rx.recv_timeout(Duration::from_secs(10)) else {
+        let Ok(endpoint_url) = rx.recv_timeout(PLUGIN_MANAGER_TIMEOUT) else {
             return Box::pin(async move { Err(jsonrpc_core::Error::internal_error()) });
         };
         Box::pin(async move { Ok(endpoint_url) })
     }
 
-    fn list_plugins(&self, _meta: Self::Metadata) -> BoxFuture<Result<Vec<String>>> {
-        not_implemented_err_async("list_plugins")
+    fn list_plugins(&self, meta: Self::Metadata) -> BoxFuture<Result<Vec<String>>> {
+        let Some(ctx) = meta else {
+            return SurfpoolError::missing_context().into();
+        };
+        let (tx, rx) = crossbeam_channel::bounded(1);
+        let _ = ctx
+            .plugin_manager_commands_tx
+            .send(PluginManagerCommand::ListPlugins(tx));
+
+        let Ok(plugins) = rx.recv_timeout(PLUGIN_MANAGER_TIMEOUT) else {
+            return SurfpoolError::plugin_manager_unavailable().into();
+        };
+        Box::pin(async move { Ok(plugins) })
     }
 
     fn rpc_addr(&self, _meta: Self::Metadata) -> Result<Option<SocketAddr>> {
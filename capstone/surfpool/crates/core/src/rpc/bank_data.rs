@@ -475,7 +475,12 @@ impl BankData for SurfpoolBankDataRpc {
             }
         }
 
-        Ok(SURFPOOL_IDENTITY_PUBKEY.to_string())
+        let leader = svm_locker.with_svm_reader(|svm_reader| {
+            svm_reader
+                .slot_leader(committed_slot)
+                .unwrap_or(SURFPOOL_IDENTITY_PUBKEY)
+        });
+        Ok(leader.to_string())
     }
 
     fn get_slot_leaders(
@@ -510,7 +515,12 @@ impl BankData for SurfpoolBankDataRpc {
             });
         }
 
-        Ok(vec![])
+        Ok(svm_locker.with_svm_reader(|svm_reader| {
+            (start_slot..start_slot + limit)
+                .filter_map(|slot| svm_reader.slot_leader(slot))
+                .map(|leader| leader.to_string())
+                .collect()
+        }))
     }
 
     fn get_block_production(
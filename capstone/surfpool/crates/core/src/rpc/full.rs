@@ -1,4 +1,4 @@
-use std::str::FromStr;
+use std::{collections::HashMap, str::FromStr};
 
 use itertools::Itertools;
 use jsonrpc_core::{BoxFuture, Error, Result};
@@ -24,11 +24,7 @@ use solana_commitment_config::{CommitmentConfig, CommitmentLevel};
 use solana_message::VersionedMessage;
 use solana_pubkey::Pubkey;
 use solana_rpc_client_api::response::Response as RpcResponse;
-use solana_sdk::{
-    compute_budget::{self, ComputeBudgetInstruction},
-    instruction::CompiledInstruction,
-    system_program,
-};
+use solana_sdk::system_program;
 use solana_signature::Signature;
 use solana_transaction::versioned::VersionedTransaction;
 use solana_transaction_error::TransactionError;
@@ -36,7 +32,7 @@ use solana_transaction_status::{
     EncodedConfirmedTransactionWithStatusMeta, TransactionBinaryEncoding, TransactionStatus,
     UiConfirmedBlock, UiTransactionEncoding,
 };
-use surfpool_types::{SimnetCommand, TransactionStatusEvent};
+use surfpool_types::{AnchorEvent, SimnetCommand, TransactionStatusEvent};
 
 use super::{
     RunloopContext, State, SurfnetRpcContext,
@@ -45,12 +41,27 @@ use super::{
 use crate::{
     error::{SurfpoolError, SurfpoolResult},
     rpc::utils::{adjust_default_transaction_config, get_default_transaction_config},
-    surfnet::{FINALIZATION_SLOT_THRESHOLD, GetTransactionResult, locker::SvmAccessContext},
+    surfnet::{
+        FINALIZATION_SLOT_THRESHOLD, GetTransactionResult,
+        fees::{ComputeBudgetRequest, FeeDetails, LAMPORTS_PER_SIGNATURE},
+        locker::SvmAccessContext,
+        staking::STAKE_MINIMUM_DELEGATION,
+    },
     types::{SurfnetTransactionStatus, surfpool_tx_metadata_to_litesvm_tx_metadata},
 };
 
 const MAX_PRIORITIZATION_FEE_BLOCKS_CACHE: usize = 150;
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SurfpoolEncodedTransaction {
+    #[serde(flatten)]
+    pub transaction: EncodedConfirmedTransactionWithStatusMeta,
+    /// The Anchor events emitted by the transaction, decoded with the registered IDLs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<AnchorEvent>,
+}
+
 #[rpc]
 pub trait Full {
     type Metadata;
@@ -902,7 +913,14 @@ pub trait Full {
     ///         "status": { "Ok": null },
     ///         ...
     ///       }
-    ///     }
+    ///     },
+    ///     "events": [
+    ///       {
+    ///         "programId": "2e6tZJbYevCm3jhgjbSFbmVbuDfErmMz32sMGSzZHgiN",
+    ///         "name": "SplitTransferEvent",
+    ///         "data": { "recipient_1_amount": 10, ... }
+    ///       }
+    ///     ]
     ///   }
     /// }
     /// ```
@@ -915,6 +933,8 @@ pub trait Full {
     /// - The `encoding` field supports formats like `base64`, `base58`, `json`, and `jsonParsed`.
     /// - If `maxSupportedTransactionVersion` is specified, transactions using a newer version will not be returned.
     /// - Depending on the commitment level, this method may or may not return the latest transactions.
+    /// - The Anchor events emitted by transactions processed by the surfnet are decoded into `events`,
+    ///   for programs with an IDL registered through `surfnet_registerIdl`.
     ///
     /// # See Also
     /// - `getSignatureStatuses`, `getConfirmedTransaction`, `getBlock`
@@ -924,7 +944,7 @@ pub trait Full {
         meta: Self::Metadata,
         signature_str: String,
         config: Option<RpcEncodingConfigWrapper<RpcTransactionConfig>>,
-    ) -> BoxFuture<Result<Option<EncodedConfirmedTransactionWithStatusMeta>>>;
+    ) -> BoxFuture<Result<Option<SurfpoolEncodedTransaction>>>;
 
     /// Returns confirmed transaction signatures for transactions involving an address.
     ///
@@ -1185,7 +1205,8 @@ pub trait Full {
     ///
     /// ## Returns
     /// A `RpcResponse` wrapping an `Option<u64>`:
-    /// - `Some(fee)` if the fee could be calculated for the given message.
+    /// - `Some(fee)` if the fee could be calculated for the given message: the base fee of each signature,
+    ///   plus the priority fee requested with `SetComputeUnitPrice` and `SetComputeUnitLimit`.
     /// - `None` if the fee could not be determined (e.g., due to invalid inputs or expired blockhash).
     ///
     /// ## Example Request
@@ -1291,16 +1312,17 @@ pub trait Full {
     /// Returns recent prioritization fees for one or more accounts.
     ///
     /// This method is useful for estimating the prioritization fee required
-    /// for a transaction to be included quickly in a block. It returns the
-    /// most recent prioritization fee paid by each account provided.
+    /// for a transaction to be included quickly in a block. It returns one entry
+    /// for each of the recent slots, with the lowest compute unit price paid in that slot.
     ///
     /// ## Parameters
     /// - `pubkey_strs` *(optional)*: A list of base-58 encoded account public keys (as strings).
-    ///   If omitted, the node may return a default or empty set.
+    ///   If provided, the fee of each slot is raised to the lowest price paid by the transactions
+    ///   writing to each of these accounts.
     ///
     /// ## Returns
     /// A list of `RpcPrioritizationFee` entries, each containing the slot and the fee paid
-    /// to prioritize transactions.
+    /// to prioritize transactions, in micro-lamports per compute unit.
     ///
     /// ## Example Request
     /// ```json
@@ -1381,18 +1403,17 @@ impl Full for SurfpoolFullRpc {
                 .map(|addr| verify_pubkey(addr))
                 .collect::<std::result::Result<Vec<Pubkey>, SurfpoolError>>()?;
 
-            meta.with_svm_reader(|svm_reader| {
+            // Rewards are paid at the start of the following epoch, so default to the previous epoch
+            let epoch = config
+                .as_ref()
+                .and_then(|config| config.epoch)
+                .unwrap_or(current_epoch.saturating_sub(1));
+
+            meta.with_svm_reader(move |svm_reader| {
+                let rewards = svm_reader.inflation_rewards.get(&epoch);
                 pubkeys
                     .iter()
-                    .map(|_| {
-                        Some(RpcInflationReward {
-                            amount: 0,
-                            commission: None,
-                            effective_slot: svm_reader.get_latest_absolute_slot(),
-                            epoch: svm_reader.latest_epoch_info().epoch,
-                            post_balance: 0,
-                        })
-                    })
+                    .map(|pubkey| rewards.and_then(|rewards| rewards.get(pubkey).cloned()))
                     .collect()
             })
             .map_err(Into::into)
@@ -1518,6 +1539,7 @@ impl Full for SurfpoolFullRpc {
             .into());
         };
 
+        let message = unsanitized_tx.message.clone();
         let (status_update_tx, status_update_rx) = crossbeam_channel::bounded(1);
         ctx.simnet_commands_tx
             .send(SimnetCommand::TransactionReceived(
@@ -1532,24 +1554,30 @@ impl Full for SurfpoolFullRpc {
 
         match status_update_rx.recv() {
             Ok(TransactionStatusEvent::SimulationFailure((error, metadata))) => {
+                let program_error = ctx.svm_locker.with_svm_reader(|svm_reader| {
+                    svm_reader.resolve_program_error(&message, &error, &metadata.logs)
+                });
+                let mut data = serde_json::to_value(get_simulate_transaction_result(
+                    surfpool_tx_metadata_to_litesvm_tx_metadata(&metadata),
+                    None,
+                    Some(error.clone()),
+                    None,
+                    false,
+                ))
+                .map_err(|e| {
+                    Error::invalid_params(format!("Failed to serialize simulation result: {e}"))
+                })?;
+                if let (Some(data), Some(program_error)) = (data.as_object_mut(), &program_error) {
+                    data.insert("programError".into(), serde_json::json!(program_error));
+                }
                 return Err(Error {
-                    data: Some(
-                        serde_json::to_value(get_simulate_transaction_result(
-                            surfpool_tx_metadata_to_litesvm_tx_metadata(&metadata),
-                            None,
-                            Some(error.clone()),
-                            None,
-                            false,
-                        ))
-                        .map_err(|e| {
-                            Error::invalid_params(format!(
-                                "Failed to serialize simulation result: {e}"
-                            ))
-                        })?,
-                    ),
+                    data: Some(data),
                     message: format!(
-                        "Transaction simulation failed: {}{}",
+                        "Transaction simulation failed: {}{}{}",
                         error,
+                        program_error
+                            .map(|program_error| format!(" ({program_error})"))
+                            .unwrap_or_default(),
                         if metadata.logs.is_empty() {
                             String::new()
                         } else {
@@ -1564,11 +1592,19 @@ impl Full for SurfpoolFullRpc {
                 });
             }
             Ok(TransactionStatusEvent::ExecutionFailure((error, metadata))) => {
+                let program_error = ctx.svm_locker.with_svm_reader(|svm_reader| {
+                    svm_reader.resolve_program_error(&message, &error, &metadata.logs)
+                });
                 return Err(Error {
-                    data: None,
+                    data: program_error
+                        .as_ref()
+                        .map(|program_error| serde_json::json!({ "programError": program_error })),
                     message: format!(
-                        "Transaction execution failed: {}{}",
+                        "Transaction execution failed: {}{}{}",
                         error,
+                        program_error
+                            .map(|program_error| format!(" ({program_error})"))
+                            .unwrap_or_default(),
                         if metadata.logs.is_empty() {
                             String::new()
                         } else {
@@ -2028,7 +2064,7 @@ impl Full for SurfpoolFullRpc {
         meta: Self::Metadata,
         signature_str: String,
         config: Option<RpcEncodingConfigWrapper<RpcTransactionConfig>>,
-    ) -> BoxFuture<Result<Option<EncodedConfirmedTransactionWithStatusMeta>>> {
+    ) -> BoxFuture<Result<Option<SurfpoolEncodedTransaction>>> {
         let mut config = config.map(|c| c.convert_to_current()).unwrap_or_default();
         adjust_default_transaction_config(&mut config);
 
@@ -2041,14 +2077,20 @@ impl Full for SurfpoolFullRpc {
                 remote_ctx,
             } = meta.get_rpc_context(())?;
 
-            // TODO: implement new interfaces in LiteSVM to get all the relevant info
-            // needed to return the actual tx, not just some metadata
             match svm_locker
                 .get_transaction(&remote_ctx.map(|(r, _)| r), &signature, config)
                 .await?
             {
                 GetTransactionResult::None(_) => Ok(None),
-                GetTransactionResult::FoundTransaction(_, meta, _) => Ok(Some(meta)),
+                GetTransactionResult::FoundTransaction(_, transaction, _) => {
+                    let events = svm_locker.with_svm_reader(|svm_reader| {
+                        svm_reader.get_transaction_anchor_events(&signature)
+                    });
+                    Ok(Some(SurfpoolEncodedTransaction {
+                        transaction,
+                        events,
+                    }))
+                }
             }
         })
     }
@@ -2148,16 +2190,37 @@ impl Full for SurfpoolFullRpc {
         &self,
         meta: Self::Metadata,
         encoded: String,
-        _config: Option<RpcContextConfig>, // TODO: use config
+        config: Option<RpcContextConfig>,
     ) -> Result<RpcResponse<Option<u64>>> {
+        let config = config.unwrap_or_default();
         let (_, message) =
             decode_and_deserialize::<VersionedMessage>(encoded, TransactionBinaryEncoding::Base64)?;
 
-        meta.with_svm_reader(|svm_reader| RpcResponse {
-            context: RpcResponseContext::new(svm_reader.get_latest_absolute_slot()),
-            value: Some((message.header().num_required_signatures as u64) * 5000),
+        let svm_locker = meta.get_svm_locker()?;
+
+        let committed_latest_slot =
+            svm_locker.get_slot_for_commitment(&config.commitment.unwrap_or_default());
+
+        if let Some(min_context_slot) = config.min_context_slot {
+            if committed_latest_slot < min_context_slot {
+                return Err(RpcCustomError::MinContextSlotNotReached {
+                    context_slot: min_context_slot,
+                }
+                .into());
+            }
+        }
+
+        // like mainnet, no fee is returned for messages whose blockhash has expired
+        let is_blockhash_valid = svm_locker.with_svm_reader(|svm_reader| {
+            svm_reader.check_blockhash_is_recent(message.recent_blockhash())
+        });
+        let fee = is_blockhash_valid
+            .then(|| FeeDetails::from_message(&message, LAMPORTS_PER_SIGNATURE).total_fee());
+
+        Ok(RpcResponse {
+            context: RpcResponseContext::new(committed_latest_slot),
+            value: fee,
         })
-        .map_err(Into::into)
     }
 
     fn get_stake_minimum_delegation(
@@ -2183,7 +2246,7 @@ impl Full for SurfpoolFullRpc {
 
             RpcResponse {
                 context: RpcResponseContext::new(context_slot),
-                value: 0,
+                value: STAKE_MINIMUM_DELEGATION,
             }
         })
         .map_err(Into::into)
@@ -2226,83 +2289,67 @@ impl Full for SurfpoolFullRpc {
                 .take(MAX_PRIORITIZATION_FEE_BLOCKS_CACHE)
                 .collect::<Vec<_>>();
 
-            // Flatten the transactions map to get all transactions in the recent blocks
-            let recent_transactions = recent_headers
-                .into_iter()
-                .flat_map(|(slot, header)| {
-                    header
-                        .signatures
+            let mut prioritization_fees = vec![];
+            for (slot, header) in recent_headers {
+                // Like mainnet, the fee of a slot is the lowest compute unit price paid in the slot,
+                // raised to the lowest price paid to write each of the filtered accounts.
+                let mut block_min_fee: Option<u64> = None;
+                let mut account_min_fees: HashMap<Pubkey, u64> = HashMap::new();
+
+                for signature in header.signatures.iter() {
+                    let Some(SurfnetTransactionStatus::Processed(status_meta)) =
+                        transactions.get(signature)
+                    else {
+                        continue;
+                    };
+                    let tx = &status_meta.transaction;
+                    let compute_unit_price =
+                        ComputeBudgetRequest::from_message(&tx.message).compute_unit_price;
+                    block_min_fee = Some(
+                        block_min_fee.map_or(compute_unit_price, |fee| fee.min(compute_unit_price)),
+                    );
+
+                    let Some(pubkeys_filter) = &pubkeys_filter else {
+                        continue;
+                    };
+
+                    // If the transaction has an ALT, the writable ALT accounts are included in the
+                    // recent prioritization fees, so we get _all_ the writable pubkeys from the message
+                    let loaded_addresses = svm_locker
+                        .get_loaded_addresses(&remote_ctx, &tx.message)
+                        .await?;
+                    let mut writable_keys = tx
+                        .message
+                        .static_account_keys()
                         .iter()
-                        .filter_map(|signature| {
-                            // Check if the signature exists in the transactions map
-                            transactions.get(signature).map(|tx| (slot, tx))
-                        })
-                        .collect::<Vec<_>>()
-                })
-                .collect::<Vec<_>>();
-
-            // Helper function to extract compute unit price from a CompiledInstruction
-            fn get_compute_unit_price(ix: CompiledInstruction, accounts: &[Pubkey]) -> Option<u64> {
-                let program_account = accounts.get(ix.program_id_index as usize)?;
-                if *program_account != compute_budget::id() {
-                    return None;
-                }
-
-                if let Ok(ComputeBudgetInstruction::SetComputeUnitPrice(price)) =
-                    borsh::from_slice::<ComputeBudgetInstruction>(&ix.data)
-                {
-                    return Some(price);
-                }
-
-                None
-            }
+                        .enumerate()
+                        .filter(|(i, _)| tx.message.is_maybe_writable(*i, None))
+                        .map(|(_, pubkey)| *pubkey)
+                        .collect::<Vec<_>>();
+                    if let Some(loaded_addresses) = loaded_addresses {
+                        writable_keys.extend(loaded_addresses.writable);
+                    }
 
-            let mut prioritization_fees = vec![];
-            for (slot, tx) in recent_transactions {
-                match tx {
-                    SurfnetTransactionStatus::Received => {}
-                    SurfnetTransactionStatus::Processed(status_meta) => {
-                        let tx = &status_meta.transaction;
-
-                        // If the transaction has an ALT and includes a compute budget instruction,
-                        // the ALT accounts are included in the recent prioritization fees,
-                        // so we get _all_ the pubkeys from the message
-                        let loaded_addresses = svm_locker
-                            .get_loaded_addresses(&remote_ctx, &tx.message)
-                            .await?;
-                        let account_keys =
-                            svm_locker.get_pubkeys_from_message(&tx.message, loaded_addresses);
-
-                        let instructions = match &tx.message {
-                            VersionedMessage::V0(msg) => &msg.instructions,
-                            VersionedMessage::Legacy(msg) => &msg.instructions,
-                        };
-
-                        // Find all compute unit prices in the transaction's instructions
-                        let compute_unit_prices = instructions
-                            .iter()
-                            .filter_map(|ix| get_compute_unit_price(ix.clone(), &account_keys))
-                            .collect::<Vec<_>>();
-
-                        for compute_unit_price in compute_unit_prices {
-                            if let Some(pubkeys_filter) = &pubkeys_filter {
-                                // If none of the accounts involved in this transaction are in the filter,
-                                // we don't include the prioritization fee, so we continue
-                                if !pubkeys_filter
-                                    .iter()
-                                    .any(|pk| account_keys.iter().any(|a| a == pk))
-                                {
-                                    continue;
-                                }
-                            }
-                            // if there's no filter, or if the filter matches an account in this transaction, we include the fee
-                            prioritization_fees.push(RpcPrioritizationFee {
-                                slot,
-                                prioritization_fee: compute_unit_price,
-                            });
-                        }
+                    for pubkey in writable_keys
+                        .into_iter()
+                        .filter(|pubkey| pubkeys_filter.contains(pubkey))
+                    {
+                        account_min_fees
+                            .entry(pubkey)
+                            .and_modify(|fee| *fee = (*fee).min(compute_unit_price))
+                            .or_insert(compute_unit_price);
                     }
                 }
+
+                let prioritization_fee = account_min_fees
+                    .values()
+                    .fold(block_min_fee.unwrap_or_default(), |fee, account_fee| {
+                        fee.max(*account_fee)
+                    });
+                prioritization_fees.push(RpcPrioritizationFee {
+                    slot,
+                    prioritization_fee,
+                });
             }
             Ok(prioritization_fees)
         })
@@ -2350,11 +2397,13 @@ mod tests {
     use solana_hash::Hash;
     use solana_keypair::Keypair;
     use solana_message::{
-        MessageHeader, legacy::Message as LegacyMessage, v0::Message as V0Message,
+        MessageHeader,
+        legacy::Message as LegacyMessage,
+        v0::{LoadedAddresses, Message as V0Message},
     };
     use solana_native_token::LAMPORTS_PER_SOL;
     use solana_pubkey::Pubkey;
-    use solana_sdk::{instruction::Instruction, system_instruction};
+    use solana_sdk::{compute_budget, instruction::Instruction, system_instruction};
     use solana_signer::Signer;
     use solana_system_interface::program as system_program;
     use solana_transaction::{
@@ -2363,8 +2412,9 @@ mod tests {
     };
     use solana_transaction_error::TransactionError;
     use solana_transaction_status::{
-        EncodedTransaction, EncodedTransactionWithStatusMeta, UiCompiledInstruction, UiMessage,
-        UiRawMessage, UiTransaction,
+        EncodedTransaction, EncodedTransactionWithStatusMeta, UiCompiledInstruction, UiInstruction,
+        UiLoadedAddresses, UiMessage, UiParsedInstruction, UiRawMessage, UiTransaction,
+        option_serializer::OptionSerializer,
     };
     use surfpool_types::{SimnetCommand, TransactionConfirmationStatus};
     use test_case::test_case;
@@ -3026,7 +3076,8 @@ mod tests {
             )
             .await
             .unwrap()
-            .unwrap();
+            .unwrap()
+            .transaction;
 
         let instructions = match tx.message.clone() {
             VersionedMessage::Legacy(message) => message
@@ -3075,6 +3126,103 @@ mod tests {
         );
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_get_transaction_encodings() {
+        let payer = Keypair::new();
+        let pk = Pubkey::new_unique();
+        let lamports = LAMPORTS_PER_SOL;
+        let mut setup = TestSetup::new(SurfpoolFullRpc);
+        let recent_blockhash = setup
+            .context
+            .svm_locker
+            .with_svm_reader(|svm_reader| svm_reader.latest_blockhash());
+
+        let _ = setup
+            .rpc
+            .request_airdrop(
+                Some(setup.context.clone()),
+                payer.pubkey().to_string(),
+                2 * lamports,
+                None,
+            )
+            .unwrap();
+
+        let tx = build_v0_transaction(
+            &payer.pubkey(),
+            &[&payer.insecure_clone()],
+            &[system_instruction::transfer(&payer.pubkey(), &pk, lamports)],
+            &recent_blockhash,
+        );
+        setup.process_txs(vec![tx.clone()]).await;
+
+        let config = |encoding| {
+            Some(RpcEncodingConfigWrapper::Current(Some(
+                RpcTransactionConfig {
+                    encoding: Some(encoding),
+                    ..get_default_transaction_config()
+                },
+            )))
+        };
+
+        // binary encodings round trip to the submitted transaction
+        let res = setup
+            .rpc
+            .get_transaction(
+                Some(setup.context.clone()),
+                tx.signatures[0].to_string(),
+                config(UiTransactionEncoding::Base64),
+            )
+            .await
+            .unwrap()
+            .unwrap()
+            .transaction;
+        let EncodedTransaction::Binary(data, TransactionBinaryEncoding::Base64) =
+            &res.transaction.transaction
+        else {
+            panic!("expected a base64 encoded transaction");
+        };
+        let decoded: VersionedTransaction =
+            bincode::deserialize(&BASE64_STANDARD.decode(data).unwrap()).unwrap();
+        assert_eq!(decoded, tx);
+        let meta = res.transaction.meta.unwrap();
+        assert_eq!(
+            meta.loaded_addresses,
+            OptionSerializer::Some(UiLoadedAddresses::from(&LoadedAddresses::default()))
+        );
+        assert!(matches!(meta.log_messages, OptionSerializer::Some(logs) if !logs.is_empty()));
+        assert_eq!(meta.return_data, OptionSerializer::Skip);
+
+        // jsonParsed decodes the message and its instructions
+        let res = setup
+            .rpc
+            .get_transaction(
+                Some(setup.context.clone()),
+                tx.signatures[0].to_string(),
+                config(UiTransactionEncoding::JsonParsed),
+            )
+            .await
+            .unwrap()
+            .unwrap()
+            .transaction;
+        let EncodedTransaction::Json(UiTransaction {
+            message: UiMessage::Parsed(message),
+            signatures,
+        }) = &res.transaction.transaction
+        else {
+            panic!("expected a parsed transaction");
+        };
+        assert_eq!(signatures, &vec![tx.signatures[0].to_string()]);
+        assert_eq!(message.account_keys.len(), 3);
+        assert!(matches!(
+            &message.instructions[0],
+            UiInstruction::Parsed(UiParsedInstruction::Parsed(ix)) if ix.program == "system"
+        ));
+        let meta = res.transaction.meta.unwrap();
+        assert_eq!(meta.loaded_addresses, OptionSerializer::Skip);
+        assert_eq!(meta.pre_balances.len(), 3);
+        assert_eq!(res.transaction.version, Some(TransactionVersion::Number(0)));
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     #[allow(deprecated)]
     async fn test_get_first_available_block() {
@@ -3193,7 +3341,7 @@ mod tests {
         }
 
         // send two transactions that include a compute budget instruction
-        {
+        let signature = {
             let tx_1 = build_legacy_transaction(
                 &payer_1.pubkey(),
                 &[&payer_1.insecure_clone()],
@@ -3221,6 +3369,7 @@ mod tests {
                 &recent_blockhash,
             );
 
+            let signature = tx_1.signatures[0];
             send_and_await_transaction(tx_1, setup.clone(), mempool_rx.clone())
                 .await
                 .join()
@@ -3230,10 +3379,36 @@ mod tests {
                 .join()
                 .unwrap();
             setup.context.svm_locker.confirm_current_block().unwrap();
-        }
+            signature
+        };
+        let tx_slot = setup.context.svm_locker.with_svm_reader(|svm_reader| {
+            match svm_reader.transactions.get(&signature) {
+                Some(SurfnetTransactionStatus::Processed(status_meta)) => status_meta.slot,
+                _ => panic!("expected a processed transaction"),
+            }
+        });
+        let fee_for_slot = |fees: &[RpcPrioritizationFee], slot: Slot| {
+            fees.iter()
+                .find(|fee| fee.slot == slot)
+                .map(|fee| fee.prioritization_fee)
+                .expect("slot should be reported")
+        };
+
+        // without an account filter, the fee of a slot is the lowest fee paid in the slot
+        let res = setup
+            .rpc
+            .get_recent_prioritization_fees(Some(setup.context.clone()), None)
+            .await
+            .unwrap();
+        assert_eq!(fee_for_slot(&res, tx_slot), 1000);
+        // slots without prioritized transactions are reported with a zero fee
+        assert!(
+            res.iter()
+                .filter(|fee| fee.slot != tx_slot)
+                .all(|fee| fee.prioritization_fee == 0)
+        );
 
-        // sending the get_recent_prioritization_fees request with an account
-        // should filter the results to only include fees for that account
+        // filtering on an account raises the fee of a slot to the lowest fee paid to write that account
         let res = setup
             .rpc
             .get_recent_prioritization_fees(
@@ -3242,22 +3417,32 @@ mod tests {
             )
             .await
             .unwrap();
-        assert_eq!(res.len(), 1);
-        assert_eq!(res[0].prioritization_fee, 1000);
+        assert_eq!(fee_for_slot(&res, tx_slot), 1000);
 
-        // sending the get_recent_prioritization_fees request without an account
-        // should return all prioritization fees
         let res = setup
             .rpc
-            .get_recent_prioritization_fees(Some(setup.context.clone()), None)
+            .get_recent_prioritization_fees(
+                Some(setup.context.clone()),
+                Some(vec![payer_2.pubkey().to_string()]),
+            )
+            .await
+            .unwrap();
+        assert_eq!(fee_for_slot(&res, tx_slot), 1002);
+
+        let res = setup
+            .rpc
+            .get_recent_prioritization_fees(
+                Some(setup.context.clone()),
+                Some(vec![
+                    payer_1.pubkey().to_string(),
+                    payer_2.pubkey().to_string(),
+                ]),
+            )
             .await
             .unwrap();
-        assert_eq!(res.len(), 2);
-        assert_eq!(res[0].prioritization_fee, 1000);
-        assert_eq!(res[1].prioritization_fee, 1002);
+        assert_eq!(fee_for_slot(&res, tx_slot), 1002);
 
-        // sending the get_recent_prioritization_fees request with some random account
-        // to filter should return no results
+        // filtering on an account that wasn't written falls back to the lowest fee of the slot
         let res = setup
             .rpc
             .get_recent_prioritization_fees(
@@ -3266,10 +3451,89 @@ mod tests {
             )
             .await
             .unwrap();
-        assert!(
-            res.is_empty(),
-            "Expected no prioritization fees for random account"
+        assert_eq!(fee_for_slot(&res, tx_slot), 1000);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_get_fee_for_message_and_fee_charged() {
+        let (mempool_tx, mempool_rx) = crossbeam_channel::unbounded();
+        let setup = TestSetup::new_with_mempool(SurfpoolFullRpc, mempool_tx);
+
+        let recent_blockhash = setup
+            .context
+            .svm_locker
+            .with_svm_reader(|svm_reader| svm_reader.latest_blockhash());
+
+        let payer = Keypair::new();
+        let receiver_pubkey = Pubkey::new_unique();
+        let _ = setup
+            .rpc
+            .request_airdrop(
+                Some(setup.context.clone()),
+                payer.pubkey().to_string(),
+                2 * LAMPORTS_PER_SOL,
+                None,
+            )
+            .unwrap();
+        setup.context.svm_locker.confirm_current_block().unwrap();
+
+        let tx = build_legacy_transaction(
+            &payer.pubkey(),
+            &[&payer.insecure_clone()],
+            &[
+                compute_budget::ComputeBudgetInstruction::set_compute_unit_limit(100_000),
+                compute_budget::ComputeBudgetInstruction::set_compute_unit_price(250_000),
+                system_instruction::transfer(&payer.pubkey(), &receiver_pubkey, LAMPORTS_PER_SOL),
+            ],
+            &recent_blockhash,
+        );
+        // 5000 lamports for the signature, and 250_000 micro-lamports for each of the 100_000 CUs
+        let expected_fee = 5_000 + 25_000;
+
+        let res = setup
+            .rpc
+            .get_fee_for_message(
+                Some(setup.context.clone()),
+                BASE64_STANDARD.encode(tx.message.serialize()),
+                None,
+            )
+            .unwrap();
+        assert_eq!(res.value, Some(expected_fee));
+
+        let balance_before = setup
+            .context
+            .svm_locker
+            .get_account_local(&payer.pubkey())
+            .inner
+            .map_account()
+            .unwrap()
+            .lamports;
+        let signature = tx.signatures[0];
+        send_and_await_transaction(tx, setup.clone(), mempool_rx)
+            .await
+            .join()
+            .unwrap();
+        let balance_after = setup
+            .context
+            .svm_locker
+            .get_account_local(&payer.pubkey())
+            .inner
+            .map_account()
+            .unwrap()
+            .lamports;
+
+        // the fee payer is charged the base fee and the priority fee
+        assert_eq!(
+            balance_before - balance_after,
+            LAMPORTS_PER_SOL + expected_fee
         );
+        let fee = setup.context.svm_locker.with_svm_reader(|svm_reader| {
+            match svm_reader.transactions.get(&signature) {
+                Some(SurfnetTransactionStatus::Processed(status_meta)) => status_meta.meta.fee,
+                _ => panic!("expected a processed transaction"),
+            }
+        });
+        assert_eq!(fee, expected_fee);
     }
 
     #[tokio::test(flavor = "multi_thread")]
@@ -4018,7 +4282,7 @@ mod tests {
 
         assert_eq!(result, expected_slot);
         assert_eq!(stake_min_delegation.context.slot, expected_slot);
-        assert_eq!(stake_min_delegation.value, 0); // minimum delegation
+        assert_eq!(stake_min_delegation.value, LAMPORTS_PER_SOL); // minimum delegation
     }
 
     #[test]
@@ -4068,7 +4332,7 @@ mod tests {
 
         assert_eq!(result, expected_slot);
         assert_eq!(stake_min_delegation.context.slot, expected_slot);
-        assert_eq!(stake_min_delegation.value, 0); // minimum delegation
+        assert_eq!(stake_min_delegation.value, LAMPORTS_PER_SOL); // minimum delegation
     }
 
     #[test]
@@ -4095,7 +4359,7 @@ mod tests {
         });
 
         assert_eq!(result.context.slot, expected_slot);
-        assert_eq!(result.value, 0);
+        assert_eq!(result.value, LAMPORTS_PER_SOL);
     }
 
     #[tokio::test(flavor = "multi_thread")]
@@ -4379,37 +4643,48 @@ mod tests {
     async fn test_get_inflation_reward() {
         let setup = TestSetup::new(SurfpoolFullRpc);
 
-        let (epoch, effective_slot) =
-            setup
-                .context
-                .clone()
-                .svm_locker
-                .with_svm_reader(|svm_reader| {
-                    (
-                        svm_reader.latest_epoch_info().epoch,
-                        svm_reader.get_latest_absolute_slot(),
-                    )
-                });
+        let validator = setup.context.svm_locker.with_svm_writer(|svm_writer| {
+            svm_writer.add_synthetic_validators(1).unwrap();
+            let slots_in_epoch = svm_writer.latest_epoch_info.slots_in_epoch;
+            svm_writer.latest_epoch_info.epoch = 1;
+            svm_writer.latest_epoch_info.slot_index = 0;
+            svm_writer.latest_epoch_info.absolute_slot = slots_in_epoch;
+            svm_writer.process_epoch_boundaries(0, 1).unwrap();
+            svm_writer.validators[0]
+        });
 
         let result = setup
             .rpc
             .get_inflation_reward(
-                Some(setup.context),
-                vec![Pubkey::new_unique().to_string()],
+                Some(setup.context.clone()),
+                vec![
+                    validator.stake_account.to_string(),
+                    Pubkey::new_unique().to_string(),
+                ],
                 None,
             )
             .await
             .unwrap();
 
-        assert_eq!(
-            result[0],
-            Some(RpcInflationReward {
-                epoch,
-                effective_slot,
-                amount: 0,
-                post_balance: 0,
-                commission: None
-            })
-        )
+        let reward = result[0].clone().unwrap();
+        assert_eq!(reward.epoch, 0);
+        assert!(reward.amount > 0);
+        assert_eq!(result[1], None);
+
+        // No rewards were paid for the current epoch yet
+        let result = setup
+            .rpc
+            .get_inflation_reward(
+                Some(setup.context),
+                vec![validator.stake_account.to_string()],
+                Some(RpcEpochConfig {
+                    epoch: Some(1),
+                    commitment: None,
+                    min_context_slot: None,
+                }),
+            )
+            .await
+            .unwrap();
+        assert_eq!(result[0], None);
     }
 }
@@ -0,0 +1,768 @@
+use jsonrpc_core::{BoxFuture, Error, Result};
+use jsonrpc_derive::rpc;
+use solana_account_decoder::UiAccount;
+use solana_client::{
+    rpc_config::{RpcAccountInfoConfig, RpcSimulateTransactionAccountsConfig},
+    rpc_custom_error::RpcCustomError,
+    rpc_response::RpcResponseContext,
+};
+use solana_clock::Slot;
+use solana_commitment_config::CommitmentConfig;
+use solana_pubkey::Pubkey;
+use solana_rpc_client_api::response::Response as RpcResponse;
+use solana_sdk::system_program;
+use solana_transaction::versioned::VersionedTransaction;
+use solana_transaction_error::TransactionError;
+use solana_transaction_status::{
+    TransactionConfirmationStatus, UiTransactionEncoding, UiTransactionReturnData,
+};
+use surfpool_types::{BundleStatusEvent, SimnetCommand};
+
+use super::{
+    RunloopContext, State, SurfnetRpcContext,
+    utils::{decode_and_deserialize, verify_pubkeys},
+};
+use crate::{
+    error::SurfpoolError,
+    surfnet::{
+        FINALIZATION_SLOT_THRESHOLD,
+        bundles::{bundle_id, verify_bundle, verify_bundle_size},
+    },
+};
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcSendBundleConfig {
+    pub encoding: Option<UiTransactionEncoding>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcBundleRequest {
+    pub encoded_transactions: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RpcSimulateBundleConfig {
+    /// The accounts to return before the execution of each transaction
+    pub pre_execution_accounts_configs: Vec<Option<RpcSimulateTransactionAccountsConfig>>,
+    /// The accounts to return after the execution of each transaction
+    pub post_execution_accounts_configs: Vec<Option<RpcSimulateTransactionAccountsConfig>>,
+    pub transaction_encoding: Option<UiTransactionEncoding>,
+    pub skip_sig_verify: bool,
+    pub replace_recent_blockhash: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RpcBundleSimulationSummary {
+    Failed {
+        error: TransactionError,
+        tx_signature: Option<String>,
+    },
+    Succeeded,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcSimulateBundleTransactionResult {
+    pub err: Option<TransactionError>,
+    pub logs: Option<Vec<String>>,
+    pub pre_execution_accounts: Option<Vec<Option<UiAccount>>>,
+    pub post_execution_accounts: Option<Vec<Option<UiAccount>>>,
+    pub units_consumed: Option<u64>,
+    pub return_data: Option<UiTransactionReturnData>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcSimulateBundleResult {
+    pub summary: RpcBundleSimulationSummary,
+    pub transaction_results: Vec<RpcSimulateBundleTransactionResult>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcBundleStatus {
+    pub bundle_id: String,
+    pub transactions: Vec<String>,
+    pub slot: Slot,
+    pub confirmation_status: TransactionConfirmationStatus,
+    pub err: std::result::Result<(), TransactionError>,
+}
+
+#[rpc]
+pub trait Jito {
+    type Metadata;
+
+    /// Sends a bundle of transactions, executed atomically and in order within a single slot.
+    ///
+    /// This method mirrors the `sendBundle` method of the Jito block engine. A bundle holds up to
+    /// five transactions, and must transfer a tip to one of the tip accounts returned by `getTipAccounts`.
+    /// If any transaction of the bundle fails, none of them is committed.
+    ///
+    /// ## Parameters
+    /// - `meta`: Metadata passed with the request.
+    /// - `transactions`: The encoded transactions of the bundle.
+    /// - `config`: An optional configuration holding the encoding of the transactions, `base58` by default.
+    ///
+    /// ## Returns
+    /// The id of the bundle, a SHA-256 hash of the signatures of its transactions.
+    ///
+    /// ## Example Request
+    /// ```json
+    /// {
+    ///   "jsonrpc": "2.0",
+    ///   "id": 1,
+    ///   "method": "sendBundle",
+    ///   "params": [["AbKi19sammgVRlYLdvIwJ8DGEBCk5TmwgRmwWw+IAlkmIqvIXBfY/qYxrFwQGJZq4UvBXDAqmCTkDvQueLsJGAUBAAECZAJMcn3luKBgNHP8T1EAa2u8OFBy6KbNp6BGdYy0yvOmOj1tcA7mbOn8u10clAmMQzVUQyVNxuvHz+pIhNdyDQCbLVhPv2kYL0TLQLjTEGRh/2eCrgXnOJOaIKoR0VRzT/wBAgIAAQwCAAAAAQAAAAAAAAA="], { "encoding": "base64" }]
+    /// }
+    /// ```
+    ///
+    /// ## Example Response
+    /// ```json
+    /// {
+    ///   "jsonrpc": "2.0",
+    ///   "result": "2id3YC2jK9G5Wo2phDx4gJVAew8DcY5NAojnVuao8rkxwPYPe8cSwE5GzhEgJA2y8fVjDEo6iR6ykBvDxrTQrtpb",
+    ///   "id": 1
+    /// }
+    /// ```
+    ///
+    /// # Notes
+    /// The request only returns once the bundle was executed: an error describing the failed
+    /// transaction is returned if the bundle was rolled back.
+    #[rpc(meta, name = "sendBundle")]
+    fn send_bundle(
+        &self,
+        meta: Self::Metadata,
+        transactions: Vec<String>,
+        config: Option<RpcSendBundleConfig>,
+    ) -> Result<String>;
+
+    /// Simulates a bundle of transactions, executed in order on a copy of the surfnet state.
+    ///
+    /// This method mirrors the `simulateBundle` method of the Jito block engine. The simulation stops
+    /// at the first failing transaction. Tips are not required.
+    ///
+    /// ## Parameters
+    /// - `meta`: Metadata passed with the request.
+    /// - `bundle`: An object holding the `encodedTransactions` of the bundle.
+    /// - `config`: An optional configuration holding, for each transaction, the accounts to return
+    ///   before and after its execution.
+    ///
+    /// ## Returns
+    /// A `RpcResponse<RpcSimulateBundleResult>` with a summary of the simulation, and the result of
+    /// each executed transaction.
+    ///
+    /// ## Example Request
+    /// ```json
+    /// {
+    ///   "jsonrpc": "2.0",
+    ///   "id": 1,
+    ///   "method": "simulateBundle",
+    ///   "params": [
+    ///     { "encodedTransactions": ["AbKi19sammgVRlYLdvIwJ8DGEBCk5TmwgRmwWw..."] },
+    ///     {
+    ///       "preExecutionAccountsConfigs": [null],
+    ///       "postExecutionAccountsConfigs": [{ "addresses": ["7cVfgArCheMR6Cs4t6vz5rfnqd56vZq4ndaBrY5xkxXy"], "encoding": "base64" }],
+    ///       "transactionEncoding": "base64",
+    ///       "skipSigVerify": true,
+    ///       "replaceRecentBlockhash": true
+    ///     }
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// ## Example Response
+    /// ```json
+    /// {
+    ///   "jsonrpc": "2.0",
+    ///   "result": {
+    ///     "context": { "slot": 123456789 },
+    ///     "value": {
+    ///       "summary": "succeeded",
+    ///       "transactionResults": [
+    ///         {
+    ///           "err": null,
+    ///           "logs": ["Program 11111111111111111111111111111111 invoke [1]", "Program 11111111111111111111111111111111 success"],
+    ///           "preExecutionAccounts": null,
+    ///           "postExecutionAccounts": [{ "lamports": 1000000, "data": ["", "base64"], "owner": "11111111111111111111111111111111", "executable": false, "rentEpoch": 0, "space": 0 }],
+    ///           "unitsConsumed": 150,
+    ///           "returnData": null
+    ///         }
+    ///       ]
+    ///     }
+    ///   },
+    ///   "id": 1
+    /// }
+    /// ```
+    #[rpc(meta, name = "simulateBundle")]
+    fn simulate_bundle(
+        &self,
+        meta: Self::Metadata,
+        bundle: RpcBundleRequest,
+        config: Option<RpcSimulateBundleConfig>,
+    ) -> BoxFuture<Result<RpcResponse<RpcSimulateBundleResult>>>;
+
+    /// Returns the statuses of bundles that landed.
+    ///
+    /// This method mirrors the `getBundleStatuses` method of the Jito block engine.
+    ///
+    /// ## Parameters
+    /// - `meta`: Metadata passed with the request.
+    /// - `bundle_ids`: The ids of the bundles, as returned by `sendBundle`.
+    ///
+    /// ## Returns
+    /// A `RpcResponse` with the status of each bundle, or `null` for bundles that didn't land.
+    ///
+    /// ## Example Request
+    /// ```json
+    /// {
+    ///   "jsonrpc": "2.0",
+    ///   "id": 1,
+    ///   "method": "getBundleStatuses",
+    ///   "params": [["892b79ed49138bfb3aa5441f0df6e06ef34f9ee8f3976c15b323605bae0cf51d"]]
+    /// }
+    /// ```
+    ///
+    /// ## Example Response
+    /// ```json
+    /// {
+    ///   "jsonrpc": "2.0",
+    ///   "result": {
+    ///     "context": { "slot": 242806119 },
+    ///     "value": [
+    ///       {
+    ///         "bundleId": "892b79ed49138bfb3aa5441f0df6e06ef34f9ee8f3976c15b323605bae0cf51d",
+    ///         "transactions": ["3bC2M9fiACSjkTXZDgeNAuQ4ScTsdKGwR42ytFdhUvikqTmBheUxfsR1fDVsM5ADCMMspuwGkdm1uKbU246x5aE3"],
+    ///         "slot": 242804011,
+    ///         "confirmationStatus": "finalized",
+    ///         "err": { "Ok": null }
+    ///       }
+    ///     ]
+    ///   },
+    ///   "id": 1
+    /// }
+    /// ```
+    #[rpc(meta, name = "getBundleStatuses")]
+    fn get_bundle_statuses(
+        &self,
+        meta: Self::Metadata,
+        bundle_ids: Vec<String>,
+    ) -> Result<RpcResponse<Vec<Option<RpcBundleStatus>>>>;
+
+    /// Returns the accounts that bundles must transfer a tip to.
+    ///
+    /// This method mirrors the `getTipAccounts` method of the Jito block engine. The tip accounts
+    /// of the Jito block engine on mainnet are used, unless others are configured.
+    ///
+    /// ## Example Request
+    /// ```json
+    /// {
+    ///   "jsonrpc": "2.0",
+    ///   "id": 1,
+    ///   "method": "getTipAccounts",
+    ///   "params": []
+    /// }
+    /// ```
+    ///
+    /// ## Example Response
+    /// ```json
+    /// {
+    ///   "jsonrpc": "2.0",
+    ///   "result": ["96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5", "HFqU5x63VTqvQss8hp11i4wVV8bD44PvwucfZ2bU7gRe"],
+    ///   "id": 1
+    /// }
+    /// ```
+    #[rpc(meta, name = "getTipAccounts")]
+    fn get_tip_accounts(&self, meta: Self::Metadata) -> Result<Vec<String>>;
+}
+
+#[derive(Clone)]
+pub struct SurfpoolJitoRpc;
+impl Jito for SurfpoolJitoRpc {
+    type Metadata = Option<RunloopContext>;
+
+    fn send_bundle(
+        &self,
+        meta: Self::Metadata,
+        transactions: Vec<String>,
+        config: Option<RpcSendBundleConfig>,
+    ) -> Result<String> {
+        let encoding = config
+            .and_then(|config| config.encoding)
+            .unwrap_or(UiTransactionEncoding::Base58);
+        let transactions = decode_bundle(transactions, encoding)?;
+
+        let tip_accounts = meta.with_svm_reader(|svm_reader| svm_reader.tip_accounts.clone())?;
+        verify_bundle(&transactions, &tip_accounts)?;
+
+        let simnet_commands_tx = meta.get_surfnet_command_tx()?;
+        let bundle_id = bundle_id(&transactions);
+        let (status_tx, status_rx) = crossbeam_channel::bounded(1);
+        simnet_commands_tx
+            .send(SimnetCommand::BundleReceived(
+                bundle_id.clone(),
+                transactions,
+                status_tx,
+            ))
+            .map_err(|_| RpcCustomError::NodeUnhealthy {
+                num_slots_behind: None,
+            })?;
+
+        match status_rx.recv() {
+            Ok(BundleStatusEvent::Landed(_)) => Ok(bundle_id),
+            Ok(BundleStatusEvent::Failed {
+                signature,
+                error,
+                logs,
+            }) => Err(Error {
+                code: jsonrpc_core::ErrorCode::ServerError(-32002),
+                message: format!(
+                    "Bundle {bundle_id} was rolled back: transaction {signature} failed: {error}"
+                ),
+                data: Some(serde_json::json!({ "logs": logs })),
+            }),
+            Ok(BundleStatusEvent::Dropped(reason)) => Err(Error {
+                code: jsonrpc_core::ErrorCode::InternalError,
+                message: format!("Bundle {bundle_id} was dropped: {reason}"),
+                data: None,
+            }),
+            Err(_) => Err(RpcCustomError::NodeUnhealthy {
+                num_slots_behind: None,
+            }
+            .into()),
+        }
+    }
+
+    fn simulate_bundle(
+        &self,
+        meta: Self::Metadata,
+        bundle: RpcBundleRequest,
+        config: Option<RpcSimulateBundleConfig>,
+    ) -> BoxFuture<Result<RpcResponse<RpcSimulateBundleResult>>> {
+        let config = config.unwrap_or_default();
+        if !config.skip_sig_verify && config.replace_recent_blockhash {
+            return SurfpoolError::sig_verify_replace_recent_blockhash_collision().into();
+        }
+
+        let encoding = config
+            .transaction_encoding
+            .unwrap_or(UiTransactionEncoding::Base64);
+        let mut transactions = match decode_bundle(bundle.encoded_transactions, encoding) {
+            Ok(transactions) => transactions,
+            Err(e) => return Box::pin(async move { Err(e) }),
+        };
+        if let Err(e) = verify_bundle_size(&transactions) {
+            return e.into();
+        }
+
+        let captured_accounts = match captured_accounts(&config, transactions.len()) {
+            Ok(captured_accounts) => captured_accounts,
+            Err(e) => return Box::pin(async move { Err(e) }),
+        };
+
+        let SurfnetRpcContext {
+            svm_locker,
+            remote_ctx,
+        } = match meta.get_rpc_context(CommitmentConfig::confirmed()) {
+            Ok(res) => res,
+            Err(e) => return e.into(),
+        };
+
+        Box::pin(async move {
+            if config.replace_recent_blockhash {
+                let latest_blockhash =
+                    svm_locker.with_svm_reader(|svm_reader| svm_reader.latest_blockhash());
+                for transaction in transactions.iter_mut() {
+                    transaction.message.set_recent_blockhash(latest_blockhash);
+                }
+            }
+
+            let executions = svm_locker
+                .simulate_bundle(
+                    &remote_ctx,
+                    &transactions,
+                    !config.skip_sig_verify,
+                    &captured_accounts,
+                )
+                .await?;
+
+            let ui_accounts =
+                |pubkeys: &[Pubkey],
+                 accounts: Vec<Option<solana_account::Account>>,
+                 config: Option<&RpcSimulateTransactionAccountsConfig>| {
+                    let config = config?;
+                    let account_config = RpcAccountInfoConfig {
+                        encoding: config.encoding,
+                        ..RpcAccountInfoConfig::default()
+                    };
+                    Some(
+                        pubkeys
+                            .iter()
+                            .zip(accounts)
+                            .map(|(pubkey, account)| {
+                                account.map(|account| {
+                                    svm_locker
+                                        .account_to_rpc_keyed_account(
+                                            pubkey,
+                                            &account,
+                                            &account_config,
+                                            None,
+                                        )
+                                        .account
+                                })
+                            })
+                            .collect(),
+                    )
+                };
+
+            let mut summary = RpcBundleSimulationSummary::Succeeded;
+            let mut transaction_results = vec![];
+            for (i, execution) in executions.into_iter().enumerate() {
+                let (pre_pubkeys, post_pubkeys) = &captured_accounts[i];
+                let (meta, err) = match execution.result {
+                    Ok(meta) => (meta, None),
+                    Err(failed) => {
+                        summary = RpcBundleSimulationSummary::Failed {
+                            error: failed.err.clone(),
+                            tx_signature: Some(execution.signature.to_string()),
+                        };
+                        (failed.meta, Some(failed.err))
+                    }
+                };
+                transaction_results.push(RpcSimulateBundleTransactionResult {
+                    err,
+                    logs: Some(meta.logs),
+                    pre_execution_accounts: ui_accounts(
+                        pre_pubkeys,
+                        execution.pre_execution_accounts,
+                        config
+                            .pre_execution_accounts_configs
+                            .get(i)
+                            .and_then(Option::as_ref),
+                    ),
+                    post_execution_accounts: ui_accounts(
+                        post_pubkeys,
+                        execution.post_execution_accounts,
+                        config
+                            .post_execution_accounts_configs
+                            .get(i)
+                            .and_then(Option::as_ref),
+                    ),
+                    units_consumed: Some(meta.compute_units_consumed),
+                    return_data: if meta.return_data.program_id == system_program::id()
+                        && meta.return_data.data.is_empty()
+                    {
+                        None
+                    } else {
+                        Some(meta.return_data.into())
+                    },
+                });
+            }
+
+            Ok(RpcResponse {
+                context: RpcResponseContext::new(svm_locker.get_latest_absolute_slot()),
+                value: RpcSimulateBundleResult {
+                    summary,
+                    transaction_results,
+                },
+            })
+        })
+    }
+
+    fn get_bundle_statuses(
+        &self,
+        meta: Self::Metadata,
+        bundle_ids: Vec<String>,
+    ) -> Result<RpcResponse<Vec<Option<RpcBundleStatus>>>> {
+        meta.with_svm_reader(move |svm_reader| {
+            let current_slot = svm_reader.get_latest_absolute_slot();
+            let value = bundle_ids
+                .iter()
+                .map(|bundle_id| {
+                    let bundle = svm_reader.bundles.get(bundle_id)?;
+                    let confirmation_status =
+                        if current_slot >= bundle.slot + FINALIZATION_SLOT_THRESHOLD {
+                            TransactionConfirmationStatus::Finalized
+                        } else if current_slot > bundle.slot {
+                            TransactionConfirmationStatus::Confirmed
+                        } else {
+                            TransactionConfirmationStatus::Processed
+                        };
+                    Some(RpcBundleStatus {
+                        bundle_id: bundle_id.clone(),
+                        transactions: bundle
+                            .signatures
+                            .iter()
+                            .map(|signature| signature.to_string())
+                            .collect(),
+                        slot: bundle.slot,
+                        confirmation_status,
+                        err: Ok(()),
+                    })
+                })
+                .collect();
+            RpcResponse {
+                context: RpcResponseContext::new(current_slot),
+                value,
+            }
+        })
+        .map_err(Into::into)
+    }
+
+    fn get_tip_accounts(&self, meta: Self::Metadata) -> Result<Vec<String>> {
+        meta.with_svm_reader(|svm_reader| {
+            svm_reader
+                .tip_accounts
+                .iter()
+                .map(|pubkey| pubkey.to_string())
+                .collect()
+        })
+        .map_err(Into::into)
+    }
+}
+
+fn decode_bundle(
+    transactions: Vec<String>,
+    encoding: UiTransactionEncoding,
+) -> Result<Vec<VersionedTransaction>> {
+    let binary_encoding = encoding.into_binary_encoding().ok_or_else(|| {
+        Error::invalid_params(format!(
+            "unsupported encoding: {encoding}. Supported encodings: base58, base64"
+        ))
+    })?;
+    transactions
+        .into_iter()
+        .map(|data| {
+            decode_and_deserialize::<VersionedTransaction>(data, binary_encoding)
+                .map(|(_, transaction)| transaction)
+        })
+        .collect()
+}
+
+/// Returns, for each transaction, the accounts to capture before and after its execution.
+fn captured_accounts(
+    config: &RpcSimulateBundleConfig,
+    transactions: usize,
+) -> Result<Vec<(Vec<Pubkey>, Vec<Pubkey>)>> {
+    let addresses = |configs: &[Option<RpcSimulateTransactionAccountsConfig>],
+                     i: usize|
+     -> Result<Vec<Pubkey>> {
+        match configs.get(i) {
+            Some(Some(config)) => Ok(verify_pubkeys(&config.addresses)?),
+            _ => Ok(vec![]),
+        }
+    };
+    for (name, configs) in [
+        (
+            "preExecutionAccountsConfigs",
+            &config.pre_execution_accounts_configs,
+        ),
+        (
+            "postExecutionAccountsConfigs",
+            &config.post_execution_accounts_configs,
+        ),
+    ] {
+        if !configs.is_empty() && configs.len() != transactions {
+            return Err(Error::invalid_params(format!(
+                "{name} must be empty or hold one entry per transaction"
+            )));
+        }
+    }
+    (0..transactions)
+        .map(|i| {
+            Ok((
+                addresses(&config.pre_execution_accounts_configs, i)?,
+                addresses(&config.post_execution_accounts_configs, i)?,
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::{Engine, prelude::BASE64_STANDARD};
+    use solana_keypair::Keypair;
+    use solana_message::Message;
+    use solana_native_token::LAMPORTS_PER_SOL;
+    use solana_signer::Signer;
+    use solana_system_interface::instruction::transfer;
+    use solana_transaction::Transaction;
+    use surfpool_types::JITO_TIP_ACCOUNTS;
+
+    use super::*;
+    use crate::{surfnet::bundles::MINIMUM_BUNDLE_TIP_LAMPORTS, tests::helpers::TestSetup};
+
+    fn transfer_transaction(
+        setup: &TestSetup<SurfpoolJitoRpc>,
+        payer: &Keypair,
+        to: &Pubkey,
+        lamports: u64,
+    ) -> VersionedTransaction {
+        let blockhash = setup
+            .context
+            .svm_locker
+            .with_svm_reader(|svm_reader| svm_reader.latest_blockhash());
+        let message = Message::new(
+            &[transfer(&payer.pubkey(), to, lamports)],
+            Some(&payer.pubkey()),
+        );
+        VersionedTransaction::from(Transaction::new(&[payer], message, blockhash))
+    }
+
+    fn encode(transaction: &VersionedTransaction) -> String {
+        BASE64_STANDARD.encode(bincode::serialize(transaction).unwrap())
+    }
+
+    fn balance(setup: &TestSetup<SurfpoolJitoRpc>, pubkey: &Pubkey) -> u64 {
+        setup.context.svm_locker.with_svm_reader(|svm_reader| {
+            svm_reader
+                .inner
+                .get_account(pubkey)
+                .map_or(0, |account| account.lamports)
+        })
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_process_bundle_is_atomic() {
+        let setup = TestSetup::new(SurfpoolJitoRpc);
+        let payer = Keypair::new();
+        let recipient = Pubkey::new_unique();
+        let tip_account = JITO_TIP_ACCOUNTS[0];
+        setup.context.svm_locker.with_svm_writer(|svm_writer| {
+            svm_writer
+                .airdrop(&payer.pubkey(), LAMPORTS_PER_SOL)
+                .unwrap();
+        });
+
+        let payment = transfer_transaction(&setup, &payer, &recipient, LAMPORTS_PER_SOL / 2);
+        let overdraft = transfer_transaction(&setup, &payer, &recipient, LAMPORTS_PER_SOL);
+        let tip = transfer_transaction(&setup, &payer, &tip_account, MINIMUM_BUNDLE_TIP_LAMPORTS);
+
+        // The second transfer can't be covered once the first one executed
+        let failing_bundle = vec![payment.clone(), overdraft.clone(), tip.clone()];
+        let status = setup
+            .context
+            .svm_locker
+            .process_bundle(&None, bundle_id(&failing_bundle), failing_bundle, true)
+            .await
+            .unwrap();
+        let BundleStatusEvent::Failed { signature, .. } = status else {
+            panic!("expected the bundle to fail, got {status:?}");
+        };
+        assert_eq!(signature, overdraft.signatures[0]);
+        assert_eq!(balance(&setup, &recipient), 0);
+        assert_eq!(balance(&setup, &payer.pubkey()), LAMPORTS_PER_SOL);
+
+        let bundle = vec![payment, tip];
+        let id = bundle_id(&bundle);
+        let status = setup
+            .context
+            .svm_locker
+            .process_bundle(&None, id.clone(), bundle, true)
+            .await
+            .unwrap();
+        let BundleStatusEvent::Landed(slot) = status else {
+            panic!("expected the bundle to land, got {status:?}");
+        };
+        assert_eq!(balance(&setup, &recipient), LAMPORTS_PER_SOL / 2);
+        assert_eq!(balance(&setup, &tip_account), MINIMUM_BUNDLE_TIP_LAMPORTS);
+
+        let statuses = setup
+            .rpc
+            .get_bundle_statuses(
+                Some(setup.context.clone()),
+                vec![id.clone(), "unknown".into()],
+            )
+            .unwrap();
+        let bundle_status = statuses.value[0].clone().unwrap();
+        assert_eq!(bundle_status.bundle_id, id);
+        assert_eq!(bundle_status.slot, slot);
+        assert_eq!(bundle_status.transactions.len(), 2);
+        assert_eq!(
+            bundle_status.confirmation_status,
+            TransactionConfirmationStatus::Processed
+        );
+        assert_eq!(statuses.value[1], None);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_simulate_bundle() {
+        let setup = TestSetup::new(SurfpoolJitoRpc);
+        let payer = Keypair::new();
+        let recipient = Pubkey::new_unique();
+        setup.context.svm_locker.with_svm_writer(|svm_writer| {
+            svm_writer
+                .airdrop(&payer.pubkey(), LAMPORTS_PER_SOL)
+                .unwrap();
+        });
+
+        let payment = transfer_transaction(&setup, &payer, &recipient, LAMPORTS_PER_SOL / 2);
+        let overdraft = transfer_transaction(&setup, &payer, &recipient, LAMPORTS_PER_SOL);
+        let accounts_config = RpcSimulateTransactionAccountsConfig {
+            encoding: None,
+            addresses: vec![recipient.to_string()],
+        };
+        let result = setup
+            .rpc
+            .simulate_bundle(
+                Some(setup.context.clone()),
+                RpcBundleRequest {
+                    encoded_transactions: vec![encode(&payment), encode(&overdraft)],
+                },
+                Some(RpcSimulateBundleConfig {
+                    pre_execution_accounts_configs: vec![Some(accounts_config.clone()), None],
+                    post_execution_accounts_configs: vec![Some(accounts_config), None],
+                    ..RpcSimulateBundleConfig::default()
+                }),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            result.value.summary,
+            RpcBundleSimulationSummary::Failed {
+                error: result.value.transaction_results[1].err.clone().unwrap(),
+                tx_signature: Some(overdraft.signatures[0].to_string()),
+            }
+        );
+        let payment_result = &result.value.transaction_results[0];
+        assert_eq!(payment_result.err, None);
+        assert_eq!(payment_result.pre_execution_accounts, Some(vec![None]));
+        let post_execution_accounts = payment_result.post_execution_accounts.clone().unwrap();
+        assert_eq!(
+            post_execution_accounts[0].as_ref().unwrap().lamports,
+            LAMPORTS_PER_SOL / 2
+        );
+
+        // Nothing was committed
+        assert_eq!(balance(&setup, &recipient), 0);
+    }
+
+    #[test]
+    fn test_send_bundle_requires_tip() {
+        let setup = TestSetup::new(SurfpoolJitoRpc);
+        let payer = Keypair::new();
+        let payment = transfer_transaction(&setup, &payer, &Pubkey::new_unique(), 1_000);
+
+        let err = setup
+            .rpc
+            .send_bundle(
+                Some(setup.context.clone()),
+                vec![encode(&payment)],
+                Some(RpcSendBundleConfig {
+                    encoding: Some(UiTransactionEncoding::Base64),
+                }),
+            )
+            .unwrap_err();
+        assert_eq!(err.code, jsonrpc_core::ErrorCode::InvalidParams);
+
+        let tip_accounts = setup
+            .rpc
+            .get_tip_accounts(Some(setup.context.clone()))
+            .unwrap();
+        assert_eq!(tip_accounts.len(), JITO_TIP_ACCOUNTS.len());
+    }
+}
@@ -0,0 +1,304 @@
+use std::{
+    cell::Cell,
+    collections::{BTreeMap, HashSet},
+    fmt::Write,
+    future::Future,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use serde_json::Value;
+use solana_rpc_client::rpc_sender::{RpcSender, RpcTransportStats};
+use solana_rpc_client_api::{client_error::Result as ClientResult, request::RpcRequest};
+
+/// The upper bounds, in seconds, of the buckets of the request duration histogram.
+const LATENCY_BUCKETS: [f64; 10] = [0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 5.0];
+
+/// The label under which calls to methods that don't exist are counted, so that arbitrary method
+/// names can't grow the metrics without bound.
+const UNKNOWN_METHOD: &str = "unknown";
+
+tokio::task_local! {
+    /// Set when the RPC call being served sends a request to the datasource.
+    static DATASOURCE_HIT: Cell<bool>;
+}
+
+#[derive(Debug, Default, Clone, PartialEq)]
+struct MethodMetrics {
+    requests: u64,
+    errors: u64,
+    local_hits: u64,
+    remote_hits: u64,
+    latency_buckets: [u64; LATENCY_BUCKETS.len()],
+    latency_sum: f64,
+}
+
+#[derive(Debug, Default)]
+struct Metrics {
+    registered_methods: HashSet<String>,
+    methods: BTreeMap<String, MethodMetrics>,
+    batches: u64,
+    batched_calls: u64,
+}
+
+/// Per-method metrics of the JSON-RPC calls served over HTTP, shared by every clone.
+#[derive(Debug, Default, Clone)]
+pub struct RpcMetrics {
+    inner: Arc<Mutex<Metrics>>,
+}
+
+impl RpcMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the methods served, the only ones whose calls are recorded under their own name.
+    pub fn register_methods(&self, methods: impl IntoIterator<Item = String>) {
+        let Ok(mut metrics) = self.inner.lock() else {
+            return;
+        };
+        metrics.registered_methods.extend(methods);
+    }
+
+    /// Records a served call, along with whether it needed the datasource.
+    ///
+    /// Calls to methods that were not registered are recorded as unknown.
+    pub fn record_call(
+        &self,
+        method: &str,
+        duration: Duration,
+        is_error: bool,
+        is_remote_hit: bool,
+    ) {
+        let Ok(mut metrics) = self.inner.lock() else {
+            return;
+        };
+        let method = if metrics.registered_methods.contains(method) {
+            method
+        } else {
+            UNKNOWN_METHOD
+        };
+        let method_metrics = metrics.methods.entry(method.to_string()).or_default();
+        method_metrics.requests += 1;
+        if is_error {
+            method_metrics.errors += 1;
+        }
+        if is_remote_hit {
+            method_metrics.remote_hits += 1;
+        } else {
+            method_metrics.local_hits += 1;
+        }
+        let seconds = duration.as_secs_f64();
+        for (count, upper_bound) in method_metrics
+            .latency_buckets
+            .iter_mut()
+            .zip(LATENCY_BUCKETS)
+        {
+            if seconds <= upper_bound {
+                *count += 1;
+            }
+        }
+        method_metrics.latency_sum += seconds;
+    }
+
+    /// Records a call to a method that doesn't exist.
+    pub fn record_unknown_call(&self, duration: Duration) {
+        self.record_call(UNKNOWN_METHOD, duration, true, false);
+    }
+
+    /// Records a batch of `calls` calls; each call is also recorded on its own.
+    pub fn record_batch(&self, calls: usize) {
+        let Ok(mut metrics) = self.inner.lock() else {
+            return;
+        };
+        metrics.batches += 1;
+        metrics.batched_calls += calls as u64;
+    }
+
+    /// Renders the metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let Ok(metrics) = self.inner.lock() else {
+            return String::new();
+        };
+        let mut out = String::new();
+
+        let counters: [(&str, &str, fn(&MethodMetrics) -> u64); 4] = [
+            (
+                "surfpool_rpc_requests_total",
+                "JSON-RPC calls served, per method.",
+                |m| m.requests,
+            ),
+            (
+                "surfpool_rpc_errors_total",
+                "JSON-RPC calls that returned an error, per method.",
+                |m| m.errors,
+            ),
+            (
+                "surfpool_rpc_local_hits_total",
+                "JSON-RPC calls served from the local SVM only, per method.",
+                |m| m.local_hits,
+            ),
+            (
+                "surfpool_rpc_remote_hits_total",
+                "JSON-RPC calls that fell back to the datasource, per method.",
+                |m| m.remote_hits,
+            ),
+        ];
+        for (name, help, value) in counters {
+            let _ = writeln!(out, "# HELP {name} {help}");
+            let _ = writeln!(out, "# TYPE {name} counter");
+            for (method, method_metrics) in metrics.methods.iter() {
+                let _ = writeln!(
+                    out,
+                    "{name}{{method=\"{}\"}} {}",
+                    escape_label(method),
+                    value(method_metrics)
+                );
+            }
+        }
+
+        let name = "surfpool_rpc_request_duration_seconds";
+        let _ = writeln!(
+            out,
+            "# HELP {name} Time spent serving JSON-RPC calls, per method."
+        );
+        let _ = writeln!(out, "# TYPE {name} histogram");
+        for (method, method_metrics) in metrics.methods.iter() {
+            let method = escape_label(method);
+            for (count, upper_bound) in method_metrics.latency_buckets.iter().zip(LATENCY_BUCKETS) {
+                let _ = writeln!(
+                    out,
+                    "{name}_bucket{{method=\"{method}\",le=\"{upper_bound}\"}} {count}"
+                );
+            }
+            let _ = writeln!(
+                out,
+                "{name}_bucket{{method=\"{method}\",le=\"+Inf\"}} {}",
+                method_metrics.requests
+            );
+            let _ = writeln!(
+                out,
+                "{name}_sum{{method=\"{method}\"}} {}",
+                method_metrics.latency_sum
+            );
+            let _ = writeln!(
+                out,
+                "{name}_count{{method=\"{method}\"}} {}",
+                method_metrics.requests
+            );
+        }
+
+        let _ = writeln!(
+            out,
+            "# HELP surfpool_rpc_batches_total JSON-RPC batch requests received."
+        );
+        let _ = writeln!(out, "# TYPE surfpool_rpc_batches_total counter");
+        let _ = writeln!(out, "surfpool_rpc_batches_total {}", metrics.batches);
+        let _ = writeln!(
+            out,
+            "# HELP surfpool_rpc_batched_calls_total JSON-RPC calls received in batch requests."
+        );
+        let _ = writeln!(out, "# TYPE surfpool_rpc_batched_calls_total counter");
+        let _ = writeln!(
+            out,
+            "surfpool_rpc_batched_calls_total {}",
+            metrics.batched_calls
+        );
+        out
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Runs `future`, returning its output along with whether it sent any request to the datasource.
+pub async fn track_datasource_hits<F: Future>(future: F) -> (F::Output, bool) {
+    DATASOURCE_HIT
+        .scope(Cell::new(false), async move {
+            let output = future.await;
+            (output, DATASOURCE_HIT.with(Cell::get))
+        })
+        .await
+}
+
+/// An [RpcSender] flagging the RPC call being served as a datasource hit on every request.
+pub struct DatasourceRpcSender<S> {
+    sender: S,
+}
+
+impl<S> DatasourceRpcSender<S> {
+    pub fn new(sender: S) -> Self {
+        Self { sender }
+    }
+}
+
+#[async_trait::async_trait]
+impl<S: RpcSender + Send + Sync> RpcSender for DatasourceRpcSender<S> {
+    async fn send(&self, request: RpcRequest, params: Value) -> ClientResult<Value> {
+        // Requests sent outside of an RPC call, e.g. by the runloops, are not tracked
+        let _ = DATASOURCE_HIT.try_with(|hit| hit.set(true));
+        self.sender.send(request, params).await
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        self.sender.get_transport_stats()
+    }
+
+    fn url(&self) -> String {
+        self.sender.url()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_track_datasource_hits() {
+        let (output, hit) = track_datasource_hits(async { 1 }).await;
+        assert_eq!(output, 1);
+        assert!(!hit);
+
+        let (_, hit) = track_datasource_hits(async {
+            let _ = DATASOURCE_HIT.try_with(|hit| hit.set(true));
+        })
+        .await;
+        assert!(hit);
+    }
+
+    #[test]
+    fn test_render_metrics() {
+        let metrics = RpcMetrics::new();
+        metrics.register_methods(["getBalance".to_string(), "getAccountInfo".to_string()]);
+        metrics.record_call("getBalance", Duration::from_millis(2), false, false);
+        metrics.record_call("getAccountInfo", Duration::from_millis(80), false, true);
+        metrics.record_call("getAccountInfo", Duration::from_millis(3), true, false);
+        metrics.record_unknown_call(Duration::ZERO);
+        // e.g. a notification to a method that doesn't exist, which gets no response
+        metrics.record_call("madeUpMethod", Duration::ZERO, false, false);
+        metrics.record_batch(2);
+
+        let rendered = metrics.render();
+        for line in [
+            "surfpool_rpc_requests_total{method=\"getAccountInfo\"} 2",
+            "surfpool_rpc_errors_total{method=\"getAccountInfo\"} 1",
+            "surfpool_rpc_local_hits_total{method=\"getAccountInfo\"} 1",
+            "surfpool_rpc_remote_hits_total{method=\"getAccountInfo\"} 1",
+            "surfpool_rpc_remote_hits_total{method=\"getBalance\"} 0",
+            "surfpool_rpc_requests_total{method=\"unknown\"} 2",
+            "surfpool_rpc_errors_total{method=\"unknown\"} 1",
+            "surfpool_rpc_request_duration_seconds_bucket{method=\"getAccountInfo\",le=\"0.005\"} 1",
+            "surfpool_rpc_request_duration_seconds_bucket{method=\"getAccountInfo\",le=\"0.1\"} 2",
+            "surfpool_rpc_request_duration_seconds_count{method=\"getAccountInfo\"} 2",
+            "surfpool_rpc_batches_total 1",
+            "surfpool_rpc_batched_calls_total 2",
+        ] {
+            assert!(rendered.lines().any(|l| l == line), "missing {line}");
+        }
+        assert!(!rendered.contains("madeUpMethod"));
+    }
+}
@@ -757,22 +757,16 @@ impl Minimal for SurfpoolMinimalRpc {
 
     fn get_vote_accounts(
         &self,
-        _meta: Self::Metadata,
+        meta: Self::Metadata,
         config: Option<RpcGetVoteAccountsConfig>,
     ) -> Result<RpcVoteAccountStatus> {
-        // validate inputs if provided
-        if let Some(config) = config {
-            // validate vote_pubkey if provided
-            if let Some(vote_pubkey_str) = config.vote_pubkey {
-                verify_pubkey(&vote_pubkey_str)?;
-            }
-        }
+        let vote_pubkey = match config.and_then(|config| config.vote_pubkey) {
+            Some(vote_pubkey_str) => Some(verify_pubkey(&vote_pubkey_str)?),
+            None => None,
+        };
 
-        // Return empty vote accounts
-        Ok(RpcVoteAccountStatus {
-            current: vec![],
-            delinquent: vec![],
-        })
+        meta.with_svm_reader(|svm_reader| svm_reader.get_vote_accounts(vote_pubkey.as_ref()))
+            .map_err(Into::into)
     }
 
     fn get_leader_schedule(
@@ -802,8 +796,14 @@ impl Minimal for SurfpoolMinimalRpc {
             return Ok(None);
         }
 
-        // return empty leader schedule if epoch found and everything is valid
-        Ok(Some(std::collections::HashMap::new()))
+        let mut leader_schedule = svm_locker.with_svm_reader(|svm_reader| {
+            svm_reader.get_leader_schedule(first_slot_in_epoch, epoch_info.slots_in_epoch)
+        });
+        if let Some(identity) = config.identity {
+            leader_schedule.retain(|leader, _| *leader == identity);
+        }
+
+        Ok(Some(leader_schedule))
     }
 }
 
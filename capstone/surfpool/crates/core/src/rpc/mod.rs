@@ -1,9 +1,10 @@
-use std::{future::Future, sync::Arc};
+use std::{future::Future, sync::Arc, time::Instant};
 
 use blake3::Hash;
 use crossbeam_channel::Sender;
 use jsonrpc_core::{
-    BoxFuture, Error, FutureResponse, Metadata, Middleware, Request, Response,
+    BoxFuture, Call, Error, ErrorCode, Failure, FutureResponse, Metadata, Middleware, Output,
+    Request, Response,
     futures::{FutureExt, future::Either},
     middleware,
 };
@@ -14,6 +15,7 @@ use surfpool_types::{SimnetCommand, types::RpcConfig};
 use crate::{
     PluginManagerCommand,
     error::{SurfpoolError, SurfpoolResult},
+    rpc::metrics::{RpcMetrics, track_datasource_hits},
     surfnet::{
         locker::SurfnetSvmLocker,
         remote::{SomeRemoteCtx, SurfnetRemoteClient},
@@ -26,6 +28,8 @@ pub mod accounts_scan;
 pub mod admin;
 pub mod bank_data;
 pub mod full;
+pub mod jito;
+pub mod metrics;
 pub mod minimal;
 pub mod surfnet_cheatcodes;
 pub mod utils;
@@ -112,6 +116,7 @@ pub struct SurfpoolMiddleware {
     pub plugin_manager_commands_tx: Sender<PluginManagerCommand>,
     pub config: RpcConfig,
     pub remote_rpc_client: Option<SurfnetRemoteClient>,
+    pub metrics: RpcMetrics,
 }
 
 impl SurfpoolMiddleware {
@@ -128,13 +133,14 @@ impl SurfpoolMiddleware {
             plugin_manager_commands_tx: plugin_manager_commands_tx.clone(),
             config: config.clone(),
             remote_rpc_client: remote_rpc_client.clone(),
+            metrics: RpcMetrics::new(),
         }
     }
 }
 
 impl Middleware<Option<RunloopContext>> for SurfpoolMiddleware {
     type Future = FutureResponse;
-    type CallFuture = middleware::NoopCallFuture;
+    type CallFuture = BoxFuture<Option<Output>>;
 
     fn on_request<F, X>(
         &self,
@@ -153,8 +159,50 @@ impl Middleware<Option<RunloopContext>> for SurfpoolMiddleware {
             plugin_manager_commands_tx: self.plugin_manager_commands_tx.clone(),
             remote_rpc_client: self.remote_rpc_client.clone(),
         });
+        if let Request::Batch(calls) = &request {
+            self.metrics.record_batch(calls.len());
+        }
         Either::Left(Box::pin(next(request, meta).map(move |res| res)))
     }
+
+    /// Called for every call of a request, including each call of a batch, to record its metrics.
+    fn on_call<F, X>(
+        &self,
+        call: Call,
+        meta: Option<RunloopContext>,
+        next: F,
+    ) -> Either<Self::CallFuture, X>
+    where
+        F: Fn(Call, Option<RunloopContext>) -> X + Send + Sync,
+        X: Future<Output = Option<Output>> + Send + 'static,
+    {
+        let method = match &call {
+            Call::MethodCall(method_call) => method_call.method.clone(),
+            Call::Notification(notification) => notification.method.clone(),
+            Call::Invalid { .. } => return Either::Right(next(call, meta)),
+        };
+        let metrics = self.metrics.clone();
+        let started_at = Instant::now();
+        let future = next(call, meta);
+        Either::Left(Box::pin(async move {
+            let (output, is_remote_hit) = track_datasource_hits(future).await;
+            let duration = started_at.elapsed();
+            match &output {
+                Some(Output::Failure(Failure { error, .. }))
+                    if error.code == ErrorCode::MethodNotFound =>
+                {
+                    metrics.record_unknown_call(duration)
+                }
+                output => metrics.record_call(
+                    &method,
+                    duration,
+                    matches!(output, Some(Output::Failure(_))),
+                    is_remote_hit,
+                ),
+            }
+            output
+        }))
+    }
 }
 
 #[derive(Clone)]
@@ -1,3 +1,5 @@
+use std::{collections::HashMap, str::FromStr};
+
 use base64::{Engine as _, engine::general_purpose::STANDARD};
 use jsonrpc_core::{BoxFuture, Error, Result, futures::future};
 use jsonrpc_derive::rpc;
@@ -8,15 +10,18 @@ use solana_commitment_config::CommitmentConfig;
 use solana_epoch_info::EpochInfo;
 use solana_rpc_client_api::response::Response as RpcResponse;
 use solana_sdk::{program_option::COption, system_program, transaction::VersionedTransaction};
+use solana_signature::Signature;
 use spl_associated_token_account::get_associated_token_address_with_program_id;
 use surfpool_types::{
-    ClockCommand, Idl, RpcProfileResultConfig, SimnetCommand, SimnetEvent, UiKeyedProfileResult,
+    ClockCommand, Idl, RpcAccountDiffConfig, RpcProfileResultConfig, RpcSendInstructionConfig,
+    SimnetCommand, SimnetEvent, UiAccountDiff, UiKeyedProfileResult, UiReplayedTransaction,
     types::{AccountUpdate, SetSomeAccount, SupplyUpdate, TokenAccountUpdate, UuidOrSignature},
 };
+use uuid::Uuid;
 
 use super::{RunloopContext, SurfnetRpcContext};
 use crate::{
-    error::SurfpoolError,
+    error::{SurfpoolError, SurfpoolResult},
     rpc::{
         State,
         utils::{verify_pubkey, verify_pubkeys},
@@ -85,12 +90,12 @@ impl AccountUpdateExt for AccountUpdate {
 }
 
 pub trait TokenAccountUpdateExt {
-    fn apply(self, token_account: &mut TokenAccount) -> Result<()>;
+    fn apply(self, token_account: &mut TokenAccount) -> SurfpoolResult<()>;
 }
 
 impl TokenAccountUpdateExt for TokenAccountUpdate {
     /// Apply the update to the account
-    fn apply(self, token_account: &mut TokenAccount) -> Result<()> {
+    fn apply(self, token_account: &mut TokenAccount) -> SurfpoolResult<()> {
         if let Some(amount) = self.amount {
             token_account.set_amount(amount);
         }
@@ -265,6 +270,70 @@ pub trait SurfnetCheatcodes {
         config: Option<RpcProfileResultConfig>,
     ) -> BoxFuture<Result<RpcResponse<UiKeyedProfileResult>>>;
 
+    /// Replays a transaction of the datasource network locally, using the programs deployed in the surfnet.
+    ///
+    /// The transaction and the accounts it touches are fetched through the datasource. The lamports and token
+    /// balances of the accounts it wrote are reset to their pre-execution values, recorded in the transaction
+    /// metadata. The transaction is then profiled on a copy of the surfnet state, with the latest local blockhash
+    /// and without signature verification.
+    ///
+    /// The replay is refused when the pre-execution state cannot be recovered: if a later transaction wrote one
+    /// of its accounts, or if it wrote the data of an account other than a token balance.
+    ///
+    /// ## Parameters
+    /// - `meta`: Metadata passed with the request.
+    /// - `signature`: The base-58 encoded signature of the transaction to replay.
+    /// - `tag`: An optional tag for the profile result.
+    /// - `config`: An optional configuration for the returned profile result.
+    ///
+    /// ## Returns
+    /// A `RpcResponse<UiReplayedTransaction>` containing the outcome of the transaction on the datasource network,
+    /// along with the profile of its local execution.
+    ///
+    /// ## Example Request
+    /// ```json
+    /// {
+    ///   "jsonrpc": "2.0",
+    ///   "id": 1,
+    ///   "method": "surfnet_replayTransaction",
+    ///   "params": ["5Nf3...", "user-report", { "depth": "transaction" }]
+    /// }
+    /// ```
+    ///
+    /// ## Example Response
+    /// ```json
+    /// {
+    ///   "jsonrpc": "2.0",
+    ///   "result": {
+    ///     "context": { "slot": 123456789 },
+    ///     "value": {
+    ///       "onchainResult": {
+    ///         "slot": 351234567,
+    ///         "err": { "InstructionError": [1, { "Custom": 6001 }] },
+    ///         "fee": 5000,
+    ///         "computeUnitsConsumed": 42012,
+    ///         "logMessages": ["Program 11111111111111111111111111111111 invoke [1]", "..."]
+    ///       },
+    ///       "profile": {
+    ///         "slot": 123456789,
+    ///         "key": { "uuid": "1f0e3c4a-..." },
+    ///         "transactionProfile": { "computeUnitsConsumed": 41880, "logMessages": ["..."], "errorMessage": "...", "accountStates": {} },
+    ///         "readonlyAccountStates": {}
+    ///       }
+    ///     }
+    ///   },
+    ///   "id": 1
+    /// }
+    /// ```
+    #[rpc(meta, name = "surfnet_replayTransaction")]
+    fn replay_transaction(
+        &self,
+        meta: Self::Metadata,
+        signature: String,
+        tag: Option<String>,
+        config: Option<RpcProfileResultConfig>,
+    ) -> BoxFuture<Result<RpcResponse<UiReplayedTransaction>>>;
+
     /// Retrieves all profiling results for a given tag.
     ///
     /// ## Parameters
@@ -377,6 +446,61 @@ pub trait SurfnetCheatcodes {
         new_authority_str: Option<String>,
     ) -> BoxFuture<Result<RpcResponse<()>>>;
 
+    /// A cheat code to deploy a program from a local ELF, replacing the executable of any program
+    /// existing at this address, including programs fetched from the datasource.
+    ///
+    /// This method writes the program and program data accounts of an upgradeable loader (v3) program,
+    /// which makes it possible to test a patched build of a mainnet program against forked state.
+    ///
+    /// ## Parameters
+    /// - `meta`: Metadata passed with the request, such as the client's request context.
+    /// - `program_id`: The base-58 encoded public key of the program.
+    /// - `program_data`: The base64 encoded ELF of the program (the content of its `.so` file).
+    /// - `authority`: The base-58 encoded public key of the upgrade authority. If omitted, the upgrade
+    ///   authority of the existing program is kept, and the program is immutable if there is none.
+    ///
+    /// ## Returns
+    /// A `RpcResponse<()>` indicating whether the deployment was successful.
+    ///
+    /// ## Example Request
+    /// ```json
+    /// {
+    ///   "jsonrpc": "2.0",
+    ///   "id": 1,
+    ///   "method": "surfnet_deployProgram",
+    ///   "params": [
+    ///     "PROGRAM_ID_BASE58",
+    ///     "f0VMRgIBAQAAAAAAAAAAAAMA9wABAAAA...",
+    ///     "AUTHORITY_BASE58"
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// ## Example Response
+    /// ```json
+    /// {
+    ///   "jsonrpc": "2.0",
+    ///   "result": {
+    ///     "context": {
+    ///       "slot": 123456789
+    ///     },
+    ///     "value": null
+    ///   },
+    ///   "id": 1
+    /// }
+    /// ```
+    ///
+    /// # See Also
+    /// - `surfnet_cloneProgramAccount`, `surfnet_setProgramAuthority`
+    #[rpc(meta, name = "surfnet_deployProgram")]
+    fn deploy_program(
+        &self,
+        meta: Self::Metadata,
+        program_id: String,
+        program_data: String,
+        authority: Option<String>,
+    ) -> BoxFuture<Result<RpcResponse<()>>>;
+
     /// A cheat code to get the transaction profile for a given signature or UUID.
     ///
     /// ## Parameters
@@ -418,6 +542,63 @@ pub trait SurfnetCheatcodes {
         config: Option<RpcProfileResultConfig>,
     ) -> Result<RpcResponse<Option<UiKeyedProfileResult>>>;
 
+    /// A cheat code to get the changes of the accounts written between two slots.
+    ///
+    /// ## Parameters
+    /// - `meta`: Metadata passed with the request, such as the client's request context.
+    /// - `start_slot`: The slot to diff from. Changes made at this slot are not included.
+    /// - `end_slot` (optional): The slot to diff to, included. If omitted, uses the latest slot.
+    /// - `config` (optional): A `RpcAccountDiffConfig` with:
+    ///   - `encoding`: The encoding of the account states, `jsonParsed` by default. The data of accounts
+    ///     owned by a program with a registered IDL is then decoded with the IDL active at each slot.
+    ///   - `accounts`: Restricts the diff to these accounts.
+    ///
+    /// ## Returns
+    /// A `RpcResponse<Vec<UiAccountDiff>>` with, for each changed account, the lamports delta, whether
+    /// its owner and data changed, and its state at both slots. Accounts holding no lamports are reported
+    /// as deleted.
+    ///
+    /// ## Example Request
+    /// ```json
+    /// {
+    ///   "jsonrpc": "2.0",
+    ///   "id": 1,
+    ///   "method": "surfnet_getAccountDiffs",
+    ///   "params": [355684400, 355684457, { "encoding": "jsonParsed" }]
+    /// }
+    /// ```
+    ///
+    /// ## Example Response
+    /// ```json
+    /// {
+    ///   "jsonrpc": "2.0",
+    ///   "result": {
+    ///     "context": {
+    ///       "slot": 355684457,
+    ///       "apiVersion": "2.2.2"
+    ///     },
+    ///     "value": [
+    ///       {
+    ///         "pubkey": "4EXSeLGxVBpAZwq7vm6evLdewpcvE2H56fpqL2pPiLFa",
+    ///         "lamportsDelta": -5000,
+    ///         "ownerChanged": false,
+    ///         "dataChanged": false,
+    ///         "change": { "type": "update", "data": [ /* ...before... */, /* ...after... */ ] }
+    ///       }
+    ///     ]
+    ///   },
+    ///   "id": 1
+    /// }
+    /// ```
+    #[rpc(meta, name = "surfnet_getAccountDiffs")]
+    fn get_account_diffs(
+        &self,
+        meta: Self::Metadata,
+        start_slot: Slot,
+        end_slot: Option<Slot>,
+        config: Option<RpcAccountDiffConfig>,
+    ) -> Result<RpcResponse<Vec<UiAccountDiff>>>;
+
     /// A cheat code to register an IDL for a given program in memory.
     ///
     /// ## Parameters
@@ -570,6 +751,74 @@ pub trait SurfnetCheatcodes {
         slot: Option<Slot>,
     ) -> Result<RpcResponse<Option<Idl>>>;
 
+    /// A cheat code to build an instruction from the IDL registered for a program, and execute it.
+    ///
+    /// The arguments are serialized with the types declared by the IDL. The accounts that are not
+    /// provided are resolved from the IDL: accounts with a constant address, and PDAs whose seeds are
+    /// arguments, constants or other accounts (including fields of their data). Missing optional accounts
+    /// are replaced with the program ID.
+    ///
+    /// The transaction is signed with the keypairs loaded on startup (the `--airdrop-keypair-path` keypairs).
+    /// When some signers of the instruction have no keypair, the transaction is executed without signature
+    /// verification.
+    ///
+    /// ## Parameters
+    /// - `meta`: Metadata passed with the request.
+    /// - `program_id`: The base-58 encoded public key of the program.
+    /// - `instruction_name`: The name of the instruction, in snake case or camel case.
+    /// - `args`: A JSON object holding the arguments of the instruction, by name. Integers that don't fit in JSON
+    ///   numbers can be given as strings, public keys are base-58 encoded, and enum variants are given by name or
+    ///   as an object holding the fields of the variant.
+    /// - `accounts`: The base-58 encoded public keys of the accounts of the instruction, by name.
+    /// - `config`: An optional `RpcSendInstructionConfig` with the fee payer, and the encoding and depth of the
+    ///   returned profile result.
+    ///
+    /// ## Returns
+    /// A `RpcResponse<UiKeyedProfileResult>` with the profile of the executed transaction, keyed by its signature.
+    ///
+    /// ## Example Request
+    /// ```json
+    /// {
+    ///   "jsonrpc": "2.0",
+    ///   "id": 1,
+    ///   "method": "surfnet_sendInstruction",
+    ///   "params": [
+    ///     "4EXSeLGxVBpAZwq7vm6evLdewpcvE2H56fpqL2pPiLFa",
+    ///     "make_offer",
+    ///     { "seed": 7, "amount": "1000000", "side": { "bid": { "price": 5 } } },
+    ///     { "maker": "5cQvx..." },
+    ///     { "depth": "transaction" }
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// ## Example Response
+    /// ```json
+    /// {
+    ///   "jsonrpc": "2.0",
+    ///   "result": {
+    ///     "context": { "slot": 123456789 },
+    ///     "value": {
+    ///       "slot": 123456789,
+    ///       "key": { "signature": "3xJ7..." },
+    ///       "transactionProfile": { "computeUnitsConsumed": 12034, "logMessages": ["..."], "accountStates": {} },
+    ///       "readonlyAccountStates": {}
+    ///     }
+    ///   },
+    ///   "id": 1
+    /// }
+    /// ```
+    #[rpc(meta, name = "surfnet_sendInstruction")]
+    fn send_instruction(
+        &self,
+        meta: Self::Metadata,
+        program_id: String,
+        instruction_name: String,
+        args: serde_json::Value,
+        accounts: HashMap<String, String>,
+        config: Option<RpcSendInstructionConfig>,
+    ) -> BoxFuture<Result<RpcResponse<UiKeyedProfileResult>>>;
+
     /// A cheat code to get the last 50 local signatures from the local network.
     /// ## Example Request
     /// ```json
@@ -710,6 +959,147 @@ pub trait SurfnetCheatcodes {
     /// ```
     #[rpc(meta, name = "surfnet_resumeClock")]
     fn resume_clock(&self, meta: Self::Metadata) -> Result<EpochInfo>;
+
+    /// A cheat code to capture the current state of the local network.
+    /// The returned id can be passed to `surfnet_revert` to restore the accounts, token indexes,
+    /// blocks, transactions and clock as they were at the time of the snapshot.
+    ///
+    /// At most 32 snapshots are kept at once: snapshots that are no longer needed should be deleted
+    /// with `surfnet_deleteSnapshot`.
+    ///
+    /// ## Returns
+    /// A `RpcResponse<String>` containing the id of the snapshot.
+    ///
+    /// ## Example Request
+    /// ```json
+    /// {
+    ///   "jsonrpc": "2.0",
+    ///   "id": 1,
+    ///   "method": "surfnet_snapshot",
+    ///   "params": []
+    /// }
+    /// ```
+    ///
+    /// ## Example Response
+    /// ```json
+    /// {
+    ///   "jsonrpc": "2.0",
+    ///   "result": {
+    ///     "context": {
+    ///       "slot": 355684457,
+    ///       "apiVersion": "2.2.2"
+    ///     },
+    ///     "value": "3b0b5c36-4d0e-4d8a-9c3a-5a8f2f1f6c2e"
+    ///   },
+    ///   "id": 1
+    /// }
+    /// ```
+    #[rpc(meta, name = "surfnet_snapshot")]
+    fn snapshot(&self, meta: Self::Metadata) -> Result<RpcResponse<String>>;
+
+    /// A cheat code to restore the local network to a state captured with `surfnet_snapshot`.
+    /// The snapshot is not consumed: reverting to the same id multiple times is supported.
+    ///
+    /// ## Parameters
+    /// - `snapshot_id`: The id returned by `surfnet_snapshot`.
+    ///
+    /// ## Returns
+    /// An `EpochInfo` object reflecting the restored clock state.
+    ///
+    /// ## Example Request
+    /// ```json
+    /// {
+    ///   "jsonrpc": "2.0",
+    ///   "id": 1,
+    ///   "method": "surfnet_revert",
+    ///   "params": ["3b0b5c36-4d0e-4d8a-9c3a-5a8f2f1f6c2e"]
+    /// }
+    /// ```
+    ///
+    /// ## Example Response
+    /// ```json
+    /// {
+    ///   "jsonrpc": "2.0",
+    ///   "result": {
+    ///     "epoch": 512,
+    ///     "slot_index": 0,
+    ///     "slots_in_epoch": 432000,
+    ///     "absolute_slot": 221184000,
+    ///     "block_height": 650000000,
+    ///     "transaction_count": 923472834
+    ///   },
+    ///   "id": 1
+    /// }
+    /// ```
+    #[rpc(meta, name = "surfnet_revert")]
+    fn revert(&self, meta: Self::Metadata, snapshot_id: String) -> Result<EpochInfo>;
+
+    /// A cheat code to delete a snapshot taken with `surfnet_snapshot`, freeing the state it holds.
+    ///
+    /// ## Parameters
+    /// - `snapshot_id`: The id returned by `surfnet_snapshot`.
+    ///
+    /// ## Example Request
+    /// ```json
+    /// {
+    ///   "jsonrpc": "2.0",
+    ///   "id": 1,
+    ///   "method": "surfnet_deleteSnapshot",
+    ///   "params": ["3b0b5c36-4d0e-4d8a-9c3a-5a8f2f1f6c2e"]
+    /// }
+    /// ```
+    ///
+    /// ## Example Response
+    /// ```json
+    /// {
+    ///   "jsonrpc": "2.0",
+    ///   "result": null,
+    ///   "id": 1
+    /// }
+    /// ```
+    #[rpc(meta, name = "surfnet_deleteSnapshot")]
+    fn delete_snapshot(&self, meta: Self::Metadata, snapshot_id: String) -> Result<()>;
+
+    /// A cheat code to write the state of the local network to a JSON file.
+    /// The file contains the accounts and their history, blocks, transactions and registered IDLs of the surfnet,
+    /// and can be loaded on startup with `surfpool start --load-state <PATH>`.
+    ///
+    /// Dumps are written to the directory given with `surfpool start --state-dir <DIR>`; the cheatcode is
+    /// disabled if no directory was given.
+    ///
+    /// ## Parameters
+    /// - `file_name`: The name of the file to write in the state dump directory. Only ASCII alphanumerics,
+    ///   `-`, `_` and `.` are allowed, and `.json` is appended if missing.
+    ///
+    /// ## Returns
+    /// A `RpcResponse<String>` with the path of the written file, on the machine running the surfnet.
+    ///
+    /// ## Example Request
+    /// ```json
+    /// {
+    ///   "jsonrpc": "2.0",
+    ///   "id": 1,
+    ///   "method": "surfnet_dumpState",
+    ///   "params": ["amm-pool"]
+    /// }
+    /// ```
+    ///
+    /// ## Example Response
+    /// ```json
+    /// {
+    ///   "jsonrpc": "2.0",
+    ///   "result": {
+    ///     "context": {
+    ///       "slot": 355684457,
+    ///       "apiVersion": "2.2.2"
+    ///     },
+    ///     "value": "/home/user/surfpool-states/amm-pool.json"
+    ///   },
+    ///   "id": 1
+    /// }
+    /// ```
+    #[rpc(meta, name = "surfnet_dumpState")]
+    fn dump_state(&self, meta: Self::Metadata, file_name: String) -> Result<RpcResponse<String>>;
 }
 
 #[derive(Clone)]
@@ -809,9 +1199,6 @@ impl SurfnetCheatcodes for SurfnetCheatcodesRpc {
             None => spl_token::id(),
         };
 
-        let associated_token_account =
-            get_associated_token_address_with_program_id(&owner, &mint, &token_program_id);
-
         let SurfnetRpcContext {
             svm_locker,
             remote_ctx,
@@ -821,60 +1208,10 @@ impl SurfnetCheatcodes for SurfnetCheatcodesRpc {
         };
 
         Box::pin(async move {
-            let get_mint_result = svm_locker
-                .get_account(&remote_ctx, &mint, None)
-                .await?
-                .inner;
-            svm_locker.write_account_update(get_mint_result);
-
-            let SvmAccessContext {
-                slot,
-                inner: mut token_account,
-                ..
-            } = svm_locker
-                .get_account(
-                    &remote_ctx,
-                    &associated_token_account,
-                    Some(Box::new(move |svm_locker| {
-                        let minimum_rent = svm_locker.with_svm_reader(|svm_reader| {
-                            svm_reader.inner.minimum_balance_for_rent_exemption(
-                                TokenAccount::get_packed_len_for_token_program_id(
-                                    &token_program_id,
-                                ),
-                            )
-                        });
-
-                        let default = TokenAccount::new(&token_program_id, owner, mint);
-                        let data = default.pack_into_vec();
-                        GetAccountResult::FoundAccount(
-                            associated_token_account,
-                            Account {
-                                lamports: minimum_rent,
-                                owner: token_program_id,
-                                executable: false,
-                                rent_epoch: 0,
-                                data,
-                            },
-                            true, // indicate that the account should be updated in the SVM, since it's new
-                        )
-                    })),
-                )
+            let SvmAccessContext { slot, .. } = svm_locker
+                .set_token_account(&remote_ctx, owner, mint, update, token_program_id)
                 .await?;
 
-            let mut token_account_data = TokenAccount::unpack(token_account.expected_data())
-                .map_err(|e| {
-                    Error::invalid_params(format!("Failed to unpack token account data: {}", e))
-                })?;
-
-            update.apply(&mut token_account_data)?;
-
-            let final_account_bytes = token_account_data.pack_into_vec();
-            token_account.apply_update(|account| {
-                account.data = final_account_bytes.clone();
-                Ok(())
-            })?;
-            svm_locker.write_account_update(token_account);
-
             Ok(RpcResponse {
                 context: RpcResponseContext::new(slot),
                 value: (),
@@ -966,6 +1303,48 @@ impl SurfnetCheatcodes for SurfnetCheatcodesRpc {
         })
     }
 
+    fn replay_transaction(
+        &self,
+        meta: Self::Metadata,
+        signature: String,
+        tag: Option<String>,
+        config: Option<RpcProfileResultConfig>,
+    ) -> BoxFuture<Result<RpcResponse<UiReplayedTransaction>>> {
+        Box::pin(async move {
+            let signature = Signature::from_str(&signature)
+                .map_err(|e| SurfpoolError::invalid_signature(&signature, e.to_string()))?;
+
+            let SurfnetRpcContext {
+                svm_locker,
+                remote_ctx,
+            } = meta.get_rpc_context(())?;
+            let Some((remote_client, _)) = remote_ctx else {
+                return Err(SurfpoolError::remote_client_required().into());
+            };
+
+            let SvmAccessContext {
+                slot,
+                inner: (uuid, onchain_result),
+                ..
+            } = svm_locker
+                .replay_transaction(&remote_client, signature, tag)
+                .await?;
+
+            let key = UuidOrSignature::Uuid(uuid);
+            let profile = svm_locker
+                .get_profile_result(key, &config.unwrap_or_default())?
+                .ok_or(SurfpoolError::expected_profile_not_found(&key))?;
+
+            Ok(RpcResponse {
+                context: RpcResponseContext::new(slot),
+                value: UiReplayedTransaction {
+                    onchain_result,
+                    profile,
+                },
+            })
+        })
+    }
+
     fn get_profile_results_by_tag(
         &self,
         meta: Self::Metadata,
@@ -1066,6 +1445,45 @@ impl SurfnetCheatcodes for SurfnetCheatcodesRpc {
         })
     }
 
+    fn deploy_program(
+        &self,
+        meta: Self::Metadata,
+        program_id: String,
+        program_data: String,
+        authority: Option<String>,
+    ) -> BoxFuture<Result<RpcResponse<()>>> {
+        let program_id = match verify_pubkey(&program_id) {
+            Ok(res) => res,
+            Err(e) => return e.into(),
+        };
+        let authority = match authority.as_deref().map(verify_pubkey).transpose() {
+            Ok(res) => res,
+            Err(e) => return e.into(),
+        };
+        let elf = match STANDARD.decode(&program_data) {
+            Ok(res) => res,
+            Err(e) => return SurfpoolError::invalid_base64_data("program data", e).into(),
+        };
+
+        let SurfnetRpcContext {
+            svm_locker,
+            remote_ctx,
+        } = match meta.get_rpc_context(CommitmentConfig::confirmed()) {
+            Ok(res) => res,
+            Err(e) => return e.into(),
+        };
+        Box::pin(async move {
+            let SvmAccessContext { slot, .. } = svm_locker
+                .deploy_program(&remote_ctx, &program_id, &elf, authority)
+                .await?;
+
+            Ok(RpcResponse {
+                context: RpcResponseContext::new(slot),
+                value: (),
+            })
+        })
+    }
+
     fn get_transaction_profile(
         &self,
         meta: Self::Metadata,
@@ -1085,6 +1503,24 @@ impl SurfnetCheatcodes for SurfnetCheatcodesRpc {
         })
     }
 
+    fn get_account_diffs(
+        &self,
+        meta: Self::Metadata,
+        start_slot: Slot,
+        end_slot: Option<Slot>,
+        config: Option<RpcAccountDiffConfig>,
+    ) -> Result<RpcResponse<Vec<UiAccountDiff>>> {
+        let config = config.unwrap_or_default();
+        let svm_locker = meta.get_svm_locker()?;
+        let SvmAccessContext {
+            slot, inner: diffs, ..
+        } = svm_locker.get_account_diffs(start_slot, end_slot, &config)?;
+        Ok(RpcResponse {
+            context: RpcResponseContext::new(slot),
+            value: diffs,
+        })
+    }
+
     fn register_idl(
         &self,
         meta: Self::Metadata,
@@ -1124,6 +1560,56 @@ impl SurfnetCheatcodes for SurfnetCheatcodesRpc {
         })
     }
 
+    fn send_instruction(
+        &self,
+        meta: Self::Metadata,
+        program_id: String,
+        instruction_name: String,
+        args: serde_json::Value,
+        accounts: HashMap<String, String>,
+        config: Option<RpcSendInstructionConfig>,
+    ) -> BoxFuture<Result<RpcResponse<UiKeyedProfileResult>>> {
+        Box::pin(async move {
+            let config = config.unwrap_or_default();
+            let program_id = verify_pubkey(&program_id)?;
+            let accounts = accounts
+                .iter()
+                .map(|(name, pubkey)| Ok((name.clone(), verify_pubkey(pubkey)?)))
+                .collect::<SurfpoolResult<HashMap<_, _>>>()?;
+            let payer = config.payer.as_deref().map(verify_pubkey).transpose()?;
+
+            let SurfnetRpcContext {
+                svm_locker,
+                remote_ctx,
+            } = meta.get_rpc_context(CommitmentConfig::confirmed())?;
+
+            let SvmAccessContext {
+                slot,
+                inner: signature,
+                ..
+            } = svm_locker
+                .send_instruction(
+                    &remote_ctx,
+                    program_id,
+                    &instruction_name,
+                    &args,
+                    accounts,
+                    payer,
+                )
+                .await?;
+
+            let key = UuidOrSignature::Signature(signature);
+            let profile = svm_locker
+                .get_profile_result(key, &config.profile_result_config())?
+                .ok_or(SurfpoolError::expected_profile_not_found(&key))?;
+
+            Ok(RpcResponse {
+                context: RpcResponseContext::new(slot),
+                value: profile,
+            })
+        })
+    }
+
     fn get_local_signatures(
         &self,
         meta: Self::Metadata,
@@ -1219,6 +1705,48 @@ impl SurfnetCheatcodes for SurfnetCheatcodesRpc {
 
         Ok(epoch_info)
     }
+
+    fn snapshot(&self, meta: Self::Metadata) -> Result<RpcResponse<String>> {
+        let svm_locker = meta.get_svm_locker()?;
+        let SvmAccessContext {
+            slot,
+            inner: snapshot_id,
+            ..
+        } = svm_locker.snapshot()?;
+        Ok(RpcResponse {
+            context: RpcResponseContext::new(slot),
+            value: snapshot_id.to_string(),
+        })
+    }
+
+    fn revert(&self, meta: Self::Metadata, snapshot_id: String) -> Result<EpochInfo> {
+        let snapshot_id = Uuid::parse_str(&snapshot_id).map_err(|e| {
+            Error::invalid_params(format!("Invalid snapshot id '{snapshot_id}': {e}"))
+        })?;
+        let svm_locker = meta.get_svm_locker()?;
+        let epoch_info = svm_locker.revert(&snapshot_id)?;
+        Ok(epoch_info)
+    }
+
+    fn delete_snapshot(&self, meta: Self::Metadata, snapshot_id: String) -> Result<()> {
+        let snapshot_id = Uuid::parse_str(&snapshot_id).map_err(|e| {
+            Error::invalid_params(format!("Invalid snapshot id '{snapshot_id}': {e}"))
+        })?;
+        let svm_locker = meta.get_svm_locker()?;
+        svm_locker.delete_snapshot(&snapshot_id)?;
+        Ok(())
+    }
+
+    fn dump_state(&self, meta: Self::Metadata, file_name: String) -> Result<RpcResponse<String>> {
+        let svm_locker = meta.get_svm_locker()?;
+        let SvmAccessContext {
+            slot, inner: path, ..
+        } = svm_locker.dump_state_to_file(&file_name)?;
+        Ok(RpcResponse {
+            context: RpcResponseContext::new(slot),
+            value: path.display().to_string(),
+        })
+    }
 }
 
 #[cfg(test)]
@@ -38,7 +38,7 @@ pub fn convert_transaction_metadata_from_canonical(
     }
 }
 
-fn optimize_filters(filters: &mut [RpcFilterType]) {
+pub fn optimize_filters(filters: &mut [RpcFilterType]) {
     filters.iter_mut().for_each(|filter_type| {
         if let RpcFilterType::Memcmp(compare) = filter_type {
             if let Err(err) = compare.convert_to_raw_bytes() {
@@ -69,7 +69,7 @@ fn verify_transaction(
     Ok(())
 }
 
-fn verify_filter(input: &RpcFilterType) -> Result<()> {
+pub fn verify_filter(input: &RpcFilterType) -> Result<()> {
     input
         .verify()
         .map_err(|e| Error::invalid_params(format!("Invalid param: {e:?}")))
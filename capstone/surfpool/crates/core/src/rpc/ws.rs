@@ -12,19 +12,26 @@ use jsonrpc_pubsub::{
 };
 use solana_account_decoder::{UiAccount, UiAccountEncoding};
 use solana_client::{
-    rpc_config::{RpcSignatureSubscribeConfig, RpcTransactionConfig, RpcTransactionLogsFilter},
+    rpc_config::{
+        RpcBlockSubscribeConfig, RpcBlockSubscribeFilter, RpcProgramAccountsConfig,
+        RpcSignatureSubscribeConfig, RpcTransactionConfig, RpcTransactionLogsFilter,
+    },
     rpc_response::{
-        ProcessedSignatureResult, ReceivedSignatureResult, RpcLogsResponse, RpcResponseContext,
-        RpcSignatureResult,
+        ProcessedSignatureResult, ReceivedSignatureResult, RpcBlockUpdate, RpcKeyedAccount,
+        RpcLogsResponse, RpcResponseContext, RpcSignatureResult,
     },
 };
+use solana_clock::Slot;
 use solana_commitment_config::{CommitmentConfig, CommitmentLevel};
 use solana_pubkey::Pubkey;
 use solana_rpc_client_api::response::{Response as RpcResponse, SlotInfo};
 use solana_signature::Signature;
 use solana_transaction_status::{TransactionConfirmationStatus, UiTransactionEncoding};
 
-use super::{State, SurfnetRpcContext, SurfpoolWebsocketMeta};
+use super::{
+    State, SurfnetRpcContext, SurfpoolWebsocketMeta,
+    utils::{optimize_filters, verify_filter},
+};
 use crate::surfnet::{GetTransactionResult, SignatureSubscriptionType};
 
 /// Configuration for account subscription requests.
@@ -576,6 +583,288 @@ pub trait Rpc {
         meta: Option<Self::Metadata>,
         subscription: SubscriptionId,
     ) -> Result<bool>;
+
+    /// Subscribe to notifications for the accounts owned by a program.
+    ///
+    /// This method allows clients to subscribe to changes of every account owned by the given program.
+    /// The subscriber will receive a notification whenever one of these accounts changes, provided it
+    /// matches all the `memcmp` and `dataSize` filters of the subscription.
+    ///
+    /// ## Parameters
+    /// - `meta`: WebSocket metadata containing RPC context and connection information.
+    /// - `subscriber`: The subscription sink for sending program account notifications to the client.
+    /// - `pubkey_str`: The program id to monitor, as a base-58 encoded string.
+    /// - `config`: Optional configuration specifying the filters, commitment level and encoding format for account data.
+    ///
+    /// ## Returns
+    /// This method establishes a continuous WebSocket subscription that will send
+    /// `RpcResponse<RpcKeyedAccount>` notifications to the subscriber whenever a matching account changes.
+    ///
+    /// ## Example WebSocket Request
+    /// ```json
+    /// {
+    ///   "jsonrpc": "2.0",
+    ///   "id": 1,
+    ///   "method": "programSubscribe",
+    ///   "params": [
+    ///     "11111111111111111111111111111111",
+    ///     {
+    ///       "encoding": "base64",
+    ///       "filters": [{ "dataSize": 80 }]
+    ///     }
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// ## Example WebSocket Response (Subscription Confirmation)
+    /// ```json
+    /// {
+    ///   "jsonrpc": "2.0",
+    ///   "result": 24040,
+    ///   "id": 1
+    /// }
+    /// ```
+    ///
+    /// ## Example WebSocket Notification
+    /// ```json
+    /// {
+    ///   "jsonrpc": "2.0",
+    ///   "method": "programNotification",
+    ///   "params": {
+    ///     "result": {
+    ///       "context": {
+    ///         "slot": 5208469
+    ///       },
+    ///       "value": {
+    ///         "pubkey": "H4vnBqifaSACnKa7acsxstsY1iV1bvJNxsCY7enrd1hq",
+    ///         "account": {
+    ///           "data": ["11116bv5nS2h3y12kD1yUKeMZvGcKLSjQgX6BeV7u1FrjeJcKfsHPXHRDEHrBesJhZyqnnq9qJeUuF7WHxiuLuL5twc38w2TXNLxnDbjmuR", "base64"],
+    ///           "executable": false,
+    ///           "lamports": 33594,
+    ///           "owner": "11111111111111111111111111111111",
+    ///           "rentEpoch": 636,
+    ///           "space": 80
+    ///         }
+    ///       }
+    ///     },
+    ///     "subscription": 24040
+    ///   }
+    /// }
+    /// ```
+    ///
+    /// ## Notes
+    /// - The subscription remains active until explicitly unsubscribed or the connection is closed.
+    /// - Invalid program ids or filters will cause the subscription to be rejected with an error.
+    /// - The `tokenAccountState` filter is not supported.
+    ///
+    /// ## See Also
+    /// - `programUnsubscribe`: Remove an active program subscription
+    /// - `getProgramAccounts`: Get the current accounts owned by a program
+    #[pubsub(
+        subscription = "programNotification",
+        subscribe,
+        name = "programSubscribe"
+    )]
+    fn program_subscribe(
+        &self,
+        meta: Self::Metadata,
+        subscriber: Subscriber<RpcResponse<RpcKeyedAccount>>,
+        pubkey_str: String,
+        config: Option<RpcProgramAccountsConfig>,
+    );
+
+    /// Unsubscribe from program account notifications.
+    ///
+    /// ## Parameters
+    /// - `meta`: Optional WebSocket metadata containing connection information.
+    /// - `subscription`: The subscription ID to remove, as returned by `programSubscribe`.
+    ///
+    /// ## Returns
+    /// A `Result<bool>` indicating whether the unsubscription was successful:
+    /// - `Ok(true)` if the subscription was successfully removed
+    /// - `Err(Error)` with `InvalidParams` if the subscription ID doesn't exist
+    ///
+    /// ## Example WebSocket Request
+    /// ```json
+    /// {
+    ///   "jsonrpc": "2.0",
+    ///   "id": 1,
+    ///   "method": "programUnsubscribe",
+    ///   "params": [24040]
+    /// }
+    /// ```
+    ///
+    /// ## See Also
+    /// - `programSubscribe`: Create a program subscription
+    #[pubsub(
+        subscription = "programNotification",
+        unsubscribe,
+        name = "programUnsubscribe"
+    )]
+    fn program_unsubscribe(
+        &self,
+        meta: Option<Self::Metadata>,
+        subscription: SubscriptionId,
+    ) -> Result<bool>;
+
+    /// Subscribe to block notifications.
+    ///
+    /// This method allows clients to receive a notification each time a block reaches the
+    /// requested commitment level, optionally restricted to the transactions mentioning an account.
+    ///
+    /// ## Parameters
+    /// - `meta`: WebSocket metadata containing RPC context and connection information.
+    /// - `subscriber`: The subscription sink for sending block notifications to the client.
+    /// - `filter`: `"all"` to receive every block, or `{ "mentionsAccountOrProgram": <pubkey> }`
+    ///   to only receive the transactions mentioning an account. Blocks without any matching transaction are skipped.
+    /// - `config`: Optional configuration specifying the commitment level, the encoding, the transaction details
+    ///   level (`full`, `accounts`, `signatures` or `none`), whether to show rewards and the max transaction version.
+    ///
+    /// ## Returns
+    /// This method establishes a continuous WebSocket subscription that will send
+    /// `RpcResponse<RpcBlockUpdate>` notifications to the subscriber.
+    ///
+    /// ## Example WebSocket Request
+    /// ```json
+    /// {
+    ///   "jsonrpc": "2.0",
+    ///   "id": 1,
+    ///   "method": "blockSubscribe",
+    ///   "params": [
+    ///     "all",
+    ///     {
+    ///       "commitment": "confirmed",
+    ///       "encoding": "base64",
+    ///       "transactionDetails": "signatures",
+    ///       "showRewards": false,
+    ///       "maxSupportedTransactionVersion": 0
+    ///     }
+    ///   ]
+    /// }
+    /// ```
+    ///
+    /// ## Example WebSocket Notification
+    /// ```json
+    /// {
+    ///   "jsonrpc": "2.0",
+    ///   "method": "blockNotification",
+    ///   "params": {
+    ///     "result": {
+    ///       "context": {
+    ///         "slot": 112301554
+    ///       },
+    ///       "value": {
+    ///         "slot": 112301554,
+    ///         "block": {
+    ///           "previousBlockhash": "GJp125YAN4ufCSUvZJVdCyWQJ7RPWMmwxoyUQySydZA",
+    ///           "blockhash": "6ojMHjctdqfB55JDpEpqfHnP96fiaHEcvzEQ2NNcxzHP",
+    ///           "parentSlot": 112301553,
+    ///           "signatures": ["5tM9xSD7zcSQfDL6FqzKp7XF2rs1jBBpAgb1vqnhMbJRYbLHvwgSbeBCJbcxW2VyMXSdEmLmCAsrVLxMnwK1x3M4"],
+    ///           "blockTime": 1639926816,
+    ///           "blockHeight": 101210751
+    ///         },
+    ///         "err": null
+    ///       }
+    ///     },
+    ///     "subscription": 14
+    ///   }
+    /// }
+    /// ```
+    ///
+    /// ## Notes
+    /// - Blocks are created when confirmed, so `processed` subscriptions are notified along with `confirmed` ones.
+    /// - Finalized notifications are sent once the block is rooted.
+    ///
+    /// ## See Also
+    /// - `blockUnsubscribe`: Remove an active block subscription
+    /// - `getBlock`: Get a confirmed block
+    #[pubsub(subscription = "blockNotification", subscribe, name = "blockSubscribe")]
+    fn block_subscribe(
+        &self,
+        meta: Self::Metadata,
+        subscriber: Subscriber<RpcResponse<RpcBlockUpdate>>,
+        filter: RpcBlockSubscribeFilter,
+        config: Option<RpcBlockSubscribeConfig>,
+    );
+
+    /// Unsubscribe from block notifications.
+    ///
+    /// ## Parameters
+    /// - `meta`: Optional WebSocket metadata containing connection information.
+    /// - `subscription`: The subscription ID to remove, as returned by `blockSubscribe`.
+    ///
+    /// ## Returns
+    /// A `Result<bool>` indicating whether the unsubscription was successful:
+    /// - `Ok(true)` if the subscription was successfully removed
+    /// - `Err(Error)` with `InvalidParams` if the subscription ID doesn't exist
+    ///
+    /// ## See Also
+    /// - `blockSubscribe`: Create a block subscription
+    #[pubsub(
+        subscription = "blockNotification",
+        unsubscribe,
+        name = "blockUnsubscribe"
+    )]
+    fn block_unsubscribe(
+        &self,
+        meta: Option<Self::Metadata>,
+        subscription: SubscriptionId,
+    ) -> Result<bool>;
+
+    /// Subscribe to root notifications.
+    ///
+    /// The subscriber receives the new root slot each time a slot gets rooted,
+    /// which happens once it is `FINALIZATION_SLOT_THRESHOLD` slots behind the latest slot.
+    ///
+    /// ## Example WebSocket Request
+    /// ```json
+    /// {
+    ///   "jsonrpc": "2.0",
+    ///   "id": 1,
+    ///   "method": "rootSubscribe"
+    /// }
+    /// ```
+    ///
+    /// ## Example WebSocket Notification
+    /// ```json
+    /// {
+    ///   "jsonrpc": "2.0",
+    ///   "method": "rootNotification",
+    ///   "params": {
+    ///     "result": 42,
+    ///     "subscription": 0
+    ///   }
+    /// }
+    /// ```
+    ///
+    /// ## See Also
+    /// - `rootUnsubscribe`: Remove an active root subscription
+    #[pubsub(subscription = "rootNotification", subscribe, name = "rootSubscribe")]
+    fn root_subscribe(&self, meta: Self::Metadata, subscriber: Subscriber<Slot>);
+
+    /// Unsubscribe from root notifications.
+    ///
+    /// ## Parameters
+    /// - `meta`: Optional WebSocket metadata containing connection information.
+    /// - `subscription`: The subscription ID to remove, as returned by `rootSubscribe`.
+    ///
+    /// ## Returns
+    /// A `Result<bool>` indicating whether the unsubscription was successful:
+    /// - `Ok(true)` if the subscription was successfully removed
+    /// - `Err(Error)` with `InvalidParams` if the subscription ID doesn't exist
+    ///
+    /// ## See Also
+    /// - `rootSubscribe`: Create a root subscription
+    #[pubsub(
+        subscription = "rootNotification",
+        unsubscribe,
+        name = "rootUnsubscribe"
+    )]
+    fn root_unsubscribe(
+        &self,
+        meta: Option<Self::Metadata>,
+        subscription: SubscriptionId,
+    ) -> Result<bool>;
 }
 
 /// WebSocket RPC server implementation for Surfpool.
@@ -590,6 +879,10 @@ pub trait Rpc {
 /// - `signature_subscription_map`: Thread-safe HashMap containing active signature subscriptions, mapping subscription IDs to their notification sinks.
 /// - `account_subscription_map`: Thread-safe HashMap containing active account subscriptions, mapping subscription IDs to their notification sinks.
 /// - `slot_subscription_map`: Thread-safe HashMap containing active slot subscriptions, mapping subscription IDs to their notification sinks.
+/// - `logs_subscription_map`: Thread-safe HashMap containing active logs subscriptions, mapping subscription IDs to their notification sinks.
+/// - `program_subscription_map`: Thread-safe HashMap containing active program subscriptions, mapping subscription IDs to their notification sinks.
+/// - `block_subscription_map`: Thread-safe HashMap containing active block subscriptions, mapping subscription IDs to their notification sinks.
+/// - `root_subscription_map`: Thread-safe HashMap containing active root subscriptions, mapping subscription IDs to their notification sinks.
 /// - `tokio_handle`: Runtime handle for spawning asynchronous subscription monitoring tasks.
 ///
 /// ## Features
@@ -620,6 +913,11 @@ pub struct SurfpoolWsRpc {
     pub slot_subscription_map: Arc<RwLock<HashMap<SubscriptionId, Sink<SlotInfo>>>>,
     pub logs_subscription_map:
         Arc<RwLock<HashMap<SubscriptionId, Sink<RpcResponse<RpcLogsResponse>>>>>,
+    pub program_subscription_map:
+        Arc<RwLock<HashMap<SubscriptionId, Sink<RpcResponse<RpcKeyedAccount>>>>>,
+    pub block_subscription_map:
+        Arc<RwLock<HashMap<SubscriptionId, Sink<RpcResponse<RpcBlockUpdate>>>>>,
+    pub root_subscription_map: Arc<RwLock<HashMap<SubscriptionId, Sink<Slot>>>>,
     pub tokio_handle: tokio::runtime::Handle,
 }
 
@@ -1162,4 +1460,310 @@ impl Rpc for SurfpoolWsRpc {
             })
         }
     }
+
+    fn program_subscribe(
+        &self,
+        meta: Self::Metadata,
+        subscriber: Subscriber<RpcResponse<RpcKeyedAccount>>,
+        pubkey_str: String,
+        config: Option<RpcProgramAccountsConfig>,
+    ) {
+        let program_id = match Pubkey::from_str(&pubkey_str) {
+            Ok(pk) => pk,
+            Err(_) => {
+                let error = Error {
+                    code: ErrorCode::InvalidParams,
+                    message: "Invalid pubkey format.".into(),
+                    data: None,
+                };
+                if subscriber.reject(error.clone()).is_err() {
+                    log::error!("Failed to reject subscriber for invalid pubkey format.");
+                }
+                return;
+            }
+        };
+
+        let mut config = config.unwrap_or_default();
+        if let Some(filters) = config.filters.as_mut() {
+            if let Err(error) = filters.iter().try_for_each(verify_filter) {
+                if subscriber.reject(error).is_err() {
+                    log::error!("Failed to reject subscriber for invalid filters.");
+                }
+                return;
+            }
+            optimize_filters(filters);
+        }
+
+        let id = self.uid.fetch_add(1, atomic::Ordering::SeqCst);
+        let sub_id = SubscriptionId::Number(id as u64);
+        let sink = match subscriber.assign_id(sub_id.clone()) {
+            Ok(sink) => sink,
+            Err(e) => {
+                log::error!("Failed to assign subscription ID: {:?}", e);
+                return;
+            }
+        };
+
+        let program_active = Arc::clone(&self.program_subscription_map);
+        let meta = meta.clone();
+        let svm_locker = match meta.get_svm_locker() {
+            Ok(locker) => locker,
+            Err(e) => {
+                log::error!("Failed to get SVM locker for program subscription: {e}");
+                if let Err(e) = sink.notify(Err(e.into())) {
+                    log::error!(
+                        "Failed to send error notification to client for SVM locker failure: {e}"
+                    );
+                }
+                return;
+            }
+        };
+
+        self.tokio_handle.spawn(async move {
+            if let Ok(mut guard) = program_active.write() {
+                guard.insert(sub_id.clone(), sink);
+            } else {
+                log::error!("Failed to acquire write lock on program_subscription_map");
+                return;
+            }
+
+            // subscribe to the updates of the accounts owned by the program
+            let rx = svm_locker.subscribe_for_program_updates(&program_id, config);
+
+            loop {
+                // if the subscription has been removed, break the loop
+                if let Ok(guard) = program_active.read() {
+                    if guard.get(&sub_id).is_none() {
+                        break;
+                    }
+                } else {
+                    log::error!("Failed to acquire read lock on program_subscription_map");
+                    break;
+                }
+
+                let Ok((slot, keyed_account)) = rx.try_recv() else {
+                    tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+                    continue;
+                };
+                if let Ok(guard) = program_active.read() {
+                    if let Some(sink) = guard.get(&sub_id) {
+                        let _ = sink.notify(Ok(RpcResponse {
+                            context: RpcResponseContext::new(slot),
+                            value: keyed_account,
+                        }));
+                    }
+                }
+            }
+        });
+    }
+
+    fn program_unsubscribe(
+        &self,
+        _meta: Option<Self::Metadata>,
+        subscription: SubscriptionId,
+    ) -> Result<bool> {
+        let removed = if let Ok(mut guard) = self.program_subscription_map.write() {
+            guard.remove(&subscription)
+        } else {
+            log::error!("Failed to acquire write lock on program_subscription_map");
+            None
+        };
+        if removed.is_some() {
+            Ok(true)
+        } else {
+            Err(Error {
+                code: ErrorCode::InvalidParams,
+                message: "Invalid subscription.".into(),
+                data: None,
+            })
+        }
+    }
+
+    fn block_subscribe(
+        &self,
+        meta: Self::Metadata,
+        subscriber: Subscriber<RpcResponse<RpcBlockUpdate>>,
+        filter: RpcBlockSubscribeFilter,
+        config: Option<RpcBlockSubscribeConfig>,
+    ) {
+        if let RpcBlockSubscribeFilter::MentionsAccountOrProgram(pubkey_str) = &filter {
+            if Pubkey::from_str(pubkey_str).is_err() {
+                let error = Error {
+                    code: ErrorCode::InvalidParams,
+                    message: "Invalid pubkey format.".into(),
+                    data: None,
+                };
+                if subscriber.reject(error.clone()).is_err() {
+                    log::error!("Failed to reject subscriber for invalid pubkey format.");
+                }
+                return;
+            }
+        }
+        let config = config.unwrap_or_default();
+
+        let id = self.uid.fetch_add(1, atomic::Ordering::SeqCst);
+        let sub_id = SubscriptionId::Number(id as u64);
+        let sink = match subscriber.assign_id(sub_id.clone()) {
+            Ok(sink) => sink,
+            Err(e) => {
+                log::error!("Failed to assign subscription ID: {:?}", e);
+                return;
+            }
+        };
+
+        let block_active = Arc::clone(&self.block_subscription_map);
+        let meta = meta.clone();
+        let svm_locker = match meta.get_svm_locker() {
+            Ok(locker) => locker,
+            Err(e) => {
+                log::error!("Failed to get SVM locker for block subscription: {e}");
+                if let Err(e) = sink.notify(Err(e.into())) {
+                    log::error!(
+                        "Failed to send error notification to client for SVM locker failure: {e}"
+                    );
+                }
+                return;
+            }
+        };
+
+        self.tokio_handle.spawn(async move {
+            if let Ok(mut guard) = block_active.write() {
+                guard.insert(sub_id.clone(), sink);
+            } else {
+                log::error!("Failed to acquire write lock on block_subscription_map");
+                return;
+            }
+
+            let rx = svm_locker.subscribe_for_block_updates(filter, config);
+
+            loop {
+                // if the subscription has been removed, break the loop
+                if let Ok(guard) = block_active.read() {
+                    if guard.get(&sub_id).is_none() {
+                        break;
+                    }
+                } else {
+                    log::error!("Failed to acquire read lock on block_subscription_map");
+                    break;
+                }
+
+                let Ok((slot, block_update)) = rx.try_recv() else {
+                    tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+                    continue;
+                };
+                if let Ok(guard) = block_active.read() {
+                    if let Some(sink) = guard.get(&sub_id) {
+                        let _ = sink.notify(Ok(RpcResponse {
+                            context: RpcResponseContext::new(slot),
+                            value: block_update,
+                        }));
+                    }
+                }
+            }
+        });
+    }
+
+    fn block_unsubscribe(
+        &self,
+        _meta: Option<Self::Metadata>,
+        subscription: SubscriptionId,
+    ) -> Result<bool> {
+        let removed = if let Ok(mut guard) = self.block_subscription_map.write() {
+            guard.remove(&subscription)
+        } else {
+            log::error!("Failed to acquire write lock on block_subscription_map");
+            None
+        };
+        if removed.is_some() {
+            Ok(true)
+        } else {
+            Err(Error {
+                code: ErrorCode::InvalidParams,
+                message: "Invalid subscription.".into(),
+                data: None,
+            })
+        }
+    }
+
+    fn root_subscribe(&self, meta: Self::Metadata, subscriber: Subscriber<Slot>) {
+        let id = self.uid.fetch_add(1, atomic::Ordering::SeqCst);
+        let sub_id = SubscriptionId::Number(id as u64);
+        let sink = match subscriber.assign_id(sub_id.clone()) {
+            Ok(sink) => sink,
+            Err(e) => {
+                log::error!("Failed to assign subscription ID: {:?}", e);
+                return;
+            }
+        };
+
+        let root_active = Arc::clone(&self.root_subscription_map);
+        let meta = meta.clone();
+        let svm_locker = match meta.get_svm_locker() {
+            Ok(locker) => locker,
+            Err(e) => {
+                log::error!("Failed to get SVM locker for root subscription: {e}");
+                if let Err(e) = sink.notify(Err(e.into())) {
+                    log::error!(
+                        "Failed to send error notification to client for SVM locker failure: {e}"
+                    );
+                }
+                return;
+            }
+        };
+
+        self.tokio_handle.spawn(async move {
+            if let Ok(mut guard) = root_active.write() {
+                guard.insert(sub_id.clone(), sink);
+            } else {
+                log::error!("Failed to acquire write lock on root_subscription_map");
+                return;
+            }
+
+            let rx = svm_locker.subscribe_for_root_updates();
+
+            loop {
+                // if the subscription has been removed, break the loop
+                if let Ok(guard) = root_active.read() {
+                    if guard.get(&sub_id).is_none() {
+                        break;
+                    }
+                } else {
+                    log::error!("Failed to acquire read lock on root_subscription_map");
+                    break;
+                }
+
+                let Ok(root) = rx.try_recv() else {
+                    tokio::time::sleep(tokio::time::Duration::from_millis(10)).await;
+                    continue;
+                };
+                if let Ok(guard) = root_active.read() {
+                    if let Some(sink) = guard.get(&sub_id) {
+                        let _ = sink.notify(Ok(root));
+                    }
+                }
+            }
+        });
+    }
+
+    fn root_unsubscribe(
+        &self,
+        _meta: Option<Self::Metadata>,
+        subscription: SubscriptionId,
+    ) -> Result<bool> {
+        let removed = if let Ok(mut guard) = self.root_subscription_map.write() {
+            guard.remove(&subscription)
+        } else {
+            log::error!("Failed to acquire write lock on root_subscription_map");
+            None
+        };
+        if removed.is_some() {
+            Ok(true)
+        } else {
+            Err(Error {
+                code: ErrorCode::InvalidParams,
+                message: "Invalid subscription.".into(),
+                data: None,
+            })
+        }
+    }
 }
@@ -20,34 +20,45 @@ use ipc_channel::{
     router::RouterProxy,
 };
 use jsonrpc_core::MetaIoHandler;
-use jsonrpc_http_server::{DomainsValidation, ServerBuilder};
+use jsonrpc_http_server::{
+    DomainsValidation, RequestMiddlewareAction, Response, ServerBuilder,
+    hyper::{self, Method, StatusCode, header::HeaderValue},
+};
 use jsonrpc_pubsub::{PubSubHandler, Session};
 use jsonrpc_ws_server::{RequestContext, ServerBuilder as WsServerBuilder};
 use libloading::{Library, Symbol};
+use solana_commitment_config::CommitmentConfig;
 #[cfg(feature = "geyser-plugin")]
 use solana_geyser_plugin_manager::geyser_plugin_manager::{
     GeyserPluginManager, LoadedGeyserPlugin,
 };
+use solana_keypair::Keypair;
 use solana_message::SimpleAddressLoader;
 use solana_sdk::transaction::MessageHash;
+use solana_signer::EncodableKey;
 use solana_transaction::sanitized::SanitizedTransaction;
 use surfpool_subgraph::SurfpoolSubgraphPlugin;
 use surfpool_types::{
-    BlockProductionMode, ClockCommand, ClockEvent, DEFAULT_RPC_URL, DataIndexingCommand,
-    SimnetCommand, SimnetEvent, SubgraphCommand, SubgraphPluginConfig, SurfpoolConfig,
+    BlockProductionMode, BundleStatusEvent, ClockCommand, ClockEvent, DEFAULT_RPC_URL,
+    DataIndexingCommand, SimnetCommand, SimnetConfig, SimnetEvent, SubgraphCommand,
+    SubgraphPluginConfig, SurfpoolConfig,
 };
 type PluginConstructor = unsafe fn() -> *mut dyn GeyserPlugin;
 use txtx_addon_kit::helpers::fs::FileLocation;
 
 use crate::{
     PluginManagerCommand,
+    error::{SurfpoolError, SurfpoolResult},
     rpc::{
         self, RunloopContext, SurfpoolMiddleware, SurfpoolWebsocketMeta,
         SurfpoolWebsocketMiddleware, accounts_data::AccountsData, accounts_scan::AccountsScan,
-        admin::AdminRpc, bank_data::BankData, full::Full, minimal::Minimal,
+        admin::AdminRpc, bank_data::BankData, full::Full, jito::Jito, minimal::Minimal,
         surfnet_cheatcodes::SurfnetCheatcodes, ws::Rpc,
     },
-    surfnet::{GeyserEvent, locker::SurfnetSvmLocker, remote::SurfnetRemoteClient},
+    surfnet::{
+        GeyserEvent, cache::RemoteAccountCache, dump::SurfnetStateDump, locker::SurfnetSvmLocker,
+        remote::SurfnetRemoteClient,
+    },
 };
 
 const BLOCKHASH_SLOT_TTL: u64 = 75;
@@ -60,25 +71,75 @@ pub async fn start_local_surfnet_runloop(
     simnet_commands_rx: Receiver<SimnetCommand>,
     geyser_events_rx: Receiver<GeyserEvent>,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let Some(simnet) = config.simnets.first() else {
+    // Only the first surfnet is started, bound to its own RPC servers if any
+    let Some(config) = config.surfnet_config(0) else {
         return Ok(());
     };
+    let simnet = &config.simnets[0];
     let block_production_mode = simnet.block_production_mode.clone();
 
     let remote_rpc_client = match simnet.offline_mode {
         true => None,
-        false => Some(SurfnetRemoteClient::new(
-            &simnet
-                .remote_rpc_url
+        false => {
+            let cache = simnet
+                .remote_cache
                 .as_ref()
-                .unwrap_or(&DEFAULT_RPC_URL.to_string()),
-        )),
+                .map(RemoteAccountCache::new)
+                .transpose()?;
+            Some(
+                SurfnetRemoteClient::new_with_traffic_mode(
+                    &simnet
+                        .remote_rpc_url
+                        .as_ref()
+                        .unwrap_or(&DEFAULT_RPC_URL.to_string()),
+                    simnet.remote_traffic.as_ref(),
+                )?
+                .with_cache(cache)
+                .with_fork_slot(simnet.fork_slot),
+            )
+        }
     };
 
+    let state_dump = simnet
+        .state_dump_path
+        .as_ref()
+        .map(SurfnetStateDump::from_file)
+        .transpose()?;
     svm_locker
-        .initialize(simnet.slot_time, &remote_rpc_client)
+        .initialize(simnet.slot_time, &remote_rpc_client, state_dump)
         .await?;
 
+    if let Some(state_dump_dir) = &simnet.state_dump_dir {
+        std::fs::create_dir_all(state_dump_dir)
+            .map_err(|e| SurfpoolError::state_dump(state_dump_dir.display(), e))?;
+    }
+
+    load_startup_state(&svm_locker, simnet, &remote_rpc_client).await?;
+
+    let signing_keypairs = simnet
+        .signer_keypair_paths
+        .iter()
+        .filter_map(|path| match Keypair::read_from_file(path) {
+            Ok(keypair) => Some(keypair),
+            Err(e) => {
+                let _ = svm_locker
+                    .simnet_events_tx()
+                    .send(SimnetEvent::warn(format!(
+                        "Unable to load signing keypair {}: {e}",
+                        path.display()
+                    )));
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+
+    svm_locker.with_svm_writer(|svm_writer| {
+        svm_writer.tip_accounts = simnet.tip_accounts.clone();
+        svm_writer.state_dump_dir = simnet.state_dump_dir.clone();
+        svm_writer.signing_keypairs = Arc::new(signing_keypairs);
+        svm_writer.add_synthetic_validators(simnet.validators)
+    })?;
+
     svm_locker.airdrop_pubkeys(simnet.airdrop_token_amount, &simnet.airdrop_addresses);
     let simnet_events_tx_cc = svm_locker.simnet_events_tx();
 
@@ -92,19 +153,14 @@ pub async fn start_local_surfnet_runloop(
 
     let simnet_config = simnet.clone();
 
-    match start_geyser_runloop(
+    start_geyser_runloop(
         config.plugin_config_path.clone(),
         plugin_manager_commands_rx,
         subgraph_commands_tx.clone(),
         simnet_events_tx_cc.clone(),
         geyser_events_rx,
-    ) {
-        Ok(_) => {}
-        Err(e) => {
-            let _ =
-                simnet_events_tx_cc.send(SimnetEvent::error(format!("Geyser plugin failed: {e}")));
-        }
-    };
+    )
+    .map_err(|e| format!("Geyser plugin failed: {e}"))?;
 
     let (clock_event_rx, clock_command_tx) =
         start_clock_runloop(simnet_config.slot_time, Some(simnet_events_tx_cc.clone()));
@@ -124,6 +180,53 @@ pub async fn start_local_surfnet_runloop(
     .await
 }
 
+/// Writes the accounts, token accounts, programs and IDLs declared in the simnet configuration.
+async fn load_startup_state(
+    svm_locker: &SurfnetSvmLocker,
+    simnet: &SimnetConfig,
+    remote_rpc_client: &Option<SurfnetRemoteClient>,
+) -> SurfpoolResult<()> {
+    let remote_ctx = remote_rpc_client
+        .clone()
+        .map(|client| (client, CommitmentConfig::confirmed()));
+
+    for preload in simnet.preloaded_accounts.iter() {
+        svm_locker.preload_account(preload)?;
+    }
+    for preset in simnet.token_accounts.iter() {
+        svm_locker
+            .set_token_account(
+                &remote_ctx,
+                preset.owner,
+                preset.mint,
+                preset.update.clone(),
+                preset.token_program.unwrap_or(spl_token::id()),
+            )
+            .await?;
+    }
+    for clone in simnet.cloned_programs.iter() {
+        svm_locker
+            .clone_program_account(&remote_ctx, &clone.source, &clone.destination)
+            .await?;
+    }
+    for deployment in simnet.program_deployments.iter() {
+        let elf = std::fs::read(&deployment.path)
+            .map_err(|e| SurfpoolError::program_file(deployment.path.display(), e))?;
+        svm_locker
+            .deploy_program(
+                &remote_ctx,
+                &deployment.program_id,
+                &elf,
+                deployment.authority,
+            )
+            .await?;
+    }
+    for idl_path in simnet.idl_paths.iter() {
+        svm_locker.register_idl_from_file(idl_path)?;
+    }
+    Ok(())
+}
+
 pub async fn start_block_production_runloop(
     clock_event_rx: Receiver<ClockEvent>,
     clock_command_tx: Sender<ClockCommand>,
@@ -180,7 +283,10 @@ pub async fn start_block_production_runloop(
                         do_produce_block = true;
                     }
                     SimnetCommand::SlotBackward(_key) => {
-
+                        block_production_mode = BlockProductionMode::Manual;
+                        if let Err(e) = svm_locker.revert_to_previous_snapshot() {
+                            let _ = svm_locker.simnet_events_tx().send(SimnetEvent::warn(format!("Unable to move back a slot: {e}")));
+                        }
                     }
                     SimnetCommand::CommandClock(update) => {
                         if let ClockCommand::UpdateSlotInterval(updated_slot_time) = update {
@@ -192,7 +298,8 @@ pub async fn start_block_production_runloop(
                         continue
                     }
                     SimnetCommand::UpdateInternalClock(clock) => {
-                        svm_locker.with_svm_writer(|svm_writer| {
+                        let result = svm_locker.with_svm_writer(|svm_writer| {
+                            let previous_epoch = svm_writer.latest_epoch_info.epoch;
                             svm_writer.inner.set_sysvar(&clock);
                             svm_writer.updated_at = clock.unix_timestamp as u64;
                             svm_writer.latest_epoch_info.absolute_slot = clock.slot;
@@ -200,8 +307,12 @@ pub async fn start_block_production_runloop(
                             svm_writer.latest_epoch_info.slot_index = clock.slot;
                             svm_writer.latest_epoch_info.epoch = clock.epoch;
                             svm_writer.latest_epoch_info.absolute_slot = clock.slot + clock.epoch * svm_writer.latest_epoch_info.slots_in_epoch;
-                            let _ = svm_writer.simnet_events_tx.send(SimnetEvent::SystemClockUpdated(clock));
+                            let _ = svm_writer.simnet_events_tx.send(SimnetEvent::SystemClockUpdated(clock.clone()));
+                            svm_writer.process_epoch_boundaries(previous_epoch, clock.epoch)
                         });
+                        if let Err(e) = result {
+                            let _ = svm_locker.simnet_events_tx().send(SimnetEvent::error(format!("Failed to process epoch boundaries: {}", e)));
+                        }
                     }
                     SimnetCommand::UpdateBlockProductionMode(update) => {
                         block_production_mode = update;
@@ -212,6 +323,19 @@ pub async fn start_block_production_runloop(
                             let _ = svm_locker.simnet_events_tx().send(SimnetEvent::error(format!("Failed to process transaction: {}", e)));
                        }
                     }
+                    SimnetCommand::ReloadProgram(program_id, elf) => {
+                        if let Err(e) = svm_locker.deploy_program(&remote_client_with_commitment, &program_id, &elf, None).await {
+                            let _ = svm_locker.simnet_events_tx().send(SimnetEvent::error(format!("Failed to reload program {}: {}", program_id, e)));
+                        }
+                        continue
+                    }
+                    SimnetCommand::BundleReceived(bundle_id, transactions, status_tx) => {
+                        let status = match svm_locker.process_bundle(&remote_client_with_commitment, bundle_id, transactions, sigverify).await {
+                            Ok(status) => status,
+                            Err(e) => BundleStatusEvent::Dropped(e.to_string()),
+                        };
+                        let _ = status_tx.send(status);
+                    }
                     SimnetCommand::Terminate(_) => {
                         let _ = svm_locker.simnet_events_tx().send(SimnetEvent::Aborted("Terminated due to inactivity.".to_string()));
                         break;
@@ -292,64 +416,34 @@ fn start_geyser_runloop(
     simnet_events_tx: Sender<SimnetEvent>,
     geyser_events_rx: Receiver<GeyserEvent>,
 ) -> Result<JoinHandle<Result<(), String>>, String> {
-    let handle: JoinHandle<Result<(), String>> = hiro_system_kit::thread_named("Geyser Plugins Handler").spawn(move || {
-        let mut indexing_enabled = false;
+    let mut indexing_enabled = false;
 
-        #[cfg(feature = "geyser-plugin")]
-        let mut plugin_manager = GeyserPluginManager::new();
-        #[cfg(not(feature = "geyser-plugin"))]
-        let mut plugin_manager = ();
+    #[cfg(feature = "geyser-plugin")]
+    let mut plugin_manager = GeyserPluginManager::new();
+    #[cfg(not(feature = "geyser-plugin"))]
+    let mut plugin_manager = ();
 
-        let mut surfpool_plugin_manager = vec![];
-
-        #[cfg(feature = "geyser-plugin")]
-        for plugin_config_path in plugin_config_paths.into_iter() {
-            let plugin_manifest_location = FileLocation::from_path(plugin_config_path);
-            let contents = plugin_manifest_location.read_content_as_utf8()?;
-            let result: serde_json::Value = match json5::from_str(&contents) {
-                Ok(res) => res,
-                Err(e) => {
-                    let error = format!("Unable to read manifest: {}", e);
-                    let _ = simnet_events_tx.send(SimnetEvent::error(error.clone()));
-                    return Err(error)
-                }
-            };
-            let (plugin_name, plugin_dylib_path) = match (result.get("name").map(|p| p.as_str()), result.get("libpath").map(|p| p.as_str())) {
-                (Some(Some(name)), Some(Some(libpath))) => (name, libpath),
-                _ => {
-                    let error = format!("Unable to retrieve dylib: {}", plugin_manifest_location);
-                    let _ = simnet_events_tx.send(SimnetEvent::error(error.clone()));
-                    return Err(error)
-                }
-            };
-
-            let mut plugin_dylib_location = plugin_manifest_location.get_parent_location().expect("path invalid");
-            plugin_dylib_location.append_path(&plugin_dylib_path).expect("path invalid");
+    // The plugins passed at startup are loaded before spawning the handler, so that a plugin
+    // failing to load prevents the surfnet from starting.
+    for plugin_config_path in plugin_config_paths.into_iter() {
+        let plugin_name = load_geyser_plugin(&mut plugin_manager, plugin_config_path, false)?;
+        indexing_enabled = true;
+        let _ = simnet_events_tx.send(SimnetEvent::PluginLoaded(plugin_name));
+    }
 
-            let (plugin, lib) = unsafe {
-                let lib = match Library::new(&plugin_dylib_location.to_string()) {
-                    Ok(lib) => lib,
-                    Err(e) => {
-                        let _ = simnet_events_tx.send(SimnetEvent::ErrorLog(Local::now(), format!("Unable to load plugin {}: {}", plugin_name, e.to_string())));
-                        continue;
-                    }
-                };
-                let constructor: Symbol<PluginConstructor> = lib
-                    .get(b"_create_plugin")
-                    .map_err(|e| format!("{}", e.to_string()))?;
-                let plugin_raw = constructor();
-                (Box::from_raw(plugin_raw), lib)
-            };
-            indexing_enabled = true;
-            plugin_manager.plugins.push(LoadedGeyserPlugin::new(lib, plugin, Some(plugin_name.to_string())));
-        }
+    let handle: JoinHandle<Result<(), String>> = hiro_system_kit::thread_named("Geyser Plugins Handler").spawn(move || {
+        let mut surfpool_plugin_manager = vec![];
 
         let ipc_router = RouterProxy::new();
 
         let err = loop {
-            use agave_geyser_plugin_interface::geyser_plugin_interface::{ReplicaAccountInfoV3, ReplicaAccountInfoVersions};
+            use agave_geyser_plugin_interface::geyser_plugin_interface::{
+                ReplicaAccountInfoV3, ReplicaAccountInfoVersions, ReplicaBlockInfoV4,
+                ReplicaBlockInfoVersions, ReplicaEntryInfoV2, ReplicaEntryInfoVersions,
+            };
+            use solana_transaction_status::RewardsAndNumPartitions;
 
-            use crate::types::GeyserAccountUpdate;
+            use crate::types::{GeyserAccountUpdate, GeyserBlockMetadata, GeyserEntryInfo};
 
             select! {
                 recv(plugin_manager_commands_rx) -> msg => {
@@ -386,9 +480,48 @@ fn start_geyser_runloop(
                                     indexing_enabled = true;
 
                                     let plugin: Box<dyn GeyserPlugin> = Box::new(plugin);
-                                    surfpool_plugin_manager.push(plugin);
+                                    surfpool_plugin_manager.push((uuid, plugin));
                                     let _ = simnet_events_tx.send(SimnetEvent::PluginLoaded("surfpool-subgraph".into()));
                                 }
+                                PluginManagerCommand::LoadGeyserPlugin(plugin_config_path, notifier) => {
+                                    let result = load_geyser_plugin(&mut plugin_manager, plugin_config_path, false);
+                                    if let Ok(plugin_name) = &result {
+                                        indexing_enabled = true;
+                                        let _ = simnet_events_tx.send(SimnetEvent::PluginLoaded(plugin_name.clone()));
+                                    }
+                                    let _ = notifier.send(result);
+                                }
+                                PluginManagerCommand::UnloadPlugin(name, notifier) => {
+                                    let subgraph_index = surfpool_plugin_manager.iter().position(|(uuid, _)| uuid.to_string() == name);
+                                    let result = match subgraph_index {
+                                        Some(index) => {
+                                            let (_, mut plugin) = surfpool_plugin_manager.remove(index);
+                                            plugin.on_unload();
+                                            Ok(())
+                                        }
+                                        None => unload_geyser_plugin(&mut plugin_manager, &name),
+                                    };
+                                    if result.is_ok() {
+                                        let _ = simnet_events_tx.send(SimnetEvent::info(format!("Plugin {name} unloaded")));
+                                    }
+                                    let _ = notifier.send(result);
+                                }
+                                PluginManagerCommand::ReloadPlugin(name, plugin_config_path, notifier) => {
+                                    let result = if surfpool_plugin_manager.iter().any(|(uuid, _)| uuid.to_string() == name) {
+                                        Err(format!("Subgraph {name} can't be reloaded, unload it and register the subgraph again"))
+                                    } else {
+                                        reload_geyser_plugin(&mut plugin_manager, &name, plugin_config_path)
+                                    };
+                                    if result.is_ok() {
+                                        let _ = simnet_events_tx.send(SimnetEvent::PluginLoaded(name));
+                                    }
+                                    let _ = notifier.send(result);
+                                }
+                                PluginManagerCommand::ListPlugins(notifier) => {
+                                    let mut plugins = list_geyser_plugins(&plugin_manager);
+                                    plugins.extend(surfpool_plugin_manager.iter().map(|(uuid, _)| uuid.to_string()));
+                                    let _ = notifier.send(plugins);
+                                }
                             }
                         },
                         Err(e) => {
@@ -422,7 +555,7 @@ fn start_geyser_runloop(
                             index: 0
                         };
 
-                        for plugin in surfpool_plugin_manager.iter() {
+                        for (_, plugin) in surfpool_plugin_manager.iter() {
                             if let Err(e) = plugin.notify_transaction(ReplicaTransactionInfoVersions::V0_0_2(&transaction_replica), transaction_with_status_meta.slot) {
                                 let _ = simnet_events_tx.send(SimnetEvent::error(format!("Failed to notify Geyser plugin of new transaction: {:?}", e)));
                             };
@@ -452,10 +585,10 @@ fn start_geyser_runloop(
                             rent_epoch: account.rent_epoch,
                             data: account.data.as_ref(),
                             write_version,
-                            txn: Some(&sanitized_transaction),
+                            txn: sanitized_transaction.as_ref(),
                         };
 
-                        for plugin in surfpool_plugin_manager.iter() {
+                        for (_, plugin) in surfpool_plugin_manager.iter() {
                             if let Err(e) = plugin.update_account(ReplicaAccountInfoVersions::V0_0_3(&account_replica), slot, false) {
                                 let _ = simnet_events_tx.send(SimnetEvent::error(format!("Failed to update account in Geyser plugin: {:?}", e)));
                             }
@@ -468,6 +601,99 @@ fn start_geyser_runloop(
                             }
                         }
                     }
+                    Ok(GeyserEvent::UpdateSlotStatus { slot, parent, status }) => {
+                        for (_, plugin) in surfpool_plugin_manager.iter() {
+                            if let Err(e) = plugin.update_slot_status(slot, parent, &status) {
+                                let _ = simnet_events_tx.send(SimnetEvent::error(format!("Failed to update slot status in Geyser plugin: {:?}", e)));
+                            }
+                        }
+
+                        #[cfg(feature = "geyser-plugin")]
+                        for plugin in plugin_manager.plugins.iter() {
+                            if let Err(e) = plugin.update_slot_status(slot, parent, &status) {
+                                let _ = simnet_events_tx.send(SimnetEvent::error(format!("Failed to update slot status in Geyser plugin: {:?}", e)));
+                            }
+                        }
+                    }
+                    Ok(GeyserEvent::NotifyBlockMetadata(block_metadata)) => {
+                        let GeyserBlockMetadata {
+                            slot,
+                            blockhash,
+                            parent_slot,
+                            parent_blockhash,
+                            block_time,
+                            block_height,
+                            executed_transaction_count,
+                            entry_count,
+                        } = block_metadata;
+
+                        let rewards = RewardsAndNumPartitions {
+                            rewards: vec![],
+                            num_partitions: None,
+                        };
+                        let block_replica = ReplicaBlockInfoV4 {
+                            parent_slot,
+                            parent_blockhash: &parent_blockhash,
+                            slot,
+                            blockhash: &blockhash,
+                            rewards: &rewards,
+                            block_time,
+                            block_height,
+                            executed_transaction_count,
+                            entry_count,
+                        };
+
+                        for (_, plugin) in surfpool_plugin_manager.iter() {
+                            if let Err(e) = plugin.notify_block_metadata(ReplicaBlockInfoVersions::V0_0_4(&block_replica)) {
+                                let _ = simnet_events_tx.send(SimnetEvent::error(format!("Failed to notify Geyser plugin of block metadata: {:?}", e)));
+                            }
+                        }
+
+                        #[cfg(feature = "geyser-plugin")]
+                        for plugin in plugin_manager.plugins.iter() {
+                            if let Err(e) = plugin.notify_block_metadata(ReplicaBlockInfoVersions::V0_0_4(&block_replica)) {
+                                let _ = simnet_events_tx.send(SimnetEvent::error(format!("Failed to notify Geyser plugin of block metadata: {:?}", e)));
+                            }
+                        }
+                    }
+                    Ok(GeyserEvent::NotifyEntry(entry)) => {
+                        let GeyserEntryInfo {
+                            slot,
+                            index,
+                            num_hashes,
+                            hash,
+                            executed_transaction_count,
+                            starting_transaction_index,
+                        } = entry;
+
+                        let entry_replica = ReplicaEntryInfoV2 {
+                            slot,
+                            index,
+                            num_hashes,
+                            hash: hash.as_ref(),
+                            executed_transaction_count,
+                            starting_transaction_index,
+                        };
+
+                        for (_, plugin) in surfpool_plugin_manager.iter() {
+                            if !plugin.entry_notifications_enabled() {
+                                continue;
+                            }
+                            if let Err(e) = plugin.notify_entry(ReplicaEntryInfoVersions::V0_0_2(&entry_replica)) {
+                                let _ = simnet_events_tx.send(SimnetEvent::error(format!("Failed to notify Geyser plugin of new entry: {:?}", e)));
+                            }
+                        }
+
+                        #[cfg(feature = "geyser-plugin")]
+                        for plugin in plugin_manager.plugins.iter() {
+                            if !plugin.entry_notifications_enabled() {
+                                continue;
+                            }
+                            if let Err(e) = plugin.notify_entry(ReplicaEntryInfoVersions::V0_0_2(&entry_replica)) {
+                                let _ = simnet_events_tx.send(SimnetEvent::error(format!("Failed to notify Geyser plugin of new entry: {:?}", e)));
+                            }
+                        }
+                    }
                 }
             }
         };
@@ -476,6 +702,184 @@ fn start_geyser_runloop(
     Ok(handle)
 }
 
+/// Reads the manifest of a Geyser plugin, a json5 file holding the `name` of the plugin and the
+/// `libpath` of its dynamic library, relative to the manifest.
+///
+/// # Returns
+/// The name of the plugin, the location of the manifest and the location of the dynamic library.
+#[cfg(feature = "geyser-plugin")]
+fn read_geyser_plugin_manifest(
+    plugin_config_path: PathBuf,
+) -> Result<(String, FileLocation, FileLocation), String> {
+    let plugin_manifest_location = FileLocation::from_path(plugin_config_path);
+    let contents = plugin_manifest_location.read_content_as_utf8()?;
+    let result: serde_json::Value =
+        json5::from_str(&contents).map_err(|e| format!("Unable to read manifest: {}", e))?;
+    let (plugin_name, plugin_dylib_path) = match (
+        result.get("name").map(|p| p.as_str()),
+        result.get("libpath").map(|p| p.as_str()),
+    ) {
+        (Some(Some(name)), Some(Some(libpath))) => (name, libpath),
+        _ => {
+            return Err(format!(
+                "Unable to retrieve dylib: {}",
+                plugin_manifest_location
+            ));
+        }
+    };
+
+    let mut plugin_dylib_location = plugin_manifest_location.get_parent_location()?;
+    plugin_dylib_location.append_path(plugin_dylib_path)?;
+    Ok((
+        plugin_name.to_string(),
+        plugin_manifest_location,
+        plugin_dylib_location,
+    ))
+}
+
+/// Loads the dynamic library of a Geyser plugin, and lets the plugin load its configuration.
+#[cfg(feature = "geyser-plugin")]
+fn load_geyser_plugin_library(
+    plugin_name: String,
+    plugin_manifest_location: &FileLocation,
+    plugin_dylib_location: &FileLocation,
+    is_reload: bool,
+) -> Result<LoadedGeyserPlugin, String> {
+    let (mut plugin, lib) = unsafe {
+        let lib = Library::new(&plugin_dylib_location.to_string())
+            .map_err(|e| format!("Unable to load plugin {}: {}", plugin_name, e))?;
+        let constructor: Symbol<PluginConstructor> = lib
+            .get(b"_create_plugin")
+            .map_err(|e| format!("Unable to load plugin {}: {}", plugin_name, e))?;
+        let plugin_raw = constructor();
+        (Box::from_raw(plugin_raw), lib)
+    };
+    plugin
+        .on_load(&plugin_manifest_location.to_string(), is_reload)
+        .map_err(|e| format!("Unable to load plugin {}: {:?}", plugin_name, e))?;
+
+    Ok(LoadedGeyserPlugin::new(lib, plugin, Some(plugin_name)))
+}
+
+#[cfg(feature = "geyser-plugin")]
+fn load_geyser_plugin(
+    plugin_manager: &mut GeyserPluginManager,
+    plugin_config_path: PathBuf,
+    is_reload: bool,
+) -> Result<String, String> {
+    let (plugin_name, plugin_manifest_location, plugin_dylib_location) =
+        read_geyser_plugin_manifest(plugin_config_path)?;
+    if plugin_manager
+        .plugins
+        .iter()
+        .any(|loaded| loaded.name() == plugin_name)
+    {
+        return Err(format!("Plugin {plugin_name} is already loaded"));
+    }
+    let plugin = load_geyser_plugin_library(
+        plugin_name.clone(),
+        &plugin_manifest_location,
+        &plugin_dylib_location,
+        is_reload,
+    )?;
+    plugin_manager.plugins.push(plugin);
+    Ok(plugin_name)
+}
+
+#[cfg(feature = "geyser-plugin")]
+fn unload_geyser_plugin(
+    plugin_manager: &mut GeyserPluginManager,
+    name: &str,
+) -> Result<(), String> {
+    let Some(index) = plugin_manager
+        .plugins
+        .iter()
+        .position(|plugin| plugin.name() == name)
+    else {
+        return Err(format!("Plugin {name} not found"));
+    };
+    let mut plugin = plugin_manager.plugins.remove(index);
+    plugin.on_unload();
+    Ok(())
+}
+
+/// Loads a Geyser plugin again from its manifest, so that a rebuilt dynamic library replaces the
+/// one in memory.
+///
+/// The new library is loaded before the previous plugin is unloaded, so that a failed reload
+/// leaves the previous plugin in place.
+#[cfg(feature = "geyser-plugin")]
+fn reload_geyser_plugin(
+    plugin_manager: &mut GeyserPluginManager,
+    name: &str,
+    plugin_config_path: PathBuf,
+) -> Result<(), String> {
+    let Some(index) = plugin_manager
+        .plugins
+        .iter()
+        .position(|plugin| plugin.name() == name)
+    else {
+        return Err(format!("Plugin {name} not found"));
+    };
+    let (plugin_name, plugin_manifest_location, plugin_dylib_location) =
+        read_geyser_plugin_manifest(plugin_config_path)?;
+    if plugin_name != name {
+        return Err(format!(
+            "Plugin {name} can't be reloaded from a manifest loading plugin {plugin_name}"
+        ));
+    }
+    let plugin = load_geyser_plugin_library(
+        plugin_name,
+        &plugin_manifest_location,
+        &plugin_dylib_location,
+        true,
+    )?;
+    let mut previous_plugin = std::mem::replace(&mut plugin_manager.plugins[index], plugin);
+    previous_plugin.on_unload();
+    Ok(())
+}
+
+#[cfg(feature = "geyser-plugin")]
+fn list_geyser_plugins(plugin_manager: &GeyserPluginManager) -> Vec<String> {
+    plugin_manager
+        .plugins
+        .iter()
+        .map(|plugin| plugin.name().to_string())
+        .collect()
+}
+
+#[cfg(not(feature = "geyser-plugin"))]
+const GEYSER_PLUGIN_FEATURE_DISABLED: &str =
+    "Geyser plugins are not supported: surfpool was built without the `geyser-plugin` feature";
+
+#[cfg(not(feature = "geyser-plugin"))]
+fn load_geyser_plugin(
+    _plugin_manager: &mut (),
+    _plugin_config_path: PathBuf,
+    _is_reload: bool,
+) -> Result<String, String> {
+    Err(GEYSER_PLUGIN_FEATURE_DISABLED.to_string())
+}
+
+#[cfg(not(feature = "geyser-plugin"))]
+fn unload_geyser_plugin(_plugin_manager: &mut (), name: &str) -> Result<(), String> {
+    Err(format!("Plugin {name} not found"))
+}
+
+#[cfg(not(feature = "geyser-plugin"))]
+fn reload_geyser_plugin(
+    _plugin_manager: &mut (),
+    _name: &str,
+    _plugin_config_path: PathBuf,
+) -> Result<(), String> {
+    Err(GEYSER_PLUGIN_FEATURE_DISABLED.to_string())
+}
+
+#[cfg(not(feature = "geyser-plugin"))]
+fn list_geyser_plugins(_plugin_manager: &()) -> Vec<String> {
+    vec![]
+}
+
 async fn start_rpc_servers_runloop(
     config: &SurfpoolConfig,
     simnet_commands_tx: &Sender<SimnetCommand>,
@@ -517,6 +921,7 @@ async fn start_http_rpc_server_runloop(
         .parse::<SocketAddr>()
         .map_err(|e| e.to_string())?;
 
+    let metrics = middleware.metrics.clone();
     let mut io = MetaIoHandler::with_middleware(middleware);
     io.extend_with(rpc::minimal::SurfpoolMinimalRpc.to_delegate());
     io.extend_with(rpc::full::SurfpoolFullRpc.to_delegate());
@@ -524,11 +929,13 @@ async fn start_http_rpc_server_runloop(
     io.extend_with(rpc::accounts_scan::SurfpoolAccountsScanRpc.to_delegate());
     io.extend_with(rpc::bank_data::SurfpoolBankDataRpc.to_delegate());
     io.extend_with(rpc::surfnet_cheatcodes::SurfnetCheatcodesRpc.to_delegate());
+    io.extend_with(rpc::jito::SurfpoolJitoRpc.to_delegate());
     io.extend_with(rpc::admin::SurfpoolAdminRpc.to_delegate());
 
     if !config.plugin_config_path.is_empty() {
         io.extend_with(rpc::admin::SurfpoolAdminRpc.to_delegate());
     }
+    metrics.register_methods(io.iter().map(|(method, _)| method.clone()));
 
     let _ = std::net::TcpListener::bind(server_bind)
         .map_err(|e| format!("Failed to start RPC server: {}", e))?;
@@ -537,6 +944,21 @@ async fn start_http_rpc_server_runloop(
         .spawn(move || {
             let server = match ServerBuilder::new(io)
                 .cors(DomainsValidation::Disabled)
+                // Serve the RPC metrics in the Prometheus text format at `GET /metrics`
+                .request_middleware(
+                    move |request: hyper::Request<hyper::Body>| -> RequestMiddlewareAction {
+                        if request.method() == Method::GET && request.uri().path() == "/metrics" {
+                            Response {
+                                code: StatusCode::OK,
+                                content_type: HeaderValue::from_static("text/plain; version=0.0.4"),
+                                content: metrics.render(),
+                            }
+                            .into()
+                        } else {
+                            request.into()
+                        }
+                    },
+                )
                 .start_http(&server_bind)
             {
                 Ok(server) => server,
@@ -588,6 +1010,9 @@ async fn start_ws_rpc_server_runloop(
                     account_subscription_map: Arc::new(RwLock::new(HashMap::new())),
                     slot_subscription_map: Arc::new(RwLock::new(HashMap::new())),
                     logs_subscription_map: Arc::new(RwLock::new(HashMap::new())),
+                    program_subscription_map: Arc::new(RwLock::new(HashMap::new())),
+                    block_subscription_map: Arc::new(RwLock::new(HashMap::new())),
+                    root_subscription_map: Arc::new(RwLock::new(HashMap::new())),
                     tokio_handle: tokio_handle.clone(),
                 }
                 .to_delegate(),
@@ -634,3 +1059,164 @@ async fn start_ws_rpc_server_runloop(
         .map_err(|e| format!("Failed to spawn WebSocket RPC Handler thread: {:?}", e))?;
     Ok(_ws_handle)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLUGIN_MANAGER_TIMEOUT: Duration = Duration::from_secs(5);
+
+    /// Starts the Geyser runloop without any plugin, returning the channel of its plugin manager
+    /// commands and the channel of its Geyser events, which must be kept open for the runloop to run.
+    fn start_plugin_manager() -> (Sender<PluginManagerCommand>, Sender<GeyserEvent>) {
+        let (plugin_manager_commands_tx, plugin_manager_commands_rx) = unbounded();
+        let (subgraph_commands_tx, _subgraph_commands_rx) = unbounded();
+        let (simnet_events_tx, _simnet_events_rx) = unbounded();
+        let (geyser_events_tx, geyser_events_rx) = unbounded();
+        start_geyser_runloop(
+            vec![],
+            plugin_manager_commands_rx,
+            subgraph_commands_tx,
+            simnet_events_tx,
+            geyser_events_rx,
+        )
+        .unwrap();
+        (plugin_manager_commands_tx, geyser_events_tx)
+    }
+
+    fn list_plugins(plugin_manager_commands_tx: &Sender<PluginManagerCommand>) -> Vec<String> {
+        let (tx, rx) = unbounded();
+        plugin_manager_commands_tx
+            .send(PluginManagerCommand::ListPlugins(tx))
+            .unwrap();
+        rx.recv_timeout(PLUGIN_MANAGER_TIMEOUT).unwrap()
+    }
+
+    #[test]
+    fn test_plugin_manager_commands_without_plugins() {
+        let (plugin_manager_commands_tx, _geyser_events_tx) = start_plugin_manager();
+        assert!(list_plugins(&plugin_manager_commands_tx).is_empty());
+
+        let (tx, rx) = unbounded();
+        plugin_manager_commands_tx
+            .send(PluginManagerCommand::LoadGeyserPlugin(
+                PathBuf::from("missing_geyser_plugin.json"),
+                tx,
+            ))
+            .unwrap();
+        assert!(rx.recv_timeout(PLUGIN_MANAGER_TIMEOUT).unwrap().is_err());
+
+        let (tx, rx) = unbounded();
+        plugin_manager_commands_tx
+            .send(PluginManagerCommand::UnloadPlugin("missing".into(), tx))
+            .unwrap();
+        assert_eq!(
+            rx.recv_timeout(PLUGIN_MANAGER_TIMEOUT).unwrap(),
+            Err("Plugin missing not found".to_string())
+        );
+
+        let (tx, rx) = unbounded();
+        plugin_manager_commands_tx
+            .send(PluginManagerCommand::ReloadPlugin(
+                "missing".into(),
+                PathBuf::from("missing_geyser_plugin.json"),
+                tx,
+            ))
+            .unwrap();
+        assert!(rx.recv_timeout(PLUGIN_MANAGER_TIMEOUT).unwrap().is_err());
+
+        assert!(list_plugins(&plugin_manager_commands_tx).is_empty());
+    }
+
+    #[cfg(all(unix, feature = "geyser-plugin"))]
+    mod geyser_plugins {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        use super::*;
+
+        const TEST_PLUGIN_NAME: &str = "test-plugin";
+
+        #[derive(Debug)]
+        struct TestPlugin {
+            unloaded: Arc<AtomicBool>,
+        }
+
+        impl GeyserPlugin for TestPlugin {
+            fn name(&self) -> &'static str {
+                TEST_PLUGIN_NAME
+            }
+
+            fn on_unload(&mut self) {
+                self.unloaded.store(true, Ordering::SeqCst);
+            }
+        }
+
+        /// Returns a plugin manager holding a [TestPlugin], along with whether it was unloaded.
+        fn plugin_manager_with_test_plugin() -> (GeyserPluginManager, Arc<AtomicBool>) {
+            let unloaded = Arc::new(AtomicBool::new(false));
+            let plugin = TestPlugin {
+                unloaded: unloaded.clone(),
+            };
+            // The plugin is built in-process, so it holds a handle to the current executable
+            let lib = Library::from(libloading::os::unix::Library::this());
+            let mut plugin_manager = GeyserPluginManager::new();
+            plugin_manager.plugins.push(LoadedGeyserPlugin::new(
+                lib,
+                Box::new(plugin),
+                Some(TEST_PLUGIN_NAME.to_string()),
+            ));
+            (plugin_manager, unloaded)
+        }
+
+        fn write_manifest(name: &str) -> PathBuf {
+            let manifest_path =
+                std::env::temp_dir().join(format!("geyser_plugin_{}.json", uuid::Uuid::new_v4()));
+            let manifest = format!(r#"{{ name: "{name}", libpath: "missing_geyser_plugin.so" }}"#);
+            std::fs::write(&manifest_path, manifest).unwrap();
+            manifest_path
+        }
+
+        #[test]
+        fn test_list_and_unload_geyser_plugins() {
+            let (mut plugin_manager, unloaded) = plugin_manager_with_test_plugin();
+            assert_eq!(list_geyser_plugins(&plugin_manager), vec![TEST_PLUGIN_NAME]);
+
+            // a plugin can't be loaded twice
+            let manifest_path = write_manifest(TEST_PLUGIN_NAME);
+            let result = load_geyser_plugin(&mut plugin_manager, manifest_path.clone(), false);
+            let _ = std::fs::remove_file(&manifest_path);
+            assert_eq!(
+                result,
+                Err(format!("Plugin {TEST_PLUGIN_NAME} is already loaded"))
+            );
+
+            assert!(unload_geyser_plugin(&mut plugin_manager, "other-plugin").is_err());
+            unload_geyser_plugin(&mut plugin_manager, TEST_PLUGIN_NAME).unwrap();
+            assert!(unloaded.load(Ordering::SeqCst));
+            assert!(list_geyser_plugins(&plugin_manager).is_empty());
+        }
+
+        #[test]
+        fn test_failed_reload_keeps_geyser_plugin_loaded() {
+            let (mut plugin_manager, unloaded) = plugin_manager_with_test_plugin();
+
+            // the manifest loads another plugin
+            let manifest_path = write_manifest("other-plugin");
+            let result =
+                reload_geyser_plugin(&mut plugin_manager, TEST_PLUGIN_NAME, manifest_path.clone());
+            let _ = std::fs::remove_file(&manifest_path);
+            assert!(result.is_err());
+            assert!(!unloaded.load(Ordering::SeqCst));
+            assert_eq!(list_geyser_plugins(&plugin_manager), vec![TEST_PLUGIN_NAME]);
+
+            // the library of the plugin can't be loaded
+            let manifest_path = write_manifest(TEST_PLUGIN_NAME);
+            let result =
+                reload_geyser_plugin(&mut plugin_manager, TEST_PLUGIN_NAME, manifest_path.clone());
+            let _ = std::fs::remove_file(&manifest_path);
+            assert!(result.unwrap_err().starts_with("Unable to load plugin"));
+            assert!(!unloaded.load(Ordering::SeqCst));
+            assert_eq!(list_geyser_plugins(&plugin_manager), vec![TEST_PLUGIN_NAME]);
+        }
+    }
+}
@@ -0,0 +1,169 @@
+//! Jito-compatible bundles: up to five transactions executed atomically and in order within a slot.
+
+use std::collections::HashSet;
+
+use litesvm::types::TransactionResult;
+use solana_account::Account;
+use solana_clock::Slot;
+use solana_pubkey::Pubkey;
+use solana_signature::Signature;
+use solana_system_interface::{instruction::SystemInstruction, program as system_program};
+use solana_transaction::versioned::VersionedTransaction;
+
+use crate::error::{SurfpoolError, SurfpoolResult};
+
+/// The maximum number of transactions in a bundle.
+pub const MAX_BUNDLE_TRANSACTIONS: usize = 5;
+/// The minimum tip, in lamports, accepted by the Jito block engine.
+pub const MINIMUM_BUNDLE_TIP_LAMPORTS: u64 = 1_000;
+
+/// A bundle whose transactions all landed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LandedBundle {
+    pub slot: Slot,
+    pub signatures: Vec<Signature>,
+}
+
+/// The execution of a transaction of a bundle, along with the state of the requested accounts.
+#[derive(Debug, Clone)]
+pub struct BundleTransactionExecution {
+    pub signature: Signature,
+    pub result: TransactionResult,
+    pub pre_execution_accounts: Vec<Option<Account>>,
+    pub post_execution_accounts: Vec<Option<Account>>,
+}
+
+/// Derives the id of a bundle from the signatures of its transactions, as done by the Jito block engine.
+pub fn bundle_id(transactions: &[VersionedTransaction]) -> String {
+    let signatures = transactions
+        .iter()
+        .map(|transaction| transaction.signatures[0].to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+    hex::encode(solana_sdk::hash::hash(signatures.as_bytes()).to_bytes())
+}
+
+/// Returns the lamports transferred to `tip_accounts` by the system transfers of `transactions`.
+///
+/// Only the static account keys of the messages are considered, as the Jito block engine rejects
+/// tip accounts loaded from address lookup tables.
+pub fn bundle_tip(transactions: &[VersionedTransaction], tip_accounts: &[Pubkey]) -> u64 {
+    let mut tip = 0u64;
+    for transaction in transactions.iter() {
+        let message = &transaction.message;
+        let account_keys = message.static_account_keys();
+        for instruction in message.instructions().iter() {
+            let Some(program_id) = account_keys.get(instruction.program_id_index as usize) else {
+                continue;
+            };
+            if *program_id != system_program::id() {
+                continue;
+            }
+            let Ok(SystemInstruction::Transfer { lamports }) =
+                bincode::deserialize::<SystemInstruction>(&instruction.data)
+            else {
+                continue;
+            };
+            let recipient = instruction
+                .accounts
+                .get(1)
+                .and_then(|index| account_keys.get(*index as usize));
+            if recipient.is_some_and(|recipient| tip_accounts.contains(recipient)) {
+                tip = tip.saturating_add(lamports);
+            }
+        }
+    }
+    tip
+}
+
+/// Checks that a bundle can be sent: it holds between one and [MAX_BUNDLE_TRANSACTIONS] distinct
+/// transactions, and tips at least [MINIMUM_BUNDLE_TIP_LAMPORTS] to `tip_accounts`.
+pub fn verify_bundle(
+    transactions: &[VersionedTransaction],
+    tip_accounts: &[Pubkey],
+) -> SurfpoolResult<()> {
+    verify_bundle_size(transactions)?;
+
+    let tip = bundle_tip(transactions, tip_accounts);
+    if tip < MINIMUM_BUNDLE_TIP_LAMPORTS {
+        return Err(SurfpoolError::invalid_bundle(format!(
+            "bundles must transfer a tip of at least {MINIMUM_BUNDLE_TIP_LAMPORTS} lamports to a tip account, found {tip}"
+        )));
+    }
+    Ok(())
+}
+
+/// Checks that a bundle holds between one and [MAX_BUNDLE_TRANSACTIONS] distinct transactions.
+pub fn verify_bundle_size(transactions: &[VersionedTransaction]) -> SurfpoolResult<()> {
+    if transactions.is_empty() || transactions.len() > MAX_BUNDLE_TRANSACTIONS {
+        return Err(SurfpoolError::invalid_bundle(format!(
+            "bundles must contain between 1 and {MAX_BUNDLE_TRANSACTIONS} transactions, found {}",
+            transactions.len()
+        )));
+    }
+
+    let mut signatures = HashSet::new();
+    for transaction in transactions.iter() {
+        let Some(signature) = transaction.signatures.first() else {
+            return Err(SurfpoolError::invalid_bundle("transactions must be signed"));
+        };
+        if !signatures.insert(*signature) {
+            return Err(SurfpoolError::invalid_bundle(format!(
+                "transaction {signature} is included more than once"
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_hash::Hash;
+    use solana_keypair::Keypair;
+    use solana_message::Message;
+    use solana_signer::Signer;
+    use solana_system_interface::instruction::transfer;
+    use solana_transaction::Transaction;
+
+    use super::*;
+
+    fn transfer_transaction(payer: &Keypair, to: &Pubkey, lamports: u64) -> VersionedTransaction {
+        let message = Message::new(
+            &[transfer(&payer.pubkey(), to, lamports)],
+            Some(&payer.pubkey()),
+        );
+        VersionedTransaction::from(Transaction::new(&[payer], message, Hash::new_unique()))
+    }
+
+    #[test]
+    fn test_verify_bundle() {
+        let payer = Keypair::new();
+        let tip_account = Pubkey::new_unique();
+        let payment = transfer_transaction(&payer, &Pubkey::new_unique(), 1_000_000);
+        let tip = transfer_transaction(&payer, &tip_account, MINIMUM_BUNDLE_TIP_LAMPORTS);
+
+        let bundle = vec![payment.clone(), tip.clone()];
+        assert_eq!(
+            bundle_tip(&bundle, &[tip_account]),
+            MINIMUM_BUNDLE_TIP_LAMPORTS
+        );
+        assert!(verify_bundle(&bundle, &[tip_account]).is_ok());
+        assert_eq!(bundle_id(&bundle), bundle_id(&bundle));
+        assert_ne!(
+            bundle_id(&bundle),
+            bundle_id(&[tip.clone(), payment.clone()])
+        );
+
+        // Transfers to other accounts are not tips
+        assert!(verify_bundle(&[payment.clone()], &[tip_account]).is_err());
+        let small_tip = transfer_transaction(&payer, &tip_account, MINIMUM_BUNDLE_TIP_LAMPORTS - 1);
+        assert!(verify_bundle(&[small_tip], &[tip_account]).is_err());
+
+        assert!(verify_bundle(&[], &[tip_account]).is_err());
+        assert!(verify_bundle(&[tip.clone(), tip.clone()], &[tip_account]).is_err());
+        let too_many = (0..=MAX_BUNDLE_TRANSACTIONS)
+            .map(|_| transfer_transaction(&payer, &tip_account, MINIMUM_BUNDLE_TIP_LAMPORTS))
+            .collect::<Vec<_>>();
+        assert!(verify_bundle(&too_many, &[tip_account]).is_err());
+    }
+}
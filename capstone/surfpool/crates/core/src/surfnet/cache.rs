@@ -0,0 +1,396 @@
+use std::{
+    collections::HashMap,
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use solana_account::Account;
+use solana_account_decoder::{UiAccount, UiAccountEncoding, encode_ui_account};
+use solana_clock::Slot;
+use solana_commitment_config::CommitmentLevel;
+use solana_pubkey::Pubkey;
+use surfpool_types::RemoteCacheConfig;
+
+use crate::error::{SurfpoolError, SurfpoolResult};
+
+/// An account fetched from the datasource RPC, or its absence.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CachedAccount {
+    pub account: Option<Account>,
+    /// The slot of the datasource when the account was fetched
+    pub slot: Slot,
+    /// The unix timestamp, in seconds, at which the account was fetched
+    pub fetched_at: u64,
+}
+
+/// A line of the cache file.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CacheFileEntry {
+    pubkey: String,
+    commitment: CommitmentLevel,
+    #[serde(default)]
+    fork_slot: Option<Slot>,
+    slot: Slot,
+    fetched_at: u64,
+    account: Option<UiAccount>,
+}
+
+/// Accounts are cached per commitment, and per fork slot the datasource state was pinned to, if any.
+type CacheKey = (Pubkey, CommitmentLevel, Option<Slot>);
+
+struct RemoteAccountCacheInner {
+    entries: HashMap<CacheKey, CachedAccount>,
+    file: Option<File>,
+}
+
+/// A cache of the accounts fetched from the datasource RPC, keyed by pubkey, commitment and fork slot.
+///
+/// When a path is configured, every fetched account is appended to a JSON lines file, which is
+/// loaded back (and compacted) on startup, so that a warm cache survives restarts.
+#[derive(Clone)]
+pub struct RemoteAccountCache {
+    ttl: Option<u64>,
+    path: Option<PathBuf>,
+    inner: Arc<Mutex<RemoteAccountCacheInner>>,
+}
+
+impl RemoteAccountCache {
+    /// Creates a cache from its configuration, loading the entries persisted at the configured path.
+    ///
+    /// Expired entries are dropped and the file is rewritten with the remaining ones.
+    pub fn new(config: &RemoteCacheConfig) -> SurfpoolResult<Self> {
+        let mut entries = HashMap::new();
+        let mut file = None;
+        if let Some(path) = &config.path {
+            entries = Self::load_entries(path, config.ttl, now())?;
+            file = Some(Self::compact(path, &entries)?);
+        }
+        Ok(Self {
+            ttl: config.ttl,
+            path: config.path.clone(),
+            inner: Arc::new(Mutex::new(RemoteAccountCacheInner { entries, file })),
+        })
+    }
+
+    /// Returns the cached state of an account, if it was fetched with this commitment at this fork slot
+    /// and has not expired.
+    pub fn get(
+        &self,
+        pubkey: &Pubkey,
+        commitment: CommitmentLevel,
+        fork_slot: Option<Slot>,
+    ) -> Option<CachedAccount> {
+        self.get_at(pubkey, commitment, fork_slot, now())
+    }
+
+    fn get_at(
+        &self,
+        pubkey: &Pubkey,
+        commitment: CommitmentLevel,
+        fork_slot: Option<Slot>,
+        timestamp: u64,
+    ) -> Option<CachedAccount> {
+        let inner = self.inner.lock().ok()?;
+        inner
+            .entries
+            .get(&(*pubkey, commitment, fork_slot))
+            .filter(|entry| !is_expired(entry, self.ttl, timestamp))
+            .cloned()
+    }
+
+    /// Stores the state of an account fetched at `slot`, and appends it to the cache file if any.
+    pub fn insert(
+        &self,
+        pubkey: Pubkey,
+        commitment: CommitmentLevel,
+        fork_slot: Option<Slot>,
+        slot: Slot,
+        account: Option<Account>,
+    ) -> SurfpoolResult<()> {
+        self.insert_at(pubkey, commitment, fork_slot, slot, account, now())
+    }
+
+    fn insert_at(
+        &self,
+        pubkey: Pubkey,
+        commitment: CommitmentLevel,
+        fork_slot: Option<Slot>,
+        slot: Slot,
+        account: Option<Account>,
+        timestamp: u64,
+    ) -> SurfpoolResult<()> {
+        let entry = CachedAccount {
+            account,
+            slot,
+            fetched_at: timestamp,
+        };
+        let mut inner = self
+            .inner
+            .lock()
+            .map_err(|e| SurfpoolError::internal(e.to_string()))?;
+        if let (Some(file), Some(path)) = (inner.file.as_mut(), self.path.as_ref()) {
+            write_entry(file, &(pubkey, commitment, fork_slot), &entry)
+                .map_err(|e| SurfpoolError::remote_cache(path.display(), e))?;
+        }
+        inner.entries.insert((pubkey, commitment, fork_slot), entry);
+        Ok(())
+    }
+
+    fn load_entries(
+        path: &Path,
+        ttl: Option<u64>,
+        timestamp: u64,
+    ) -> SurfpoolResult<HashMap<CacheKey, CachedAccount>> {
+        let mut entries = HashMap::new();
+        let file = match File::open(path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(entries),
+            Err(e) => return Err(SurfpoolError::remote_cache(path.display(), e)),
+        };
+        for line in BufReader::new(file).lines() {
+            let line = line.map_err(|e| SurfpoolError::remote_cache(path.display(), e))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            // A truncated last line is expected if the process was killed while appending to the file
+            let Ok(entry) = serde_json::from_str::<CacheFileEntry>(&line) else {
+                continue;
+            };
+            let Ok(pubkey) = Pubkey::from_str(&entry.pubkey) else {
+                continue;
+            };
+            let account = match entry.account {
+                Some(ui_account) => match ui_account.decode::<Account>() {
+                    Some(account) => Some(account),
+                    None => continue,
+                },
+                None => None,
+            };
+            let cached_account = CachedAccount {
+                account,
+                slot: entry.slot,
+                fetched_at: entry.fetched_at,
+            };
+            // Later lines override earlier ones
+            let key = (pubkey, entry.commitment, entry.fork_slot);
+            if is_expired(&cached_account, ttl, timestamp) {
+                entries.remove(&key);
+            } else {
+                entries.insert(key, cached_account);
+            }
+        }
+        Ok(entries)
+    }
+
+    /// Rewrites the cache file with the given entries, and returns it opened in append mode.
+    fn compact(path: &Path, entries: &HashMap<CacheKey, CachedAccount>) -> SurfpoolResult<File> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| SurfpoolError::remote_cache(path.display(), e))?;
+        }
+        let mut file =
+            File::create(path).map_err(|e| SurfpoolError::remote_cache(path.display(), e))?;
+        for (key, entry) in entries.iter() {
+            write_entry(&mut file, key, entry)
+                .map_err(|e| SurfpoolError::remote_cache(path.display(), e))?;
+        }
+        drop(file);
+        OpenOptions::new()
+            .append(true)
+            .open(path)
+            .map_err(|e| SurfpoolError::remote_cache(path.display(), e))
+    }
+}
+
+fn write_entry(file: &mut File, key: &CacheKey, entry: &CachedAccount) -> Result<(), String> {
+    let (pubkey, commitment, fork_slot) = key;
+    let line = CacheFileEntry {
+        pubkey: pubkey.to_string(),
+        commitment: *commitment,
+        fork_slot: *fork_slot,
+        slot: entry.slot,
+        fetched_at: entry.fetched_at,
+        account: entry.account.as_ref().map(|account| {
+            encode_ui_account(pubkey, account, UiAccountEncoding::Base64, None, None)
+        }),
+    };
+    let mut bytes = serde_json::to_vec(&line).map_err(|e| e.to_string())?;
+    bytes.push(b'\n');
+    file.write_all(&bytes).map_err(|e| e.to_string())
+}
+
+fn is_expired(entry: &CachedAccount, ttl: Option<u64>, timestamp: u64) -> bool {
+    ttl.is_some_and(|ttl| timestamp.saturating_sub(entry.fetched_at) >= ttl)
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account(lamports: u64) -> Account {
+        Account {
+            lamports,
+            data: vec![1, 2, 3],
+            owner: Pubkey::new_unique(),
+            executable: false,
+            rent_epoch: 0,
+        }
+    }
+
+    #[test]
+    fn test_cache_ttl() {
+        let cache = RemoteAccountCache::new(&RemoteCacheConfig {
+            ttl: Some(60),
+            path: None,
+        })
+        .unwrap();
+        let pubkey = Pubkey::new_unique();
+        let missing = Pubkey::new_unique();
+        let fetched_account = account(1);
+        cache
+            .insert_at(
+                pubkey,
+                CommitmentLevel::Confirmed,
+                None,
+                10,
+                Some(fetched_account.clone()),
+                1_000,
+            )
+            .unwrap();
+        cache
+            .insert_at(missing, CommitmentLevel::Confirmed, None, 10, None, 1_000)
+            .unwrap();
+
+        let cached = cache
+            .get_at(&pubkey, CommitmentLevel::Confirmed, None, 1_059)
+            .unwrap();
+        assert_eq!(cached.account, Some(fetched_account));
+        assert_eq!(cached.slot, 10);
+        assert!(
+            cache
+                .get_at(&missing, CommitmentLevel::Confirmed, None, 1_059)
+                .is_some_and(|cached| cached.account.is_none())
+        );
+        assert!(
+            cache
+                .get_at(&pubkey, CommitmentLevel::Finalized, None, 1_059)
+                .is_none()
+        );
+        assert!(
+            cache
+                .get_at(&pubkey, CommitmentLevel::Confirmed, None, 1_060)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_cache_fork_slot() {
+        let path = std::env::temp_dir()
+            .join(format!("surfpool-cache-{}", Pubkey::new_unique()))
+            .join("accounts.jsonl");
+        let config = RemoteCacheConfig {
+            ttl: None,
+            path: Some(path.clone()),
+        };
+        let pubkey = Pubkey::new_unique();
+
+        let cache = RemoteAccountCache::new(&config).unwrap();
+        cache
+            .insert(
+                pubkey,
+                CommitmentLevel::Confirmed,
+                Some(100),
+                120,
+                Some(account(1)),
+            )
+            .unwrap();
+        cache
+            .insert(
+                pubkey,
+                CommitmentLevel::Confirmed,
+                None,
+                150,
+                Some(account(2)),
+            )
+            .unwrap();
+        drop(cache);
+
+        // Accounts fetched at a fork slot are only served at that fork slot
+        let cache = RemoteAccountCache::new(&config).unwrap();
+        let pinned = cache
+            .get(&pubkey, CommitmentLevel::Confirmed, Some(100))
+            .unwrap();
+        assert_eq!(pinned.account.unwrap().lamports, 1);
+        let latest = cache
+            .get(&pubkey, CommitmentLevel::Confirmed, None)
+            .unwrap();
+        assert_eq!(latest.account.unwrap().lamports, 2);
+        assert!(
+            cache
+                .get(&pubkey, CommitmentLevel::Confirmed, Some(101))
+                .is_none()
+        );
+
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_cache_persistence() {
+        let path = std::env::temp_dir()
+            .join(format!("surfpool-cache-{}", Pubkey::new_unique()))
+            .join("accounts.jsonl");
+        let config = RemoteCacheConfig {
+            ttl: Some(3_600),
+            path: Some(path.clone()),
+        };
+        let fresh = Pubkey::new_unique();
+        let stale = Pubkey::new_unique();
+        let fresh_account = account(42);
+
+        let cache = RemoteAccountCache::new(&config).unwrap();
+        cache
+            .insert(fresh, CommitmentLevel::Confirmed, None, 5, Some(account(1)))
+            .unwrap();
+        cache
+            .insert(
+                fresh,
+                CommitmentLevel::Confirmed,
+                None,
+                6,
+                Some(fresh_account.clone()),
+            )
+            .unwrap();
+        cache
+            .insert_at(stale, CommitmentLevel::Confirmed, None, 1, None, 0)
+            .unwrap();
+        drop(cache);
+
+        let cache = RemoteAccountCache::new(&config).unwrap();
+        let cached = cache.get(&fresh, CommitmentLevel::Confirmed, None).unwrap();
+        assert_eq!(cached.account, Some(fresh_account));
+        assert_eq!(cached.slot, 6);
+        assert!(
+            cache
+                .get(&stale, CommitmentLevel::Confirmed, None)
+                .is_none()
+        );
+
+        // The file was compacted on load
+        let lines = std::fs::read_to_string(&path).unwrap().lines().count();
+        assert_eq!(lines, 1);
+
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+}
@@ -0,0 +1,457 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    path::Path,
+    str::FromStr,
+};
+
+use base64::{Engine, prelude::BASE64_STANDARD};
+use solana_account::Account;
+use solana_account_decoder::{UiAccount, UiAccountEncoding, encode_ui_account};
+use solana_clock::{Clock, MAX_RECENT_BLOCKHASHES, Slot};
+use solana_epoch_info::EpochInfo;
+use solana_hash::Hash;
+use solana_message::v0::LoadedAddresses;
+use solana_pubkey::Pubkey;
+use solana_sdk::transaction::VersionedTransaction;
+use solana_storage_proto::StoredTransactionStatusMeta;
+use solana_transaction_status::TransactionStatusMeta;
+use surfpool_types::Idl;
+
+use super::{BlockHeader, BlockIdentifier, svm::SurfnetSvm};
+use crate::{
+    error::{SurfpoolError, SurfpoolResult},
+    types::{SurfnetTransactionStatus, TransactionWithStatusMeta},
+};
+
+/// Version of the state dump format, bumped on breaking changes.
+pub const SURFNET_STATE_DUMP_VERSION: u8 = 1;
+
+const MAX_STATE_DUMP_FILE_NAME_LEN: usize = 128;
+
+/// A serializable copy of the ledger state of a [SurfnetSvm].
+///
+/// Token and owner indexes are not stored: they are rebuilt from the accounts when the dump is loaded.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SurfnetStateDump {
+    pub version: u8,
+    pub epoch_info: EpochInfo,
+    pub chain_tip: BlockIdentifier,
+    pub transactions_processed: u64,
+    pub accounts: BTreeMap<String, UiAccount>,
+    pub blocks: BTreeMap<Slot, BlockHeader>,
+    pub transactions: Vec<TransactionDump>,
+    pub registered_idls: Vec<IdlDump>,
+    /// The recorded states of each account, oldest first.
+    #[serde(default)]
+    pub account_history: BTreeMap<String, Vec<AccountStateDump>>,
+    /// The accounts whose oldest states were evicted from their history.
+    #[serde(default)]
+    pub truncated_account_histories: Vec<String>,
+    pub total_supply: u64,
+    pub circulating_supply: u64,
+    pub non_circulating_supply: u64,
+    pub non_circulating_accounts: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionDump {
+    pub slot: Slot,
+    /// The base64 encoded, bincode serialized transaction.
+    pub transaction: String,
+    pub meta: StoredTransactionStatusMeta,
+    pub loaded_addresses: LoadedAddresses,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountStateDump {
+    pub slot: Slot,
+    pub account: UiAccount,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IdlDump {
+    pub slot: Slot,
+    pub idl: Idl,
+}
+
+impl SurfnetStateDump {
+    /// Reads a state dump from a JSON file.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> SurfpoolResult<Self> {
+        let path = path.as_ref();
+        let bytes =
+            std::fs::read(path).map_err(|e| SurfpoolError::state_dump(path.display(), e))?;
+        let dump: SurfnetStateDump = serde_json::from_slice(&bytes)
+            .map_err(|e| SurfpoolError::state_dump(path.display(), e))?;
+        if dump.version != SURFNET_STATE_DUMP_VERSION {
+            return Err(SurfpoolError::state_dump(
+                path.display(),
+                format!(
+                    "unsupported version {} (expected {})",
+                    dump.version, SURFNET_STATE_DUMP_VERSION
+                ),
+            ));
+        }
+        Ok(dump)
+    }
+
+    /// Writes the state dump to a JSON file.
+    pub fn write_to_file<P: AsRef<Path>>(&self, path: P) -> SurfpoolResult<()> {
+        let path = path.as_ref();
+        let bytes = serde_json::to_vec_pretty(self)
+            .map_err(|e| SurfpoolError::state_dump(path.display(), e))?;
+        std::fs::write(path, bytes).map_err(|e| SurfpoolError::state_dump(path.display(), e))
+    }
+}
+
+/// Validates the name of a state dump file written by `surfnet_dumpState`.
+///
+/// The name is provided by RPC clients, so it can't be a path: only ASCII alphanumerics, `-`, `_` and `.`
+/// are allowed, and it can't start with a `.`. The `.json` extension is appended if missing.
+pub fn state_dump_file_name(name: &str) -> SurfpoolResult<String> {
+    let is_valid = !name.is_empty()
+        && name.len() <= MAX_STATE_DUMP_FILE_NAME_LEN
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !is_valid {
+        return Err(SurfpoolError::invalid_state_dump_file_name(name));
+    }
+    if name.ends_with(".json") {
+        Ok(name.to_string())
+    } else {
+        Ok(format!("{name}.json"))
+    }
+}
+
+impl SurfnetSvm {
+    /// Serializes the accounts, blocks, transactions and registered IDLs of the SVM.
+    ///
+    /// # Returns
+    /// A [SurfnetStateDump] that can be written to disk and later restored with [SurfnetSvm::load_state_dump].
+    pub fn dump_state(&self) -> SurfpoolResult<SurfnetStateDump> {
+        let accounts = self
+            .accounts_registry
+            .iter()
+            .map(|(pubkey, account)| {
+                (
+                    pubkey.to_string(),
+                    encode_ui_account(pubkey, account, UiAccountEncoding::Base64, None, None),
+                )
+            })
+            .collect();
+
+        let mut transactions = vec![];
+        for status in self.transactions.values() {
+            let SurfnetTransactionStatus::Processed(tx) = status else {
+                continue;
+            };
+            let mut meta = tx.meta.clone();
+            let loaded_addresses = std::mem::take(&mut meta.loaded_addresses);
+            let signature = tx.transaction.signatures[0];
+            let meta = StoredTransactionStatusMeta::try_from(meta)
+                .map_err(|e| SurfpoolError::internal(format!("{signature}: {e}")))?;
+            let transaction = bincode::serialize(&tx.transaction)
+                .map_err(|e| SurfpoolError::internal(format!("{signature}: {e}")))?;
+            transactions.push(TransactionDump {
+                slot: tx.slot,
+                transaction: BASE64_STANDARD.encode(transaction),
+                meta,
+                loaded_addresses,
+            });
+        }
+        transactions.sort_by_key(|tx| tx.slot);
+
+        let registered_idls = self
+            .registered_idls
+            .values()
+            .flat_map(|versions| {
+                versions.iter().map(|versioned_idl| IdlDump {
+                    slot: versioned_idl.0,
+                    idl: versioned_idl.1.clone(),
+                })
+            })
+            .collect();
+
+        let account_history = self
+            .account_history
+            .iter()
+            .map(|(pubkey, states)| {
+                let states = states
+                    .iter()
+                    .map(|(slot, account)| AccountStateDump {
+                        slot: *slot,
+                        account: encode_ui_account(
+                            pubkey,
+                            account,
+                            UiAccountEncoding::Base64,
+                            None,
+                            None,
+                        ),
+                    })
+                    .collect();
+                (pubkey.to_string(), states)
+            })
+            .collect();
+
+        Ok(SurfnetStateDump {
+            version: SURFNET_STATE_DUMP_VERSION,
+            epoch_info: self.latest_epoch_info.clone(),
+            chain_tip: self.chain_tip.clone(),
+            transactions_processed: self.transactions_processed,
+            accounts,
+            blocks: self
+                .blocks
+                .iter()
+                .map(|(slot, block)| (*slot, block.clone()))
+                .collect(),
+            transactions,
+            registered_idls,
+            account_history,
+            truncated_account_histories: self
+                .truncated_account_histories
+                .iter()
+                .map(|pubkey| pubkey.to_string())
+                .collect(),
+            total_supply: self.total_supply,
+            circulating_supply: self.circulating_supply,
+            non_circulating_supply: self.non_circulating_supply,
+            non_circulating_accounts: self.non_circulating_accounts.clone(),
+        })
+    }
+
+    /// Restores a [SurfnetStateDump] on top of the current state of the SVM.
+    ///
+    /// Accounts are written through [SurfnetSvm::set_account], so token and owner indexes are rebuilt.
+    /// Executable accounts are written last, so that their program data is available when LiteSVM loads them.
+    /// The history of the restored accounts is the dumped one, rather than the writes made while loading.
+    ///
+    /// # Arguments
+    /// * `dump` - The state dump to restore.
+    ///
+    /// # Returns
+    /// `Ok(())` on success, or an error if an account or transaction could not be decoded.
+    pub fn load_state_dump(&mut self, dump: SurfnetStateDump) -> SurfpoolResult<()> {
+        // Accounts are written at the dumped slot
+        self.latest_epoch_info = dump.epoch_info;
+
+        let mut accounts = Vec::with_capacity(dump.accounts.len());
+        for (pubkey, ui_account) in dump.accounts.iter() {
+            let pubkey = Pubkey::from_str(pubkey)
+                .map_err(|e| SurfpoolError::invalid_pubkey(pubkey, e.to_string()))?;
+            let Some(account) = ui_account.decode::<Account>() else {
+                return Err(SurfpoolError::invalid_account_data(
+                    pubkey,
+                    (),
+                    Some("unable to decode account data"),
+                ));
+            };
+            accounts.push((pubkey, account));
+        }
+        accounts.sort_by_key(|(_, account)| account.executable);
+        for (pubkey, account) in accounts {
+            self.set_account(&pubkey, account)?;
+        }
+        for (pubkey, states) in dump.account_history.iter() {
+            let pubkey = Pubkey::from_str(pubkey)
+                .map_err(|e| SurfpoolError::invalid_pubkey(pubkey, e.to_string()))?;
+            let mut history = VecDeque::with_capacity(states.len());
+            for AccountStateDump { slot, account } in states.iter() {
+                let Some(account) = account.decode::<Account>() else {
+                    return Err(SurfpoolError::invalid_account_data(
+                        pubkey,
+                        (),
+                        Some("unable to decode account history"),
+                    ));
+                };
+                history.push_back((*slot, account));
+            }
+            self.account_history.insert(pubkey, history);
+        }
+        for pubkey in dump.truncated_account_histories.iter() {
+            let pubkey = Pubkey::from_str(pubkey)
+                .map_err(|e| SurfpoolError::invalid_pubkey(pubkey, e.to_string()))?;
+            self.truncated_account_histories.insert(pubkey);
+        }
+
+        for tx in dump.transactions {
+            let bytes = BASE64_STANDARD
+                .decode(&tx.transaction)
+                .map_err(|e| SurfpoolError::invalid_base64_data("transaction", e))?;
+            let transaction: VersionedTransaction = bincode::deserialize(&bytes)
+                .map_err(|e| SurfpoolError::deserialize_error("transaction", e))?;
+            let mut meta = TransactionStatusMeta::from(tx.meta);
+            meta.loaded_addresses = tx.loaded_addresses;
+            self.transactions.insert(
+                transaction.signatures[0],
+                SurfnetTransactionStatus::Processed(Box::new(TransactionWithStatusMeta {
+                    slot: tx.slot,
+                    transaction,
+                    meta,
+                })),
+            );
+        }
+
+        for IdlDump { slot, idl } in dump.registered_idls {
+            self.register_idl(idl, Some(slot));
+        }
+
+        self.restore_blockhashes(&dump.chain_tip, &dump.blocks)?;
+        self.blocks.extend(dump.blocks);
+        self.chain_tip = dump.chain_tip;
+        self.transactions_processed = dump.transactions_processed;
+        self.total_supply = dump.total_supply;
+        self.circulating_supply = dump.circulating_supply;
+        self.non_circulating_supply = dump.non_circulating_supply;
+        self.non_circulating_accounts = dump.non_circulating_accounts;
+
+        let clock = Clock {
+            slot: self.latest_epoch_info.absolute_slot,
+            epoch: self.latest_epoch_info.epoch,
+            unix_timestamp: chrono::Utc::now().timestamp(),
+            epoch_start_timestamp: 0, // todo
+            leader_schedule_epoch: 0, // todo
+        };
+        self.inner.set_sysvar(&clock);
+
+        Ok(())
+    }
+
+    /// Brings the latest blockhash of the SVM to the dumped chain tip, and restores the recent blockhashes
+    /// of the dumped blocks, so that transactions built against them are accepted.
+    ///
+    /// LiteSVM derives each blockhash from the previous one, starting from the same genesis blockhash, and
+    /// the chain tip index counts the blockhashes derived since genesis: deriving them again from a fresh
+    /// SVM leads to the dumped chain tip.
+    #[allow(deprecated)]
+    fn restore_blockhashes(
+        &mut self,
+        chain_tip: &BlockIdentifier,
+        blocks: &BTreeMap<Slot, BlockHeader>,
+    ) -> SurfpoolResult<()> {
+        for _ in self.chain_tip.index..chain_tip.index {
+            self.inner.expire_blockhash();
+        }
+        if self.inner.latest_blockhash().to_string() != chain_tip.hash {
+            return Err(SurfpoolError::invalid_state_dump_chain_tip(
+                chain_tip.index,
+                &chain_tip.hash,
+            ));
+        }
+
+        let lamports_per_signature = self
+            .inner
+            .get_sysvar::<solana_sdk::sysvar::recent_blockhashes::RecentBlockhashes>()
+            .first()
+            .map(|entry| entry.fee_calculator.lamports_per_signature)
+            .unwrap_or_default();
+        let mut recent_blockhashes = Vec::with_capacity(MAX_RECENT_BLOCKHASHES);
+        for (slot, block) in blocks.iter().rev().take(MAX_RECENT_BLOCKHASHES) {
+            let blockhash = Hash::from_str(&block.hash)
+                .map_err(|e| SurfpoolError::deserialize_error("blockhash", e))?;
+            recent_blockhashes.push((*slot, blockhash));
+        }
+        if recent_blockhashes.is_empty() {
+            return Ok(());
+        }
+
+        self.inner.set_sysvar(
+            &solana_sdk::sysvar::recent_blockhashes::RecentBlockhashes::from_iter(
+                recent_blockhashes
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (_, blockhash))| {
+                        solana_sdk::sysvar::recent_blockhashes::IterItem(
+                            i as u64,
+                            blockhash,
+                            lamports_per_signature,
+                        )
+                    }),
+            ),
+        );
+        self.inner
+            .set_sysvar(&solana_sdk::sysvar::slot_hashes::SlotHashes::new(
+                &recent_blockhashes,
+            ));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dump_and_load_state() {
+        let (mut svm, _events_rx, _geyser_rx) = SurfnetSvm::new();
+
+        let payer = Pubkey::new_unique();
+        svm.airdrop(&payer, 1_000_000_000).unwrap();
+        svm.confirm_current_block().unwrap();
+        let data_account = Pubkey::new_unique();
+        svm.set_account(
+            &data_account,
+            Account {
+                lamports: 1_000_000,
+                data: vec![7; 64],
+                owner: Pubkey::new_unique(),
+                executable: false,
+                rent_epoch: 0,
+            },
+        )
+        .unwrap();
+
+        let dump = svm.dump_state().unwrap();
+        let serialized = serde_json::to_vec(&dump).unwrap();
+        let dump: SurfnetStateDump = serde_json::from_slice(&serialized).unwrap();
+
+        let (mut restored, _events_rx, _geyser_rx) = SurfnetSvm::new();
+        restored.load_state_dump(dump).unwrap();
+
+        assert_eq!(
+            restored.get_latest_absolute_slot(),
+            svm.get_latest_absolute_slot()
+        );
+        assert_eq!(restored.chain_tip, svm.chain_tip);
+        assert_eq!(restored.latest_blockhash(), svm.latest_blockhash());
+        assert!(restored.check_blockhash_is_recent(&svm.latest_blockhash()));
+        assert_eq!(restored.accounts_registry, svm.accounts_registry);
+        assert_eq!(
+            restored.inner.get_account(&data_account),
+            svm.inner.get_account(&data_account)
+        );
+        assert_eq!(restored.account_history, svm.account_history);
+        assert_eq!(restored.blocks.len(), svm.blocks.len());
+        assert_eq!(restored.transactions.len(), svm.transactions.len());
+        for (signature, status) in svm.transactions.iter() {
+            let restored_status = restored.transactions.get(signature).unwrap();
+            assert_eq!(
+                restored_status.expect_processed().meta,
+                status.expect_processed().meta
+            );
+        }
+    }
+
+    #[test]
+    fn test_state_dump_file_name() {
+        assert_eq!(state_dump_file_name("amm-pool").unwrap(), "amm-pool.json");
+        assert_eq!(
+            state_dump_file_name("pool_v1.json").unwrap(),
+            "pool_v1.json"
+        );
+        for name in [
+            "",
+            "../pool",
+            "/tmp/pool",
+            "fixtures/pool",
+            ".hidden",
+            "pool\\x",
+        ] {
+            assert!(state_dump_file_name(name).is_err(), "{name}");
+        }
+    }
+}
@@ -0,0 +1,268 @@
+//! Decoding of the Anchor events emitted by programs, with the IDLs registered on the surfnet.
+//!
+//! Anchor programs emit events either by logging them (`emit!`), which produces `Program data:`
+//! log lines, or through a self-CPI carrying the event in its instruction data (`emit_cpi!`).
+
+use base64::{Engine, prelude::BASE64_STANDARD};
+use solana_message::{
+    AccountKeys, VersionedMessage, compiled_instruction::CompiledInstruction, v0::LoadedAddresses,
+};
+use solana_pubkey::Pubkey;
+use solana_signature::Signature;
+use surfpool_types::AnchorEvent;
+use txtx_addon_network_svm_types::subgraph::idl::parse_bytes_to_value_with_expected_idl_type_def_ty;
+
+use super::svm::{SurfnetSvm, get_txtx_value_json_converters};
+use crate::types::SurfnetTransactionStatus;
+
+/// The tag prefixing the instruction data of the self-CPIs performed by `emit_cpi!`.
+pub const EVENT_IX_TAG_LE: [u8; 8] = 0x1d9acb512ea545e4u64.to_le_bytes();
+
+const PROGRAM_DATA_LOG_PREFIX: &str = "Program data: ";
+const ANCHOR_DISCRIMINATOR_LEN: usize = 8;
+
+impl SurfnetSvm {
+    /// Decodes the Anchor events emitted by a transaction, in the order they were emitted.
+    ///
+    /// `account_keys` are the account keys of the transaction, including the addresses loaded from
+    /// lookup tables, and `inner_instructions` the instructions invoked through CPIs, in the order
+    /// of their execution. Events of programs without a registered IDL are skipped.
+    pub fn decode_anchor_events(
+        &self,
+        account_keys: &[Pubkey],
+        logs: &[String],
+        inner_instructions: &[CompiledInstruction],
+    ) -> Vec<AnchorEvent> {
+        let mut events = vec![];
+        let mut invoke_stack: Vec<Pubkey> = vec![];
+        let mut inner_instructions = inner_instructions.iter();
+
+        for log in logs.iter() {
+            if let Some(data) = log.strip_prefix(PROGRAM_DATA_LOG_PREFIX) {
+                let Some(program_id) = invoke_stack.last() else {
+                    continue;
+                };
+                for field in data.split_whitespace() {
+                    if let Some(event) = BASE64_STANDARD
+                        .decode(field)
+                        .ok()
+                        .and_then(|data| self.decode_anchor_event(program_id, &data))
+                    {
+                        events.push(event);
+                    }
+                }
+                continue;
+            }
+
+            let Some(mut parts) = log
+                .strip_prefix("Program ")
+                .map(|rest| rest.split_whitespace())
+            else {
+                continue;
+            };
+            let (Some(program_id), Some(status)) = (
+                parts.next().and_then(|id| id.parse::<Pubkey>().ok()),
+                parts.next(),
+            ) else {
+                continue;
+            };
+            match status {
+                "invoke" => {
+                    // Every invocation past the top-level instructions is a CPI, recorded in order
+                    // in the inner instructions
+                    if !invoke_stack.is_empty() {
+                        if let Some(event) = inner_instructions.next().and_then(|instruction| {
+                            self.decode_anchor_cpi_event(account_keys, instruction)
+                        }) {
+                            events.push(event);
+                        }
+                    }
+                    invoke_stack.push(program_id);
+                }
+                "success" | "failed:" => {
+                    invoke_stack.pop();
+                }
+                _ => {}
+            }
+        }
+
+        // When the logs were truncated, the remaining CPIs can't be ordered relative to the logs
+        for instruction in inner_instructions {
+            if let Some(event) = self.decode_anchor_cpi_event(account_keys, instruction) {
+                events.push(event);
+            }
+        }
+        events
+    }
+
+    /// Decodes the Anchor events emitted by a transaction processed by the surfnet.
+    ///
+    /// Failed transactions didn't emit any event, as their effects were rolled back.
+    pub fn get_transaction_anchor_events(&self, signature: &Signature) -> Vec<AnchorEvent> {
+        let Some(SurfnetTransactionStatus::Processed(transaction)) =
+            self.transactions.get(signature)
+        else {
+            return vec![];
+        };
+        if transaction.meta.status.is_err() {
+            return vec![];
+        }
+
+        let account_keys = transaction_account_keys(
+            &transaction.transaction.message,
+            &transaction.meta.loaded_addresses,
+        );
+        let inner_instructions = transaction
+            .meta
+            .inner_instructions
+            .iter()
+            .flatten()
+            .flat_map(|inner_instructions| inner_instructions.instructions.iter())
+            .map(|inner_instruction| inner_instruction.instruction.clone())
+            .collect::<Vec<_>>();
+        self.decode_anchor_events(
+            &account_keys,
+            transaction.meta.log_messages.as_deref().unwrap_or_default(),
+            &inner_instructions,
+        )
+    }
+
+    fn decode_anchor_cpi_event(
+        &self,
+        account_keys: &[Pubkey],
+        instruction: &CompiledInstruction,
+    ) -> Option<AnchorEvent> {
+        let program_id = account_keys.get(instruction.program_id_index as usize)?;
+        let data = instruction.data.strip_prefix(&EVENT_IX_TAG_LE)?;
+        self.decode_anchor_event(program_id, data)
+    }
+
+    /// Decodes an event of `program_id` with the most recent of its registered IDLs that knows it.
+    fn decode_anchor_event(&self, program_id: &Pubkey, data: &[u8]) -> Option<AnchorEvent> {
+        if data.len() < ANCHOR_DISCRIMINATOR_LEN {
+            return None;
+        }
+        let (discriminator, rest) = data.split_at(ANCHOR_DISCRIMINATOR_LEN);
+
+        self.get_active_idls(program_id)
+            .into_iter()
+            .find_map(|idl| {
+                let event = idl
+                    .events
+                    .iter()
+                    .find(|event| event.discriminator.eq(&discriminator))?;
+                let event_type = idl.types.iter().find(|t| t.name == event.name)?;
+                let parsed_value = parse_bytes_to_value_with_expected_idl_type_def_ty(
+                    rest,
+                    &event_type.ty,
+                    &idl.types,
+                    &vec![],
+                    &event_type.generics,
+                )
+                .ok()?;
+                Some(AnchorEvent {
+                    program_id: program_id.to_string(),
+                    name: event.name.clone(),
+                    data: parsed_value.to_json(Some(&get_txtx_value_json_converters())),
+                })
+            })
+    }
+}
+
+/// Returns the account keys of a message, followed by the addresses it loaded from lookup tables,
+/// in the order used by the indexes of its instructions.
+pub fn transaction_account_keys(
+    message: &VersionedMessage,
+    loaded_addresses: &LoadedAddresses,
+) -> Vec<Pubkey> {
+    AccountKeys::new(message.static_account_keys(), Some(loaded_addresses))
+        .iter()
+        .copied()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use borsh::BorshSerialize;
+    use surfpool_types::Idl;
+
+    use super::*;
+
+    #[derive(BorshSerialize)]
+    struct SplitTransferEvent {
+        recipient_1_amount: u64,
+        recipient_1: Pubkey,
+        recipient_2_amount: u64,
+        recipient_2: Pubkey,
+        mint: Pubkey,
+        token_program_id: Pubkey,
+    }
+
+    #[test]
+    fn test_decode_anchor_events() {
+        let (mut svm, _events_rx, _geyser_rx) = SurfnetSvm::new();
+        let idl: Idl =
+            serde_json::from_slice(include_bytes!("../tests/assets/idl_v1.json")).unwrap();
+        svm.register_idl(idl.clone(), Some(0));
+        let program_id = Pubkey::from_str_const(&idl.address);
+
+        let recipient = Pubkey::new_unique();
+        let mut event_data = idl.events[0].discriminator.clone();
+        SplitTransferEvent {
+            recipient_1_amount: 10,
+            recipient_1: recipient,
+            recipient_2_amount: 20,
+            recipient_2: recipient,
+            mint: Pubkey::new_unique(),
+            token_program_id: Pubkey::new_unique(),
+        }
+        .serialize(&mut event_data)
+        .unwrap();
+
+        let account_keys = vec![Pubkey::new_unique(), program_id];
+        let cpi_data = [EVENT_IX_TAG_LE.as_slice(), &event_data].concat();
+        let logs = vec![
+            format!("Program {program_id} invoke [1]"),
+            format!(
+                "{PROGRAM_DATA_LOG_PREFIX}{}",
+                BASE64_STANDARD.encode(&event_data)
+            ),
+            format!("Program {program_id} invoke [2]"),
+            format!("Program {program_id} success"),
+            // events of programs without a registered IDL are ignored
+            format!("Program {} invoke [2]", account_keys[0]),
+            format!(
+                "{PROGRAM_DATA_LOG_PREFIX}{}",
+                BASE64_STANDARD.encode(&event_data)
+            ),
+            format!("Program {} success", account_keys[0]),
+            format!("Program {program_id} success"),
+        ];
+        let inner_instructions = vec![
+            CompiledInstruction::new_from_raw_parts(1, cpi_data, vec![]),
+            CompiledInstruction::new_from_raw_parts(0, vec![], vec![]),
+        ];
+
+        let events = svm.decode_anchor_events(&account_keys, &logs, &inner_instructions);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0], events[1]);
+        assert_eq!(events[0].program_id, program_id.to_string());
+        assert_eq!(events[0].name, "SplitTransferEvent");
+        assert_eq!(events[0].data["recipient_1_amount"], 10);
+        assert_eq!(events[0].data["recipient_2"], recipient.to_string());
+
+        // data not matching an event of the IDL is ignored
+        let logs = vec![
+            format!("Program {program_id} invoke [1]"),
+            format!(
+                "{PROGRAM_DATA_LOG_PREFIX}{}",
+                BASE64_STANDARD.encode([0; 16])
+            ),
+            format!("Program {program_id} success"),
+        ];
+        assert!(
+            svm.decode_anchor_events(&account_keys, &logs, &[])
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,217 @@
+use solana_message::VersionedMessage;
+use solana_pubkey::Pubkey;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk_ids::{
+    bpf_loader, bpf_loader_deprecated, bpf_loader_upgradeable, compute_budget, ed25519_program,
+    loader_v4, secp256k1_program, system_program, vote,
+};
+
+/// The base fee charged for each signature of a transaction.
+pub const LAMPORTS_PER_SIGNATURE: u64 = 5_000;
+/// The compute units allocated to each non-builtin instruction when no limit is requested.
+pub const DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT: u32 = 200_000;
+/// The compute units allocated to each builtin instruction when no limit is requested.
+pub const MAX_BUILTIN_ALLOCATION_COMPUTE_UNIT_LIMIT: u32 = 3_000;
+/// The maximum compute units a transaction can request.
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+const MICRO_LAMPORTS_PER_LAMPORT: u128 = 1_000_000;
+
+/// The compute budget requested by a message through the Compute Budget program.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ComputeBudgetRequest {
+    /// The compute unit limit, either requested with `SetComputeUnitLimit` or derived from the instructions
+    pub compute_unit_limit: u32,
+    /// The compute unit price requested with `SetComputeUnitPrice`, in micro-lamports
+    pub compute_unit_price: u64,
+}
+
+impl ComputeBudgetRequest {
+    pub fn from_message(message: &VersionedMessage) -> Self {
+        let account_keys = message.static_account_keys();
+        let mut requested_limit = None;
+        let mut requested_price = None;
+        let mut default_limit: u32 = 0;
+
+        for ix in message.instructions() {
+            let Some(program_id) = account_keys.get(ix.program_id_index as usize) else {
+                continue;
+            };
+            if *program_id == compute_budget::id() {
+                match borsh::from_slice::<ComputeBudgetInstruction>(&ix.data) {
+                    Ok(ComputeBudgetInstruction::SetComputeUnitLimit(limit)) => {
+                        requested_limit.get_or_insert(limit);
+                    }
+                    Ok(ComputeBudgetInstruction::SetComputeUnitPrice(price)) => {
+                        requested_price.get_or_insert(price);
+                    }
+                    _ => {}
+                }
+            }
+            let allocation = if is_builtin_program(program_id) {
+                MAX_BUILTIN_ALLOCATION_COMPUTE_UNIT_LIMIT
+            } else {
+                DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT
+            };
+            default_limit = default_limit.saturating_add(allocation);
+        }
+
+        Self {
+            compute_unit_limit: requested_limit
+                .unwrap_or(default_limit)
+                .min(MAX_COMPUTE_UNIT_LIMIT),
+            compute_unit_price: requested_price.unwrap_or_default(),
+        }
+    }
+
+    /// The priority fee, in lamports: the compute unit price times the compute unit limit, rounded up.
+    pub fn prioritization_fee(&self) -> u64 {
+        let micro_lamports = (self.compute_unit_price as u128)
+            .saturating_mul(self.compute_unit_limit as u128)
+            .saturating_add(MICRO_LAMPORTS_PER_LAMPORT - 1);
+        u64::try_from(micro_lamports / MICRO_LAMPORTS_PER_LAMPORT).unwrap_or(u64::MAX)
+    }
+}
+
+/// The fee charged to the fee payer of a transaction.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FeeDetails {
+    pub signature_fee: u64,
+    pub prioritization_fee: u64,
+}
+
+impl FeeDetails {
+    /// Computes the fee of a message like the runtime does: a base fee for each transaction and
+    /// precompile signature, plus the priority fee requested through the Compute Budget program.
+    pub fn from_message(message: &VersionedMessage, lamports_per_signature: u64) -> Self {
+        Self {
+            signature_fee: lamports_per_signature.saturating_mul(num_signatures(message)),
+            prioritization_fee: ComputeBudgetRequest::from_message(message).prioritization_fee(),
+        }
+    }
+
+    pub fn total_fee(&self) -> u64 {
+        self.signature_fee.saturating_add(self.prioritization_fee)
+    }
+}
+
+/// Counts the transaction signatures of a message, along with the signatures verified by the
+/// ed25519 and secp256k1 precompiles, which are charged as well.
+fn num_signatures(message: &VersionedMessage) -> u64 {
+    let account_keys = message.static_account_keys();
+    let precompile_signatures = message
+        .instructions()
+        .iter()
+        .filter(|ix| {
+            account_keys
+                .get(ix.program_id_index as usize)
+                .is_some_and(|id| *id == ed25519_program::id() || *id == secp256k1_program::id())
+        })
+        .map(|ix| ix.data.first().copied().unwrap_or_default() as u64)
+        .sum::<u64>();
+    (message.header().num_required_signatures as u64).saturating_add(precompile_signatures)
+}
+
+/// Builtins only reserve a minimal amount of compute units when no limit is requested.
+/// Builtins that have been migrated to BPF programs are excluded.
+fn is_builtin_program(program_id: &Pubkey) -> bool {
+    [
+        system_program::id(),
+        compute_budget::id(),
+        vote::id(),
+        bpf_loader::id(),
+        bpf_loader_deprecated::id(),
+        bpf_loader_upgradeable::id(),
+        loader_v4::id(),
+        ed25519_program::id(),
+        secp256k1_program::id(),
+    ]
+    .contains(program_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_hash::Hash;
+    use solana_keypair::Keypair;
+    use solana_message::{Message, v0};
+    use solana_sdk::instruction::{AccountMeta, Instruction};
+    use solana_signer::Signer;
+    use solana_system_interface::instruction as system_instruction;
+
+    use super::*;
+
+    fn legacy_message(payer: &Pubkey, instructions: &[Instruction]) -> VersionedMessage {
+        VersionedMessage::Legacy(Message::new(instructions, Some(payer)))
+    }
+
+    #[test]
+    fn test_base_fee_only() {
+        let payer = Keypair::new().pubkey();
+        let message = legacy_message(
+            &payer,
+            &[system_instruction::transfer(
+                &payer,
+                &Pubkey::new_unique(),
+                1,
+            )],
+        );
+        let fee = FeeDetails::from_message(&message, LAMPORTS_PER_SIGNATURE);
+        assert_eq!(fee.signature_fee, 5_000);
+        assert_eq!(fee.prioritization_fee, 0);
+        assert_eq!(fee.total_fee(), 5_000);
+    }
+
+    #[test]
+    fn test_priority_fee_with_requested_limit() {
+        let payer = Keypair::new().pubkey();
+        let other_signer = Keypair::new().pubkey();
+        let message = legacy_message(
+            &payer,
+            &[
+                ComputeBudgetInstruction::set_compute_unit_limit(300_000),
+                ComputeBudgetInstruction::set_compute_unit_price(10_001),
+                system_instruction::transfer(&payer, &other_signer, 1),
+                system_instruction::transfer(&other_signer, &payer, 1),
+            ],
+        );
+        let fee = FeeDetails::from_message(&message, LAMPORTS_PER_SIGNATURE);
+        assert_eq!(fee.signature_fee, 10_000);
+        // 10_001 micro-lamports * 300_000 CUs = 3_000.3 lamports, rounded up
+        assert_eq!(fee.prioritization_fee, 3_001);
+        assert_eq!(fee.total_fee(), 13_001);
+    }
+
+    #[test]
+    fn test_priority_fee_with_default_limit() {
+        let payer = Keypair::new().pubkey();
+        let program_id = Pubkey::new_unique();
+        let message = VersionedMessage::V0(
+            v0::Message::try_compile(
+                &payer,
+                &[
+                    ComputeBudgetInstruction::set_compute_unit_price(1_000_000),
+                    system_instruction::transfer(&payer, &Pubkey::new_unique(), 1),
+                    Instruction::new_with_bytes(
+                        program_id,
+                        &[],
+                        vec![AccountMeta::new(payer, true)],
+                    ),
+                ],
+                &[],
+                Hash::default(),
+            )
+            .unwrap(),
+        );
+        let budget = ComputeBudgetRequest::from_message(&message);
+        assert_eq!(budget.compute_unit_limit, 3_000 + 3_000 + 200_000);
+        assert_eq!(budget.prioritization_fee(), 206_000);
+
+        let many_instructions = (0..10)
+            .map(|_| Instruction::new_with_bytes(program_id, &[], vec![]))
+            .collect::<Vec<_>>();
+        let budget =
+            ComputeBudgetRequest::from_message(&legacy_message(&payer, &many_instructions));
+        assert_eq!(budget.compute_unit_limit, MAX_COMPUTE_UNIT_LIMIT);
+        assert_eq!(budget.prioritization_fee(), 0);
+    }
+}
@@ -0,0 +1,175 @@
+//! Decoding of the IDLs that programs publish on-chain, either in the IDL account of Anchor
+//! programs or in the `idl` metadata account of the Program Metadata program.
+
+use std::io::Read;
+
+use base64::{Engine, prelude::BASE64_STANDARD};
+use flate2::read::{GzDecoder, ZlibDecoder};
+use solana_pubkey::Pubkey;
+use surfpool_types::Idl;
+
+/// The program storing the metadata of programs, such as their IDL.
+pub const PROGRAM_METADATA_PROGRAM_ID: Pubkey =
+    Pubkey::from_str_const("ProgM6JCCvbYkfKqJYHePx4xxSUSqJp7rh8Lyv7nk7S");
+
+const ANCHOR_IDL_SEED: &str = "anchor:idl";
+/// Discriminator, authority and length of the compressed IDL.
+const ANCHOR_IDL_HEADER_LEN: usize = 8 + 32 + 4;
+
+const METADATA_IDL_SEED: &[u8; 16] = b"idl\0\0\0\0\0\0\0\0\0\0\0\0\0";
+const METADATA_ACCOUNT_DISCRIMINATOR: u8 = 2;
+const METADATA_HEADER_LEN: usize = 96;
+
+/// The maximum length of a decompressed IDL, so that a crafted account can't exhaust memory.
+const MAX_IDL_LEN: u64 = 16 * 1024 * 1024;
+
+/// Returns the address of the IDL account of an Anchor program.
+pub fn anchor_idl_address(program_id: &Pubkey) -> Pubkey {
+    let (base, _) = Pubkey::find_program_address(&[], program_id);
+    Pubkey::create_with_seed(&base, ANCHOR_IDL_SEED, program_id)
+        .expect("the Anchor IDL seed is valid")
+}
+
+/// Returns the address of the canonical `idl` metadata account of a program, whose authority is
+/// the upgrade authority of the program.
+pub fn metadata_idl_address(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[program_id.as_ref(), METADATA_IDL_SEED],
+        &PROGRAM_METADATA_PROGRAM_ID,
+    )
+    .0
+}
+
+/// Decodes the zlib compressed IDL stored in the IDL account of an Anchor program.
+///
+/// IDLs predating the Anchor 0.30 format are not supported.
+pub fn decode_anchor_idl_account(program_id: &Pubkey, data: &[u8]) -> Option<Idl> {
+    let len_bytes = data.get(ANCHOR_IDL_HEADER_LEN - 4..ANCHOR_IDL_HEADER_LEN)?;
+    let len = u32::from_le_bytes(len_bytes.try_into().ok()?) as usize;
+    let compressed = data.get(ANCHOR_IDL_HEADER_LEN..ANCHOR_IDL_HEADER_LEN + len)?;
+
+    let json = decompress(ZlibDecoder::new(compressed))?;
+    parse_idl(program_id, &json)
+}
+
+/// Decodes the IDL stored in a metadata account of the Program Metadata program.
+///
+/// Only JSON IDLs whose data is stored in the account itself are supported; IDLs stored at a URL or
+/// in another account are skipped.
+pub fn decode_metadata_idl_account(program_id: &Pubkey, data: &[u8]) -> Option<Idl> {
+    let header = data.get(..METADATA_HEADER_LEN)?;
+    let (discriminator, encoding, compression, format, data_source) =
+        (header[0], header[83], header[84], header[85], header[86]);
+    // The format must be JSON, and the data source the account itself
+    if discriminator != METADATA_ACCOUNT_DISCRIMINATOR || format != 1 || data_source != 0 {
+        return None;
+    }
+    let len = u32::from_le_bytes(header[87..91].try_into().ok()?) as usize;
+    let raw = data.get(METADATA_HEADER_LEN..METADATA_HEADER_LEN + len)?;
+
+    let decoded = match encoding {
+        0 | 1 => raw.to_vec(),
+        2 => bs58::decode(raw).into_vec().ok()?,
+        3 => BASE64_STANDARD.decode(raw).ok()?,
+        _ => return None,
+    };
+    let json = match compression {
+        0 => decoded,
+        1 => decompress(GzDecoder::new(decoded.as_slice()))?,
+        2 => decompress(ZlibDecoder::new(decoded.as_slice()))?,
+        _ => return None,
+    };
+    parse_idl(program_id, &json)
+}
+
+/// Reads a decompressed IDL, returning `None` if it is longer than [MAX_IDL_LEN].
+fn decompress(decoder: impl Read) -> Option<Vec<u8>> {
+    let mut json = vec![];
+    decoder.take(MAX_IDL_LEN + 1).read_to_end(&mut json).ok()?;
+    (json.len() as u64 <= MAX_IDL_LEN).then_some(json)
+}
+
+fn parse_idl(program_id: &Pubkey, json: &[u8]) -> Option<Idl> {
+    let mut idl: Idl = serde_json::from_slice(json).ok()?;
+    // The address of the IDL must be the program it's registered for
+    idl.address = program_id.to_string();
+    Some(idl)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::{Compression, write::ZlibEncoder};
+
+    use super::*;
+
+    const IDL_JSON: &[u8] = include_bytes!("../tests/assets/idl_v1.json");
+
+    fn zlib(data: &[u8]) -> Vec<u8> {
+        let mut encoder = ZlibEncoder::new(vec![], Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_decode_anchor_idl_account() {
+        let program_id = Pubkey::new_unique();
+        let compressed = zlib(IDL_JSON);
+        let data = [
+            vec![0; 8],
+            Pubkey::new_unique().to_bytes().to_vec(),
+            (compressed.len() as u32).to_le_bytes().to_vec(),
+            compressed,
+            // IDL accounts are allocated with extra space
+            vec![0; 64],
+        ]
+        .concat();
+
+        let idl = decode_anchor_idl_account(&program_id, &data).unwrap();
+        assert_eq!(idl.address, program_id.to_string());
+        assert_eq!(idl.metadata.name, "split_token_transfer");
+
+        assert!(decode_anchor_idl_account(&program_id, &data[..60]).is_none());
+    }
+
+    #[test]
+    fn test_decode_metadata_idl_account() {
+        let program_id = Pubkey::new_unique();
+        let compressed = zlib(IDL_JSON);
+        let mut header = vec![0; METADATA_HEADER_LEN];
+        header[0] = METADATA_ACCOUNT_DISCRIMINATOR;
+        header[1..33].copy_from_slice(program_id.as_ref());
+        header[67..83].copy_from_slice(METADATA_IDL_SEED);
+        header[84] = 2; // zlib
+        header[85] = 1; // json
+        header[87..91].copy_from_slice(&(compressed.len() as u32).to_le_bytes());
+        let data = [header.clone(), compressed].concat();
+
+        let idl = decode_metadata_idl_account(&program_id, &data).unwrap();
+        assert_eq!(idl.address, program_id.to_string());
+        assert_eq!(idl.metadata.name, "split_token_transfer");
+
+        // IDLs stored at a URL are skipped
+        let mut url_data = data.clone();
+        url_data[86] = 1;
+        assert!(decode_metadata_idl_account(&program_id, &url_data).is_none());
+
+        // uncompressed, utf8 encoded IDLs
+        header[83] = 1;
+        header[84] = 0;
+        header[87..91].copy_from_slice(&(IDL_JSON.len() as u32).to_le_bytes());
+        let data = [header, IDL_JSON.to_vec()].concat();
+        assert!(decode_metadata_idl_account(&program_id, &data).is_some());
+    }
+
+    #[test]
+    fn test_decompress_max_idl_len() {
+        let json = decompress(ZlibDecoder::new(zlib(IDL_JSON).as_slice())).unwrap();
+        assert_eq!(json, IDL_JSON);
+
+        // A few KBs can decompress to an arbitrary length
+        let bomb = zlib(&vec![b' '; MAX_IDL_LEN as usize + 1]);
+        assert!(decompress(ZlibDecoder::new(bomb.as_slice())).is_none());
+    }
+}
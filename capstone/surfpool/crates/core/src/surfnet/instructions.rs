@@ -0,0 +1,692 @@
+//! Building of program instructions from the IDLs registered on the surfnet.
+//!
+//! The arguments of an instruction are serialized from JSON with the types declared by the IDL,
+//! and the accounts that are not provided are resolved from their constant address or, for PDAs,
+//! derived from their seeds.
+
+use std::{collections::HashMap, str::FromStr};
+
+use anchor_lang_idl::types::{
+    IdlArrayLen, IdlDefinedFields, IdlInstruction, IdlInstructionAccount,
+    IdlInstructionAccountItem, IdlPda, IdlSeed, IdlType, IdlTypeDef, IdlTypeDefTy,
+};
+use convert_case::{Case, Casing};
+use serde_json::Value;
+use solana_pubkey::Pubkey;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use surfpool_types::Idl;
+use txtx_addon_network_svm_types::subgraph::idl::parse_bytes_to_value_with_expected_idl_type_def_ty;
+
+use super::svm::{SurfnetSvm, get_txtx_value_json_converters};
+use crate::error::{SurfpoolError, SurfpoolResult};
+
+impl SurfnetSvm {
+    /// Builds an instruction of `program_id` with the most recent IDL registered for the program.
+    ///
+    /// `args` is a JSON object holding the arguments of the instruction by name, and `accounts`
+    /// maps account names to their addresses. The accounts that are not provided are resolved from
+    /// the IDL when they have a constant address or are PDAs whose seeds are known. Missing optional
+    /// accounts are replaced with `program_id`, as Anchor clients do.
+    pub fn build_idl_instruction(
+        &self,
+        program_id: &Pubkey,
+        instruction_name: &str,
+        args: &Value,
+        accounts: &HashMap<String, Pubkey>,
+    ) -> SurfpoolResult<Instruction> {
+        let idl = self
+            .get_active_idls(program_id)
+            .into_iter()
+            .next()
+            .ok_or(SurfpoolError::idl_not_found(*program_id))?;
+        let instruction = idl
+            .instructions
+            .iter()
+            .find(|ix| {
+                ix.name == instruction_name || ix.name.to_case(Case::Camel) == instruction_name
+            })
+            .ok_or_else(|| {
+                SurfpoolError::idl_instruction_not_found(*program_id, instruction_name)
+            })?;
+        let invalid =
+            |reason: String| SurfpoolError::invalid_instruction(&instruction.name, reason);
+
+        let mut data = instruction.discriminator.clone();
+        for arg in instruction.args.iter() {
+            let value = get_value_field(args, &arg.name, &arg.ty)
+                .ok_or_else(|| invalid(format!("missing argument {}", arg.name)))?;
+            encode_idl_value(value, &arg.ty, &idl.types, &mut data)
+                .map_err(|e| invalid(format!("invalid argument {}: {e}", arg.name)))?;
+        }
+
+        let idl_accounts = flatten_accounts(&instruction.accounts);
+        let mut resolved = idl_accounts
+            .iter()
+            .filter_map(|account| {
+                let pubkey = get_account_address(accounts, &account.name)?;
+                Some((account.name.clone(), pubkey))
+            })
+            .collect::<HashMap<_, _>>();
+        // Accounts can be derived from other accounts: resolve them until no more progress is made
+        loop {
+            let mut progress = false;
+            for account in idl_accounts.iter() {
+                if resolved.contains_key(&account.name) {
+                    continue;
+                }
+                let address = match (&account.address, &account.pda) {
+                    (Some(address), _) => Some(Pubkey::from_str(address).map_err(|e| {
+                        invalid(format!("invalid address of account {}: {e}", account.name))
+                    })?),
+                    (None, Some(pda)) => self
+                        .resolve_pda(idl, instruction, program_id, pda, args, &resolved)
+                        .map_err(|e| {
+                            invalid(format!("unable to derive account {}: {e}", account.name))
+                        })?,
+                    (None, None) => None,
+                };
+                if let Some(address) = address {
+                    resolved.insert(account.name.clone(), address);
+                    progress = true;
+                }
+            }
+            if !progress {
+                break;
+            }
+        }
+
+        let accounts = idl_accounts
+            .iter()
+            .map(|account| match resolved.get(&account.name) {
+                Some(pubkey) => Ok(AccountMeta {
+                    pubkey: *pubkey,
+                    is_signer: account.signer,
+                    is_writable: account.writable,
+                }),
+                None if account.optional => Ok(AccountMeta::new_readonly(*program_id, false)),
+                None => Err(invalid(format!("missing account {}", account.name))),
+            })
+            .collect::<SurfpoolResult<Vec<_>>>()?;
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts,
+            data,
+        })
+    }
+
+    /// Derives the address of a PDA, or returns `None` if some of its seeds are not known yet.
+    fn resolve_pda(
+        &self,
+        idl: &Idl,
+        instruction: &IdlInstruction,
+        program_id: &Pubkey,
+        pda: &IdlPda,
+        args: &Value,
+        resolved: &HashMap<String, Pubkey>,
+    ) -> Result<Option<Pubkey>, String> {
+        let mut seeds = vec![];
+        for seed in pda.seeds.iter() {
+            let Some(seed) = self.resolve_seed(idl, instruction, seed, args, resolved)? else {
+                return Ok(None);
+            };
+            seeds.push(seed);
+        }
+        let pda_program_id = match &pda.program {
+            Some(seed) => {
+                let Some(program_id) = self.resolve_seed(idl, instruction, seed, args, resolved)?
+                else {
+                    return Ok(None);
+                };
+                Pubkey::try_from(program_id.as_slice())
+                    .map_err(|_| "the program seed is not a public key".to_string())?
+            }
+            None => *program_id,
+        };
+        let seeds = seeds.iter().map(Vec::as_slice).collect::<Vec<_>>();
+        Ok(Some(
+            Pubkey::find_program_address(&seeds, &pda_program_id).0,
+        ))
+    }
+
+    /// Returns the bytes of a PDA seed, or `None` if the account it refers to is not known yet.
+    fn resolve_seed(
+        &self,
+        idl: &Idl,
+        instruction: &IdlInstruction,
+        seed: &IdlSeed,
+        args: &Value,
+        resolved: &HashMap<String, Pubkey>,
+    ) -> Result<Option<Vec<u8>>, String> {
+        match seed {
+            IdlSeed::Const(seed) => Ok(Some(seed.value.clone())),
+            IdlSeed::Arg(seed) => {
+                let (name, path) = split_path(&seed.path);
+                let arg = instruction
+                    .args
+                    .iter()
+                    .find(|arg| arg.name == name)
+                    .ok_or_else(|| format!("unknown argument {name}"))?;
+                let value =
+                    get_field(args, name).ok_or_else(|| format!("missing argument {name}"))?;
+                let (value, ty) = get_path(value, &arg.ty, path, &idl.types)?;
+                seed_bytes(value, &ty, &idl.types).map(Some)
+            }
+            IdlSeed::Account(seed) => {
+                let (name, path) = split_path(&seed.path);
+                let Some(pubkey) = resolved.get(name) else {
+                    return Ok(None);
+                };
+                if path.is_empty() {
+                    return Ok(Some(pubkey.to_bytes().to_vec()));
+                }
+
+                // The seed is a field of the data of the account
+                let account_name = seed
+                    .account
+                    .as_ref()
+                    .ok_or_else(|| format!("the type of account {name} is unknown"))?;
+                let idl_account = idl
+                    .accounts
+                    .iter()
+                    .find(|account| &account.name == account_name)
+                    .ok_or_else(|| format!("unknown account type {account_name}"))?;
+                let type_def = find_type_def(account_name, &idl.types)?;
+                let account = self
+                    .inner
+                    .get_account(pubkey)
+                    .ok_or_else(|| format!("account {name} ({pubkey}) not found"))?;
+                let data = account
+                    .data
+                    .strip_prefix(idl_account.discriminator.as_slice())
+                    .ok_or_else(|| format!("account {name} ({pubkey}) is not a {account_name}"))?;
+                let value = parse_bytes_to_value_with_expected_idl_type_def_ty(
+                    data,
+                    &type_def.ty,
+                    &idl.types,
+                    &vec![],
+                    &type_def.generics,
+                )
+                .map_err(|_| format!("unable to decode account {name} ({pubkey})"))?
+                .to_json(Some(&get_txtx_value_json_converters()));
+                let ty = IdlType::Defined {
+                    name: account_name.clone(),
+                    generics: vec![],
+                };
+                let (value, ty) = get_path(&value, &ty, path, &idl.types)?;
+                seed_bytes(value, &ty, &idl.types).map(Some)
+            }
+        }
+    }
+}
+
+/// Returns the accounts of an instruction, with the accounts of composite items inlined.
+fn flatten_accounts(items: &[IdlInstructionAccountItem]) -> Vec<&IdlInstructionAccount> {
+    items
+        .iter()
+        .flat_map(|item| match item {
+            IdlInstructionAccountItem::Single(account) => vec![account],
+            IdlInstructionAccountItem::Composite(composite) => {
+                flatten_accounts(&composite.accounts)
+            }
+        })
+        .collect()
+}
+
+fn get_account_address(accounts: &HashMap<String, Pubkey>, name: &str) -> Option<Pubkey> {
+    accounts
+        .get(name)
+        .or_else(|| accounts.get(&name.to_case(Case::Camel)))
+        .copied()
+}
+
+/// Returns the field `name` of a JSON object, looked up by its IDL name or in camel case.
+fn get_field<'a>(value: &'a Value, name: &str) -> Option<&'a Value> {
+    value
+        .get(name)
+        .or_else(|| value.get(name.to_case(Case::Camel)))
+}
+
+/// Returns the value of an argument or a field, a missing optional one being `null`.
+fn get_value_field<'a>(value: &'a Value, name: &str, ty: &IdlType) -> Option<&'a Value> {
+    static NULL: Value = Value::Null;
+    get_field(value, name).or(matches!(ty, IdlType::Option(_)).then_some(&NULL))
+}
+
+/// Splits a seed path such as `escrow.maker` into the name of the argument or account and the path
+/// of the field within it.
+fn split_path(path: &str) -> (&str, &str) {
+    path.split_once('.').unwrap_or((path, ""))
+}
+
+/// Follows the path of a field within a struct, returning the value and type of the field.
+fn get_path<'a>(
+    value: &'a Value,
+    ty: &IdlType,
+    path: &str,
+    types: &[IdlTypeDef],
+) -> Result<(&'a Value, IdlType), String> {
+    let mut value = value;
+    let mut ty = ty.clone();
+    for field_name in path.split('.').filter(|name| !name.is_empty()) {
+        let IdlType::Defined { name, .. } = &ty else {
+            return Err(format!("{field_name} is not a field of a struct"));
+        };
+        let IdlTypeDefTy::Struct {
+            fields: Some(IdlDefinedFields::Named(fields)),
+        } = &find_type_def(name, types)?.ty
+        else {
+            return Err(format!("{name} has no field {field_name}"));
+        };
+        let field = fields
+            .iter()
+            .find(|field| field.name == field_name)
+            .ok_or_else(|| format!("{name} has no field {field_name}"))?;
+        value =
+            get_field(value, &field.name).ok_or_else(|| format!("missing field {field_name}"))?;
+        ty = field.ty.clone();
+    }
+    Ok((value, ty))
+}
+
+/// Returns the bytes of a seed value: strings and bytes are used as is, other values are borsh
+/// serialized.
+fn seed_bytes(value: &Value, ty: &IdlType, types: &[IdlTypeDef]) -> Result<Vec<u8>, String> {
+    let mut bytes = vec![];
+    encode_idl_value(value, ty, types, &mut bytes)?;
+    match ty {
+        // Strip the length prefix
+        IdlType::String | IdlType::Bytes => Ok(bytes.split_off(4)),
+        _ => Ok(bytes),
+    }
+}
+
+fn find_type_def<'a>(name: &str, types: &'a [IdlTypeDef]) -> Result<&'a IdlTypeDef, String> {
+    types
+        .iter()
+        .find(|type_def| type_def.name == name)
+        .ok_or_else(|| format!("unknown type {name}"))
+}
+
+fn expected(kind: &str, value: &Value) -> String {
+    format!("expected {kind}, found {value}")
+}
+
+/// Parses a number given either as a JSON number or as a string, for integers that don't fit in
+/// JSON numbers.
+fn parse_number<T: FromStr>(value: &Value) -> Result<T, String> {
+    let number = match value {
+        Value::Number(number) => number.to_string(),
+        Value::String(number) => number.clone(),
+        _ => return Err(expected("a number", value)),
+    };
+    number
+        .parse()
+        .map_err(|_| expected("a valid number", value))
+}
+
+fn as_array<'a>(value: &'a Value) -> Result<&'a Vec<Value>, String> {
+    value.as_array().ok_or_else(|| expected("an array", value))
+}
+
+/// Serializes a JSON value to borsh with its IDL type.
+fn encode_idl_value(
+    value: &Value,
+    ty: &IdlType,
+    types: &[IdlTypeDef],
+    out: &mut Vec<u8>,
+) -> Result<(), String> {
+    match ty {
+        IdlType::Bool => out.push(
+            value
+                .as_bool()
+                .ok_or_else(|| expected("a boolean", value))? as u8,
+        ),
+        IdlType::U8 => out.extend(parse_number::<u8>(value)?.to_le_bytes()),
+        IdlType::I8 => out.extend(parse_number::<i8>(value)?.to_le_bytes()),
+        IdlType::U16 => out.extend(parse_number::<u16>(value)?.to_le_bytes()),
+        IdlType::I16 => out.extend(parse_number::<i16>(value)?.to_le_bytes()),
+        IdlType::U32 => out.extend(parse_number::<u32>(value)?.to_le_bytes()),
+        IdlType::I32 => out.extend(parse_number::<i32>(value)?.to_le_bytes()),
+        IdlType::F32 => out.extend(parse_number::<f32>(value)?.to_le_bytes()),
+        IdlType::U64 => out.extend(parse_number::<u64>(value)?.to_le_bytes()),
+        IdlType::I64 => out.extend(parse_number::<i64>(value)?.to_le_bytes()),
+        IdlType::F64 => out.extend(parse_number::<f64>(value)?.to_le_bytes()),
+        IdlType::U128 => out.extend(parse_number::<u128>(value)?.to_le_bytes()),
+        IdlType::I128 => out.extend(parse_number::<i128>(value)?.to_le_bytes()),
+        IdlType::Bytes => {
+            let bytes = as_array(value)?
+                .iter()
+                .map(parse_number::<u8>)
+                .collect::<Result<Vec<_>, _>>()?;
+            out.extend((bytes.len() as u32).to_le_bytes());
+            out.extend(bytes);
+        }
+        IdlType::String => {
+            let string = value.as_str().ok_or_else(|| expected("a string", value))?;
+            out.extend((string.len() as u32).to_le_bytes());
+            out.extend(string.as_bytes());
+        }
+        IdlType::Pubkey => {
+            let pubkey = value
+                .as_str()
+                .and_then(|pubkey| Pubkey::from_str(pubkey).ok())
+                .ok_or_else(|| expected("a base-58 public key", value))?;
+            out.extend(pubkey.to_bytes());
+        }
+        IdlType::Option(inner) => {
+            if value.is_null() {
+                out.push(0);
+            } else {
+                out.push(1);
+                encode_idl_value(value, inner, types, out)?;
+            }
+        }
+        IdlType::Vec(inner) => {
+            let items = as_array(value)?;
+            out.extend((items.len() as u32).to_le_bytes());
+            for item in items.iter() {
+                encode_idl_value(item, inner, types, out)?;
+            }
+        }
+        IdlType::Array(inner, IdlArrayLen::Value(len)) => {
+            let items = as_array(value)?;
+            if items.len() != *len {
+                return Err(expected(&format!("an array of {len} items"), value));
+            }
+            for item in items.iter() {
+                encode_idl_value(item, inner, types, out)?;
+            }
+        }
+        IdlType::Defined { name, generics } if generics.is_empty() => {
+            encode_type_def(value, &find_type_def(name, types)?.ty, types, out)?;
+        }
+        _ => return Err(format!("unsupported type {ty:?}")),
+    }
+    Ok(())
+}
+
+fn encode_type_def(
+    value: &Value,
+    ty: &IdlTypeDefTy,
+    types: &[IdlTypeDef],
+    out: &mut Vec<u8>,
+) -> Result<(), String> {
+    match ty {
+        IdlTypeDefTy::Struct { fields } => encode_fields(value, fields.as_ref(), types, out),
+        IdlTypeDefTy::Enum { variants } => {
+            // Variants are given by name, or as an object holding the fields of the variant
+            let (name, fields_value) = match value {
+                Value::String(name) => (name.as_str(), None),
+                Value::Object(map) if map.len() == 1 => {
+                    let (name, fields) = map.iter().next().unwrap();
+                    (name.as_str(), Some(fields))
+                }
+                _ => return Err(expected("an enum variant", value)),
+            };
+            let (index, variant) = variants
+                .iter()
+                .enumerate()
+                .find(|(_, variant)| {
+                    variant.name == name || variant.name.to_case(Case::Camel) == name
+                })
+                .ok_or_else(|| format!("unknown variant {name}"))?;
+            out.push(index as u8);
+            match (&variant.fields, fields_value) {
+                (None, _) => Ok(()),
+                (Some(fields), Some(value)) => encode_fields(value, Some(fields), types, out),
+                (Some(_), None) => Err(format!("missing fields of variant {name}")),
+            }
+        }
+        IdlTypeDefTy::Type { alias } => encode_idl_value(value, alias, types, out),
+    }
+}
+
+/// Serializes the fields of a struct or enum variant: named fields are given as a JSON object,
+/// tuple fields as an array.
+fn encode_fields(
+    value: &Value,
+    fields: Option<&IdlDefinedFields>,
+    types: &[IdlTypeDef],
+    out: &mut Vec<u8>,
+) -> Result<(), String> {
+    match fields {
+        None => Ok(()),
+        Some(IdlDefinedFields::Named(fields)) => {
+            for field in fields.iter() {
+                let field_value = get_value_field(value, &field.name, &field.ty)
+                    .ok_or_else(|| format!("missing field {}", field.name))?;
+                encode_idl_value(field_value, &field.ty, types, out)?;
+            }
+            Ok(())
+        }
+        Some(IdlDefinedFields::Tuple(tys)) => {
+            let items = as_array(value)?;
+            if items.len() != tys.len() {
+                return Err(expected(&format!("an array of {} items", tys.len()), value));
+            }
+            for (item, ty) in items.iter().zip(tys.iter()) {
+                encode_idl_value(item, ty, types, out)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_account::Account;
+
+    use super::*;
+
+    fn escrow_idl() -> Idl {
+        serde_json::from_value(serde_json::json!({
+            "address": "2e6tZJbYevCm3jhgjbSFbmVbuDfErmMz32sMGSzZHgiN",
+            "metadata": { "name": "escrow", "version": "0.1.0", "spec": "0.1.0" },
+            "instructions": [
+                {
+                    "name": "make_offer",
+                    "discriminator": [1, 2, 3, 4, 5, 6, 7, 8],
+                    "accounts": [
+                        { "name": "maker", "writable": true, "signer": true },
+                        {
+                            "name": "escrow",
+                            "writable": true,
+                            "pda": {
+                                "seeds": [
+                                    { "kind": "const", "value": [101, 115, 99, 114, 111, 119] },
+                                    { "kind": "account", "path": "maker" },
+                                    { "kind": "arg", "path": "seed" }
+                                ]
+                            }
+                        },
+                        { "name": "system_program", "address": "11111111111111111111111111111111" }
+                    ],
+                    "args": [
+                        { "name": "seed", "type": "u64" },
+                        { "name": "amount", "type": "u64" },
+                        { "name": "side", "type": { "defined": { "name": "Side" } } },
+                        { "name": "memo", "type": { "option": "string" } }
+                    ]
+                },
+                {
+                    "name": "take_offer",
+                    "discriminator": [8, 7, 6, 5, 4, 3, 2, 1],
+                    "accounts": [
+                        { "name": "taker", "signer": true },
+                        { "name": "escrow", "writable": true },
+                        {
+                            "name": "vault",
+                            "writable": true,
+                            "pda": {
+                                "seeds": [
+                                    { "kind": "const", "value": [118, 97, 117, 108, 116] },
+                                    { "kind": "account", "path": "escrow.seed", "account": "Escrow" }
+                                ]
+                            }
+                        },
+                        { "name": "referrer", "optional": true }
+                    ],
+                    "args": []
+                }
+            ],
+            "accounts": [{ "name": "Escrow", "discriminator": [9, 9, 9, 9, 9, 9, 9, 9] }],
+            "types": [
+                {
+                    "name": "Escrow",
+                    "type": {
+                        "kind": "struct",
+                        "fields": [
+                            { "name": "seed", "type": "u64" },
+                            { "name": "maker", "type": "pubkey" }
+                        ]
+                    }
+                },
+                {
+                    "name": "Side",
+                    "type": {
+                        "kind": "enum",
+                        "variants": [
+                            { "name": "Ask" },
+                            { "name": "Bid", "fields": [{ "name": "price", "type": "u64" }] }
+                        ]
+                    }
+                }
+            ]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_build_idl_instruction() {
+        let (mut svm, _events_rx, _geyser_rx) = SurfnetSvm::new();
+        let idl = escrow_idl();
+        svm.register_idl(idl.clone(), Some(0));
+        let program_id = Pubkey::from_str_const(&idl.address);
+        let maker = Pubkey::new_unique();
+
+        let instruction = svm
+            .build_idl_instruction(
+                &program_id,
+                "makeOffer",
+                &serde_json::json!({
+                    "seed": 7,
+                    "amount": "1000",
+                    "side": { "Bid": { "price": 5 } },
+                    "memo": "hi"
+                }),
+                &HashMap::from([("maker".to_string(), maker)]),
+            )
+            .unwrap();
+        let escrow = Pubkey::find_program_address(
+            &[b"escrow", maker.as_ref(), &7u64.to_le_bytes()],
+            &program_id,
+        )
+        .0;
+        assert_eq!(
+            instruction.accounts,
+            vec![
+                AccountMeta::new(maker, true),
+                AccountMeta::new(escrow, false),
+                AccountMeta::new_readonly(Pubkey::default(), false),
+            ]
+        );
+        let data = [
+            vec![1, 2, 3, 4, 5, 6, 7, 8],
+            7u64.to_le_bytes().to_vec(),
+            1000u64.to_le_bytes().to_vec(),
+            vec![1],
+            5u64.to_le_bytes().to_vec(),
+            vec![1, 2, 0, 0, 0],
+            b"hi".to_vec(),
+        ]
+        .concat();
+        assert_eq!(instruction.data, data);
+
+        // missing optional arguments are `None`
+        let instruction = svm
+            .build_idl_instruction(
+                &program_id,
+                "make_offer",
+                &serde_json::json!({ "seed": 7, "amount": 1000, "side": "Ask" }),
+                &HashMap::from([("maker".to_string(), maker)]),
+            )
+            .unwrap();
+        let data = [
+            vec![1, 2, 3, 4, 5, 6, 7, 8],
+            7u64.to_le_bytes().to_vec(),
+            1000u64.to_le_bytes().to_vec(),
+            vec![0],
+            vec![0],
+        ]
+        .concat();
+        assert_eq!(instruction.data, data);
+
+        // seeds can be read from the data of other accounts
+        let escrow_data = [
+            vec![9; 8],
+            7u64.to_le_bytes().to_vec(),
+            maker.to_bytes().to_vec(),
+        ]
+        .concat();
+        svm.set_account(
+            &escrow,
+            Account {
+                lamports: 1_000_000,
+                data: escrow_data,
+                owner: program_id,
+                executable: false,
+                rent_epoch: 0,
+            },
+        )
+        .unwrap();
+        let taker = Pubkey::new_unique();
+        let instruction = svm
+            .build_idl_instruction(
+                &program_id,
+                "take_offer",
+                &serde_json::json!({}),
+                &HashMap::from([("taker".to_string(), taker), ("escrow".to_string(), escrow)]),
+            )
+            .unwrap();
+        let vault = Pubkey::find_program_address(&[b"vault", &7u64.to_le_bytes()], &program_id).0;
+        assert_eq!(
+            instruction.accounts,
+            vec![
+                AccountMeta::new_readonly(taker, true),
+                AccountMeta::new(escrow, false),
+                AccountMeta::new(vault, false),
+                AccountMeta::new_readonly(program_id, false),
+            ]
+        );
+
+        // required accounts and arguments must be provided
+        assert!(
+            svm.build_idl_instruction(
+                &program_id,
+                "take_offer",
+                &serde_json::json!({}),
+                &HashMap::from([("taker".to_string(), taker)]),
+            )
+            .is_err()
+        );
+        assert!(
+            svm.build_idl_instruction(
+                &program_id,
+                "make_offer",
+                &serde_json::json!({ "seed": 7 }),
+                &HashMap::from([("maker".to_string(), maker)]),
+            )
+            .is_err()
+        );
+        assert!(
+            svm.build_idl_instruction(
+                &program_id,
+                "unknown",
+                &serde_json::json!({}),
+                &HashMap::new(),
+            )
+            .is_err()
+        );
+    }
+}
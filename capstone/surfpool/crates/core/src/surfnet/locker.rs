@@ -1,5 +1,6 @@
 use std::{
     collections::{BTreeMap, HashMap, HashSet},
+    path::{Path, PathBuf},
     sync::Arc,
 };
 
@@ -19,20 +20,22 @@ use solana_account_decoder::{
 use solana_address_lookup_table_interface::state::AddressLookupTable;
 use solana_client::{
     rpc_config::{
-        RpcAccountInfoConfig, RpcBlockConfig, RpcLargestAccountsConfig, RpcLargestAccountsFilter,
+        RpcAccountInfoConfig, RpcBlockConfig, RpcBlockSubscribeConfig, RpcBlockSubscribeFilter,
+        RpcLargestAccountsConfig, RpcLargestAccountsFilter, RpcProgramAccountsConfig,
         RpcSignaturesForAddressConfig, RpcTransactionConfig, RpcTransactionLogsFilter,
     },
     rpc_filter::RpcFilterType,
     rpc_request::TokenAccountsFilter,
     rpc_response::{
-        RpcAccountBalance, RpcConfirmedTransactionStatusWithSignature, RpcKeyedAccount,
-        RpcLogsResponse, RpcTokenAccountBalance,
+        RpcAccountBalance, RpcBlockUpdate, RpcConfirmedTransactionStatusWithSignature,
+        RpcKeyedAccount, RpcLogsResponse, RpcTokenAccountBalance,
     },
 };
 use solana_clock::{Clock, Slot};
 use solana_commitment_config::{CommitmentConfig, CommitmentLevel};
 use solana_epoch_info::EpochInfo;
 use solana_hash::Hash;
+use solana_keypair::Keypair;
 use solana_message::{
     Message, MessageHeader, SimpleAddressLoader, VersionedMessage,
     v0::{LoadedAddresses, MessageAddressTableLookup},
@@ -45,17 +48,21 @@ use solana_sdk::{
     transaction::{SanitizedTransaction, VersionedTransaction},
 };
 use solana_signature::Signature;
+use solana_signer::Signer;
 use solana_transaction::Transaction;
 use solana_transaction_error::TransactionError;
 use solana_transaction_status::{
     EncodedConfirmedTransactionWithStatusMeta,
     TransactionConfirmationStatus as SolanaTransactionConfirmationStatus, UiConfirmedBlock,
-    UiTransactionEncoding,
+    UiTransactionEncoding, UiTransactionTokenBalance,
 };
+use spl_associated_token_account::get_associated_token_address_with_program_id;
 use surfpool_types::{
-    ComputeUnitsEstimationResult, ExecutionCapture, Idl, KeyedProfileResult, ProfileResult,
-    RpcProfileResultConfig, SimnetCommand, SimnetEvent, TransactionConfirmationStatus,
-    TransactionStatusEvent, UiKeyedProfileResult, UuidOrSignature, VersionedIdl,
+    AccountPreload, BundleStatusEvent, ComputeUnitsEstimationResult, ExecutionCapture, Idl,
+    KeyedProfileResult, ProfileResult, RpcAccountDiffConfig, RpcProfileResultConfig, SimnetCommand,
+    SimnetEvent, TransactionConfirmationStatus, TransactionStatusEvent, UiAccountDiff,
+    UiKeyedProfileResult, UiOnchainTransactionResult, UuidOrSignature, VersionedIdl,
+    types::TokenAccountUpdate,
 };
 use tokio::sync::RwLock;
 use txtx_addon_kit::indexmap::IndexSet;
@@ -63,12 +70,20 @@ use uuid::Uuid;
 
 use super::{
     AccountFactory, GetAccountResult, GetTransactionResult, GeyserEvent, SignatureSubscriptionType,
-    SurfnetSvm, remote::SurfnetRemoteClient,
+    SurfnetSvm,
+    bundles::{BundleTransactionExecution, LandedBundle},
+    dump::{SurfnetStateDump, state_dump_file_name},
+    events::transaction_account_keys,
+    preload::{read_account_file, read_idl_file},
+    remote::SurfnetRemoteClient,
 };
 use crate::{
     error::{SurfpoolError, SurfpoolResult},
     helpers::time_travel::calculate_time_travel_clock,
-    rpc::utils::{convert_transaction_metadata_from_canonical, verify_pubkey},
+    rpc::{
+        surfnet_cheatcodes::TokenAccountUpdateExt,
+        utils::{convert_transaction_metadata_from_canonical, verify_pubkey, verify_pubkeys},
+    },
     surfnet::FINALIZATION_SLOT_THRESHOLD,
     types::{
         GeyserAccountUpdate, RemoteRpcResult, SurfnetTransactionStatus, TimeTravelConfig,
@@ -189,27 +204,45 @@ impl SurfnetSvmLocker {
 
     /// Initializes the locked `SurfnetSvm` by fetching or defaulting epoch info,
     /// then calling its `initialize` method. Returns the epoch info on success.
+    ///
+    /// When a `state_dump` is provided, the surfnet resumes from the dumped state: its epoch info is used
+    /// instead of the one of the datasource, and the dump is restored before anything else is written.
     pub async fn initialize(
         &self,
         slot_time: u64,
         remote_ctx: &Option<SurfnetRemoteClient>,
+        state_dump: Option<SurfnetStateDump>,
     ) -> SurfpoolResult<EpochInfo> {
-        let epoch_info = if let Some(remote_client) = remote_ctx {
-            remote_client.get_epoch_info().await?
-        } else {
-            EpochInfo {
+        let epoch_info = match (&state_dump, remote_ctx) {
+            (Some(state_dump), _) => state_dump.epoch_info.clone(),
+            (None, Some(remote_client)) => remote_client.get_epoch_info().await?,
+            (None, None) => EpochInfo {
                 epoch: 0,
                 slot_index: 0,
                 slots_in_epoch: 0,
                 absolute_slot: 0,
                 block_height: 0,
                 transaction_count: None,
-            }
+            },
         };
+        let loaded_counts = state_dump
+            .as_ref()
+            .map(|dump| (dump.accounts.len(), dump.transactions.len()));
 
         self.with_svm_writer(|svm_writer| {
             svm_writer.initialize(epoch_info.clone(), slot_time, remote_ctx);
-        });
+            match state_dump {
+                Some(state_dump) => svm_writer.load_state_dump(state_dump),
+                None => Ok(()),
+            }
+        })?;
+
+        if let Some((accounts_count, transactions_count)) = loaded_counts {
+            let _ = self.simnet_events_tx().send(SimnetEvent::info(format!(
+                "State loaded ({} accounts, {} transactions)",
+                accounts_count, transactions_count
+            )));
+        }
         Ok(epoch_info)
     }
 }
@@ -247,6 +280,12 @@ impl SurfnetSvmLocker {
 
         if result.inner.is_none() {
             let remote_account = client.get_account(pubkey, commitment_config).await?;
+            self.discover_program_idls(
+                client,
+                std::slice::from_ref(&remote_account),
+                commitment_config,
+            )
+            .await;
             Ok(result.with_new_value(remote_account))
         } else {
             Ok(result)
@@ -276,6 +315,31 @@ impl SurfnetSvmLocker {
         }
     }
 
+    /// Registers the IDLs published on-chain by the programs fetched from the datasource, so that
+    /// their accounts, events and errors are decoded like those of local programs. Programs that
+    /// already have a registered IDL are skipped.
+    async fn discover_program_idls(
+        &self,
+        client: &SurfnetRemoteClient,
+        remote_results: &[GetAccountResult],
+        commitment_config: CommitmentConfig,
+    ) {
+        for result in remote_results.iter() {
+            let GetAccountResult::FoundProgramAccount((program_id, _), _) = result else {
+                continue;
+            };
+            let has_idl = self
+                .with_svm_reader(|svm_reader| svm_reader.registered_idls.contains_key(program_id));
+            if has_idl {
+                continue;
+            }
+            // A program without a decodable IDL is still loaded
+            if let Ok(Some(idl)) = client.get_program_idl(program_id, commitment_config).await {
+                self.register_idl(idl, None);
+            }
+        }
+    }
+
     /// Retrieves multiple accounts from local cache, returning a contextualized result.
     pub fn get_multiple_accounts_local(
         &self,
@@ -342,6 +406,8 @@ impl SurfnetSvmLocker {
         let mut remote_results = client
             .get_multiple_accounts(&missing_accounts, commitment_config)
             .await?;
+        self.discover_program_idls(client, &remote_results, commitment_config)
+            .await;
         let mut combined_results = found_accounts.clone();
         combined_results.append(&mut remote_results);
 
@@ -671,17 +737,16 @@ impl SurfnetSvmLocker {
         self.with_svm_reader(|svm_reader| {
             let latest_absolute_slot = svm_reader.get_latest_absolute_slot();
 
-            let Some(entry) = svm_reader.transactions.get(signature) else {
+            // Transactions still being processed are not returned, as with a real validator
+            let Some(SurfnetTransactionStatus::Processed(transaction_with_status_meta)) =
+                svm_reader.transactions.get(signature)
+            else {
                 return Ok(GetTransactionResult::None(*signature));
             };
 
-            let transaction_with_status_meta = entry.expect_processed();
             let slot = transaction_with_status_meta.slot;
-            let block_time = svm_reader
-                .blocks
-                .get(&slot)
-                .map(|b| b.block_time)
-                .unwrap_or(0);
+            // Block times are stored in milliseconds, the RPC reports them in seconds
+            let block_time = svm_reader.blocks.get(&slot).map(|b| b.block_time / 1000);
             let encoded = transaction_with_status_meta.encode(
                 config.encoding.unwrap_or(UiTransactionEncoding::JsonParsed),
                 config.max_supported_transaction_version,
@@ -692,7 +757,7 @@ impl SurfnetSvmLocker {
                 EncodedConfirmedTransactionWithStatusMeta {
                     slot,
                     transaction: encoded,
-                    block_time: Some(block_time),
+                    block_time,
                 },
                 latest_absolute_slot,
             ))
@@ -768,14 +833,7 @@ impl SurfnetSvmLocker {
         transaction: VersionedTransaction,
         tag: Option<String>,
     ) -> SurfpoolContextualizedResult<Uuid> {
-        let mut svm_clone = self.with_svm_reader(|svm_reader| svm_reader.clone());
-
-        let (dummy_simnet_tx, _) = crossbeam_channel::bounded(1);
-        let (dummy_geyser_tx, _) = crossbeam_channel::bounded(1);
-        svm_clone.simnet_events_tx = dummy_simnet_tx;
-        svm_clone.geyser_events_tx = dummy_geyser_tx;
-
-        let svm_locker = SurfnetSvmLocker::new(svm_clone);
+        let svm_locker = self.profiling_svm_locker();
 
         let (status_tx, _) = crossbeam_channel::unbounded();
 
@@ -803,6 +861,246 @@ impl SurfnetSvmLocker {
         Ok(self.with_contextualized_svm_reader(|_| uuid))
     }
 
+    /// Re-executes a transaction of the datasource network on a copy of the SVM, and stores its profile.
+    ///
+    /// The accounts of the transaction are fetched through the datasource, which only serves their latest
+    /// state. The replay is refused if a later transaction wrote them; otherwise the accounts written by the
+    /// transaction are reset to their pre-execution state, see [SurfnetSvm::restore_pre_execution_state].
+    /// The recent blockhash is replaced with the latest local one, so signatures are not verified.
+    ///
+    /// # Returns
+    /// The UUID of the profile result, along with the outcome of the transaction on the datasource network.
+    pub async fn replay_transaction(
+        &self,
+        remote_client: &SurfnetRemoteClient,
+        signature: Signature,
+        tag: Option<String>,
+    ) -> SurfpoolContextualizedResult<(Uuid, UiOnchainTransactionResult)> {
+        let remote_ctx = Some((remote_client.clone(), CommitmentConfig::confirmed()));
+        let encoded_tx = remote_client.get_encoded_transaction(&signature).await?;
+        let Some(mut transaction) = encoded_tx.transaction.transaction.decode() else {
+            return Err(SurfpoolError::deserialize_error(
+                "transaction",
+                format!("unable to decode transaction {signature}"),
+            ));
+        };
+        let Some(meta) = encoded_tx.transaction.meta else {
+            return Err(SurfpoolError::internal(format!(
+                "Transaction {signature} has no status metadata"
+            )));
+        };
+        let onchain_result = UiOnchainTransactionResult {
+            slot: encoded_tx.slot,
+            err: meta.err.clone(),
+            fee: meta.fee,
+            compute_units_consumed: meta.compute_units_consumed.clone().into(),
+            log_messages: meta.log_messages.clone().into(),
+        };
+
+        let svm_locker = self.profiling_svm_locker();
+
+        let loaded_addresses = svm_locker
+            .get_loaded_addresses(&remote_ctx, &transaction.message)
+            .await?;
+        let transaction_accounts =
+            svm_locker.get_pubkeys_from_message(&transaction.message, loaded_addresses.clone());
+        let account_updates = svm_locker
+            .get_multiple_accounts(&remote_ctx, &transaction_accounts, None)
+            .await?
+            .inner;
+
+        // The datasource serves the latest state of the accounts, which is the one right after the
+        // transaction only if no later transaction wrote them. Programs and sysvars are not checked.
+        let checked_accounts = account_updates
+            .iter()
+            .filter_map(
+                |update| match update.clone().map_account_with_token_data() {
+                    Some(((_, account), _))
+                        if account.executable || account.owner == solana_sdk_ids::sysvar::id() =>
+                    {
+                        None
+                    }
+                    Some(((pubkey, _), _)) => Some(pubkey),
+                    None => match update {
+                        GetAccountResult::None(pubkey) => Some(*pubkey),
+                        _ => None,
+                    },
+                },
+            )
+            .collect::<Vec<_>>();
+        remote_client
+            .ensure_unwritten_since_transaction(&signature, &checked_accounts)
+            .await?;
+
+        // The balances of the metadata follow the order of the keys loaded by the runtime
+        let mut account_keys = transaction.message.static_account_keys().to_vec();
+        if let Some(loaded_addresses) = &loaded_addresses {
+            account_keys.extend(loaded_addresses.writable.iter());
+            account_keys.extend(loaded_addresses.readonly.iter());
+        }
+        // Failed transactions only charge their fee payer
+        let written_accounts = account_keys
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| {
+                if meta.err.is_some() {
+                    *i == 0
+                } else {
+                    transaction.message.is_maybe_writable(*i, None)
+                }
+            })
+            .map(|(_, pubkey)| *pubkey)
+            .collect::<Vec<_>>();
+        let pre_token_balances: Vec<UiTransactionTokenBalance> =
+            Option::from(meta.pre_token_balances).unwrap_or_default();
+
+        svm_locker.with_svm_writer(|svm_writer| {
+            for update in account_updates.iter() {
+                svm_writer.write_account_update(update.clone());
+            }
+            svm_writer.restore_pre_execution_state(
+                &signature,
+                &account_keys,
+                &written_accounts,
+                &meta.pre_balances,
+                &pre_token_balances,
+            )
+        })?;
+
+        let latest_blockhash =
+            svm_locker.with_svm_reader(|svm_reader| svm_reader.latest_blockhash());
+        transaction.message.set_recent_blockhash(latest_blockhash);
+
+        let (status_tx, _) = crossbeam_channel::unbounded();
+        let skip_preflight = true; // skip preflight checks during transaction replay
+        let sigverify = false; // the signatures don't cover the replaced blockhash
+        let do_propagate_status_updates = false; // don't propagate status updates during transaction replay
+        let mut profile_result = svm_locker
+            .fetch_all_tx_accounts_then_process_tx_returning_profile_res(
+                &remote_ctx,
+                transaction,
+                status_tx,
+                skip_preflight,
+                sigverify,
+                do_propagate_status_updates,
+            )
+            .await?;
+
+        let uuid = Uuid::new_v4();
+        profile_result.key = UuidOrSignature::Uuid(uuid);
+
+        self.with_svm_writer(|svm_writer| {
+            svm_writer.write_simulated_profile_result(uuid, tag, profile_result);
+        });
+
+        Ok(self.with_contextualized_svm_reader(|_| (uuid, onchain_result.clone())))
+    }
+
+    /// Builds an instruction with the IDL registered for `program_id`, and processes it in a transaction.
+    ///
+    /// The transaction is paid by `payer`, defaulting to the first signer of the instruction. It is signed
+    /// with the keypairs loaded on startup; the other signatures are placeholders, in which case signatures
+    /// are not verified.
+    ///
+    /// # Returns
+    /// The signature of the processed transaction, whose profile result is recorded even if it failed.
+    pub async fn send_instruction(
+        &self,
+        remote_ctx: &Option<(SurfnetRemoteClient, CommitmentConfig)>,
+        program_id: Pubkey,
+        instruction_name: &str,
+        args: &serde_json::Value,
+        accounts: HashMap<String, Pubkey>,
+        payer: Option<Pubkey>,
+    ) -> SurfpoolContextualizedResult<Signature> {
+        // The data of the provided accounts can be required to derive the other accounts
+        let provided_accounts = accounts.values().copied().collect::<Vec<_>>();
+        let account_updates = self
+            .get_multiple_accounts(remote_ctx, &provided_accounts, None)
+            .await?
+            .inner;
+        self.write_multiple_account_updates(&account_updates);
+
+        let instruction = self.with_svm_reader(|svm_reader| {
+            svm_reader.build_idl_instruction(&program_id, instruction_name, args, &accounts)
+        })?;
+        let payer = match payer {
+            Some(payer) => payer,
+            None => instruction
+                .accounts
+                .iter()
+                .find(|account| account.is_signer)
+                .map(|account| account.pubkey)
+                .ok_or_else(|| {
+                    SurfpoolError::invalid_instruction(
+                        instruction_name,
+                        "a payer is required for instructions without signers",
+                    )
+                })?,
+        };
+
+        let (latest_blockhash, signing_keypairs) = self.with_svm_reader(|svm_reader| {
+            (
+                svm_reader.latest_blockhash(),
+                svm_reader.signing_keypairs.clone(),
+            )
+        });
+        let message = VersionedMessage::Legacy(Message::new_with_blockhash(
+            &[instruction],
+            Some(&payer),
+            &latest_blockhash,
+        ));
+        let message_data = message.serialize();
+        let mut sigverify = true;
+        let signatures = message.static_account_keys()
+            [..message.header().num_required_signatures as usize]
+            .iter()
+            .map(|signer| {
+                match signing_keypairs
+                    .iter()
+                    .find(|keypair| keypair.pubkey() == *signer)
+                {
+                    Some(keypair) => keypair.sign_message(&message_data),
+                    None => {
+                        sigverify = false;
+                        Keypair::new().sign_message(&message_data)
+                    }
+                }
+            })
+            .collect();
+        let transaction = VersionedTransaction {
+            signatures,
+            message,
+        };
+        let signature = transaction.signatures[0];
+
+        let (status_tx, _) = crossbeam_channel::unbounded();
+        let skip_preflight = true; // record the profile of failed instructions as well
+        self.process_transaction(
+            remote_ctx,
+            transaction,
+            status_tx,
+            skip_preflight,
+            sigverify,
+        )
+        .await?;
+
+        Ok(self.with_contextualized_svm_reader(|_| signature))
+    }
+
+    /// Returns a locker over a copy of the SVM whose events are discarded, to execute transactions
+    /// without affecting the state of the surfnet.
+    fn profiling_svm_locker(&self) -> SurfnetSvmLocker {
+        let mut svm_clone = self.with_svm_reader(|svm_reader| svm_reader.clone());
+
+        let (dummy_simnet_tx, _) = crossbeam_channel::bounded(1);
+        let (dummy_geyser_tx, _) = crossbeam_channel::bounded(1);
+        svm_clone.simnet_events_tx = dummy_simnet_tx;
+        svm_clone.geyser_events_tx = dummy_geyser_tx;
+
+        SurfnetSvmLocker::new(svm_clone)
+    }
+
     async fn fetch_all_tx_accounts_then_process_tx_returning_profile_res(
         &self,
         remote_ctx: &Option<(SurfnetRemoteClient, CommitmentConfig)>,
@@ -1098,7 +1396,7 @@ impl SurfnetSvmLocker {
 
     fn handle_simulation_failure(
         &self,
-        signature: Signature,
+        transaction: &VersionedTransaction,
         failed_transaction_metadata: FailedTransactionMetadata,
         pre_execution_capture: ExecutionCapture,
         simulated_slot: Slot,
@@ -1107,6 +1405,7 @@ impl SurfnetSvmLocker {
     ) -> ProfileResult {
         let FailedTransactionMetadata { err, meta } = failed_transaction_metadata;
 
+        let signature = transaction.signatures[0];
         let cus = meta.compute_units_consumed;
         let log_messages = meta.logs.clone();
         let err_string = err.to_string();
@@ -1115,8 +1414,9 @@ impl SurfnetSvmLocker {
             let meta = convert_transaction_metadata_from_canonical(&meta);
             let simnet_events_tx = self.simnet_events_tx();
             let _ = simnet_events_tx.try_send(SimnetEvent::error(format!(
-                "Transaction simulation failed: {}",
-                err
+                "Transaction simulation failed: {}{}",
+                err,
+                self.describe_program_error(transaction, &err, &log_messages)
             )));
 
             let _ = status_tx.try_send(TransactionStatusEvent::SimulationFailure((
@@ -1148,6 +1448,21 @@ impl SurfnetSvmLocker {
         )
     }
 
+    /// Describes the custom program error of a failed transaction, resolved with the registered IDLs,
+    /// to be appended to the error.
+    fn describe_program_error(
+        &self,
+        transaction: &VersionedTransaction,
+        err: &TransactionError,
+        logs: &[String],
+    ) -> String {
+        self.with_svm_reader(|svm_reader| {
+            svm_reader.resolve_program_error(&transaction.message, err, logs)
+        })
+        .map(|program_error| format!(" ({program_error})"))
+        .unwrap_or_default()
+    }
+
     fn handle_execution_failure(
         &self,
         failed_transaction_metadata: FailedTransactionMetadata,
@@ -1185,7 +1500,11 @@ impl SurfnetSvmLocker {
                     });
                 }
                 self.with_svm_writer(|svm_writer| {
-                    svm_writer.notify_account_subscribers(pubkey, &after.unwrap_or_default());
+                    svm_writer.notify_account_subscribers(
+                        pubkey,
+                        before.as_ref(),
+                        &after.unwrap_or_default(),
+                    );
                 });
             }
         }
@@ -1209,8 +1528,9 @@ impl SurfnetSvmLocker {
             let meta_canonical = convert_transaction_metadata_from_canonical(&meta);
             let simnet_events_tx = self.simnet_events_tx();
             let _ = simnet_events_tx.try_send(SimnetEvent::error(format!(
-                "Transaction execution failed: {}",
-                err
+                "Transaction execution failed: {}{}",
+                err,
+                self.describe_program_error(&transaction, &err, &log_messages)
             )));
             let _ = status_tx.try_send(TransactionStatusEvent::ExecutionFailure((
                 err.clone(),
@@ -1280,12 +1600,27 @@ impl SurfnetSvmLocker {
         let logs = transaction_metadata.logs.clone();
         let signature = transaction.signatures[0];
 
-        let post_execution_capture = self.with_svm_writer(|svm_writer| {
+        let (post_execution_capture, events) = self.with_svm_writer(|svm_writer| {
             let accounts_after = pubkeys_from_message
                 .iter()
                 .map(|p| svm_writer.inner.get_account(p))
                 .collect::<Vec<Option<Account>>>();
 
+            let inner_instructions = transaction_metadata
+                .inner_instructions
+                .iter()
+                .flatten()
+                .map(|inner_instruction| inner_instruction.instruction.clone())
+                .collect::<Vec<_>>();
+            let events = svm_writer.decode_anchor_events(
+                &transaction_account_keys(
+                    &transaction.message,
+                    &loaded_addresses.clone().unwrap_or_default(),
+                ),
+                &logs,
+                &inner_instructions,
+            );
+
             let sanitized_transaction = if do_propagate {
                 SanitizedTransaction::try_create(
                     transaction.clone(),
@@ -1318,13 +1653,17 @@ impl SurfnetSvmLocker {
                                     *pubkey,
                                     after.clone(),
                                     svm_writer.get_latest_absolute_slot(),
-                                    sanitized_transaction.clone(),
+                                    Some(sanitized_transaction.clone()),
                                     write_version,
                                 ),
                             ));
                         }
                     }
-                    svm_writer.notify_account_subscribers(pubkey, &after.unwrap_or_default());
+                    svm_writer.notify_account_subscribers(
+                        pubkey,
+                        before.as_ref(),
+                        &after.unwrap_or_default(),
+                    );
                 }
             }
 
@@ -1380,6 +1719,11 @@ impl SurfnetSvmLocker {
                 let _ = svm_writer
                     .simnet_events_tx
                     .try_send(SimnetEvent::transaction_processed(transaction_meta, None));
+                for event in events.iter() {
+                    let _ = svm_writer
+                        .simnet_events_tx
+                        .try_send(SimnetEvent::anchor_event_emitted(signature, event.clone()));
+                }
 
                 let _ = svm_writer
                     .geyser_events_tx
@@ -1409,16 +1753,18 @@ impl SurfnetSvmLocker {
                 );
             }
 
-            Ok::<ExecutionCapture, SurfpoolError>(post_execution_capture)
+            Ok::<_, SurfpoolError>((post_execution_capture, events))
         })?;
 
-        Ok(ProfileResult::new(
+        let mut profile_result = ProfileResult::new(
             pre_execution_capture,
             post_execution_capture,
             cus,
             Some(logs),
             None,
-        ))
+        );
+        profile_result.events = events;
+        Ok(profile_result)
     }
 
     async fn process_transaction_internal(
@@ -1455,7 +1801,7 @@ impl SurfnetSvmLocker {
                 )?,
             ProcessTransactionResult::SimulationFailure(failed_transaction_metadata) => self
                 .handle_simulation_failure(
-                    transaction.signatures[0],
+                    &transaction,
                     failed_transaction_metadata,
                     pre_execution_capture,
                     self.get_latest_absolute_slot(),
@@ -1508,6 +1854,182 @@ impl SurfnetSvmLocker {
     }
 }
 
+/// Functions for executing bundles in the underlying SurfnetSvm instance
+impl SurfnetSvmLocker {
+    /// Executes the transactions of a bundle in order on a copy of the SVM, stopping at the first
+    /// failure, and captures the state of the requested accounts around each transaction.
+    ///
+    /// `captured_accounts` holds, for each transaction, the accounts to capture before and after its
+    /// execution. The accounts of every transaction are fetched from the datasource beforehand.
+    pub async fn simulate_bundle(
+        &self,
+        remote_ctx: &Option<(SurfnetRemoteClient, CommitmentConfig)>,
+        transactions: &[VersionedTransaction],
+        sigverify: bool,
+        captured_accounts: &[(Vec<Pubkey>, Vec<Pubkey>)],
+    ) -> SurfpoolResult<Vec<BundleTransactionExecution>> {
+        for transaction in transactions.iter() {
+            let loaded_addresses = self
+                .get_loaded_addresses(remote_ctx, &transaction.message)
+                .await?;
+            let pubkeys = self.get_pubkeys_from_message(&transaction.message, loaded_addresses);
+            let account_updates = self
+                .get_multiple_accounts(remote_ctx, &pubkeys, None)
+                .await?
+                .inner;
+            self.write_multiple_account_updates(&account_updates);
+        }
+
+        let svm_locker = self.profiling_svm_locker();
+        let executions = svm_locker.with_svm_writer(|svm_writer| {
+            let mut executions = vec![];
+            for (i, transaction) in transactions.iter().enumerate() {
+                let (pre_pubkeys, post_pubkeys) = captured_accounts
+                    .get(i)
+                    .map(|(pre, post)| (pre.as_slice(), post.as_slice()))
+                    .unwrap_or_default();
+                let pre_execution_accounts = pre_pubkeys
+                    .iter()
+                    .map(|pubkey| svm_writer.inner.get_account(pubkey))
+                    .collect();
+                let result = svm_writer.send_transaction(
+                    transaction.clone(),
+                    false, /* cu_analysis_enabled */
+                    sigverify,
+                );
+                let post_execution_accounts = post_pubkeys
+                    .iter()
+                    .map(|pubkey| svm_writer.inner.get_account(pubkey))
+                    .collect();
+
+                let is_failure = result.is_err();
+                executions.push(BundleTransactionExecution {
+                    signature: transaction.signatures[0],
+                    result,
+                    pre_execution_accounts,
+                    post_execution_accounts,
+                });
+                if is_failure {
+                    break;
+                }
+            }
+            executions
+        });
+        Ok(executions)
+    }
+
+    /// Executes a bundle atomically: its transactions are first executed on a copy of the SVM, and
+    /// only committed, in order and within the current slot, if every one of them succeeds.
+    pub async fn process_bundle(
+        &self,
+        remote_ctx: &Option<(SurfnetRemoteClient, CommitmentConfig)>,
+        bundle_id: String,
+        transactions: Vec<VersionedTransaction>,
+        sigverify: bool,
+    ) -> SurfpoolResult<BundleStatusEvent> {
+        let executions = self
+            .simulate_bundle(remote_ctx, &transactions, sigverify, &[])
+            .await?;
+        let failure = executions.into_iter().find_map(|execution| {
+            let signature = execution.signature;
+            execution.result.err().map(|failed| (signature, failed))
+        });
+        if let Some((signature, failed)) = failure {
+            return Ok(BundleStatusEvent::Failed {
+                signature,
+                error: failed.err,
+                logs: failed.meta.logs,
+            });
+        }
+
+        let slot = self.get_latest_absolute_slot();
+        let signatures = transactions
+            .iter()
+            .map(|transaction| transaction.signatures[0])
+            .collect();
+
+        if let Some(failure) = self
+            .commit_bundle_transactions(remote_ctx, transactions, sigverify)
+            .await?
+        {
+            return Ok(failure);
+        }
+
+        self.with_svm_writer(|svm_writer| {
+            svm_writer
+                .bundles
+                .insert(bundle_id, LandedBundle { slot, signatures });
+        });
+        Ok(BundleStatusEvent::Landed(slot))
+    }
+
+    /// Commits the transactions of a bundle in order, restoring the state before the bundle if one
+    /// of them fails, since the state may have changed since the bundle was simulated.
+    ///
+    /// # Returns
+    /// The failure of the bundle, if a transaction failed to execute.
+    async fn commit_bundle_transactions(
+        &self,
+        remote_ctx: &Option<(SurfnetRemoteClient, CommitmentConfig)>,
+        transactions: Vec<VersionedTransaction>,
+        sigverify: bool,
+    ) -> SurfpoolResult<Option<BundleStatusEvent>> {
+        let (pre_bundle_state, queued_transactions) = self.with_svm_reader(|svm_reader| {
+            (
+                svm_reader.capture_snapshot(),
+                svm_reader.transactions_queued_for_confirmation.len(),
+            )
+        });
+        let mut outcome = Ok(None);
+        for transaction in transactions {
+            let signature = transaction.signatures[0];
+            let (status_tx, status_rx) = crossbeam_channel::unbounded();
+            let skip_preflight = true; // the bundle was already executed successfully
+            if let Err(e) = self
+                .process_transaction(
+                    remote_ctx,
+                    transaction,
+                    status_tx,
+                    skip_preflight,
+                    sigverify,
+                )
+                .await
+            {
+                outcome = Err(e);
+                break;
+            }
+            let failure = status_rx.try_iter().find_map(|event| match event {
+                TransactionStatusEvent::SimulationFailure((error, meta))
+                | TransactionStatusEvent::ExecutionFailure((error, meta)) => {
+                    Some(BundleStatusEvent::Failed {
+                        signature,
+                        error,
+                        logs: meta.logs,
+                    })
+                }
+                TransactionStatusEvent::VerificationFailure(reason) => {
+                    Some(BundleStatusEvent::Dropped(reason))
+                }
+                TransactionStatusEvent::Success(_) => None,
+            });
+            if failure.is_some() {
+                outcome = Ok(failure);
+                break;
+            }
+        }
+
+        if !matches!(outcome, Ok(None)) {
+            self.with_svm_writer(|svm_writer| {
+                svm_writer.restore_snapshot(pre_bundle_state);
+                svm_writer
+                    .transactions_queued_for_confirmation
+                    .truncate(queued_transactions);
+            });
+        }
+        outcome
+    }
+}
+
 /// Functions for writing account updates to the underlying SurfnetSvm instance
 impl SurfnetSvmLocker {
     /// Writes a single account update into the SVM state if present.
@@ -1540,6 +2062,67 @@ impl SurfnetSvmLocker {
 
 /// Token account related functions
 impl SurfnetSvmLocker {
+    /// Updates the associated token account of `owner` for `mint`, creating it if it doesn't exist.
+    pub async fn set_token_account(
+        &self,
+        remote_ctx: &Option<(SurfnetRemoteClient, CommitmentConfig)>,
+        owner: Pubkey,
+        mint: Pubkey,
+        update: TokenAccountUpdate,
+        token_program_id: Pubkey,
+    ) -> SurfpoolContextualizedResult<()> {
+        let associated_token_account =
+            get_associated_token_address_with_program_id(&owner, &mint, &token_program_id);
+
+        let get_mint_result = self.get_account(remote_ctx, &mint, None).await?.inner;
+        self.write_account_update(get_mint_result);
+
+        let result = self
+            .get_account(
+                remote_ctx,
+                &associated_token_account,
+                Some(Box::new(move |svm_locker| {
+                    let minimum_rent = svm_locker.with_svm_reader(|svm_reader| {
+                        svm_reader.inner.minimum_balance_for_rent_exemption(
+                            TokenAccount::get_packed_len_for_token_program_id(&token_program_id),
+                        )
+                    });
+
+                    let default = TokenAccount::new(&token_program_id, owner, mint);
+                    let data = default.pack_into_vec();
+                    GetAccountResult::FoundAccount(
+                        associated_token_account,
+                        Account {
+                            lamports: minimum_rent,
+                            owner: token_program_id,
+                            executable: false,
+                            rent_epoch: 0,
+                            data,
+                        },
+                        true, // indicate that the account should be updated in the SVM, since it's new
+                    )
+                })),
+            )
+            .await?;
+        let mut token_account = result.inner.clone();
+
+        let mut token_account_data = TokenAccount::unpack(token_account.expected_data())
+            .map_err(|e| SurfpoolError::deserialize_error("token account data", e))?;
+
+        update.apply(&mut token_account_data)?;
+
+        let final_account_bytes = token_account_data.pack_into_vec();
+        token_account
+            .apply_update(|account| {
+                account.data = final_account_bytes.clone();
+                Ok(())
+            })
+            .map_err(|e| SurfpoolError::internal(e.to_string()))?;
+        self.write_account_update(token_account);
+
+        Ok(result.with_new_value(()))
+    }
+
     /// Fetches all token accounts for an owner, returning remote results and missing pubkeys contexts.
     pub async fn get_token_accounts_by_owner(
         &self,
@@ -2218,6 +2801,40 @@ impl SurfnetSvmLocker {
             })
         })
     }
+
+    /// Diffs the accounts written between `start_slot` and `end_slot`, defaulting to the latest slot.
+    pub fn get_account_diffs(
+        &self,
+        start_slot: Slot,
+        end_slot: Option<Slot>,
+        config: &RpcAccountDiffConfig,
+    ) -> SurfpoolResult<SvmAccessContext<Vec<UiAccountDiff>>> {
+        let accounts = config
+            .accounts
+            .as_ref()
+            .map(|accounts| verify_pubkeys(accounts))
+            .transpose()?;
+        let encoding = config.encoding.unwrap_or(UiAccountEncoding::JsonParsed);
+
+        let SvmAccessContext {
+            slot,
+            latest_epoch_info,
+            latest_blockhash,
+            inner: diffs,
+        } = self.with_contextualized_svm_reader(|svm_reader| {
+            let end_slot = end_slot.unwrap_or_else(|| svm_reader.get_latest_absolute_slot());
+            if start_slot > end_slot {
+                return Err(SurfpoolError::invalid_slot_range(start_slot, end_slot));
+            }
+            svm_reader.get_account_diffs(start_slot, end_slot, accounts.as_deref(), encoding)
+        });
+        Ok(SvmAccessContext::new(
+            slot,
+            latest_epoch_info,
+            latest_blockhash,
+            diffs?,
+        ))
+    }
 }
 /// Program account related functions
 impl SurfnetSvmLocker {
@@ -2284,6 +2901,21 @@ impl SurfnetSvmLocker {
             )?;
 
             svm_writer.set_account(destination_program_id, new_program_account.clone())?;
+
+            // The destination program is decoded with the IDL of the source program, unless it has its own
+            if svm_writer
+                .get_active_idls(destination_program_id)
+                .is_empty()
+            {
+                if let Some(mut idl) = svm_writer
+                    .get_active_idls(source_program_id)
+                    .first()
+                    .map(|idl| (*idl).clone())
+                {
+                    idl.address = destination_program_id.to_string();
+                    svm_writer.register_idl(idl, None);
+                }
+            }
             Ok::<(), SurfpoolError>(())
         })?;
 
@@ -2416,6 +3048,52 @@ impl SurfnetSvmLocker {
         ))
     }
 
+    /// Deploys an ELF at `program_id` with the upgradeable loader (v3), replacing any program
+    /// (local or fetched from the datasource) at this address.
+    ///
+    /// When no `upgrade_authority` is provided, the upgrade authority of the existing program is kept.
+    pub async fn deploy_program(
+        &self,
+        remote_ctx: &Option<(SurfnetRemoteClient, CommitmentConfig)>,
+        program_id: &Pubkey,
+        elf: &[u8],
+        upgrade_authority: Option<Pubkey>,
+    ) -> SurfpoolContextualizedResult<()> {
+        let programdata_address = get_program_data_address(program_id);
+        let result = self
+            .get_account(remote_ctx, &programdata_address, None)
+            .await?;
+
+        let upgrade_authority = match upgrade_authority {
+            Some(upgrade_authority) => Some(upgrade_authority),
+            None => match &result.inner {
+                GetAccountResult::FoundAccount(_, programdata_account, _) => {
+                    match bincode::deserialize::<UpgradeableLoaderState>(&programdata_account.data)
+                    {
+                        Ok(UpgradeableLoaderState::ProgramData {
+                            upgrade_authority_address,
+                            ..
+                        }) => upgrade_authority_address,
+                        _ => None,
+                    }
+                }
+                _ => None,
+            },
+        };
+
+        self.with_svm_writer(|svm_writer| {
+            svm_writer.deploy_program(program_id, elf, upgrade_authority)
+        })?;
+
+        let _ = self.simnet_events_tx().send(SimnetEvent::info(format!(
+            "Deployed {} bytes of program data at {}",
+            elf.len(),
+            program_id
+        )));
+
+        Ok(result.with_new_value(()))
+    }
+
     pub async fn get_program_accounts(
         &self,
         remote_ctx: &Option<SurfnetRemoteClient>,
@@ -2645,6 +3323,87 @@ impl SurfnetSvmLocker {
         Ok(epoch_info)
     }
 
+    /// Captures the current state of the underlying SVM, returning the snapshot id.
+    pub fn snapshot(&self) -> SurfpoolContextualizedResult<Uuid> {
+        let snapshot_id = self.with_svm_writer(|svm_writer| svm_writer.snapshot())?;
+        let _ = self.simnet_events_tx().send(SimnetEvent::info(format!(
+            "Snapshot {} taken at slot {}",
+            snapshot_id,
+            self.get_latest_absolute_slot()
+        )));
+        Ok(self.with_contextualized_svm_reader(|_| snapshot_id))
+    }
+
+    /// Deletes a snapshot, freeing the state it holds.
+    pub fn delete_snapshot(&self, snapshot_id: &Uuid) -> SurfpoolResult<()> {
+        self.with_svm_writer(|svm_writer| svm_writer.delete_snapshot(snapshot_id))
+    }
+
+    /// Restores the latest snapshot taken before the current slot.
+    pub fn revert_to_previous_snapshot(&self) -> SurfpoolResult<EpochInfo> {
+        let slot = self.get_latest_absolute_slot();
+        let snapshot_id = self
+            .with_svm_reader(|svm_reader| svm_reader.latest_snapshot_before(slot))
+            .ok_or_else(|| SurfpoolError::no_snapshot_before(slot))?;
+        self.revert(&snapshot_id)
+    }
+
+    /// Restores the state of the underlying SVM from a previously taken snapshot.
+    pub fn revert(&self, snapshot_id: &Uuid) -> SurfpoolResult<EpochInfo> {
+        let epoch_info = self.with_svm_writer(|svm_writer| svm_writer.revert(snapshot_id))?;
+        let _ = self.simnet_events_tx().send(SimnetEvent::info(format!(
+            "Reverted to snapshot {} (epoch {} / slot {})",
+            snapshot_id, epoch_info.epoch, epoch_info.absolute_slot
+        )));
+        Ok(epoch_info)
+    }
+
+    /// Writes the state of the underlying SVM to the JSON file `file_name`, in the state dump directory.
+    ///
+    /// # Returns
+    /// The path of the written file.
+    pub fn dump_state_to_file(&self, file_name: &str) -> SurfpoolContextualizedResult<PathBuf> {
+        let Some(state_dump_dir) =
+            self.with_svm_reader(|svm_reader| svm_reader.state_dump_dir.clone())
+        else {
+            return Err(SurfpoolError::state_dumps_disabled());
+        };
+        let path = state_dump_dir.join(state_dump_file_name(file_name)?);
+        let dump = self.with_svm_reader(|svm_reader| svm_reader.dump_state())?;
+        dump.write_to_file(&path)?;
+        let _ = self.simnet_events_tx().send(SimnetEvent::info(format!(
+            "State dumped to {} ({} accounts, {} transactions)",
+            path.display(),
+            dump.accounts.len(),
+            dump.transactions.len()
+        )));
+        Ok(self.with_contextualized_svm_reader(|_| path.clone()))
+    }
+
+    /// Writes an account read from a local file into the SVM.
+    pub fn preload_account(&self, preload: &AccountPreload) -> SurfpoolResult<()> {
+        self.with_svm_writer(|svm_writer| {
+            let (pubkey, account) = read_account_file(preload, |data_len| {
+                svm_writer
+                    .inner
+                    .minimum_balance_for_rent_exemption(data_len)
+            })?;
+            svm_writer.set_account(&pubkey, account)
+        })
+    }
+
+    /// Registers an Anchor IDL read from a local JSON file.
+    pub fn register_idl_from_file(&self, path: &Path) -> SurfpoolResult<()> {
+        let idl = read_idl_file(path)?;
+        let _ = self.simnet_events_tx().send(SimnetEvent::info(format!(
+            "IDL of program {} registered from {}",
+            idl.address,
+            path.display()
+        )));
+        self.register_idl(idl, None);
+        Ok(())
+    }
+
     /// Retrieves the latest absolute slot from the underlying SVM.
     pub fn get_latest_absolute_slot(&self) -> Slot {
         self.with_svm_reader(|svm_reader| svm_reader.get_latest_absolute_slot())
@@ -2710,6 +3469,31 @@ impl SurfnetSvmLocker {
         self.with_svm_writer(|svm_writer| svm_writer.subscribe_for_slot_updates())
     }
 
+    /// Subscribes for updates of the accounts owned by a program and returns a receiver of keyed accounts.
+    pub fn subscribe_for_program_updates(
+        &self,
+        program_id: &Pubkey,
+        config: RpcProgramAccountsConfig,
+    ) -> Receiver<(Slot, RpcKeyedAccount)> {
+        self.with_svm_writer(|svm_writer| {
+            svm_writer.subscribe_for_program_updates(program_id, config)
+        })
+    }
+
+    /// Subscribes for block updates and returns a receiver of block updates.
+    pub fn subscribe_for_block_updates(
+        &self,
+        filter: RpcBlockSubscribeFilter,
+        config: RpcBlockSubscribeConfig,
+    ) -> Receiver<(Slot, RpcBlockUpdate)> {
+        self.with_svm_writer(|svm_writer| svm_writer.subscribe_for_block_updates(filter, config))
+    }
+
+    /// Subscribes for root updates and returns a receiver of the new roots.
+    pub fn subscribe_for_root_updates(&self) -> Receiver<Slot> {
+        self.with_svm_writer(|svm_writer| svm_writer.subscribe_for_root_updates())
+    }
+
     /// Subscribes for logs updates and returns a receiver of logs updates.
     pub fn subscribe_for_logs_updates(
         &self,
@@ -2722,8 +3506,8 @@ impl SurfnetSvmLocker {
     }
 }
 
-// Helper function to apply filters
-fn apply_rpc_filters(account_data: &[u8], filters: &[RpcFilterType]) -> SurfpoolResult<bool> {
+// Helper function to apply filters, also used in the svm.rs
+pub fn apply_rpc_filters(account_data: &[u8], filters: &[RpcFilterType]) -> SurfpoolResult<bool> {
     for filter in filters {
         match filter {
             RpcFilterType::DataSize(size) => {
@@ -2822,3 +3606,246 @@ pub fn format_ui_amount(amount: u64, decimals: u8) -> f64 {
         amount as f64
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Write, sync::Mutex};
+
+    use base64::{Engine, prelude::BASE64_STANDARD};
+    use flate2::{Compression, write::ZlibEncoder};
+    use serde_json::{Value, json};
+    use solana_rpc_client::rpc_sender::{RpcSender, RpcTransportStats};
+    use solana_rpc_client_api::{
+        client_error::{ErrorKind as ClientErrorKind, Result as ClientResult},
+        request::RpcRequest,
+    };
+    use solana_sdk::{bpf_loader_upgradeable, system_instruction::transfer};
+
+    use super::*;
+    use crate::surfnet::{idl_accounts::anchor_idl_address, svm::SurfnetSvm};
+
+    const IDL_JSON: &[u8] = include_bytes!("../tests/assets/idl_v1.json");
+
+    /// Serves the accounts of a datasource, and records the accounts requested.
+    #[derive(Default)]
+    struct MockAccountsSender {
+        accounts: HashMap<Pubkey, Account>,
+        requested: Arc<Mutex<Vec<Pubkey>>>,
+    }
+
+    impl MockAccountsSender {
+        fn ui_account(&self, pubkey: &Value) -> Value {
+            let pubkey = Pubkey::from_str_const(pubkey.as_str().unwrap_or_default());
+            self.requested.lock().unwrap().push(pubkey);
+            match self.accounts.get(&pubkey) {
+                Some(account) => json!({
+                    "lamports": account.lamports,
+                    "data": [BASE64_STANDARD.encode(&account.data), "base64"],
+                    "owner": account.owner.to_string(),
+                    "executable": account.executable,
+                    "rentEpoch": account.rent_epoch,
+                    "space": account.data.len(),
+                }),
+                None => Value::Null,
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl RpcSender for MockAccountsSender {
+        async fn send(&self, request: RpcRequest, params: Value) -> ClientResult<Value> {
+            let value = match request {
+                RpcRequest::GetAccountInfo => self.ui_account(&params[0]),
+                RpcRequest::GetMultipleAccounts => params[0]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .map(|pubkey| self.ui_account(pubkey))
+                    .collect(),
+                _ => return Err(ClientErrorKind::Custom(format!("unexpected {request}")).into()),
+            };
+            Ok(json!({ "context": { "slot": 1 }, "value": value }))
+        }
+
+        fn get_transport_stats(&self) -> RpcTransportStats {
+            RpcTransportStats::default()
+        }
+
+        fn url(&self) -> String {
+            "http://localhost:8899".to_string()
+        }
+    }
+
+    /// Returns a datasource serving an upgradeable program, along with the IDL account of the program.
+    fn program_datasource(program_id: &Pubkey) -> MockAccountsSender {
+        let program_data_address = get_program_data_address(program_id);
+        let program_account = Account {
+            lamports: 1_000_000,
+            data: bincode::serialize(&UpgradeableLoaderState::Program {
+                programdata_address: program_data_address,
+            })
+            .unwrap(),
+            owner: bpf_loader_upgradeable::id(),
+            executable: true,
+            rent_epoch: 0,
+        };
+        let program_data_account = Account {
+            lamports: 1_000_000,
+            data: [
+                bincode::serialize(&UpgradeableLoaderState::ProgramData {
+                    slot: 0,
+                    upgrade_authority_address: Some(Pubkey::new_unique()),
+                })
+                .unwrap(),
+                include_bytes!("../tests/assets/metaplex_program.bin").to_vec(),
+            ]
+            .concat(),
+            owner: bpf_loader_upgradeable::id(),
+            executable: false,
+            rent_epoch: 0,
+        };
+
+        let mut encoder = ZlibEncoder::new(vec![], Compression::default());
+        encoder.write_all(IDL_JSON).unwrap();
+        let compressed = encoder.finish().unwrap();
+        let idl_account = Account {
+            lamports: 1_000_000,
+            data: [
+                vec![0; 8],
+                Pubkey::new_unique().to_bytes().to_vec(),
+                (compressed.len() as u32).to_le_bytes().to_vec(),
+                compressed,
+            ]
+            .concat(),
+            owner: *program_id,
+            executable: false,
+            rent_epoch: 0,
+        };
+
+        let mut sender = MockAccountsSender::default();
+        sender.accounts.insert(*program_id, program_account);
+        sender
+            .accounts
+            .insert(program_data_address, program_data_account);
+        sender
+            .accounts
+            .insert(anchor_idl_address(program_id), idl_account);
+        sender
+    }
+
+    #[tokio::test]
+    async fn test_commit_bundle_transactions_rolls_back_on_failure() {
+        let (mut svm, _events_rx, _geyser_rx) = SurfnetSvm::new();
+        let payer = Keypair::new();
+        let recipient = Pubkey::new_unique();
+        svm.airdrop(&payer.pubkey(), 1_000_000_000).unwrap();
+        let blockhash = svm.latest_blockhash();
+        let queued_transactions = svm.transactions_queued_for_confirmation.len();
+        let svm_locker = SurfnetSvmLocker::new(svm);
+
+        let transfer_tx = |lamports: u64| {
+            VersionedTransaction::from(Transaction::new_signed_with_payer(
+                &[transfer(&payer.pubkey(), &recipient, lamports)],
+                Some(&payer.pubkey()),
+                &[&payer],
+                blockhash,
+            ))
+        };
+        let first = transfer_tx(1_000_000);
+        // The second transfer exceeds the balance of the payer, so it fails once committed
+        let second = transfer_tx(10_000_000_000);
+        let second_signature = second.signatures[0];
+        let first_signature = first.signatures[0];
+
+        let failure = svm_locker
+            .commit_bundle_transactions(&None, vec![first, second], true)
+            .await
+            .unwrap();
+        let Some(BundleStatusEvent::Failed { signature, .. }) = failure else {
+            panic!("expected the bundle to fail: {failure:?}");
+        };
+        assert_eq!(signature, second_signature);
+
+        // The first transfer was rolled back along with the second one
+        svm_locker.with_svm_reader(|svm_reader| {
+            assert!(svm_reader.inner.get_account(&recipient).is_none());
+            assert_eq!(
+                svm_reader.inner.get_balance(&payer.pubkey()),
+                Some(1_000_000_000)
+            );
+            assert!(!svm_reader.transactions.contains_key(&first_signature));
+            assert_eq!(
+                svm_reader.transactions_queued_for_confirmation.len(),
+                queued_transactions
+            );
+        });
+    }
+
+    #[tokio::test]
+    async fn test_discover_program_idls() {
+        let (svm, _events_rx, _geyser_rx) = SurfnetSvm::new();
+        let svm_locker = SurfnetSvmLocker::new(svm);
+        let program_id = Pubkey::new_unique();
+        let sender = program_datasource(&program_id);
+        let requested = sender.requested.clone();
+        let remote_ctx = Some((
+            SurfnetRemoteClient::new_with_sender(sender),
+            CommitmentConfig::confirmed(),
+        ));
+
+        // The IDL of a program fetched from the datasource is registered
+        let result = svm_locker
+            .get_account(&remote_ctx, &program_id, None)
+            .await
+            .unwrap();
+        assert!(matches!(
+            result.inner,
+            GetAccountResult::FoundProgramAccount(..)
+        ));
+        let idl = svm_locker.with_svm_reader(|svm_reader| {
+            svm_reader
+                .get_active_idls(&program_id)
+                .first()
+                .map(|idl| (*idl).clone())
+        });
+        let idl = idl.unwrap();
+        assert_eq!(idl.address, program_id.to_string());
+        assert_eq!(idl.metadata.name, "split_token_transfer");
+
+        // Programs with a registered IDL are skipped
+        let idl_address = anchor_idl_address(&program_id);
+        requested.lock().unwrap().clear();
+        svm_locker
+            .get_multiple_accounts(&remote_ctx, &[program_id], None)
+            .await
+            .unwrap();
+        assert!(!requested.lock().unwrap().contains(&idl_address));
+    }
+
+    #[tokio::test]
+    async fn test_clone_program_account_registers_idl() {
+        let (svm, _events_rx, _geyser_rx) = SurfnetSvm::new();
+        let svm_locker = SurfnetSvmLocker::new(svm);
+        let (source, destination) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let remote_ctx = Some((
+            SurfnetRemoteClient::new_with_sender(program_datasource(&source)),
+            CommitmentConfig::confirmed(),
+        ));
+
+        svm_locker
+            .clone_program_account(&remote_ctx, &source, &destination)
+            .await
+            .unwrap();
+
+        // The destination program is decoded with the IDL published by the source program
+        let idl = svm_locker.with_svm_reader(|svm_reader| {
+            svm_reader
+                .get_active_idls(&destination)
+                .first()
+                .map(|idl| (*idl).clone())
+        });
+        let idl = idl.unwrap();
+        assert_eq!(idl.address, destination.to_string());
+        assert_eq!(idl.metadata.name, "split_token_transfer");
+    }
+}
@@ -1,11 +1,19 @@
 use std::collections::HashMap;
 
+use agave_geyser_plugin_interface::geyser_plugin_interface::SlotStatus;
 use crossbeam_channel::Sender;
 use jsonrpc_core::Result as RpcError;
 use locker::SurfnetSvmLocker;
 use solana_account::Account;
 use solana_account_decoder::{UiAccount, UiAccountEncoding};
-use solana_client::{rpc_config::RpcTransactionLogsFilter, rpc_response::RpcLogsResponse};
+use solana_client::{
+    rpc_config::{
+        RpcAccountInfoConfig, RpcBlockSubscribeConfig, RpcBlockSubscribeFilter,
+        RpcTransactionLogsFilter,
+    },
+    rpc_filter::RpcFilterType,
+    rpc_response::{RpcBlockUpdate, RpcKeyedAccount, RpcLogsResponse},
+};
 use solana_clock::Slot;
 use solana_commitment_config::CommitmentLevel;
 use solana_epoch_info::EpochInfo;
@@ -18,27 +26,48 @@ use svm::SurfnetSvm;
 
 use crate::{
     error::{SurfpoolError, SurfpoolResult},
-    types::{GeyserAccountUpdate, TransactionWithStatusMeta},
+    types::{GeyserAccountUpdate, GeyserBlockMetadata, GeyserEntryInfo, TransactionWithStatusMeta},
 };
 
+pub mod bundles;
+pub mod cache;
+pub mod dump;
+pub mod events;
+pub mod fees;
+pub mod idl_accounts;
+pub mod instructions;
 pub mod locker;
+pub mod preload;
+pub mod program_errors;
+pub mod recorder;
 pub mod remote;
+pub mod staking;
 pub mod svm;
 
 pub const SURFPOOL_IDENTITY_PUBKEY: Pubkey =
     Pubkey::from_str_const("SUrFPooLSUrFPooLSUrFPooLSUrFPooLSUrFPooLSUr");
 pub const FINALIZATION_SLOT_THRESHOLD: u64 = 31;
 pub const SLOTS_PER_EPOCH: u64 = 432000;
+/// The maximum number of snapshots kept at once, each one holding a copy of the ledger state.
+pub const MAX_SNAPSHOTS: usize = 32;
+/// The maximum number of states kept in the history of an account.
+pub const MAX_ACCOUNT_HISTORY_LEN: usize = 64;
 
 pub type AccountFactory = Box<dyn Fn(SurfnetSvmLocker) -> GetAccountResult + Send + Sync>;
 
 pub enum GeyserEvent {
     NotifyTransaction(TransactionWithStatusMeta, Option<SanitizedTransaction>),
     UpdateAccount(GeyserAccountUpdate),
-    // todo: add more events
+    UpdateSlotStatus {
+        slot: Slot,
+        parent: Option<Slot>,
+        status: SlotStatus,
+    },
+    NotifyBlockMetadata(GeyserBlockMetadata),
+    NotifyEntry(GeyserEntryInfo),
 }
 
-#[derive(Debug, Eq, PartialEq, Hash, Clone)]
+#[derive(Debug, Eq, PartialEq, Hash, Clone, Serialize, Deserialize)]
 pub struct BlockIdentifier {
     pub index: u64,
     pub hash: String,
@@ -57,7 +86,7 @@ impl BlockIdentifier {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BlockHeader {
     pub hash: String,
     pub previous_blockhash: String,
@@ -87,6 +116,21 @@ pub type LogsSubscriptionData = (
     Sender<(Slot, RpcLogsResponse)>,
 );
 
+pub type ProgramSubscriptionData = HashMap<
+    Pubkey,
+    Vec<(
+        RpcAccountInfoConfig,
+        Option<Vec<RpcFilterType>>,
+        Sender<(Slot, RpcKeyedAccount)>,
+    )>,
+>;
+
+pub type BlockSubscriptionData = (
+    RpcBlockSubscribeFilter,
+    RpcBlockSubscribeConfig,
+    Sender<(Slot, RpcBlockUpdate)>,
+);
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum SignatureSubscriptionType {
     Received,
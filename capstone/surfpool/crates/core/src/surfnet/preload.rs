@@ -0,0 +1,154 @@
+use std::{path::Path, str::FromStr};
+
+use base64::{Engine, prelude::BASE64_STANDARD};
+use solana_account::Account;
+use solana_account_decoder::UiAccount;
+use solana_pubkey::Pubkey;
+use surfpool_types::{AccountFileFormat, AccountPreload, Idl};
+
+use crate::error::{SurfpoolError, SurfpoolResult};
+
+/// A keyed account, as printed by `solana account <ADDRESS> --output json`.
+#[derive(Deserialize)]
+struct KeyedAccountFile {
+    pubkey: String,
+    account: UiAccount,
+}
+
+/// Reads the account described by `preload` from its file.
+///
+/// `minimum_balance` provides the rent exempt minimum for a data length, used when a base64 file
+/// comes without lamports.
+pub fn read_account_file<F>(
+    preload: &AccountPreload,
+    minimum_balance: F,
+) -> SurfpoolResult<(Pubkey, Account)>
+where
+    F: Fn(usize) -> u64,
+{
+    let path = &preload.path;
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| SurfpoolError::account_file(path.display(), e))?;
+
+    match &preload.format {
+        AccountFileFormat::Json => {
+            let keyed_account: KeyedAccountFile = serde_json::from_str(&content)
+                .map_err(|e| SurfpoolError::account_file(path.display(), e))?;
+            let pubkey = match preload.pubkey {
+                Some(pubkey) => pubkey,
+                None => Pubkey::from_str(&keyed_account.pubkey)
+                    .map_err(|e| SurfpoolError::account_file(path.display(), e))?,
+            };
+            let account = keyed_account.account.decode::<Account>().ok_or_else(|| {
+                SurfpoolError::account_file(path.display(), "unsupported account data encoding")
+            })?;
+            Ok((pubkey, account))
+        }
+        AccountFileFormat::Base64 {
+            owner,
+            lamports,
+            executable,
+        } => {
+            let pubkey = preload.pubkey.ok_or_else(|| {
+                SurfpoolError::account_file(
+                    path.display(),
+                    "the address of a base64 encoded account must be provided",
+                )
+            })?;
+            let data = BASE64_STANDARD
+                .decode(content.trim())
+                .map_err(|e| SurfpoolError::account_file(path.display(), e))?;
+            let account = Account {
+                lamports: lamports.unwrap_or_else(|| minimum_balance(data.len())),
+                data,
+                owner: *owner,
+                executable: *executable,
+                rent_epoch: 0,
+            };
+            Ok((pubkey, account))
+        }
+    }
+}
+
+/// Reads an Anchor IDL from a JSON file.
+pub fn read_idl_file(path: &Path) -> SurfpoolResult<Idl> {
+    let content =
+        std::fs::read_to_string(path).map_err(|e| SurfpoolError::idl_file(path.display(), e))?;
+    serde_json::from_str(&content).map_err(|e| SurfpoolError::idl_file(path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_account_decoder::{UiAccountEncoding, encode_ui_account};
+
+    use super::*;
+
+    #[test]
+    fn test_read_account_files() {
+        let dir = std::env::temp_dir().join(format!("surfpool-preload-{}", Pubkey::new_unique()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let pubkey = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let account = Account {
+            lamports: 1_000_000,
+            data: vec![1, 2, 3, 4],
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        };
+
+        let json_path = dir.join("account.json");
+        let keyed_account = serde_json::json!({
+            "pubkey": pubkey.to_string(),
+            "account": encode_ui_account(&pubkey, &account, UiAccountEncoding::Base64, None, None),
+        });
+        std::fs::write(&json_path, keyed_account.to_string()).unwrap();
+        let (read_pubkey, read_account) = read_account_file(
+            &AccountPreload {
+                pubkey: None,
+                path: json_path,
+                format: AccountFileFormat::Json,
+            },
+            |_| unreachable!(),
+        )
+        .unwrap();
+        assert_eq!(read_pubkey, pubkey);
+        assert_eq!(read_account, account);
+
+        let base64_path = dir.join("account.b64");
+        std::fs::write(
+            &base64_path,
+            format!("{}\n", BASE64_STANDARD.encode(&account.data)),
+        )
+        .unwrap();
+        let base64_preload = AccountPreload {
+            pubkey: Some(pubkey),
+            path: base64_path,
+            format: AccountFileFormat::Base64 {
+                owner,
+                lamports: None,
+                executable: false,
+            },
+        };
+        let (read_pubkey, read_account) =
+            read_account_file(&base64_preload, |len| len as u64 * 10).unwrap();
+        assert_eq!(read_pubkey, pubkey);
+        assert_eq!(read_account.lamports, 40);
+        assert_eq!(read_account.data, account.data);
+        assert_eq!(read_account.owner, owner);
+
+        // Base64 files don't provide the address of the account
+        assert!(
+            read_account_file(
+                &AccountPreload {
+                    pubkey: None,
+                    ..base64_preload
+                },
+                |_| 0
+            )
+            .is_err()
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,157 @@
+//! Resolution of custom program errors into the names and messages declared by registered IDLs.
+
+use solana_message::VersionedMessage;
+use solana_pubkey::Pubkey;
+use solana_sdk::instruction::InstructionError;
+use solana_transaction_error::TransactionError;
+use surfpool_types::NamedProgramError;
+
+use super::svm::SurfnetSvm;
+
+impl SurfnetSvm {
+    /// Resolves the custom program error of a failed transaction with the registered IDLs.
+    ///
+    /// The error can be returned by a program invoked through CPIs rather than by the program of
+    /// the failed instruction: the program that returned it is found in the `logs` of the transaction.
+    ///
+    /// # Returns
+    /// `None` if the transaction didn't fail with a custom program error, or if no IDL registered
+    /// for the program declares the error.
+    pub fn resolve_program_error(
+        &self,
+        message: &VersionedMessage,
+        err: &TransactionError,
+        logs: &[String],
+    ) -> Option<NamedProgramError> {
+        let TransactionError::InstructionError(instruction_index, InstructionError::Custom(code)) =
+            err
+        else {
+            return None;
+        };
+        let instruction = message.instructions().get(*instruction_index as usize)?;
+        let instruction_program_id = message
+            .static_account_keys()
+            .get(instruction.program_id_index as usize)?;
+        let program_id = failing_program_id(logs, *code).unwrap_or(*instruction_program_id);
+
+        let (idl, error) = self
+            .get_active_idls(&program_id)
+            .into_iter()
+            .find_map(|idl| {
+                idl.errors
+                    .iter()
+                    .find(|error| error.code == *code)
+                    .map(|error| (idl, error))
+            })?;
+        let instruction_name = self
+            .get_active_idls(instruction_program_id)
+            .into_iter()
+            .find_map(|idl| {
+                idl.instructions
+                    .iter()
+                    .find(|ix| {
+                        !ix.discriminator.is_empty()
+                            && instruction.data.starts_with(&ix.discriminator)
+                    })
+                    .map(|ix| ix.name.clone())
+            });
+
+        Some(NamedProgramError {
+            instruction_index: *instruction_index,
+            instruction_name,
+            program_id: program_id.to_string(),
+            program_name: idl.metadata.name.clone(),
+            code: *code,
+            name: error.name.clone(),
+            message: error.msg.clone(),
+        })
+    }
+}
+
+/// Finds the program that returned the custom error `code` in the logs of a transaction.
+fn failing_program_id(logs: &[String], code: u32) -> Option<Pubkey> {
+    let suffix = format!(" failed: custom program error: {code:#x}");
+    logs.iter().find_map(|log| {
+        log.strip_prefix("Program ")?
+            .strip_suffix(suffix.as_str())?
+            .parse()
+            .ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use solana_hash::Hash;
+    use solana_message::{Message, compiled_instruction::CompiledInstruction};
+    use surfpool_types::Idl;
+
+    use super::*;
+
+    #[test]
+    fn test_resolve_program_error() {
+        let (mut svm, _events_rx, _geyser_rx) = SurfnetSvm::new();
+        let mut idl: Idl =
+            serde_json::from_slice(include_bytes!("../tests/assets/idl_v1.json")).unwrap();
+        idl.errors = serde_json::from_value(serde_json::json!([{
+            "code": 6001,
+            "name": "FreezePeriodNotMet",
+            "msg": "The freeze period is not over"
+        }]))
+        .unwrap();
+        idl.instructions = serde_json::from_value(serde_json::json!([{
+            "name": "unstake",
+            "discriminator": [1, 2, 3, 4, 5, 6, 7, 8],
+            "accounts": [],
+            "args": []
+        }]))
+        .unwrap();
+        svm.register_idl(idl.clone(), Some(0));
+        let program_id = Pubkey::from_str_const(&idl.address);
+
+        let payer = Pubkey::new_unique();
+        let message = VersionedMessage::Legacy(Message::new_with_compiled_instructions(
+            1,
+            0,
+            1,
+            vec![payer, program_id],
+            Hash::new_unique(),
+            vec![
+                CompiledInstruction::new_from_raw_parts(1, vec![0; 8], vec![]),
+                CompiledInstruction::new_from_raw_parts(
+                    1,
+                    vec![1, 2, 3, 4, 5, 6, 7, 8, 42],
+                    vec![],
+                ),
+            ],
+        ));
+        let err = TransactionError::InstructionError(1, InstructionError::Custom(6001));
+
+        let program_error = svm.resolve_program_error(&message, &err, &[]).unwrap();
+        assert_eq!(
+            program_error,
+            NamedProgramError {
+                instruction_index: 1,
+                instruction_name: Some("unstake".to_string()),
+                program_id: program_id.to_string(),
+                program_name: idl.metadata.name.clone(),
+                code: 6001,
+                name: "FreezePeriodNotMet".to_string(),
+                message: Some("The freeze period is not over".to_string()),
+            }
+        );
+        assert_eq!(
+            program_error.to_string(),
+            "Instruction 1 (unstake) failed with split_token_transfer::FreezePeriodNotMet (custom program error 0x1771): The freeze period is not over"
+        );
+
+        // errors returned by a program without a registered IDL are not resolved
+        let logs = vec![format!(
+            "Program {} failed: custom program error: 0x1771",
+            Pubkey::new_unique()
+        )];
+        assert_eq!(svm.resolve_program_error(&message, &err, &logs), None);
+
+        let err = TransactionError::InstructionError(1, InstructionError::Custom(6002));
+        assert_eq!(svm.resolve_program_error(&message, &err, &[]), None);
+    }
+}
@@ -0,0 +1,214 @@
+use std::{
+    collections::HashMap,
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use serde_json::Value;
+use solana_rpc_client::{
+    http_sender::HttpSender,
+    rpc_sender::{RpcSender, RpcTransportStats},
+};
+use solana_rpc_client_api::{
+    client_error::{ErrorKind as ClientErrorKind, Result as ClientResult},
+    request::RpcRequest,
+};
+
+use crate::error::{SurfpoolError, SurfpoolResult};
+
+/// A request sent to the datasource RPC, along with its response: a line of a fixture file.
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedInteraction {
+    pub method: String,
+    pub params: Value,
+    pub response: Value,
+}
+
+fn interaction_key(method: &str, params: &Value) -> String {
+    format!("{method} {params}")
+}
+
+/// An [RpcSender] forwarding requests to the datasource over HTTP, and appending every successful
+/// response to a JSON lines fixture file that can later be served by a [ReplayingRpcSender].
+pub struct RecordingRpcSender {
+    sender: HttpSender,
+    path: PathBuf,
+    file: Mutex<File>,
+}
+
+impl RecordingRpcSender {
+    pub fn new(url: &str, path: &Path) -> SurfpoolResult<Self> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .map_err(|e| SurfpoolError::remote_traffic_fixture(path.display(), e))?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| SurfpoolError::remote_traffic_fixture(path.display(), e))?;
+        Ok(Self {
+            sender: HttpSender::new(url),
+            path: path.to_path_buf(),
+            file: Mutex::new(file),
+        })
+    }
+
+    fn record(&self, method: String, params: Value, response: &Value) -> Result<(), String> {
+        let interaction = RecordedInteraction {
+            method,
+            params,
+            response: response.clone(),
+        };
+        let mut line = serde_json::to_vec(&interaction).map_err(|e| e.to_string())?;
+        line.push(b'\n');
+        let mut file = self.file.lock().map_err(|e| e.to_string())?;
+        file.write_all(&line).map_err(|e| e.to_string())
+    }
+}
+
+#[async_trait::async_trait]
+impl RpcSender for RecordingRpcSender {
+    async fn send(&self, request: RpcRequest, params: Value) -> ClientResult<Value> {
+        let response = self.sender.send(request, params.clone()).await?;
+        self.record(request.to_string(), params, &response)
+            .map_err(|e| {
+                ClientErrorKind::Custom(format!(
+                    "unable to record response to {}: {e}",
+                    self.path.display()
+                ))
+            })?;
+        Ok(response)
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        self.sender.get_transport_stats()
+    }
+
+    fn url(&self) -> String {
+        self.sender.url()
+    }
+}
+
+/// An [RpcSender] serving the responses recorded by a [RecordingRpcSender], without any network access.
+///
+/// When a request was recorded several times, the latest response is served.
+pub struct ReplayingRpcSender {
+    url: String,
+    path: PathBuf,
+    responses: HashMap<String, Value>,
+}
+
+impl ReplayingRpcSender {
+    pub fn new(url: &str, path: &Path) -> SurfpoolResult<Self> {
+        let file = File::open(path)
+            .map_err(|e| SurfpoolError::remote_traffic_fixture(path.display(), e))?;
+        let mut responses = HashMap::new();
+        for line in BufReader::new(file).lines() {
+            let line =
+                line.map_err(|e| SurfpoolError::remote_traffic_fixture(path.display(), e))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let interaction: RecordedInteraction = serde_json::from_str(&line)
+                .map_err(|e| SurfpoolError::remote_traffic_fixture(path.display(), e))?;
+            responses.insert(
+                interaction_key(&interaction.method, &interaction.params),
+                interaction.response,
+            );
+        }
+        Ok(Self {
+            url: url.to_string(),
+            path: path.to_path_buf(),
+            responses,
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl RpcSender for ReplayingRpcSender {
+    async fn send(&self, request: RpcRequest, params: Value) -> ClientResult<Value> {
+        let method = request.to_string();
+        self.responses
+            .get(&interaction_key(&method, &params))
+            .cloned()
+            .ok_or_else(|| {
+                ClientErrorKind::Custom(format!(
+                    "no response to {method} {params} was recorded in {}",
+                    self.path.display()
+                ))
+                .into()
+            })
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        RpcTransportStats::default()
+    }
+
+    fn url(&self) -> String {
+        self.url.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use solana_pubkey::Pubkey;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_replay_recorded_interactions() {
+        let path = std::env::temp_dir()
+            .join(format!("surfpool-fixture-{}", Pubkey::new_unique()))
+            .join("traffic.jsonl");
+        let pubkey = Pubkey::new_unique().to_string();
+        let params = json!([pubkey, { "encoding": "base64" }]);
+        let interactions = [
+            RecordedInteraction {
+                method: "getAccountInfo".to_string(),
+                params: params.clone(),
+                response: json!({ "context": { "slot": 1 }, "value": null }),
+            },
+            RecordedInteraction {
+                method: "getAccountInfo".to_string(),
+                params: params.clone(),
+                response: json!({ "context": { "slot": 2 }, "value": null }),
+            },
+        ];
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let lines = interactions
+            .iter()
+            .map(|interaction| serde_json::to_string(interaction).unwrap())
+            .collect::<Vec<_>>()
+            .join("\n");
+        std::fs::write(&path, lines).unwrap();
+
+        let sender = ReplayingRpcSender::new("http://localhost:8899", &path).unwrap();
+        let response = sender
+            .send(RpcRequest::GetAccountInfo, params.clone())
+            .await
+            .unwrap();
+        assert_eq!(response["context"]["slot"], 2);
+        assert!(
+            sender
+                .send(
+                    RpcRequest::GetAccountInfo,
+                    json!([Pubkey::new_unique().to_string()])
+                )
+                .await
+                .is_err()
+        );
+        assert!(
+            sender
+                .send(RpcRequest::GetGenesisHash, Value::Null)
+                .await
+                .is_err()
+        );
+
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+}
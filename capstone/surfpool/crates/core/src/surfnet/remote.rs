@@ -1,7 +1,9 @@
-use std::str::FromStr;
+use std::{collections::HashMap, str::FromStr, sync::Arc};
 
+use jsonrpc_core::futures::{StreamExt, TryStreamExt, stream};
 use serde_json::json;
 use solana_account::Account;
+use solana_account_decoder::UiAccountEncoding;
 use solana_client::{
     nonblocking::rpc_client::RpcClient,
     rpc_client::GetConfirmedSignaturesForAddress2Config,
@@ -21,26 +23,67 @@ use solana_commitment_config::CommitmentConfig;
 use solana_epoch_info::EpochInfo;
 use solana_hash::Hash;
 use solana_pubkey::Pubkey;
+use solana_rpc_client::{
+    http_sender::HttpSender, rpc_client::RpcClientConfig, rpc_sender::RpcSender,
+};
 use solana_sdk::bpf_loader_upgradeable::get_program_data_address;
 use solana_signature::Signature;
-use solana_transaction_status::UiConfirmedBlock;
+use solana_transaction_status::{
+    EncodedConfirmedTransactionWithStatusMeta, EncodedTransaction, TransactionDetails,
+    UiConfirmedBlock, UiMessage, UiTransactionEncoding,
+};
+use surfpool_types::{Idl, RemoteTrafficMode};
 
 use super::GetTransactionResult;
 use crate::{
     error::{SurfpoolError, SurfpoolResult},
-    rpc::utils::is_method_not_supported_error,
-    surfnet::{GetAccountResult, locker::is_supported_token_program},
+    rpc::{metrics::DatasourceRpcSender, utils::is_method_not_supported_error},
+    surfnet::{
+        GetAccountResult,
+        cache::RemoteAccountCache,
+        idl_accounts::{
+            anchor_idl_address, decode_anchor_idl_account, decode_metadata_idl_account,
+            metadata_idl_address,
+        },
+        locker::is_supported_token_program,
+        recorder::{RecordingRpcSender, ReplayingRpcSender},
+    },
     types::{RemoteRpcResult, TokenAccount},
 };
 
+/// The maximum number of transactions landed after the fork slot, or after a replayed transaction,
+/// that are inspected per account. `getSignaturesForAddress` doesn't return more in a single page.
+///
+/// Checking that accounts were not written costs one `getSignaturesForAddress` call per account, plus one
+/// `getTransaction` call per transaction referencing them: up to this many per account, although a
+/// transaction referencing several of the accounts checked together is only fetched once.
+const MAX_SIGNATURES_PER_ACCOUNT: usize = 1_000;
+/// The number of concurrent requests made to check that accounts were not written since a point in time.
+const WRITE_CHECK_CONCURRENCY: usize = 16;
+
+/// Transaction history, used to check that accounts were not written since a point in time, is not served
+/// at the processed commitment.
+fn write_check_commitment(commitment_config: CommitmentConfig) -> CommitmentConfig {
+    if commitment_config.is_at_least_confirmed() {
+        commitment_config
+    } else {
+        CommitmentConfig::confirmed()
+    }
+}
+
 pub struct SurfnetRemoteClient {
-    pub client: RpcClient,
+    pub client: Arc<RpcClient>,
+    /// The cache of the accounts fetched from the datasource, shared by all the clones of this client
+    pub cache: Option<RemoteAccountCache>,
+    /// The slot the datasource state is pinned to, if any
+    pub fork_slot: Option<Slot>,
 }
 impl Clone for SurfnetRemoteClient {
     fn clone(&self) -> Self {
-        let remote_rpc_url = self.client.url();
         SurfnetRemoteClient {
-            client: RpcClient::new(remote_rpc_url),
+            client: self.client.clone(),
+            cache: self.cache.clone(),
+            fork_slot: self.fork_slot,
         }
     }
 }
@@ -58,54 +101,373 @@ impl SomeRemoteCtx for Option<SurfnetRemoteClient> {
 
 impl SurfnetRemoteClient {
     pub fn new(remote_rpc_url: &str) -> Self {
+        Self::new_with_sender(HttpSender::new(remote_rpc_url))
+    }
+
+    /// Creates a client recording every response of the datasource to a fixture file, or serving
+    /// them from one if the traffic mode is [RemoteTrafficMode::Replay].
+    pub fn new_with_traffic_mode(
+        remote_rpc_url: &str,
+        traffic_mode: Option<&RemoteTrafficMode>,
+    ) -> SurfpoolResult<Self> {
+        match traffic_mode {
+            None => Ok(Self::new(remote_rpc_url)),
+            Some(RemoteTrafficMode::Record(path)) => Ok(Self::new_with_sender(
+                RecordingRpcSender::new(remote_rpc_url, path)?,
+            )),
+            Some(RemoteTrafficMode::Replay(path)) => Ok(Self::new_with_sender(
+                ReplayingRpcSender::new(remote_rpc_url, path)?,
+            )),
+        }
+    }
+
+    /// Every request goes through a [DatasourceRpcSender], so that the RPC metrics can tell which
+    /// calls reached the datasource.
+    pub(crate) fn new_with_sender<S: RpcSender + Send + Sync + 'static>(sender: S) -> Self {
         SurfnetRemoteClient {
-            client: RpcClient::new(remote_rpc_url.to_string()),
+            client: Arc::new(RpcClient::new_sender(
+                DatasourceRpcSender::new(sender),
+                RpcClientConfig::default(),
+            )),
+            cache: None,
+            fork_slot: None,
         }
     }
 
-    pub async fn get_epoch_info(&self) -> SurfpoolResult<EpochInfo> {
-        self.client.get_epoch_info().await.map_err(Into::into)
+    /// Serves the accounts fetched from the datasource from the given cache, when they are fresh enough.
+    pub fn with_cache(mut self, cache: Option<RemoteAccountCache>) -> Self {
+        self.cache = cache;
+        self
     }
 
-    pub async fn get_account(
+    /// Pins every account read to the state of the datasource at `fork_slot`.
+    pub fn with_fork_slot(mut self, fork_slot: Option<Slot>) -> Self {
+        self.fork_slot = fork_slot;
+        self
+    }
+
+    /// When the client is pinned to a fork slot, accounts are read at a commitment the transaction history
+    /// used to check them is served at.
+    fn account_commitment(&self, commitment_config: CommitmentConfig) -> CommitmentConfig {
+        match self.fork_slot {
+            Some(_) => write_check_commitment(commitment_config),
+            None => commitment_config,
+        }
+    }
+
+    fn account_info_config(&self, commitment_config: CommitmentConfig) -> RpcAccountInfoConfig {
+        RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(self.account_commitment(commitment_config)),
+            data_slice: None,
+            min_context_slot: self.fork_slot,
+        }
+    }
+
+    /// Applies the fork slot to a caller provided config, unless it already sets a minimum context slot.
+    fn pinned_account_info_config(&self, config: &RpcAccountInfoConfig) -> RpcAccountInfoConfig {
+        RpcAccountInfoConfig {
+            commitment: config
+                .commitment
+                .map(|commitment_config| self.account_commitment(commitment_config)),
+            min_context_slot: config.min_context_slot.or(self.fork_slot),
+            ..config.clone()
+        }
+    }
+
+    /// The RPC only serves the latest state of an account, so when the client is pinned to a fork slot
+    /// this refuses the accounts written by a transaction that landed after the fork slot.
+    ///
+    /// Transactions only reading an account are ignored. The accounts are checked together, and each
+    /// transaction is fetched once even when it references several of them.
+    ///
+    /// `context_slot` is the slot the accounts were read at: the transaction history is requested with it
+    /// as minimum context slot, so that it covers every write the accounts read may reflect.
+    async fn ensure_unwritten_since_fork_slot(
+        &self,
+        pubkeys: &[Pubkey],
+        commitment_config: CommitmentConfig,
+        context_slot: Slot,
+    ) -> SurfpoolResult<()> {
+        let Some(fork_slot) = self.fork_slot else {
+            return Ok(());
+        };
+        let commitment_config = write_check_commitment(commitment_config);
+        let signatures = stream::iter(pubkeys.iter().map(|pubkey| async move {
+            self.get_signatures_after_slot(pubkey, fork_slot, commitment_config, context_slot)
+                .await
+                .map(|signatures| (*pubkey, signatures))
+        }))
+        .buffer_unordered(WRITE_CHECK_CONCURRENCY)
+        .try_collect::<Vec<_>>()
+        .await?;
+
+        match self
+            .find_written_account(signatures, commitment_config)
+            .await?
+        {
+            Some((pubkey, slot)) => Err(SurfpoolError::account_modified_after_fork_slot(
+                pubkey, fork_slot, slot,
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Ensures that none of `pubkeys` was written by a transaction landed after the one of `signature`.
+    pub async fn ensure_unwritten_since_transaction(
+        &self,
+        signature: &Signature,
+        pubkeys: &[Pubkey],
+    ) -> SurfpoolResult<()> {
+        let commitment_config = CommitmentConfig::confirmed();
+        let signatures = stream::iter(pubkeys.iter().map(|pubkey| async move {
+            let statuses = self
+                .client
+                .get_signatures_for_address_with_config(
+                    pubkey,
+                    GetConfirmedSignaturesForAddress2Config {
+                        until: Some(*signature),
+                        limit: Some(MAX_SIGNATURES_PER_ACCOUNT),
+                        commitment: Some(commitment_config),
+                        ..Default::default()
+                    },
+                )
+                .await
+                .map_err(|e| SurfpoolError::get_account(*pubkey, e))?;
+            // A full page of transactions may not reach back to the one of `signature`
+            if statuses.len() == MAX_SIGNATURES_PER_ACCOUNT {
+                return Err(SurfpoolError::too_many_transactions_after_transaction(
+                    *pubkey,
+                    *signature,
+                    MAX_SIGNATURES_PER_ACCOUNT,
+                ));
+            }
+            Ok((*pubkey, statuses))
+        }))
+        .buffer_unordered(WRITE_CHECK_CONCURRENCY)
+        .try_collect::<Vec<_>>()
+        .await?;
+
+        match self
+            .find_written_account(signatures, commitment_config)
+            .await?
+        {
+            Some((pubkey, slot)) => Err(SurfpoolError::account_modified_after_transaction(
+                pubkey, *signature, slot,
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// Fetches the transactions referencing each account, and returns an account written by one of them,
+    /// along with the slot it landed at.
+    async fn find_written_account(
+        &self,
+        signatures: Vec<(Pubkey, Vec<RpcConfirmedTransactionStatusWithSignature>)>,
+        commitment_config: CommitmentConfig,
+    ) -> SurfpoolResult<Option<(Pubkey, Slot)>> {
+        // The accounts referenced by each transaction, and the slot it landed at
+        let mut transactions: HashMap<Signature, (Slot, Vec<Pubkey>)> = HashMap::new();
+        for (pubkey, statuses) in signatures {
+            for status in statuses {
+                let signature = Signature::from_str(&status.signature)
+                    .map_err(|e| SurfpoolError::get_account(pubkey, e))?;
+                transactions
+                    .entry(signature)
+                    .or_insert_with(|| (status.slot, vec![]))
+                    .1
+                    .push(pubkey);
+            }
+        }
+
+        let written = stream::iter(transactions.into_iter().map(
+            |(signature, (slot, pubkeys))| async move {
+                let transaction = self
+                    .client
+                    .get_transaction_with_config(
+                        &signature,
+                        RpcTransactionConfig {
+                            encoding: Some(UiTransactionEncoding::JsonParsed),
+                            commitment: Some(commitment_config),
+                            max_supported_transaction_version: Some(0),
+                        },
+                    )
+                    .await
+                    .map_err(|e| SurfpoolError::get_transaction(signature, e))?;
+                // Transactions that can't be decoded are considered to write every account they reference
+                let written = written_accounts(&transaction);
+                Ok::<_, SurfpoolError>(
+                    pubkeys
+                        .into_iter()
+                        .find(|pubkey| {
+                            written
+                                .as_ref()
+                                .is_none_or(|written| written.contains(pubkey))
+                        })
+                        .map(|pubkey| (pubkey, slot)),
+                )
+            },
+        ))
+        .buffer_unordered(WRITE_CHECK_CONCURRENCY)
+        .try_collect::<Vec<_>>()
+        .await?;
+        Ok(written.into_iter().flatten().next())
+    }
+
+    /// Returns the statuses of the transactions referencing an account that landed after `slot`, as seen by
+    /// a datasource that reached `min_context_slot`.
+    async fn get_signatures_after_slot(
         &self,
         pubkey: &Pubkey,
+        slot: Slot,
         commitment_config: CommitmentConfig,
-    ) -> SurfpoolResult<GetAccountResult> {
+        min_context_slot: Slot,
+    ) -> SurfpoolResult<Vec<RpcConfirmedTransactionStatusWithSignature>> {
+        // the RPC client's get_signatures_for_address_with_config doesn't allow providing a minimum context slot,
+        // so we need to use the send method directly
+        let statuses: Vec<RpcConfirmedTransactionStatusWithSignature> = self
+            .client
+            .send(
+                RpcRequest::GetSignaturesForAddress,
+                json!([
+                    pubkey.to_string(),
+                    RpcSignaturesForAddressConfig {
+                        limit: Some(MAX_SIGNATURES_PER_ACCOUNT),
+                        commitment: Some(commitment_config),
+                        min_context_slot: Some(min_context_slot),
+                        ..Default::default()
+                    }
+                ]),
+            )
+            .await
+            .map_err(|e| SurfpoolError::get_account(*pubkey, e))?;
+        // A full page of transactions after the fork slot may not be all of them
+        if statuses.len() == MAX_SIGNATURES_PER_ACCOUNT
+            && statuses.last().is_some_and(|status| status.slot > slot)
+        {
+            return Err(SurfpoolError::too_many_transactions_after_fork_slot(
+                *pubkey,
+                slot,
+                MAX_SIGNATURES_PER_ACCOUNT,
+            ));
+        }
+        Ok(statuses
+            .into_iter()
+            .filter(|status| status.slot > slot)
+            .collect())
+    }
+
+    /// Fetches an account from the datasource, unless a fresh copy is available in the cache.
+    ///
+    /// Cached accounts were fetched at the same fork slot, and already checked against it.
+    async fn fetch_account(
+        &self,
+        pubkey: &Pubkey,
+        commitment_config: CommitmentConfig,
+    ) -> SurfpoolResult<Option<Account>> {
+        if let Some(cached) = self
+            .cache
+            .as_ref()
+            .and_then(|cache| cache.get(pubkey, commitment_config.commitment, self.fork_slot))
+        {
+            return Ok(cached.account);
+        }
         let res = self
             .client
-            .get_account_with_commitment(pubkey, commitment_config)
+            .get_account_with_config(pubkey, self.account_info_config(commitment_config))
             .await
             .map_err(|e| SurfpoolError::get_account(*pubkey, e))?;
+        self.ensure_unwritten_since_fork_slot(
+            std::slice::from_ref(pubkey),
+            commitment_config,
+            res.context.slot,
+        )
+        .await?;
+        self.cache_account(pubkey, commitment_config, res.context.slot, &res.value);
+        Ok(res.value)
+    }
+
+    fn cache_account(
+        &self,
+        pubkey: &Pubkey,
+        commitment_config: CommitmentConfig,
+        slot: Slot,
+        account: &Option<Account>,
+    ) {
+        if let Some(cache) = &self.cache {
+            // Failing to persist an entry should not fail the fetch itself
+            if let Err(e) = cache.insert(
+                *pubkey,
+                commitment_config.commitment,
+                self.fork_slot,
+                slot,
+                account.clone(),
+            ) {
+                warn!("Unable to cache remote account {pubkey}: {e}");
+            }
+        }
+    }
+
+    /// Returns the epoch info of the datasource, or the one at the fork slot if the client is pinned to one.
+    pub async fn get_epoch_info(&self) -> SurfpoolResult<EpochInfo> {
+        let Some(fork_slot) = self.fork_slot else {
+            return self.client.get_epoch_info().await.map_err(Into::into);
+        };
+        let epoch_schedule = self.client.get_epoch_schedule().await?;
+        let block = self
+            .client
+            .get_block_with_config(
+                fork_slot,
+                RpcBlockConfig {
+                    transaction_details: Some(TransactionDetails::None),
+                    rewards: Some(false),
+                    ..Default::default()
+                },
+            )
+            .await
+            .map_err(|e| SurfpoolError::invalid_fork_slot(fork_slot, e))?;
+        let (epoch, slot_index) = epoch_schedule.get_epoch_and_slot_index(fork_slot);
+        Ok(EpochInfo {
+            epoch,
+            slot_index,
+            slots_in_epoch: epoch_schedule.get_slots_in_epoch(epoch),
+            absolute_slot: fork_slot,
+            block_height: block.block_height.unwrap_or_default(),
+            transaction_count: None,
+        })
+    }
+
+    pub async fn get_account(
+        &self,
+        pubkey: &Pubkey,
+        commitment_config: CommitmentConfig,
+    ) -> SurfpoolResult<GetAccountResult> {
+        let res = self.fetch_account(pubkey, commitment_config).await?;
 
-        let result = match res.value {
+        let result = match res {
             Some(account) => {
                 let mut result = None;
                 if is_supported_token_program(&account.owner) {
                     if let Some(token_account) = TokenAccount::unpack(&account.data).ok() {
                         let mint = self
-                            .client
-                            .get_account_with_commitment(&token_account.mint(), commitment_config)
-                            .await
-                            .map_err(|e| SurfpoolError::get_account(*pubkey, e))?;
+                            .fetch_account(&token_account.mint(), commitment_config)
+                            .await?;
 
                         result = Some(GetAccountResult::FoundTokenAccount(
                             (*pubkey, account.clone()),
-                            (token_account.mint(), mint.value),
+                            (token_account.mint(), mint),
                         ));
                     };
                 } else if account.executable {
                     let program_data_address = get_program_data_address(pubkey);
 
                     let program_data = self
-                        .client
-                        .get_account_with_commitment(&program_data_address, commitment_config)
-                        .await
-                        .map_err(|e| SurfpoolError::get_account(*pubkey, e))?;
+                        .fetch_account(&program_data_address, commitment_config)
+                        .await?;
 
                     result = Some(GetAccountResult::FoundProgramAccount(
                         (*pubkey, account.clone()),
-                        (program_data_address, program_data.value),
+                        (program_data_address, program_data),
                     ));
                 }
 
@@ -120,16 +482,65 @@ impl SurfnetRemoteClient {
         Ok(result)
     }
 
+    /// Fetches the IDL published on-chain by a program, looking first for the IDL account of Anchor
+    /// programs, then for the `idl` account of the Program Metadata program.
+    pub async fn get_program_idl(
+        &self,
+        program_id: &Pubkey,
+        commitment_config: CommitmentConfig,
+    ) -> SurfpoolResult<Option<Idl>> {
+        if let Some(idl) = self
+            .fetch_account(&anchor_idl_address(program_id), commitment_config)
+            .await?
+            .and_then(|account| decode_anchor_idl_account(program_id, &account.data))
+        {
+            return Ok(Some(idl));
+        }
+        Ok(self
+            .fetch_account(&metadata_idl_address(program_id), commitment_config)
+            .await?
+            .and_then(|account| decode_metadata_idl_account(program_id, &account.data)))
+    }
+
     pub async fn get_multiple_accounts(
         &self,
         pubkeys: &[Pubkey],
         commitment_config: CommitmentConfig,
     ) -> SurfpoolResult<Vec<GetAccountResult>> {
-        let remote_accounts = self
-            .client
-            .get_multiple_accounts(pubkeys)
-            .await
-            .map_err(SurfpoolError::get_multiple_accounts)?;
+        let mut remote_accounts: Vec<Option<Account>> = vec![None; pubkeys.len()];
+        let mut uncached_indexes = vec![];
+        for (i, pubkey) in pubkeys.iter().enumerate() {
+            match self
+                .cache
+                .as_ref()
+                .and_then(|cache| cache.get(pubkey, commitment_config.commitment, self.fork_slot))
+            {
+                Some(cached) => remote_accounts[i] = cached.account,
+                None => uncached_indexes.push(i),
+            }
+        }
+
+        if !uncached_indexes.is_empty() {
+            let uncached_pubkeys = uncached_indexes
+                .iter()
+                .map(|i| pubkeys[*i])
+                .collect::<Vec<_>>();
+            let res = self
+                .client
+                .get_multiple_accounts_with_config(
+                    &uncached_pubkeys,
+                    self.account_info_config(commitment_config),
+                )
+                .await
+                .map_err(SurfpoolError::get_multiple_accounts)?;
+            let slot = res.context.slot;
+            self.ensure_unwritten_since_fork_slot(&uncached_pubkeys, commitment_config, slot)
+                .await?;
+            for (i, account) in uncached_indexes.into_iter().zip(res.value) {
+                self.cache_account(&pubkeys[i], commitment_config, slot, &account);
+                remote_accounts[i] = account;
+            }
+        }
 
         let mut accounts_result = vec![];
         for (pubkey, remote_account) in pubkeys.iter().zip(remote_accounts) {
@@ -145,14 +556,12 @@ impl SurfnetRemoteClient {
                     let program_data_address = get_program_data_address(pubkey);
 
                     let program_data = self
-                        .client
-                        .get_account_with_commitment(&program_data_address, commitment_config)
-                        .await
-                        .map_err(|e| SurfpoolError::get_account(*pubkey, e))?;
+                        .fetch_account(&program_data_address, commitment_config)
+                        .await?;
 
                     accounts_result.push(GetAccountResult::FoundProgramAccount(
                         (*pubkey, remote_account),
-                        (program_data_address, program_data.value),
+                        (program_data_address, program_data),
                     ));
                 }
             } else {
@@ -178,6 +587,24 @@ impl SurfnetRemoteClient {
         }
     }
 
+    /// Fetches a confirmed transaction, encoded so that it can be decoded back into a versioned transaction.
+    pub async fn get_encoded_transaction(
+        &self,
+        signature: &Signature,
+    ) -> SurfpoolResult<EncodedConfirmedTransactionWithStatusMeta> {
+        self.client
+            .get_transaction_with_config(
+                signature,
+                RpcTransactionConfig {
+                    encoding: Some(UiTransactionEncoding::Base64),
+                    commitment: Some(CommitmentConfig::confirmed()),
+                    max_supported_transaction_version: Some(0),
+                },
+            )
+            .await
+            .map_err(|e| SurfpoolError::get_transaction(*signature, e))
+    }
+
     pub async fn get_token_accounts_by_owner(
         &self,
         owner: Pubkey,
@@ -197,11 +624,21 @@ impl SurfnetRemoteClient {
             .client
             .send(
                 RpcRequest::GetTokenAccountsByOwner,
-                json!([owner.to_string(), token_account_filter, config]),
+                json!([
+                    owner.to_string(),
+                    token_account_filter,
+                    self.pinned_account_info_config(config)
+                ]),
             )
             .await;
-        res.map_err(|e| SurfpoolError::get_token_accounts(owner, filter, e))
-            .map(|res| res.value)
+        let res = res.map_err(|e| SurfpoolError::get_token_accounts(owner, filter, e))?;
+        self.ensure_unwritten_since_fork_slot(
+            &keyed_accounts_pubkeys(&res.value),
+            config.commitment.unwrap_or_default(),
+            res.context.slot,
+        )
+        .await?;
+        Ok(res.value)
     }
 
     pub async fn get_token_largest_accounts(
@@ -240,12 +677,24 @@ impl SurfnetRemoteClient {
             .client
             .send(
                 RpcRequest::GetTokenAccountsByDelegate,
-                json!([delegate.to_string(), token_account_filter, config]),
+                json!([
+                    delegate.to_string(),
+                    token_account_filter,
+                    self.pinned_account_info_config(config)
+                ]),
             )
             .await;
 
-        res.map_err(|e| SurfpoolError::get_token_accounts_by_delegate_error(delegate, filter, e))
-            .map(|res| res.value)
+        let res = res.map_err(|e| {
+            SurfpoolError::get_token_accounts_by_delegate_error(delegate, filter, e)
+        })?;
+        self.ensure_unwritten_since_fork_slot(
+            &keyed_accounts_pubkeys(&res.value),
+            config.commitment.unwrap_or_default(),
+            res.context.slot,
+        )
+        .await?;
+        Ok(res.value)
     }
 
     pub async fn get_program_accounts(
@@ -254,20 +703,37 @@ impl SurfnetRemoteClient {
         account_config: RpcAccountInfoConfig,
         filters: Option<Vec<RpcFilterType>>,
     ) -> SurfpoolResult<RemoteRpcResult<Vec<(Pubkey, Account)>>> {
+        let commitment_config = account_config.commitment.unwrap_or_default();
         handle_remote_rpc(|| async {
-            self.client
+            let accounts = self
+                .client
                 .get_program_accounts_with_config(
                     program_id,
                     RpcProgramAccountsConfig {
                         filters,
                         with_context: Some(false),
-                        account_config,
+                        account_config: self.pinned_account_info_config(&account_config),
                         ..Default::default()
                     },
                 )
                 .await
-                .map(|res| res)
-                .map_err(|e| SurfpoolError::get_program_accounts(*program_id, e))
+                .map_err(|e| SurfpoolError::get_program_accounts(*program_id, e))?;
+            let pubkeys = accounts
+                .iter()
+                .map(|(pubkey, _)| *pubkey)
+                .collect::<Vec<_>>();
+            if self.fork_slot.is_some() && !pubkeys.is_empty() {
+                // The context slot of the accounts isn't returned, but they were read at most at
+                // the slot reached once they are returned
+                let context_slot = self
+                    .client
+                    .get_slot_with_commitment(self.account_commitment(commitment_config))
+                    .await
+                    .map_err(|e| SurfpoolError::get_program_accounts(*program_id, e))?;
+                self.ensure_unwritten_since_fork_slot(&pubkeys, commitment_config, context_slot)
+                    .await?;
+            }
+            Ok::<_, SurfpoolError>(accounts)
         })
         .await
     }
@@ -322,6 +788,40 @@ impl SurfnetRemoteClient {
     }
 }
 
+/// Returns the accounts written by a confirmed transaction, fetched with the `jsonParsed` encoding:
+/// the writable accounts of its message, or only its fee payer if it failed.
+///
+/// Returns `None` if the transaction is not encoded as expected.
+fn written_accounts(
+    transaction: &EncodedConfirmedTransactionWithStatusMeta,
+) -> Option<Vec<Pubkey>> {
+    let EncodedTransaction::Json(ui_transaction) = &transaction.transaction.transaction else {
+        return None;
+    };
+    let UiMessage::Parsed(message) = &ui_transaction.message else {
+        return None;
+    };
+    let failed = transaction
+        .transaction
+        .meta
+        .as_ref()
+        .is_some_and(|meta| meta.err.is_some());
+    message
+        .account_keys
+        .iter()
+        .enumerate()
+        .filter(|(i, account)| if failed { *i == 0 } else { account.writable })
+        .map(|(_, account)| Pubkey::from_str(&account.pubkey).ok())
+        .collect()
+}
+
+fn keyed_accounts_pubkeys(accounts: &[RpcKeyedAccount]) -> Vec<Pubkey> {
+    accounts
+        .iter()
+        .filter_map(|account| Pubkey::from_str(&account.pubkey).ok())
+        .collect()
+}
+
 /// Handles remote RPC calls, returning a `RemoteRpcResult` indicating whether the method was supported.
 /// If the method is not supported, it returns `RemoteRpcResult::MethodNotSupported`.
 /// If the method is supported, it returns `RemoteRpcResult::Ok(T)`.
@@ -338,3 +838,256 @@ where
         Err(e) => Err(e),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+    use solana_rpc_client::rpc_sender::RpcTransportStats;
+    use solana_rpc_client_api::client_error::{
+        ErrorKind as ClientErrorKind, Result as ClientResult,
+    };
+
+    use super::*;
+
+    /// Serves the signatures of an address and the transactions, keyed by their first parameter.
+    #[derive(Default)]
+    struct MockRpcSender {
+        signatures: HashMap<String, Value>,
+        transactions: HashMap<String, Value>,
+        /// The slot reached by the datasource, refusing the requests with a greater minimum context slot
+        slot: Option<Slot>,
+    }
+
+    #[async_trait::async_trait]
+    impl RpcSender for MockRpcSender {
+        async fn send(&self, request: RpcRequest, params: Value) -> ClientResult<Value> {
+            let key = params[0].as_str().unwrap_or_default();
+            if let (Some(slot), Some(min_context_slot)) =
+                (self.slot, params[1]["minContextSlot"].as_u64())
+            {
+                if min_context_slot > slot {
+                    return Err(ClientErrorKind::Custom(format!(
+                        "minimum context slot {min_context_slot} has not been reached"
+                    ))
+                    .into());
+                }
+            }
+            let responses = match request {
+                RpcRequest::GetSignaturesForAddress => &self.signatures,
+                RpcRequest::GetTransaction => &self.transactions,
+                _ => return Err(ClientErrorKind::Custom(format!("unexpected {request}")).into()),
+            };
+            responses.get(key).cloned().ok_or_else(|| {
+                ClientErrorKind::Custom(format!("no response to {request} {key}")).into()
+            })
+        }
+
+        fn get_transport_stats(&self) -> RpcTransportStats {
+            RpcTransportStats::default()
+        }
+
+        fn url(&self) -> String {
+            "http://localhost:8899".to_string()
+        }
+    }
+
+    fn signature_status(signature: &Signature, slot: Slot) -> Value {
+        json!({ "signature": signature.to_string(), "slot": slot, "err": null })
+    }
+
+    fn parsed_transaction(slot: Slot, account_keys: &[(Pubkey, bool)], failed: bool) -> Value {
+        let account_keys = account_keys
+            .iter()
+            .enumerate()
+            .map(|(i, (pubkey, writable))| {
+                json!({
+                    "pubkey": pubkey.to_string(),
+                    "writable": writable,
+                    "signer": i == 0,
+                    "source": "transaction",
+                })
+            })
+            .collect::<Vec<_>>();
+        let err = failed.then(|| json!({ "InstructionError": [0, "InvalidArgument"] }));
+        let status = match &err {
+            Some(err) => json!({ "Err": err }),
+            None => json!({ "Ok": null }),
+        };
+        json!({
+            "slot": slot,
+            "blockTime": null,
+            "version": 0,
+            "transaction": {
+                "signatures": [Signature::new_unique().to_string()],
+                "message": {
+                    "accountKeys": account_keys,
+                    "recentBlockhash": Hash::default().to_string(),
+                    "instructions": [],
+                },
+            },
+            "meta": {
+                "err": err,
+                "status": status,
+                "fee": 5000,
+                "preBalances": [],
+                "postBalances": [],
+            },
+        })
+    }
+
+    #[test]
+    fn test_written_accounts() {
+        let (payer, writable, readonly) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let keys = [(payer, true), (writable, true), (readonly, false)];
+
+        let transaction = serde_json::from_value(parsed_transaction(1, &keys, false)).unwrap();
+        assert_eq!(written_accounts(&transaction), Some(vec![payer, writable]));
+
+        // Failed transactions only charge their fee payer
+        let transaction = serde_json::from_value(parsed_transaction(1, &keys, true)).unwrap();
+        assert_eq!(written_accounts(&transaction), Some(vec![payer]));
+    }
+
+    #[tokio::test]
+    async fn test_ensure_unwritten_since_fork_slot() {
+        let fork_slot = 100;
+        let (payer, read, written, untouched) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let (read_tx, write_tx, old_tx) = (
+            Signature::new_unique(),
+            Signature::new_unique(),
+            Signature::new_unique(),
+        );
+
+        let mut sender = MockRpcSender::default();
+        sender.signatures.insert(
+            read.to_string(),
+            json!([
+                signature_status(&read_tx, 120),
+                signature_status(&old_tx, 90)
+            ]),
+        );
+        sender.signatures.insert(
+            written.to_string(),
+            json!([
+                signature_status(&write_tx, 130),
+                signature_status(&read_tx, 120)
+            ]),
+        );
+        // Transactions landed before the fork slot are not fetched
+        sender.signatures.insert(
+            untouched.to_string(),
+            json!([signature_status(&old_tx, 90)]),
+        );
+        sender.transactions.insert(
+            read_tx.to_string(),
+            parsed_transaction(
+                120,
+                &[(payer, true), (read, false), (written, false)],
+                false,
+            ),
+        );
+        sender.transactions.insert(
+            write_tx.to_string(),
+            parsed_transaction(130, &[(payer, true), (written, true)], false),
+        );
+
+        sender.slot = Some(150);
+
+        let client = SurfnetRemoteClient::new_with_sender(sender).with_fork_slot(Some(fork_slot));
+        let commitment_config = CommitmentConfig::confirmed();
+
+        // Accounts only read after the fork slot are served
+        client
+            .ensure_unwritten_since_fork_slot(&[read, untouched], commitment_config, 140)
+            .await
+            .unwrap();
+
+        let error = client
+            .ensure_unwritten_since_fork_slot(&[read, written, untouched], commitment_config, 140)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            SurfpoolError::account_modified_after_fork_slot(written, fork_slot, 130).to_string()
+        );
+
+        // The transaction history must cover the slot the accounts were read at
+        assert!(
+            client
+                .ensure_unwritten_since_fork_slot(&[untouched], commitment_config, 160)
+                .await
+                .is_err()
+        );
+
+        // Without a fork slot, nothing is checked
+        let client = SurfnetRemoteClient::new_with_sender(MockRpcSender::default());
+        client
+            .ensure_unwritten_since_fork_slot(&[written], commitment_config, 140)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_ensure_unwritten_since_transaction() {
+        let (payer, read, written) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let (replayed_tx, read_tx, write_tx) = (
+            Signature::new_unique(),
+            Signature::new_unique(),
+            Signature::new_unique(),
+        );
+
+        // Only the transactions landed after the replayed one are served
+        let mut sender = MockRpcSender::default();
+        sender
+            .signatures
+            .insert(read.to_string(), json!([signature_status(&read_tx, 120)]));
+        sender.signatures.insert(
+            written.to_string(),
+            json!([
+                signature_status(&write_tx, 130),
+                signature_status(&read_tx, 120)
+            ]),
+        );
+        sender.transactions.insert(
+            read_tx.to_string(),
+            parsed_transaction(
+                120,
+                &[(payer, true), (read, false), (written, false)],
+                false,
+            ),
+        );
+        sender.transactions.insert(
+            write_tx.to_string(),
+            parsed_transaction(130, &[(payer, true), (written, true)], false),
+        );
+        let client = SurfnetRemoteClient::new_with_sender(sender);
+
+        client
+            .ensure_unwritten_since_transaction(&replayed_tx, &[read])
+            .await
+            .unwrap();
+
+        let error = client
+            .ensure_unwritten_since_transaction(&replayed_tx, &[read, written])
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            SurfpoolError::account_modified_after_transaction(written, replayed_tx, 130)
+                .to_string()
+        );
+    }
+}
@@ -0,0 +1,623 @@
+//! Simulation of the stake program economics: synthetic validators voting on every slot, stake
+//! warmup and cooldown, and inflation rewards paid at epoch boundaries. Rewards approximate the
+//! ones of the runtime, see [SurfnetSvm::process_epoch_boundaries].
+
+use std::collections::{HashMap, HashSet};
+
+use solana_account::Account;
+use solana_client::rpc_response::{RpcInflationReward, RpcVoteAccountInfo, RpcVoteAccountStatus};
+use solana_clock::{Clock, Epoch, Slot};
+use solana_native_token::LAMPORTS_PER_SOL;
+use solana_pubkey::Pubkey;
+use solana_sdk_ids::{stake, system_program, vote};
+use solana_stake_interface::{
+    stake_flags::StakeFlags,
+    stake_history::{StakeHistory, StakeHistoryEntry},
+    state::{Authorized, Delegation, Meta, Stake, StakeStateV2},
+};
+use solana_vote_interface::state::{VoteInit, VoteState, VoteStateVersions};
+use surfpool_types::SimnetEvent;
+
+use super::{FINALIZATION_SLOT_THRESHOLD, SURFPOOL_IDENTITY_PUBKEY, svm::SurfnetSvm};
+use crate::error::{SurfpoolError, SurfpoolResult};
+
+/// The lamports delegated to each synthetic validator at genesis.
+pub const SYNTHETIC_VALIDATOR_STAKE: u64 = 1_000_000 * LAMPORTS_PER_SOL;
+/// The commission, in percent, charged by each synthetic validator on the rewards of its stakers.
+pub const SYNTHETIC_VALIDATOR_COMMISSION: u8 = 5;
+/// The number of consecutive slots assigned to a leader in the leader schedule.
+pub const NUM_CONSECUTIVE_LEADER_SLOTS: u64 = 4;
+/// The minimum delegation enforced by the stake program, with every feature enabled.
+pub const STAKE_MINIMUM_DELEGATION: u64 = LAMPORTS_PER_SOL;
+
+const SYNTHETIC_VALIDATOR_IDENTITY_BALANCE: u64 = 1_000 * LAMPORTS_PER_SOL;
+/// The number of epochs of credits returned for each vote account, as done by validators.
+const MAX_RPC_EPOCH_CREDITS_HISTORY: usize = 5;
+/// Every feature is enabled at genesis, including the reduced warmup and cooldown rate.
+const NEW_WARMUP_COOLDOWN_RATE_EPOCH: Option<Epoch> = Some(0);
+
+/// The stake and vote accounts updated while closing epochs, written back once every epoch is closed.
+struct EpochBoundaryState {
+    stake_history: StakeHistory,
+    stake_accounts: Vec<(Pubkey, Account, (Meta, Stake, StakeFlags))>,
+    vote_accounts: HashMap<Pubkey, (Account, VoteState)>,
+    updated_accounts: HashSet<Pubkey>,
+}
+
+/// A validator simulated by the surfnet, with its identity, vote and stake accounts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyntheticValidator {
+    pub identity: Pubkey,
+    pub vote_account: Pubkey,
+    pub stake_account: Pubkey,
+}
+
+impl SyntheticValidator {
+    /// Derives the accounts of the validator at `index`; the first validator is the surfnet itself.
+    pub fn new(index: usize) -> Self {
+        let identity = if index == 0 {
+            SURFPOOL_IDENTITY_PUBKEY
+        } else {
+            Pubkey::create_with_seed(
+                &SURFPOOL_IDENTITY_PUBKEY,
+                &format!("validator-{index}"),
+                &system_program::id(),
+            )
+            .expect("seed is shorter than the maximum seed length")
+        };
+        let vote_account = Pubkey::create_with_seed(&identity, "vote", &vote::id())
+            .expect("seed is shorter than the maximum seed length");
+        let stake_account = Pubkey::create_with_seed(&identity, "stake", &stake::id())
+            .expect("seed is shorter than the maximum seed length");
+        Self {
+            identity,
+            vote_account,
+            stake_account,
+        }
+    }
+}
+
+impl SurfnetSvm {
+    /// Creates `count` synthetic validators, each with a funded identity, a vote account and a
+    /// fully active stake delegation.
+    ///
+    /// Accounts already present, e.g. loaded from a state dump, are left untouched.
+    pub fn add_synthetic_validators(&mut self, count: usize) -> SurfpoolResult<()> {
+        let clock = self.inner.get_sysvar::<Clock>();
+        for index in self.validators.len()..self.validators.len() + count {
+            let validator = SyntheticValidator::new(index);
+            if self.inner.get_account(&validator.vote_account).is_none() {
+                self.set_genesis_account(
+                    &validator.identity,
+                    Account {
+                        lamports: SYNTHETIC_VALIDATOR_IDENTITY_BALANCE,
+                        data: vec![],
+                        owner: system_program::id(),
+                        executable: false,
+                        rent_epoch: 0,
+                    },
+                )?;
+
+                let vote_state = VoteState::new(
+                    &VoteInit {
+                        node_pubkey: validator.identity,
+                        authorized_voter: validator.identity,
+                        authorized_withdrawer: validator.identity,
+                        commission: SYNTHETIC_VALIDATOR_COMMISSION,
+                    },
+                    &clock,
+                );
+                let mut vote_data = vec![0; VoteState::size_of()];
+                VoteState::serialize(&VoteStateVersions::new_current(vote_state), &mut vote_data)
+                    .map_err(|e| {
+                    SurfpoolError::internal(format!("Failed to serialize vote account: {e}"))
+                })?;
+                self.set_genesis_account(
+                    &validator.vote_account,
+                    Account {
+                        lamports: self
+                            .inner
+                            .minimum_balance_for_rent_exemption(vote_data.len()),
+                        data: vote_data,
+                        owner: vote::id(),
+                        executable: false,
+                        rent_epoch: 0,
+                    },
+                )?;
+
+                let rent_exempt_reserve = self
+                    .inner
+                    .minimum_balance_for_rent_exemption(StakeStateV2::size_of());
+                let stake_state = StakeStateV2::Stake(
+                    Meta {
+                        rent_exempt_reserve,
+                        authorized: Authorized::auto(&validator.identity),
+                        ..Meta::default()
+                    },
+                    Stake {
+                        // Bootstrap delegations are active from the first epoch
+                        delegation: Delegation::new(
+                            &validator.vote_account,
+                            SYNTHETIC_VALIDATOR_STAKE,
+                            Epoch::MAX,
+                        ),
+                        credits_observed: 0,
+                    },
+                    StakeFlags::empty(),
+                );
+                self.set_genesis_account(
+                    &validator.stake_account,
+                    Account {
+                        lamports: SYNTHETIC_VALIDATOR_STAKE + rent_exempt_reserve,
+                        data: serialize_stake_state(&stake_state)?,
+                        owner: stake::id(),
+                        executable: false,
+                        rent_epoch: 0,
+                    },
+                )?;
+            }
+            self.validators.push(validator);
+        }
+        Ok(())
+    }
+
+    /// Processes the boundaries of every epoch from `from_epoch` up to `to_epoch`, the current epoch.
+    ///
+    /// The stake and vote accounts are loaded and written back once, however many epochs are
+    /// crossed at once, e.g. by a time travel.
+    pub fn process_epoch_boundaries(
+        &mut self,
+        from_epoch: Epoch,
+        to_epoch: Epoch,
+    ) -> SurfpoolResult<()> {
+        if from_epoch >= to_epoch {
+            return Ok(());
+        }
+        let slots_in_epoch = self.latest_epoch_info.slots_in_epoch;
+        let current_epoch_first_slot = self
+            .latest_epoch_info
+            .absolute_slot
+            .saturating_sub(self.latest_epoch_info.slot_index);
+
+        let stake_accounts = self.get_stake_accounts();
+        let vote_accounts = self
+            .validators
+            .iter()
+            .map(|validator| validator.vote_account)
+            .chain(
+                stake_accounts
+                    .iter()
+                    .map(|(_, _, (_, stake, _))| stake.delegation.voter_pubkey),
+            )
+            .filter_map(|vote_pubkey| {
+                self.get_vote_state(&vote_pubkey)
+                    .map(|vote_account| (vote_pubkey, vote_account))
+            })
+            .collect();
+        let mut state = EpochBoundaryState {
+            stake_history: self.inner.get_sysvar::<StakeHistory>(),
+            stake_accounts,
+            vote_accounts,
+            updated_accounts: HashSet::new(),
+        };
+        for epoch in from_epoch..to_epoch {
+            let effective_slot = current_epoch_first_slot
+                .saturating_sub((to_epoch - epoch - 1).saturating_mul(slots_in_epoch));
+            self.process_epoch_boundary(epoch, effective_slot, &mut state)?;
+        }
+
+        self.inner.set_sysvar(&state.stake_history);
+        for (vote_pubkey, (mut account, vote_state)) in state.vote_accounts {
+            if !state.updated_accounts.contains(&vote_pubkey) {
+                continue;
+            }
+            if self
+                .validators
+                .iter()
+                .any(|validator| validator.vote_account == vote_pubkey)
+            {
+                account.data = serialize_vote_state(vote_state, account.data.len())?;
+            }
+            self.set_account(&vote_pubkey, account)?;
+        }
+        for (pubkey, account, _) in state.stake_accounts {
+            if state.updated_accounts.contains(&pubkey) {
+                self.set_account(&pubkey, account)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Closes `epoch`: records its stake history entry, credits the votes of the synthetic
+    /// validators and pays the inflation rewards earned by the active stake at `effective_slot`.
+    ///
+    /// Rewards are an approximation of the ones of the runtime: each delegation earns its effective
+    /// stake times the validator inflation rate over the epoch, rather than a share of the epoch
+    /// inflation proportional to its points (vote credits times stake) and the total capitalization.
+    fn process_epoch_boundary(
+        &mut self,
+        epoch: Epoch,
+        effective_slot: Slot,
+        state: &mut EpochBoundaryState,
+    ) -> SurfpoolResult<()> {
+        let slots_in_epoch = self.latest_epoch_info.slots_in_epoch;
+
+        let history_entry = state.stake_accounts.iter().fold(
+            StakeHistoryEntry::default(),
+            |entry, (_, _, (_, stake, _))| {
+                entry
+                    + stake.delegation.stake_activating_and_deactivating(
+                        epoch,
+                        &state.stake_history,
+                        NEW_WARMUP_COOLDOWN_RATE_EPOCH,
+                    )
+            },
+        );
+        state.stake_history.add(epoch, history_entry);
+
+        for validator in self.validators.iter() {
+            if let Some((_, vote_state)) = state.vote_accounts.get_mut(&validator.vote_account) {
+                vote_state.increment_credits(epoch, slots_in_epoch);
+                state.updated_accounts.insert(validator.vote_account);
+            }
+        }
+
+        let slots_per_year = self.genesis_config.slots_per_year();
+        let year = effective_slot as f64 / slots_per_year;
+        let rate = self.inflation.validator(year) * (slots_in_epoch as f64 / slots_per_year);
+
+        let mut rewards = HashMap::new();
+        let mut voter_rewards: HashMap<Pubkey, (u64, u8)> = HashMap::new();
+        let mut total_rewards = 0;
+        for (pubkey, account, (meta, stake, flags)) in state.stake_accounts.iter_mut() {
+            let effective_stake =
+                stake
+                    .delegation
+                    .stake(epoch, &state.stake_history, NEW_WARMUP_COOLDOWN_RATE_EPOCH);
+            if effective_stake == 0 {
+                continue;
+            }
+            let Some((_, vote_state)) = state.vote_accounts.get(&stake.delegation.voter_pubkey)
+            else {
+                continue;
+            };
+            let reward = (effective_stake as f64 * rate) as u64;
+            if reward == 0 {
+                continue;
+            }
+            let commission = vote_state.commission.min(100);
+            let voter_reward = reward * commission as u64 / 100;
+            let staker_reward = reward - voter_reward;
+
+            stake.delegation.stake += staker_reward;
+            stake.credits_observed = vote_state.credits();
+            account.lamports += staker_reward;
+            account.data = serialize_stake_state(&StakeStateV2::Stake(*meta, *stake, *flags))?;
+            state.updated_accounts.insert(*pubkey);
+
+            let voter_entry = voter_rewards
+                .entry(stake.delegation.voter_pubkey)
+                .or_insert((0, commission));
+            voter_entry.0 += voter_reward;
+            total_rewards += reward;
+            rewards.insert(
+                *pubkey,
+                RpcInflationReward {
+                    epoch,
+                    effective_slot,
+                    amount: staker_reward,
+                    post_balance: account.lamports,
+                    commission: Some(commission),
+                },
+            );
+        }
+
+        for (vote_pubkey, (amount, commission)) in voter_rewards {
+            if amount == 0 {
+                continue;
+            }
+            let Some((account, _)) = state.vote_accounts.get_mut(&vote_pubkey) else {
+                continue;
+            };
+            account.lamports += amount;
+            state.updated_accounts.insert(vote_pubkey);
+            rewards.insert(
+                vote_pubkey,
+                RpcInflationReward {
+                    epoch,
+                    effective_slot,
+                    amount,
+                    post_balance: account.lamports,
+                    commission: Some(commission),
+                },
+            );
+        }
+
+        if !rewards.is_empty() {
+            let _ = self.simnet_events_tx.send(SimnetEvent::info(format!(
+                "Epoch {} closed: {} lamports of inflation rewards paid to {} accounts",
+                epoch,
+                total_rewards,
+                rewards.len()
+            )));
+        }
+        self.inflation_rewards.insert(epoch, rewards);
+        Ok(())
+    }
+
+    /// Returns the vote accounts of the synthetic validators, or only the one at `vote_pubkey`.
+    pub fn get_vote_accounts(&self, vote_pubkey: Option<&Pubkey>) -> RpcVoteAccountStatus {
+        let epoch = self.latest_epoch_info.epoch;
+        let slot = self.latest_epoch_info.absolute_slot;
+        let stake_history = self.inner.get_sysvar::<StakeHistory>();
+
+        let mut activated_stakes: HashMap<Pubkey, u64> = HashMap::new();
+        for (_, _, (_, stake, _)) in self.get_stake_accounts() {
+            *activated_stakes
+                .entry(stake.delegation.voter_pubkey)
+                .or_default() +=
+                stake
+                    .delegation
+                    .stake(epoch, &stake_history, NEW_WARMUP_COOLDOWN_RATE_EPOCH);
+        }
+
+        let current = self
+            .validators
+            .iter()
+            .filter(|validator| vote_pubkey.is_none_or(|pubkey| *pubkey == validator.vote_account))
+            .filter_map(|validator| {
+                let (_, vote_state) = self.get_vote_state(&validator.vote_account)?;
+                let activated_stake = activated_stakes
+                    .get(&validator.vote_account)
+                    .copied()
+                    .unwrap_or_default();
+                let epoch_credits = vote_state.epoch_credits();
+                Some(RpcVoteAccountInfo {
+                    vote_pubkey: validator.vote_account.to_string(),
+                    node_pubkey: vote_state.node_pubkey.to_string(),
+                    activated_stake,
+                    commission: vote_state.commission,
+                    epoch_vote_account: activated_stake > 0,
+                    epoch_credits: epoch_credits[epoch_credits
+                        .len()
+                        .saturating_sub(MAX_RPC_EPOCH_CREDITS_HISTORY)..]
+                        .to_vec(),
+                    last_vote: slot,
+                    root_slot: slot.saturating_sub(FINALIZATION_SLOT_THRESHOLD),
+                })
+            })
+            .collect();
+
+        RpcVoteAccountStatus {
+            current,
+            delinquent: vec![],
+        }
+    }
+
+    /// Returns the identity of the leader of `slot`, if any validator is simulated.
+    pub fn slot_leader(&self, slot: Slot) -> Option<Pubkey> {
+        if self.validators.is_empty() {
+            return None;
+        }
+        let index = (slot / NUM_CONSECUTIVE_LEADER_SLOTS) as usize % self.validators.len();
+        Some(self.validators[index].identity)
+    }
+
+    /// Returns the leader schedule of the epoch starting at `first_slot`, keyed by validator identity
+    /// with slot indexes relative to the start of the epoch.
+    pub fn get_leader_schedule(
+        &self,
+        first_slot: Slot,
+        slots_in_epoch: u64,
+    ) -> HashMap<String, Vec<usize>> {
+        let mut schedule: HashMap<String, Vec<usize>> = HashMap::new();
+        for slot_index in 0..slots_in_epoch {
+            if let Some(leader) = self.slot_leader(first_slot + slot_index) {
+                schedule
+                    .entry(leader.to_string())
+                    .or_default()
+                    .push(slot_index as usize);
+            }
+        }
+        schedule
+    }
+
+    /// Writes an account of the genesis state, which isn't notified to subscribers.
+    fn set_genesis_account(&mut self, pubkey: &Pubkey, account: Account) -> SurfpoolResult<()> {
+        self.inner
+            .set_account(*pubkey, account.clone())
+            .map_err(|e| SurfpoolError::set_account(*pubkey, e))?;
+        self.update_account_registries(pubkey, &account)
+    }
+
+    /// Returns the delegated stake accounts along with their decoded state.
+    fn get_stake_accounts(&self) -> Vec<(Pubkey, Account, (Meta, Stake, StakeFlags))> {
+        self.get_account_owned_by(stake::id())
+            .into_iter()
+            .filter_map(|(pubkey, account)| {
+                match bincode::deserialize::<StakeStateV2>(&account.data) {
+                    Ok(StakeStateV2::Stake(meta, stake, flags)) => {
+                        Some((pubkey, account, (meta, stake, flags)))
+                    }
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    fn get_vote_state(&self, vote_pubkey: &Pubkey) -> Option<(Account, VoteState)> {
+        let account = self.inner.get_account(vote_pubkey)?;
+        if account.owner != vote::id() {
+            return None;
+        }
+        let vote_state = VoteState::deserialize(&account.data).ok()?;
+        Some((account, vote_state))
+    }
+}
+
+fn serialize_stake_state(stake_state: &StakeStateV2) -> SurfpoolResult<Vec<u8>> {
+    let mut data = bincode::serialize(stake_state)
+        .map_err(|e| SurfpoolError::internal(format!("Failed to serialize stake account: {e}")))?;
+    data.resize(StakeStateV2::size_of(), 0);
+    Ok(data)
+}
+
+fn serialize_vote_state(vote_state: VoteState, len: usize) -> SurfpoolResult<Vec<u8>> {
+    let mut data = vec![0; len.max(VoteState::size_of())];
+    VoteState::serialize(&VoteStateVersions::new_current(vote_state), &mut data)
+        .map_err(|e| SurfpoolError::internal(format!("Failed to serialize vote account: {e}")))?;
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stake_warmup_and_inflation_rewards() {
+        let (mut svm, _events_rx, _geyser_rx) = SurfnetSvm::new();
+        svm.add_synthetic_validators(2).unwrap();
+        assert_eq!(svm.validators.len(), 2);
+
+        let status = svm.get_vote_accounts(None);
+        assert_eq!(status.current.len(), 2);
+        assert!(
+            status
+                .current
+                .iter()
+                .all(|info| info.activated_stake == SYNTHETIC_VALIDATOR_STAKE)
+        );
+        let validator = svm.validators[1];
+        let status = svm.get_vote_accounts(Some(&validator.vote_account));
+        assert_eq!(status.current.len(), 1);
+        assert_eq!(
+            status.current[0].node_pubkey,
+            validator.identity.to_string()
+        );
+
+        // A user delegation is activated at epoch 0 and warms up gradually
+        let user_stake = Pubkey::new_unique();
+        let user_delegation = 2 * SYNTHETIC_VALIDATOR_STAKE;
+        let rent_exempt_reserve = svm
+            .inner
+            .minimum_balance_for_rent_exemption(StakeStateV2::size_of());
+        let stake_state = StakeStateV2::Stake(
+            Meta {
+                rent_exempt_reserve,
+                authorized: Authorized::auto(&user_stake),
+                ..Meta::default()
+            },
+            Stake {
+                delegation: Delegation::new(&validator.vote_account, user_delegation, 0),
+                credits_observed: 0,
+            },
+            StakeFlags::empty(),
+        );
+        svm.set_account(
+            &user_stake,
+            Account {
+                lamports: user_delegation + rent_exempt_reserve,
+                data: serialize_stake_state(&stake_state).unwrap(),
+                owner: stake::id(),
+                executable: false,
+                rent_epoch: 0,
+            },
+        )
+        .unwrap();
+
+        let slots_in_epoch = svm.latest_epoch_info.slots_in_epoch;
+        for epoch in 1..=3 {
+            svm.latest_epoch_info.epoch = epoch;
+            svm.latest_epoch_info.slot_index = 0;
+            svm.latest_epoch_info.absolute_slot = epoch * slots_in_epoch;
+            svm.process_epoch_boundaries(epoch - 1, epoch).unwrap();
+        }
+
+        let stake_history = svm.inner.get_sysvar::<StakeHistory>();
+        let entry_0 = stake_history.get(0).unwrap();
+        assert_eq!(entry_0.effective, 2 * SYNTHETIC_VALIDATOR_STAKE);
+        assert_eq!(entry_0.activating, user_delegation);
+        let entry_1 = stake_history.get(1).unwrap();
+        assert!(entry_1.activating > 0 && entry_1.activating < user_delegation);
+        assert!(entry_1.effective > entry_0.effective);
+
+        // Rewards of epoch 0 are only paid to active stake
+        assert!(!svm.inflation_rewards[&0].contains_key(&user_stake));
+        let reward = svm.inflation_rewards[&0][&validator.stake_account].clone();
+        assert!(reward.amount > 0);
+        assert_eq!(reward.commission, Some(SYNTHETIC_VALIDATOR_COMMISSION));
+        assert_eq!(reward.effective_slot, slots_in_epoch);
+        assert!(svm.inflation_rewards[&0].contains_key(&validator.vote_account));
+        let user_reward = svm.inflation_rewards[&1][&user_stake].clone();
+        assert!(user_reward.amount > 0);
+        assert!(user_reward.post_balance > user_delegation + rent_exempt_reserve);
+
+        let (_, vote_state) = svm.get_vote_state(&validator.vote_account).unwrap();
+        assert_eq!(vote_state.credits(), 3 * slots_in_epoch);
+    }
+
+    #[test]
+    fn test_epoch_boundaries_jump() {
+        let (mut stepped_svm, _events_rx, _geyser_rx) = SurfnetSvm::new();
+        let (mut jumped_svm, _jumped_events_rx, _jumped_geyser_rx) = SurfnetSvm::new();
+        stepped_svm.add_synthetic_validators(2).unwrap();
+        jumped_svm.add_synthetic_validators(2).unwrap();
+        let slots_in_epoch = stepped_svm.latest_epoch_info.slots_in_epoch;
+
+        for epoch in 1..=3 {
+            stepped_svm.latest_epoch_info.epoch = epoch;
+            stepped_svm.latest_epoch_info.slot_index = 0;
+            stepped_svm.latest_epoch_info.absolute_slot = epoch * slots_in_epoch;
+            stepped_svm
+                .process_epoch_boundaries(epoch - 1, epoch)
+                .unwrap();
+        }
+        // A time travel crosses several epochs at once
+        jumped_svm.latest_epoch_info.epoch = 3;
+        jumped_svm.latest_epoch_info.slot_index = 0;
+        jumped_svm.latest_epoch_info.absolute_slot = 3 * slots_in_epoch;
+        jumped_svm.process_epoch_boundaries(0, 3).unwrap();
+
+        for epoch in 0..3 {
+            assert_eq!(
+                jumped_svm.inflation_rewards[&epoch],
+                stepped_svm.inflation_rewards[&epoch]
+            );
+        }
+        assert_eq!(
+            jumped_svm.inner.get_sysvar::<StakeHistory>(),
+            stepped_svm.inner.get_sysvar::<StakeHistory>()
+        );
+        for validator in stepped_svm.validators.clone() {
+            assert_eq!(
+                jumped_svm.inner.get_account(&validator.stake_account),
+                stepped_svm.inner.get_account(&validator.stake_account)
+            );
+            assert_eq!(
+                jumped_svm.inner.get_account(&validator.vote_account),
+                stepped_svm.inner.get_account(&validator.vote_account)
+            );
+        }
+    }
+
+    #[test]
+    fn test_leader_schedule() {
+        let (mut svm, _events_rx, _geyser_rx) = SurfnetSvm::new();
+        assert_eq!(svm.slot_leader(0), None);
+        assert!(svm.get_leader_schedule(0, 16).is_empty());
+
+        svm.add_synthetic_validators(2).unwrap();
+        assert_eq!(svm.slot_leader(3), Some(SURFPOOL_IDENTITY_PUBKEY));
+        assert_eq!(svm.slot_leader(4), Some(svm.validators[1].identity));
+
+        let schedule = svm.get_leader_schedule(16, 16);
+        assert_eq!(
+            schedule[&SURFPOOL_IDENTITY_PUBKEY.to_string()],
+            vec![0, 1, 2, 3, 8, 9, 10, 11]
+        );
+        assert_eq!(
+            schedule[&svm.validators[1].identity.to_string()],
+            vec![4, 5, 6, 7, 12, 13, 14, 15]
+        );
+    }
+}
@@ -1,5 +1,10 @@
-use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::{
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+    path::PathBuf,
+    sync::Arc,
+};
 
+use agave_geyser_plugin_interface::geyser_plugin_interface::SlotStatus;
 use chrono::Utc;
 use convert_case::Casing;
 use crossbeam_channel::{Receiver, Sender, unbounded};
@@ -14,37 +19,56 @@ use solana_account_decoder::{
     UiAccount, UiAccountData, UiAccountEncoding, UiDataSliceConfig, encode_ui_account,
     parse_account_data::{AccountAdditionalDataV3, ParsedAccount, SplTokenAdditionalDataV2},
 };
+use solana_bpf_loader_program::syscalls::create_program_runtime_environment_v1;
 use solana_client::{
     rpc_client::SerializableTransaction,
-    rpc_config::{RpcAccountInfoConfig, RpcBlockConfig, RpcTransactionLogsFilter},
-    rpc_response::{RpcKeyedAccount, RpcLogsResponse, RpcPerfSample},
+    rpc_config::{
+        RpcAccountInfoConfig, RpcBlockConfig, RpcBlockSubscribeConfig, RpcBlockSubscribeFilter,
+        RpcProgramAccountsConfig, RpcTransactionLogsFilter,
+    },
+    rpc_response::{
+        RpcBlockUpdate, RpcBlockUpdateError, RpcInflationReward, RpcKeyedAccount, RpcLogsResponse,
+        RpcPerfSample,
+    },
 };
-use solana_clock::{Clock, MAX_RECENT_BLOCKHASHES, Slot};
+use solana_clock::{Clock, Epoch, MAX_RECENT_BLOCKHASHES, Slot};
 use solana_commitment_config::CommitmentLevel;
+use solana_compute_budget::compute_budget::ComputeBudget;
 use solana_epoch_info::EpochInfo;
 use solana_feature_set::{FeatureSet, disable_new_loader_v3_deployments};
 use solana_hash::Hash;
 use solana_keypair::Keypair;
 use solana_message::{Message, VersionedMessage, v0::LoadedAddresses};
+use solana_program_runtime::{
+    invoke_context::InvokeContext,
+    solana_sbpf::{elf::Executable, verifier::RequisiteVerifier},
+};
 use solana_pubkey::Pubkey;
 use solana_rpc_client_api::response::SlotInfo;
 use solana_sdk::{
-    genesis_config::GenesisConfig, inflation::Inflation, program_option::COption,
-    system_instruction, transaction::VersionedTransaction,
+    bpf_loader_upgradeable::{self, UpgradeableLoaderState, get_program_data_address},
+    genesis_config::GenesisConfig,
+    inflation::Inflation,
+    program_option::COption,
+    system_instruction,
+    transaction::VersionedTransaction,
 };
 use solana_sdk_ids::system_program;
 use solana_signature::Signature;
 use solana_signer::Signer;
 use solana_transaction_error::TransactionError;
-use solana_transaction_status::{TransactionDetails, TransactionStatusMeta, UiConfirmedBlock};
+use solana_transaction_status::{
+    TransactionDetails, TransactionStatusMeta, UiConfirmedBlock, UiTransactionTokenBalance,
+};
 use spl_token_2022::extension::{
     BaseStateWithExtensions, StateWithExtensions, interest_bearing_mint::InterestBearingConfig,
     scaled_ui_amount::ScaledUiAmountConfig,
 };
 use surfpool_types::{
-    AccountChange, AccountProfileState, DEFAULT_SLOT_TIME_MS, Idl, ProfileResult, RpcProfileDepth,
-    RpcProfileResultConfig, SimnetEvent, TransactionConfirmationStatus, TransactionStatusEvent,
-    UiAccountChange, UiAccountProfileState, UiProfileResult, VersionedIdl,
+    AccountChange, AccountProfileState, DEFAULT_SLOT_TIME_MS, Idl, JITO_TIP_ACCOUNTS,
+    ProfileResult, RpcProfileDepth, RpcProfileResultConfig, SimnetEvent,
+    TransactionConfirmationStatus, TransactionStatusEvent, UiAccountChange, UiAccountDiff,
+    UiAccountProfileState, UiProfileResult, VersionedIdl,
     types::{
         ComputeUnitsEstimationResult, KeyedProfileResult, UiKeyedProfileResult, UuidOrSignature,
     },
@@ -54,15 +78,23 @@ use txtx_addon_network_svm_types::subgraph::idl::parse_bytes_to_value_with_expec
 use uuid::Uuid;
 
 use super::{
-    AccountSubscriptionData, BlockHeader, BlockIdentifier, FINALIZATION_SLOT_THRESHOLD,
-    GetAccountResult, GeyserEvent, SLOTS_PER_EPOCH, SignatureSubscriptionData,
-    SignatureSubscriptionType, remote::SurfnetRemoteClient,
+    AccountSubscriptionData, BlockHeader, BlockIdentifier, BlockSubscriptionData,
+    FINALIZATION_SLOT_THRESHOLD, GetAccountResult, GeyserEvent, MAX_ACCOUNT_HISTORY_LEN,
+    MAX_SNAPSHOTS, ProgramSubscriptionData, SLOTS_PER_EPOCH, SignatureSubscriptionData,
+    SignatureSubscriptionType, bundles::LandedBundle, fees::LAMPORTS_PER_SIGNATURE,
+    remote::SurfnetRemoteClient, staking::SyntheticValidator,
 };
 use crate::{
     error::{SurfpoolError, SurfpoolResult},
     rpc::utils::convert_transaction_metadata_from_canonical,
-    surfnet::{LogsSubscriptionData, locker::is_supported_token_program},
-    types::{MintAccount, SurfnetTransactionStatus, TokenAccount, TransactionWithStatusMeta},
+    surfnet::{
+        LogsSubscriptionData,
+        locker::{apply_rpc_filters, is_supported_token_program},
+    },
+    types::{
+        GeyserAccountUpdate, GeyserBlockMetadata, GeyserEntryInfo, MintAccount,
+        SurfnetTransactionStatus, TokenAccount, TransactionWithStatusMeta,
+    },
 };
 
 pub type AccountOwner = Pubkey;
@@ -100,6 +132,9 @@ pub struct SurfnetSvm {
     pub signature_subscriptions: HashMap<Signature, Vec<SignatureSubscriptionData>>,
     pub account_subscriptions: AccountSubscriptionData,
     pub slot_subscriptions: Vec<Sender<SlotInfo>>,
+    pub program_subscriptions: ProgramSubscriptionData,
+    pub block_subscriptions: Vec<BlockSubscriptionData>,
+    pub root_subscriptions: Vec<Sender<Slot>>,
     pub profile_tag_map: HashMap<String, Vec<UuidOrSignature>>,
     pub simulated_transaction_profiles: HashMap<Uuid, KeyedProfileResult>,
     pub executed_transaction_profiles: HashMap<Signature, KeyedProfileResult>,
@@ -125,6 +160,91 @@ pub struct SurfnetSvm {
     /// the update with higher write_version should supersede the one with lower write_version.
     pub write_version: u64,
     pub registered_idls: HashMap<Pubkey, BinaryHeap<VersionedIdl>>,
+    /// The successive states of each account, along with the slot at which they were written.
+    /// Only the last state written in a slot, and the latest [MAX_ACCOUNT_HISTORY_LEN] states, are kept.
+    pub account_history: HashMap<Pubkey, VecDeque<(Slot, Account)>>,
+    /// The accounts whose oldest states were evicted from `account_history`.
+    pub truncated_account_histories: HashSet<Pubkey>,
+    /// State captured with `surfnet_snapshot`, restorable with `surfnet_revert`.
+    pub snapshots: HashMap<Uuid, Arc<SurfnetSvmSnapshot>>,
+    /// The synthetic validators, taking turns as slot leaders.
+    pub validators: Vec<SyntheticValidator>,
+    /// The inflation rewards distributed at the end of each epoch, by recipient.
+    pub inflation_rewards: HashMap<Epoch, HashMap<Pubkey, RpcInflationReward>>,
+    /// The accounts that bundles must transfer a tip to.
+    pub tip_accounts: Vec<Pubkey>,
+    /// The bundles that landed, by bundle id.
+    pub bundles: HashMap<String, LandedBundle>,
+    /// The directory `surfnet_dumpState` writes state dumps to; dumps are disabled if `None`.
+    pub state_dump_dir: Option<PathBuf>,
+    /// The keypairs, loaded from disk on startup, signing the transactions built by `surfnet_sendInstruction`.
+    pub signing_keypairs: Arc<Vec<Keypair>>,
+}
+
+/// A point-in-time copy of the ledger state of a [SurfnetSvm].
+///
+/// Event channels, subscriptions and profiling results are not part of a snapshot:
+/// they keep flowing across a revert. Neither are the transactions queued for confirmation,
+/// whose clients were already notified.
+#[derive(Clone)]
+pub struct SurfnetSvmSnapshot {
+    pub inner: LiteSVM,
+    pub chain_tip: BlockIdentifier,
+    pub blocks: HashMap<Slot, BlockHeader>,
+    pub transactions: HashMap<Signature, SurfnetTransactionStatus>,
+    pub perf_samples: VecDeque<RpcPerfSample>,
+    pub transactions_processed: u64,
+    pub latest_epoch_info: EpochInfo,
+    pub accounts_registry: HashMap<Pubkey, Account>,
+    pub accounts_by_owner: HashMap<Pubkey, Vec<Pubkey>>,
+    pub account_associated_data: HashMap<Pubkey, AccountAdditionalDataV3>,
+    pub token_accounts: HashMap<Pubkey, TokenAccount>,
+    pub token_mints: HashMap<Pubkey, MintAccount>,
+    pub token_accounts_by_owner: HashMap<Pubkey, Vec<Pubkey>>,
+    pub token_accounts_by_delegate: HashMap<Pubkey, Vec<Pubkey>>,
+    pub token_accounts_by_mint: HashMap<Pubkey, Vec<Pubkey>>,
+    pub total_supply: u64,
+    pub circulating_supply: u64,
+    pub non_circulating_supply: u64,
+    pub non_circulating_accounts: Vec<String>,
+    pub registered_idls: HashMap<Pubkey, BinaryHeap<VersionedIdl>>,
+    pub account_history: HashMap<Pubkey, VecDeque<(Slot, Account)>>,
+    pub truncated_account_histories: HashSet<Pubkey>,
+    pub validators: Vec<SyntheticValidator>,
+    pub write_version: u64,
+    pub inflation_rewards: HashMap<Epoch, HashMap<Pubkey, RpcInflationReward>>,
+    pub bundles: HashMap<String, LandedBundle>,
+}
+
+/// Returns the features enabled in the SVM of a surfnet.
+fn surfnet_feature_set() -> FeatureSet {
+    let mut feature_set = FeatureSet::all_enabled();
+    // v2.2 of the solana_sdk deprecates the v3 loader, and enables the v4 loader by default.
+    // In order to keep the v3 deployments enabled, we need to remove the
+    // `disable_new_loader_v3_deployments` feature from the active set, and add it to the inactive set.
+    let _ = feature_set
+        .active
+        .remove(&disable_new_loader_v3_deployments::id());
+    feature_set
+        .inactive
+        .insert(disable_new_loader_v3_deployments::id());
+    feature_set
+}
+
+/// Checks that an ELF can be deployed, running the verifications the upgradeable loader runs on deployment.
+fn verify_program_elf(elf: &[u8]) -> Result<(), String> {
+    let program_runtime_environment = create_program_runtime_environment_v1(
+        &surfnet_feature_set(),
+        &ComputeBudget::default(),
+        true,  // reject_deployment_of_broken_elfs
+        false, // debugging_features
+    )
+    .map_err(|e| e.to_string())?;
+    let executable = Executable::<InvokeContext>::load(elf, Arc::new(program_runtime_environment))
+        .map_err(|e| e.to_string())?;
+    executable
+        .verify::<RequisiteVerifier>()
+        .map_err(|e| e.to_string())
 }
 
 impl SurfnetSvm {
@@ -135,19 +255,8 @@ impl SurfnetSvm {
         let (simnet_events_tx, simnet_events_rx) = crossbeam_channel::bounded(1024);
         let (geyser_events_tx, geyser_events_rx) = crossbeam_channel::bounded(1024);
 
-        let mut feature_set = FeatureSet::all_enabled();
-        // v2.2 of the solana_sdk deprecates the v3 loader, and enables the v4 loader by default.
-        // In order to keep the v3 deployments enabled, we need to remove the
-        // `disable_new_loader_v3_deployments` feature from the active set, and add it to the inactive set.
-        let _ = feature_set
-            .active
-            .remove(&disable_new_loader_v3_deployments::id());
-        feature_set
-            .inactive
-            .insert(disable_new_loader_v3_deployments::id());
-
         let inner = LiteSVM::new()
-            .with_feature_set(feature_set)
+            .with_feature_set(surfnet_feature_set())
             .with_blockhash_check(false)
             .with_sigverify(false);
 
@@ -175,6 +284,9 @@ impl SurfnetSvm {
                 signature_subscriptions: HashMap::new(),
                 account_subscriptions: HashMap::new(),
                 slot_subscriptions: Vec::new(),
+                program_subscriptions: HashMap::new(),
+                block_subscriptions: Vec::new(),
+                root_subscriptions: Vec::new(),
                 profile_tag_map: HashMap::new(),
                 simulated_transaction_profiles: HashMap::new(),
                 executed_transaction_profiles: HashMap::new(),
@@ -197,6 +309,15 @@ impl SurfnetSvm {
                 inflation: Inflation::default(),
                 write_version: 0,
                 registered_idls: HashMap::new(),
+                account_history: HashMap::new(),
+                truncated_account_histories: HashSet::new(),
+                snapshots: HashMap::new(),
+                validators: Vec::new(),
+                inflation_rewards: HashMap::new(),
+                tip_accounts: JITO_TIP_ACCOUNTS.to_vec(),
+                bundles: HashMap::new(),
+                state_dump_dir: None,
+                signing_keypairs: Arc::new(vec![]),
             },
             simnet_events_rx,
             geyser_events_rx,
@@ -294,14 +415,16 @@ impl SurfnetSvm {
                     transaction: tx.clone(),
                     meta: TransactionStatusMeta {
                         status: Ok(()),
-                        fee: 5000,
+                        fee: LAMPORTS_PER_SIGNATURE,
                         pre_balances: vec![
                             account.lamports,
                             account.lamports.saturating_sub(lamports),
                             system_lamports,
                         ],
                         post_balances: vec![
-                            account.lamports.saturating_sub(lamports + 5000),
+                            account
+                                .lamports
+                                .saturating_sub(lamports + LAMPORTS_PER_SIGNATURE),
                             account.lamports,
                             system_lamports,
                         ],
@@ -449,11 +572,18 @@ impl SurfnetSvm {
             .set_account(*pubkey, account.clone())
             .map_err(|e| SurfpoolError::set_account(*pubkey, e))?;
 
+        // Keep the previous state of an account changing owner, to notify the subscribers of its previous owner
+        let previous_account = self
+            .accounts_registry
+            .get(pubkey)
+            .filter(|previous_account| previous_account.owner != account.owner)
+            .cloned();
+
         // Update the account registries and indexes
         self.update_account_registries(pubkey, &account)?;
 
         // Notify account subscribers
-        self.notify_account_subscribers(pubkey, &account);
+        self.notify_account_subscribers(pubkey, previous_account.as_ref(), &account);
 
         let _ = self
             .simnet_events_tx
@@ -461,6 +591,80 @@ impl SurfnetSvm {
         Ok(())
     }
 
+    /// Writes the program and program data accounts of an upgradeable loader (v3) program from an ELF,
+    /// replacing any program previously deployed at `program_id`.
+    ///
+    /// The ELF is verified before any account is written. The program data account is written first, so
+    /// that LiteSVM loads the new executable in its program cache when the program account is written.
+    pub fn deploy_program(
+        &mut self,
+        program_id: &Pubkey,
+        elf: &[u8],
+        upgrade_authority: Option<Pubkey>,
+    ) -> SurfpoolResult<()> {
+        verify_program_elf(elf).map_err(|e| SurfpoolError::invalid_program_elf(program_id, e))?;
+
+        let programdata_address = get_program_data_address(program_id);
+
+        let mut programdata = bincode::serialize(&UpgradeableLoaderState::ProgramData {
+            slot: self.get_latest_absolute_slot(),
+            upgrade_authority_address: upgrade_authority,
+        })
+        .map_err(|e| SurfpoolError::internal(format!("Failed to serialize program data: {e}")))?;
+        programdata.resize(UpgradeableLoaderState::size_of_programdata_metadata(), 0);
+        programdata.extend_from_slice(elf);
+
+        let program = bincode::serialize(&UpgradeableLoaderState::Program {
+            programdata_address,
+        })
+        .map_err(|e| SurfpoolError::internal(format!("Failed to serialize program: {e}")))?;
+
+        self.set_account(
+            &programdata_address,
+            Account {
+                lamports: self
+                    .inner
+                    .minimum_balance_for_rent_exemption(programdata.len()),
+                data: programdata,
+                owner: bpf_loader_upgradeable::id(),
+                executable: false,
+                rent_epoch: 0,
+            },
+        )?;
+        self.set_account(
+            program_id,
+            Account {
+                lamports: self.inner.minimum_balance_for_rent_exemption(program.len()),
+                data: program,
+                owner: bpf_loader_upgradeable::id(),
+                executable: true,
+                rent_epoch: 0,
+            },
+        )
+    }
+
+    /// Records the state of an account in its history, at the current slot.
+    ///
+    /// Executable accounts and the accounts of the upgradeable loader, holding program ELFs, are not tracked.
+    fn record_account_history(&mut self, pubkey: &Pubkey, account: &Account) {
+        if account.executable || account.owner == bpf_loader_upgradeable::id() {
+            return;
+        }
+        let slot = self.get_latest_absolute_slot();
+        let history = self.account_history.entry(*pubkey).or_default();
+        if history
+            .back()
+            .is_some_and(|(written_at, _)| *written_at == slot)
+        {
+            history.pop_back();
+        }
+        if history.len() >= MAX_ACCOUNT_HISTORY_LEN {
+            history.pop_front();
+            self.truncated_account_histories.insert(*pubkey);
+        }
+        history.push_back((slot, account.clone()));
+    }
+
     pub fn update_account_registries(
         &mut self,
         pubkey: &Pubkey,
@@ -482,6 +686,7 @@ impl SurfnetSvm {
 
         // update the main registry
         self.accounts_registry.insert(*pubkey, account.clone());
+        self.record_account_history(pubkey, account);
 
         // add to owner index (check for duplicates)
         let owner_accounts = self.accounts_by_owner.entry(account.owner).or_default();
@@ -660,8 +865,13 @@ impl SurfnetSvm {
         match self.inner.send_transaction(tx.clone()) {
             Ok(res) => Ok(res),
             Err(tx_failure) => {
-                let transaction_meta =
+                let mut transaction_meta =
                     convert_transaction_metadata_from_canonical(&tx_failure.meta);
+                if let Some(program_error) =
+                    self.resolve_program_error(&tx.message, &tx_failure.err, &tx_failure.meta.logs)
+                {
+                    transaction_meta.logs.push(program_error.to_string());
+                }
 
                 let _ = self
                     .simnet_events_tx
@@ -874,6 +1084,119 @@ impl SurfnetSvm {
         }
     }
 
+    /// Resets the accounts of a transaction of the datasource network from their state right after its
+    /// execution to the one before, using the balances recorded in its metadata.
+    ///
+    /// Only lamports and token amounts are recorded: an error is returned if an account written by the
+    /// transaction holds other data, or was closed by it. Accounts created by the transaction are removed.
+    pub fn restore_pre_execution_state(
+        &mut self,
+        signature: &Signature,
+        account_keys: &[Pubkey],
+        written_accounts: &[Pubkey],
+        pre_balances: &[u64],
+        pre_token_balances: &[UiTransactionTokenBalance],
+    ) -> SurfpoolResult<()> {
+        let token_account_indexes = pre_token_balances
+            .iter()
+            .map(|balance| balance.account_index as usize)
+            .collect::<Vec<_>>();
+        for (index, (pubkey, lamports)) in account_keys.iter().zip(pre_balances).enumerate() {
+            if *lamports == 0 || !written_accounts.contains(pubkey) {
+                continue;
+            }
+            let restorable = self
+                .inner
+                .get_account(pubkey)
+                .filter(|account| account.lamports > 0)
+                .is_some_and(|account| {
+                    account.data.is_empty()
+                        || (token_account_indexes.contains(&index)
+                            && TokenAccount::unpack(&account.data).is_ok())
+                });
+            if !restorable {
+                return Err(SurfpoolError::unrecoverable_account_state(
+                    *pubkey, *signature,
+                ));
+            }
+        }
+
+        for (pubkey, lamports) in account_keys.iter().zip(pre_balances) {
+            let account = match self.inner.get_account(pubkey) {
+                Some(account) => account,
+                None if *lamports == 0 => continue,
+                None => Account::default(),
+            };
+            self.set_account(
+                pubkey,
+                Account {
+                    lamports: *lamports,
+                    ..account
+                },
+            )?;
+        }
+        for balance in pre_token_balances {
+            let Some(pubkey) = account_keys.get(balance.account_index as usize) else {
+                continue;
+            };
+            let Some(mut account) = self.inner.get_account(pubkey) else {
+                continue;
+            };
+            let (Ok(mut token_account), Ok(amount)) = (
+                TokenAccount::unpack(&account.data),
+                balance.ui_token_amount.amount.parse::<u64>(),
+            ) else {
+                continue;
+            };
+            token_account.set_amount(amount);
+            // Token-2022 extensions are stored after the base account, and are left untouched
+            let packed = token_account.pack_into_vec();
+            account.data[..packed.len()].copy_from_slice(&packed);
+            self.set_account(pubkey, account)?;
+        }
+        Ok(())
+    }
+
+    /// Sends the entry and the metadata of a newly confirmed block to the Geyser plugins,
+    /// followed by its `Processed` and `Confirmed` slot status transitions.
+    fn notify_geyser_block_confirmed(&self, slot: Slot) {
+        let Some(block) = self.blocks.get(&slot) else {
+            return;
+        };
+        let executed_transaction_count = block.signatures.len() as u64;
+        let parent = slot.checked_sub(1);
+
+        let _ = self
+            .geyser_events_tx
+            .send(GeyserEvent::NotifyEntry(GeyserEntryInfo {
+                slot,
+                index: 0,
+                num_hashes: 1,
+                hash: block.hash.parse::<Hash>().unwrap_or_default(),
+                executed_transaction_count,
+                starting_transaction_index: 0,
+            }));
+        let _ = self
+            .geyser_events_tx
+            .send(GeyserEvent::NotifyBlockMetadata(GeyserBlockMetadata {
+                slot,
+                blockhash: block.hash.clone(),
+                parent_slot: parent.unwrap_or_default(),
+                parent_blockhash: block.previous_blockhash.clone(),
+                block_time: Some(block.block_time / 1000),
+                block_height: Some(block.block_height),
+                executed_transaction_count,
+                entry_count: 1,
+            }));
+        for status in [SlotStatus::Processed, SlotStatus::Confirmed] {
+            let _ = self.geyser_events_tx.send(GeyserEvent::UpdateSlotStatus {
+                slot,
+                parent,
+                status,
+            });
+        }
+    }
+
     pub fn confirm_current_block(&mut self) -> Result<(), SurfpoolError> {
         self.updated_at = Utc::now().timestamp_millis() as u64;
         // Confirm processed transactions
@@ -883,8 +1206,9 @@ impl SurfnetSvm {
         let previous_chain_tip = self.chain_tip.clone();
         self.chain_tip = self.new_blockhash();
 
+        let confirmed_slot = self.get_latest_absolute_slot();
         self.blocks.insert(
-            self.get_latest_absolute_slot(),
+            confirmed_slot,
             BlockHeader {
                 hash: self.chain_tip.hash.clone(),
                 previous_blockhash: previous_chain_tip.hash,
@@ -906,6 +1230,7 @@ impl SurfnetSvm {
         });
 
         self.updated_at = self.updated_at + self.slot_time;
+        let previous_epoch = self.latest_epoch_info.epoch;
         self.latest_epoch_info.slot_index += 1;
         self.latest_epoch_info.block_height = self.chain_tip.index;
         self.latest_epoch_info.absolute_slot += 1;
@@ -918,6 +1243,17 @@ impl SurfnetSvm {
         let new_slot = self.latest_epoch_info.absolute_slot;
         let root = new_slot.saturating_sub(FINALIZATION_SLOT_THRESHOLD);
         self.notify_slot_subscribers(new_slot, parent_slot, root);
+        self.notify_block_subscribers(confirmed_slot, CommitmentLevel::Confirmed);
+        self.notify_geyser_block_confirmed(confirmed_slot);
+        if new_slot >= FINALIZATION_SLOT_THRESHOLD {
+            self.notify_root_subscribers(root);
+            self.notify_block_subscribers(root, CommitmentLevel::Finalized);
+            let _ = self.geyser_events_tx.send(GeyserEvent::UpdateSlotStatus {
+                slot: root,
+                parent: root.checked_sub(1),
+                status: SlotStatus::Rooted,
+            });
+        }
 
         let clock: Clock = Clock {
             slot: self.latest_epoch_info.absolute_slot,
@@ -932,6 +1268,14 @@ impl SurfnetSvm {
             .send(SimnetEvent::SystemClockUpdated(clock.clone()));
         self.inner.set_sysvar(&clock);
 
+        // Failing to pay rewards should not stop block production
+        if let Err(e) = self.process_epoch_boundaries(previous_epoch, self.latest_epoch_info.epoch)
+        {
+            let _ = self.simnet_events_tx.send(SimnetEvent::error(format!(
+                "Failed to process epoch boundaries: {e}"
+            )));
+        }
+
         self.finalize_transactions()?;
 
         Ok(())
@@ -1006,11 +1350,19 @@ impl SurfnetSvm {
         }
     }
 
+    /// Notifies the subscribers of an updated account, and the subscribers of its owner.
+    ///
+    /// # Arguments
+    /// * `account_updated_pubkey` - The public key of the updated account.
+    /// * `previous_account` - The state of the account before the update, if known.
+    /// * `account` - The updated account.
     pub fn notify_account_subscribers(
         &mut self,
         account_updated_pubkey: &Pubkey,
+        previous_account: Option<&Account>,
         account: &Account,
     ) {
+        self.notify_program_subscribers(account_updated_pubkey, previous_account, account);
         let mut remaining = vec![];
         if let Some(subscriptions) = self.account_subscriptions.remove(account_updated_pubkey) {
             for (encoding, tx) in subscriptions {
@@ -1051,7 +1403,15 @@ impl SurfnetSvm {
         let Some(block) = self.blocks.get(&slot) else {
             return Ok(None);
         };
+        self.encode_block(block, config).map(Some)
+    }
 
+    /// Encodes a block header and its transactions according to the block retrieval configuration.
+    pub fn encode_block(
+        &self,
+        block: &BlockHeader,
+        config: &RpcBlockConfig,
+    ) -> SurfpoolResult<UiConfirmedBlock> {
         let show_rewards = config.rewards.unwrap_or(true);
         let transaction_details = config
             .transaction_details
@@ -1113,7 +1473,7 @@ impl SurfnetSvm {
             block_time: Some(block.block_time / 1000),
             block_height: Some(block.block_height),
         };
-        Ok(Some(block))
+        Ok(block)
     }
 
     /// Returns the blockhash for a given slot, if available.
@@ -1274,6 +1634,192 @@ impl SurfnetSvm {
             .retain(|tx| tx.send(SlotInfo { slot, parent, root }).is_ok());
     }
 
+    /// Subscribes for updates of the accounts owned by a program, matching the filters of the config.
+    pub fn subscribe_for_program_updates(
+        &mut self,
+        program_id: &Pubkey,
+        config: RpcProgramAccountsConfig,
+    ) -> Receiver<(Slot, RpcKeyedAccount)> {
+        self.updated_at = Utc::now().timestamp_millis() as u64;
+        let (tx, rx) = unbounded();
+        self.program_subscriptions
+            .entry(*program_id)
+            .or_default()
+            .push((config.account_config, config.filters, tx));
+        rx
+    }
+
+    /// Notifies the subscribers of the owner of an updated account, if the account matches their filters.
+    ///
+    /// When the account changed owner, or was closed, the subscribers of its previous owner are notified
+    /// as well, if the previous state of the account matched their filters.
+    pub fn notify_program_subscribers(
+        &mut self,
+        pubkey: &Pubkey,
+        previous_account: Option<&Account>,
+        account: &Account,
+    ) {
+        if let Some(previous_account) =
+            previous_account.filter(|previous_account| previous_account.owner != account.owner)
+        {
+            self.notify_owner_subscribers(
+                &previous_account.owner,
+                pubkey,
+                previous_account,
+                account,
+            );
+        }
+        self.notify_owner_subscribers(&account.owner, pubkey, account, account);
+    }
+
+    /// Sends an updated account to the subscribers of `owner` whose filters match `filtered_account`.
+    fn notify_owner_subscribers(
+        &mut self,
+        owner: &Pubkey,
+        pubkey: &Pubkey,
+        filtered_account: &Account,
+        account: &Account,
+    ) {
+        let Some(subscriptions) = self.program_subscriptions.remove(owner) else {
+            return;
+        };
+        let slot = self.get_latest_absolute_slot();
+        let mut remaining = vec![];
+        for (config, filters, tx) in subscriptions {
+            let matches = match &filters {
+                Some(filters) => {
+                    apply_rpc_filters(&filtered_account.data, filters).unwrap_or(false)
+                }
+                None => true,
+            };
+            if matches {
+                let keyed_account =
+                    self.account_to_rpc_keyed_account(pubkey, account, &config, None);
+                if tx.send((slot, keyed_account)).is_err() {
+                    // The receiver has been dropped, so we can drop the subscription
+                    continue;
+                }
+            }
+            remaining.push((config, filters, tx));
+        }
+        if !remaining.is_empty() {
+            self.program_subscriptions.insert(*owner, remaining);
+        }
+    }
+
+    /// Subscribes for the blocks reaching the commitment level of the config.
+    pub fn subscribe_for_block_updates(
+        &mut self,
+        filter: RpcBlockSubscribeFilter,
+        config: RpcBlockSubscribeConfig,
+    ) -> Receiver<(Slot, RpcBlockUpdate)> {
+        self.updated_at = Utc::now().timestamp_millis() as u64;
+        let (tx, rx) = unbounded();
+        self.block_subscriptions.push((filter, config, tx));
+        rx
+    }
+
+    /// Notifies the block subscribers waiting for the given commitment level of the block at `slot`.
+    ///
+    /// Blocks are only created once confirmed, so subscriptions at the processed level are notified
+    /// along with the confirmed ones.
+    pub fn notify_block_subscribers(&mut self, slot: Slot, commitment_level: CommitmentLevel) {
+        let Some(block) = self.blocks.get(&slot).cloned() else {
+            return;
+        };
+        let subscriptions = std::mem::take(&mut self.block_subscriptions);
+        let mut remaining = vec![];
+        for (filter, config, tx) in subscriptions {
+            let expected_level = match config.commitment.unwrap_or_default().commitment {
+                CommitmentLevel::Processed => CommitmentLevel::Confirmed,
+                level => level,
+            };
+            if expected_level != commitment_level {
+                remaining.push((filter, config, tx));
+                continue;
+            }
+
+            let block = match &filter {
+                RpcBlockSubscribeFilter::All => Some(block.clone()),
+                RpcBlockSubscribeFilter::MentionsAccountOrProgram(pubkey) => {
+                    let Ok(pubkey) = pubkey.parse::<Pubkey>() else {
+                        remaining.push((filter, config, tx));
+                        continue;
+                    };
+                    let signatures = block
+                        .signatures
+                        .iter()
+                        .filter(|signature| self.transaction_mentions(signature, &pubkey))
+                        .cloned()
+                        .collect::<Vec<_>>();
+                    (!signatures.is_empty()).then(|| BlockHeader {
+                        signatures,
+                        ..block.clone()
+                    })
+                }
+            };
+            let Some(block) = block else {
+                remaining.push((filter, config, tx));
+                continue;
+            };
+
+            let block_config = RpcBlockConfig {
+                encoding: config.encoding,
+                transaction_details: config.transaction_details,
+                rewards: config.show_rewards,
+                commitment: config.commitment,
+                max_supported_transaction_version: config.max_supported_transaction_version,
+            };
+            let update = match self.encode_block(&block, &block_config) {
+                Ok(block) => RpcBlockUpdate {
+                    slot,
+                    block: Some(block),
+                    err: None,
+                },
+                // legacy and v0 transactions are the only ones processed by surfnet, so the
+                // encoding can only fail on v0 transactions when no version is supported
+                Err(_) => RpcBlockUpdate {
+                    slot,
+                    block: None,
+                    err: Some(RpcBlockUpdateError::UnsupportedTransactionVersion(0)),
+                },
+            };
+            if tx.send((slot, update)).is_ok() {
+                remaining.push((filter, config, tx));
+            }
+        }
+        self.block_subscriptions = remaining;
+    }
+
+    /// Returns whether a processed transaction mentions the given account, either statically
+    /// or through an address lookup table.
+    fn transaction_mentions(&self, signature: &Signature, pubkey: &Pubkey) -> bool {
+        let Some(SurfnetTransactionStatus::Processed(tx_data)) = self.transactions.get(signature)
+        else {
+            return false;
+        };
+        tx_data
+            .transaction
+            .message
+            .static_account_keys()
+            .iter()
+            .chain(tx_data.meta.loaded_addresses.writable.iter())
+            .chain(tx_data.meta.loaded_addresses.readonly.iter())
+            .any(|key| key == pubkey)
+    }
+
+    pub fn subscribe_for_root_updates(&mut self) -> Receiver<Slot> {
+        self.updated_at = Utc::now().timestamp_millis() as u64;
+        let (tx, rx) = unbounded();
+        self.root_subscriptions.push(tx);
+        rx
+    }
+
+    pub fn notify_root_subscribers(&mut self, root: Slot) {
+        self.updated_at = Utc::now().timestamp_millis() as u64;
+        self.root_subscriptions.retain(|tx| tx.send(root).is_ok());
+    }
+
     pub fn write_simulated_profile_result(
         &mut self,
         uuid: Uuid,
@@ -1344,6 +1890,177 @@ impl SurfnetSvm {
             .push(VersionedIdl(slot, idl));
     }
 
+    /// Returns the IDLs registered for `program_id` at or before the latest slot, most recent first.
+    pub fn get_active_idls(&self, program_id: &Pubkey) -> Vec<&Idl> {
+        let Some(registered_idls) = self.registered_idls.get(program_id) else {
+            return vec![];
+        };
+        let mut idls = registered_idls
+            .iter()
+            .filter(|VersionedIdl(slot, _)| *slot <= self.latest_epoch_info.absolute_slot)
+            .collect::<Vec<_>>();
+        idls.sort_by(|a, b| b.cmp(a));
+        idls.into_iter().map(|VersionedIdl(_, idl)| idl).collect()
+    }
+
+    /// Captures the current ledger state so that it can later be restored with [SurfnetSvm::revert].
+    ///
+    /// # Returns
+    /// The id of the newly created snapshot, or an error if [MAX_SNAPSHOTS] snapshots are already kept.
+    pub fn snapshot(&mut self) -> SurfpoolResult<Uuid> {
+        if self.snapshots.len() >= MAX_SNAPSHOTS {
+            return Err(SurfpoolError::too_many_snapshots(MAX_SNAPSHOTS));
+        }
+        let snapshot = self.capture_snapshot();
+        let id = Uuid::new_v4();
+        self.snapshots.insert(id, Arc::new(snapshot));
+        Ok(id)
+    }
+
+    /// Copies the current ledger state, without keeping it in the snapshots restorable by id.
+    pub fn capture_snapshot(&self) -> SurfnetSvmSnapshot {
+        SurfnetSvmSnapshot {
+            inner: self.inner.clone(),
+            chain_tip: self.chain_tip.clone(),
+            blocks: self.blocks.clone(),
+            transactions: self.transactions.clone(),
+            perf_samples: self.perf_samples.clone(),
+            transactions_processed: self.transactions_processed,
+            latest_epoch_info: self.latest_epoch_info.clone(),
+            accounts_registry: self.accounts_registry.clone(),
+            accounts_by_owner: self.accounts_by_owner.clone(),
+            account_associated_data: self.account_associated_data.clone(),
+            token_accounts: self.token_accounts.clone(),
+            token_mints: self.token_mints.clone(),
+            token_accounts_by_owner: self.token_accounts_by_owner.clone(),
+            token_accounts_by_delegate: self.token_accounts_by_delegate.clone(),
+            token_accounts_by_mint: self.token_accounts_by_mint.clone(),
+            total_supply: self.total_supply,
+            circulating_supply: self.circulating_supply,
+            non_circulating_supply: self.non_circulating_supply,
+            non_circulating_accounts: self.non_circulating_accounts.clone(),
+            registered_idls: self.registered_idls.clone(),
+            account_history: self.account_history.clone(),
+            truncated_account_histories: self.truncated_account_histories.clone(),
+            validators: self.validators.clone(),
+            write_version: self.write_version,
+            inflation_rewards: self.inflation_rewards.clone(),
+            bundles: self.bundles.clone(),
+        }
+    }
+
+    /// Deletes a snapshot, freeing the state it holds.
+    pub fn delete_snapshot(&mut self, snapshot_id: &Uuid) -> SurfpoolResult<()> {
+        self.snapshots
+            .remove(snapshot_id)
+            .map(|_| ())
+            .ok_or_else(|| SurfpoolError::snapshot_not_found(snapshot_id))
+    }
+
+    /// Returns the id of the latest snapshot taken before `slot`, if any.
+    pub fn latest_snapshot_before(&self, slot: Slot) -> Option<Uuid> {
+        self.snapshots
+            .iter()
+            .filter(|(_, snapshot)| snapshot.latest_epoch_info.absolute_slot < slot)
+            .max_by_key(|(_, snapshot)| snapshot.latest_epoch_info.absolute_slot)
+            .map(|(id, _)| *id)
+    }
+
+    /// Restores the ledger state captured by [SurfnetSvm::snapshot].
+    ///
+    /// The snapshot is kept, so the same state can be restored multiple times.
+    /// Subscribers and Geyser plugins are notified of the accounts that differ between the current state
+    /// and the snapshot. The transactions queued for confirmation are dropped, since they are either part
+    /// of the restored state already or reverted.
+    ///
+    /// # Arguments
+    /// * `snapshot_id` - The id returned when the snapshot was taken.
+    ///
+    /// # Returns
+    /// The restored epoch info, or an error if the snapshot does not exist.
+    pub fn revert(&mut self, snapshot_id: &Uuid) -> SurfpoolResult<EpochInfo> {
+        let Some(snapshot) = self.snapshots.get(snapshot_id).cloned() else {
+            return Err(SurfpoolError::snapshot_not_found(snapshot_id));
+        };
+        self.transactions_queued_for_confirmation.clear();
+        self.transactions_queued_for_finalization.clear();
+        Ok(self.restore_snapshot(snapshot.as_ref().clone()))
+    }
+
+    /// Restores a ledger state copied with [SurfnetSvm::capture_snapshot].
+    ///
+    /// Subscribers and Geyser plugins are notified of the accounts that differ between the current state
+    /// and the snapshot. The transactions queued for confirmation are left to the caller.
+    pub fn restore_snapshot(&mut self, snapshot: SurfnetSvmSnapshot) -> EpochInfo {
+        let changed_accounts = self
+            .accounts_registry
+            .iter()
+            .filter(|(pubkey, account)| snapshot.accounts_registry.get(pubkey) != Some(account))
+            .map(|(pubkey, _)| *pubkey)
+            .chain(
+                snapshot
+                    .accounts_registry
+                    .keys()
+                    .filter(|pubkey| !self.accounts_registry.contains_key(pubkey))
+                    .copied(),
+            )
+            .collect::<Vec<_>>();
+        let previous_accounts = changed_accounts
+            .iter()
+            .map(|pubkey| self.accounts_registry.get(pubkey).cloned())
+            .collect::<Vec<_>>();
+
+        self.inner = snapshot.inner;
+        self.chain_tip = snapshot.chain_tip;
+        self.blocks = snapshot.blocks;
+        self.transactions = snapshot.transactions;
+        self.perf_samples = snapshot.perf_samples;
+        self.transactions_processed = snapshot.transactions_processed;
+        self.latest_epoch_info = snapshot.latest_epoch_info;
+        self.accounts_registry = snapshot.accounts_registry;
+        self.accounts_by_owner = snapshot.accounts_by_owner;
+        self.account_associated_data = snapshot.account_associated_data;
+        self.token_accounts = snapshot.token_accounts;
+        self.token_mints = snapshot.token_mints;
+        self.token_accounts_by_owner = snapshot.token_accounts_by_owner;
+        self.token_accounts_by_delegate = snapshot.token_accounts_by_delegate;
+        self.token_accounts_by_mint = snapshot.token_accounts_by_mint;
+        self.total_supply = snapshot.total_supply;
+        self.circulating_supply = snapshot.circulating_supply;
+        self.non_circulating_supply = snapshot.non_circulating_supply;
+        self.non_circulating_accounts = snapshot.non_circulating_accounts;
+        self.registered_idls = snapshot.registered_idls;
+        self.account_history = snapshot.account_history;
+        self.truncated_account_histories = snapshot.truncated_account_histories;
+        self.validators = snapshot.validators;
+        self.write_version = snapshot.write_version;
+        self.inflation_rewards = snapshot.inflation_rewards;
+        self.bundles = snapshot.bundles;
+        self.updated_at = Utc::now().timestamp_millis() as u64;
+
+        let slot = self.get_latest_absolute_slot();
+        for (pubkey, previous_account) in changed_accounts.into_iter().zip(previous_accounts) {
+            let account = self
+                .accounts_registry
+                .get(&pubkey)
+                .cloned()
+                .unwrap_or_default();
+            let write_version = self.increment_write_version();
+            let _ =
+                self.geyser_events_tx
+                    .send(GeyserEvent::UpdateAccount(GeyserAccountUpdate::new(
+                        pubkey,
+                        account.clone(),
+                        slot,
+                        None,
+                        write_version,
+                    )));
+            self.notify_account_subscribers(&pubkey, previous_account.as_ref(), &account);
+        }
+
+        self.latest_epoch_info.clone()
+    }
+
     fn encode_ui_account_profile_state(
         &self,
         pubkey: &Pubkey,
@@ -1409,6 +2126,7 @@ impl SurfnetSvm {
             compute_units_consumed,
             log_messages,
             error_message,
+            events,
         } = profile_result;
 
         let account_states = pre_execution_capture
@@ -1435,6 +2153,7 @@ impl SurfnetSvm {
             compute_units_consumed,
             log_messages,
             error_message,
+            events,
         }
     }
 
@@ -1494,6 +2213,107 @@ impl SurfnetSvm {
         }
     }
 
+    /// Returns the accounts written after `start_slot` and up to `end_slot`, along with their state
+    /// at `start_slot` and at `end_slot`, sorted by pubkey.
+    ///
+    /// Accounts holding no lamports are reported as missing, and accounts that end up in their
+    /// initial state are left out. Fails if the oldest retained state of an account was written after
+    /// `start_slot` while older states were evicted, since its state at `start_slot` is unknown.
+    pub fn get_account_changes(
+        &self,
+        start_slot: Slot,
+        end_slot: Slot,
+        accounts: Option<&[Pubkey]>,
+    ) -> SurfpoolResult<Vec<(Pubkey, Option<Account>, Option<Account>)>> {
+        let state_at = |history: &VecDeque<(Slot, Account)>, slot: Slot| {
+            let written = history.partition_point(|(written_at, _)| *written_at <= slot);
+            written
+                .checked_sub(1)
+                .map(|index| history[index].1.clone())
+                .filter(|account| account.lamports > 0)
+        };
+
+        let mut changes = vec![];
+        for (pubkey, history) in self
+            .account_history
+            .iter()
+            .filter(|(pubkey, _)| accounts.is_none_or(|accounts| accounts.contains(pubkey)))
+        {
+            if let Some((oldest_slot, _)) = history.front() {
+                if start_slot < *oldest_slot && self.truncated_account_histories.contains(pubkey) {
+                    return Err(SurfpoolError::account_history_truncated(
+                        *pubkey,
+                        *oldest_slot,
+                        start_slot,
+                    ));
+                }
+            }
+            let before = state_at(history, start_slot);
+            let after = state_at(history, end_slot);
+            if before != after {
+                changes.push((*pubkey, before, after));
+            }
+        }
+        changes.sort_by_key(|(pubkey, _, _)| *pubkey);
+        Ok(changes)
+    }
+
+    /// Diffs the accounts written between `start_slot` and `end_slot`.
+    ///
+    /// Each state is encoded with the IDL that was active at its slot, if any.
+    pub fn get_account_diffs(
+        &self,
+        start_slot: Slot,
+        end_slot: Slot,
+        accounts: Option<&[Pubkey]>,
+        encoding: UiAccountEncoding,
+    ) -> SurfpoolResult<Vec<UiAccountDiff>> {
+        let diffs = self
+            .get_account_changes(start_slot, end_slot, accounts)?
+            .into_iter()
+            .map(|(pubkey, before, after)| {
+                let lamports =
+                    |account: &Option<Account>| account.as_ref().map_or(0, |a| a.lamports) as i128;
+                let lamports_delta = (lamports(&after) - lamports(&before))
+                    .clamp(i64::MIN as i128, i64::MAX as i128)
+                    as i64;
+                let owner_changed =
+                    before.as_ref().map(|a| a.owner) != after.as_ref().map(|a| a.owner);
+                let data_changed =
+                    before.as_ref().map(|a| &a.data) != after.as_ref().map(|a| &a.data);
+
+                let encode = |account: &Account, slot: Slot| {
+                    let additional_data = self.get_additional_data(&pubkey, None);
+                    self.encode_ui_account_at_slot(
+                        &pubkey,
+                        account,
+                        encoding,
+                        additional_data,
+                        None,
+                        slot,
+                    )
+                };
+                let change = match (&before, &after) {
+                    (None, Some(after)) => UiAccountChange::Create(encode(after, end_slot)),
+                    (Some(before), None) => UiAccountChange::Delete(encode(before, start_slot)),
+                    (Some(before), Some(after)) => {
+                        UiAccountChange::Update(encode(before, start_slot), encode(after, end_slot))
+                    }
+                    (None, None) => UiAccountChange::Unchanged(None),
+                };
+
+                UiAccountDiff {
+                    pubkey: pubkey.to_string(),
+                    lamports_delta,
+                    owner_changed,
+                    data_changed,
+                    change,
+                }
+            })
+            .collect();
+        Ok(diffs)
+    }
+
     pub fn encode_ui_account<T: ReadableAccount>(
         &self,
         pubkey: &Pubkey,
@@ -1501,10 +2321,30 @@ impl SurfnetSvm {
         encoding: UiAccountEncoding,
         additional_data: Option<AccountAdditionalDataV3>,
         data_slice_config: Option<UiDataSliceConfig>,
+    ) -> UiAccount {
+        self.encode_ui_account_at_slot(
+            pubkey,
+            account,
+            encoding,
+            additional_data,
+            data_slice_config,
+            self.latest_epoch_info.absolute_slot,
+        )
+    }
+
+    /// Encodes an account like [SurfnetSvm::encode_ui_account], parsing its data with the IDLs
+    /// registered at or before `filter_slot`.
+    pub fn encode_ui_account_at_slot<T: ReadableAccount>(
+        &self,
+        pubkey: &Pubkey,
+        account: &T,
+        encoding: UiAccountEncoding,
+        additional_data: Option<AccountAdditionalDataV3>,
+        data_slice_config: Option<UiDataSliceConfig>,
+        filter_slot: Slot,
     ) -> UiAccount {
         let owner_program_id = account.owner();
 
-        let filter_slot = self.latest_epoch_info.absolute_slot; // todo: consider if we should pass in a slot
         match encoding {
             UiAccountEncoding::JsonParsed => {
                 if let Some(registered_idls) = self.registered_idls.get(owner_program_id) {
@@ -1593,6 +2433,8 @@ mod tests {
     use borsh::BorshSerialize;
     // use test_log::test; // uncomment to get logs from litesvm
     use solana_account::Account;
+    use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+    use solana_commitment_config::CommitmentConfig;
     use solana_sdk::{
         bpf_loader_upgradeable::{self, get_program_data_address},
         program_pack::Pack,
@@ -1654,6 +2496,299 @@ mod tests {
         assert_eq!(mint_accounts[0].0, token_account_pubkey);
     }
 
+    #[test]
+    fn test_snapshot_and_revert() {
+        let (mut svm, _events_rx, geyser_rx) = SurfnetSvm::new();
+
+        let payer = Pubkey::new_unique();
+        svm.airdrop(&payer, 1_000_000_000).unwrap();
+        svm.confirm_current_block().unwrap();
+
+        let snapshot_id = svm.snapshot().unwrap();
+        let snapshot_slot = svm.get_latest_absolute_slot();
+
+        let new_account = Pubkey::new_unique();
+        svm.set_account(
+            &new_account,
+            Account {
+                lamports: 42,
+                data: vec![1, 2, 3],
+                owner: Pubkey::new_unique(),
+                executable: false,
+                rent_epoch: 0,
+            },
+        )
+        .unwrap();
+        svm.airdrop(&payer, 1_000_000_000).unwrap();
+        svm.confirm_current_block().unwrap();
+        svm.confirm_current_block().unwrap();
+        assert_eq!(svm.get_latest_absolute_slot(), snapshot_slot + 2);
+        assert_eq!(svm.latest_snapshot_before(snapshot_slot), None);
+        assert_eq!(
+            svm.latest_snapshot_before(snapshot_slot + 2),
+            Some(snapshot_id)
+        );
+
+        // transactions queued at the time of the revert are not confirmed afterwards
+        let (status_tx, status_rx) = crossbeam_channel::unbounded();
+        svm.transactions_queued_for_confirmation
+            .push_back((VersionedTransaction::default(), status_tx));
+        while geyser_rx.try_recv().is_ok() {}
+
+        svm.revert(&snapshot_id).unwrap();
+        assert!(svm.transactions_queued_for_confirmation.is_empty());
+        assert!(svm.transactions_queued_for_finalization.is_empty());
+        svm.confirm_current_block().unwrap();
+        assert!(status_rx.try_recv().is_err());
+
+        // geyser plugins are notified of the reverted accounts
+        let mut reverted_accounts = vec![];
+        while let Ok(event) = geyser_rx.try_recv() {
+            if let GeyserEvent::UpdateAccount(update) = event {
+                reverted_accounts.push((update.pubkey, update.account.lamports));
+            }
+        }
+        assert!(reverted_accounts.contains(&(new_account, 0)));
+        svm.revert(&snapshot_id).unwrap();
+        assert_eq!(svm.get_latest_absolute_slot(), snapshot_slot);
+        assert!(svm.inner.get_account(&new_account).is_none());
+        assert!(!svm.accounts_registry.contains_key(&new_account));
+        assert_eq!(
+            svm.inner.get_account(&payer).unwrap().lamports,
+            1_000_000_000
+        );
+        assert_eq!(svm.transactions.len(), 1);
+
+        // a snapshot can be reverted to more than once
+        svm.set_account(&new_account, Account::default()).unwrap();
+        svm.revert(&snapshot_id).unwrap();
+        assert!(!svm.accounts_registry.contains_key(&new_account));
+
+        // validators added after the snapshot are reverted along with their accounts
+        svm.add_synthetic_validators(1).unwrap();
+        let validator = svm.validators[0];
+        svm.revert(&snapshot_id).unwrap();
+        assert!(svm.validators.is_empty());
+        assert!(svm.inner.get_account(&validator.vote_account).is_none());
+
+        assert!(svm.revert(&Uuid::new_v4()).is_err());
+
+        // snapshots are capped, and can be deleted
+        for _ in 1..MAX_SNAPSHOTS {
+            svm.snapshot().unwrap();
+        }
+        assert!(svm.snapshot().is_err());
+        svm.delete_snapshot(&snapshot_id).unwrap();
+        assert!(svm.revert(&snapshot_id).is_err());
+        assert!(svm.delete_snapshot(&snapshot_id).is_err());
+        assert!(svm.snapshot().is_ok());
+    }
+
+    #[test]
+    fn test_program_subscription_filters() {
+        let (mut svm, _events_rx, _geyser_rx) = SurfnetSvm::new();
+
+        let program_id = Pubkey::new_unique();
+        let rx = svm.subscribe_for_program_updates(
+            &program_id,
+            RpcProgramAccountsConfig {
+                filters: Some(vec![
+                    RpcFilterType::DataSize(4),
+                    RpcFilterType::Memcmp(Memcmp::new_raw_bytes(0, vec![1, 2])),
+                ]),
+                ..Default::default()
+            },
+        );
+        let account = |data: Vec<u8>, owner: Pubkey| Account {
+            lamports: 1_000_000,
+            data,
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        };
+
+        let matching_pubkey = Pubkey::new_unique();
+        svm.set_account(&matching_pubkey, account(vec![1, 2, 3, 4], program_id))
+            .unwrap();
+        // wrong data size
+        svm.set_account(&Pubkey::new_unique(), account(vec![1, 2, 3], program_id))
+            .unwrap();
+        // memcmp mismatch
+        svm.set_account(&Pubkey::new_unique(), account(vec![2, 2, 3, 4], program_id))
+            .unwrap();
+        // owned by another program
+        svm.set_account(
+            &Pubkey::new_unique(),
+            account(vec![1, 2, 3, 4], Pubkey::new_unique()),
+        )
+        .unwrap();
+
+        let (slot, keyed_account) = rx.try_recv().unwrap();
+        assert_eq!(slot, svm.get_latest_absolute_slot());
+        assert_eq!(keyed_account.pubkey, matching_pubkey.to_string());
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_program_subscription_previous_owner() {
+        let (mut svm, _events_rx, _geyser_rx) = SurfnetSvm::new();
+
+        let program_id = Pubkey::new_unique();
+        let rx = svm.subscribe_for_program_updates(
+            &program_id,
+            RpcProgramAccountsConfig {
+                filters: Some(vec![RpcFilterType::DataSize(4)]),
+                ..Default::default()
+            },
+        );
+        let account = |lamports: u64, data: Vec<u8>, owner: Pubkey| Account {
+            lamports,
+            data,
+            owner,
+            executable: false,
+            rent_epoch: 0,
+        };
+
+        let pubkey = Pubkey::new_unique();
+        svm.set_account(&pubkey, account(1_000_000, vec![1, 2, 3, 4], program_id))
+            .unwrap();
+        assert_eq!(rx.try_recv().unwrap().1.pubkey, pubkey.to_string());
+
+        // the previous owner is notified when the account is assigned to another program,
+        // even though the new data doesn't match its filters
+        let other_program_id = Pubkey::new_unique();
+        svm.set_account(&pubkey, account(1_000_000, vec![0; 8], other_program_id))
+            .unwrap();
+        let (_, keyed_account) = rx.try_recv().unwrap();
+        assert_eq!(keyed_account.pubkey, pubkey.to_string());
+        assert_eq!(keyed_account.account.owner, other_program_id.to_string());
+
+        // and is no longer notified of its updates
+        svm.set_account(
+            &pubkey,
+            account(1_000_000, vec![1, 2, 3, 4], other_program_id),
+        )
+        .unwrap();
+        assert!(rx.try_recv().is_err());
+
+        // the previous owner is notified when the account is closed
+        svm.set_account(&pubkey, account(1_000_000, vec![1, 2, 3, 4], program_id))
+            .unwrap();
+        assert!(rx.try_recv().is_ok());
+        svm.set_account(&pubkey, account(0, vec![1, 2, 3, 4], program_id))
+            .unwrap();
+        let (_, keyed_account) = rx.try_recv().unwrap();
+        assert_eq!(keyed_account.pubkey, pubkey.to_string());
+        assert_eq!(keyed_account.account.lamports, 0);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_block_and_root_subscriptions() {
+        let (mut svm, _events_rx, _geyser_rx) = SurfnetSvm::new();
+
+        let confirmed_rx = svm.subscribe_for_block_updates(
+            RpcBlockSubscribeFilter::All,
+            RpcBlockSubscribeConfig {
+                commitment: Some(CommitmentConfig::confirmed()),
+                transaction_details: Some(TransactionDetails::Signatures),
+                ..Default::default()
+            },
+        );
+        let mentions_rx = svm.subscribe_for_block_updates(
+            RpcBlockSubscribeFilter::MentionsAccountOrProgram(Pubkey::new_unique().to_string()),
+            RpcBlockSubscribeConfig {
+                commitment: Some(CommitmentConfig::confirmed()),
+                ..Default::default()
+            },
+        );
+        let finalized_rx =
+            svm.subscribe_for_block_updates(RpcBlockSubscribeFilter::All, Default::default());
+        let root_rx = svm.subscribe_for_root_updates();
+
+        let slot = svm.get_latest_absolute_slot();
+        svm.confirm_current_block().unwrap();
+
+        let (notified_slot, update) = confirmed_rx.try_recv().unwrap();
+        assert_eq!(notified_slot, slot);
+        assert_eq!(update.slot, slot);
+        assert!(update.err.is_none());
+        let block = update.block.unwrap();
+        assert_eq!(block.signatures, Some(vec![]));
+        assert!(block.transactions.is_none());
+        // blocks without any transaction mentioning the account are skipped
+        assert!(mentions_rx.try_recv().is_err());
+        assert!(finalized_rx.try_recv().is_err());
+        assert!(root_rx.try_recv().is_err());
+
+        for _ in 1..FINALIZATION_SLOT_THRESHOLD {
+            svm.confirm_current_block().unwrap();
+        }
+        assert_eq!(root_rx.try_recv().unwrap(), slot);
+        let (_, update) = finalized_rx.try_recv().unwrap();
+        assert_eq!(update.slot, slot);
+        assert!(update.block.unwrap().transactions.is_some());
+    }
+
+    #[test]
+    fn test_geyser_block_and_slot_status_events() {
+        let (mut svm, _events_rx, geyser_rx) = SurfnetSvm::new();
+
+        // Confirm the genesis block first, so that the block under test has a parent slot
+        svm.confirm_current_block().unwrap();
+        while geyser_rx.try_recv().is_ok() {}
+
+        let slot = svm.get_latest_absolute_slot();
+        svm.confirm_current_block().unwrap();
+        let blockhash = svm.blocks.get(&slot).unwrap().hash.clone();
+
+        match geyser_rx.try_recv().unwrap() {
+            GeyserEvent::NotifyEntry(entry) => {
+                assert_eq!(entry.slot, slot);
+                assert_eq!(entry.hash.to_string(), blockhash);
+                assert_eq!(entry.executed_transaction_count, 0);
+            }
+            _ => panic!("expected an entry notification"),
+        }
+        match geyser_rx.try_recv().unwrap() {
+            GeyserEvent::NotifyBlockMetadata(block) => {
+                assert_eq!(block.slot, slot);
+                assert_eq!(block.blockhash, blockhash);
+                assert_eq!(block.parent_slot, slot - 1);
+                assert_eq!(block.entry_count, 1);
+            }
+            _ => panic!("expected a block metadata notification"),
+        }
+        let mut statuses = vec![];
+        while let Ok(GeyserEvent::UpdateSlotStatus {
+            slot: status_slot,
+            parent,
+            status,
+        }) = geyser_rx.try_recv()
+        {
+            assert_eq!(status_slot, slot);
+            assert_eq!(parent, Some(slot - 1));
+            statuses.push(status);
+        }
+        assert!(matches!(
+            statuses.as_slice(),
+            [SlotStatus::Processed, SlotStatus::Confirmed]
+        ));
+
+        for _ in 1..FINALIZATION_SLOT_THRESHOLD {
+            svm.confirm_current_block().unwrap();
+        }
+        let rooted = geyser_rx.try_iter().any(|event| match event {
+            GeyserEvent::UpdateSlotStatus {
+                slot: rooted_slot,
+                parent,
+                status: SlotStatus::Rooted,
+            } => rooted_slot == slot && parent == Some(slot - 1),
+            _ => false,
+        });
+        assert!(rooted);
+    }
+
     #[test]
     fn test_account_update_removes_old_indexes() {
         let (mut svm, _events_rx, _geyser_rx) = SurfnetSvm::new();
@@ -2189,4 +3324,334 @@ mod tests {
             assert_eq!(ui_account, expected_account);
         }
     }
+
+    #[test]
+    fn test_account_diffs() {
+        let (mut svm, _events_rx, _geyser_rx) = SurfnetSvm::new();
+
+        let idl: Idl =
+            serde_json::from_slice(&include_bytes!("../tests/assets/idl_v1.json").to_vec())
+                .unwrap();
+        svm.register_idl(idl.clone(), Some(0));
+
+        #[derive(borsh::BorshSerialize)]
+        pub struct CustomAccount {
+            pub my_custom_data: u64,
+            pub another_field: String,
+            pub bool: bool,
+            pub pubkey: Pubkey,
+        }
+        let custom_account_data = |my_custom_data: u64| {
+            let mut data = idl.accounts[0].discriminator.clone();
+            CustomAccount {
+                my_custom_data,
+                another_field: "test".to_string(),
+                bool: true,
+                pubkey: Pubkey::default(),
+            }
+            .serialize(&mut data)
+            .unwrap();
+            data
+        };
+
+        let payer = Pubkey::new_unique();
+        let program_account = Pubkey::new_unique();
+        let closed_account = Pubkey::new_unique();
+        let program_id: Pubkey = idl.address.parse().unwrap();
+
+        let start_slot = svm.get_latest_absolute_slot();
+        svm.set_account(&payer, Account::new(10_000, 0, &system_program::id()))
+            .unwrap();
+        svm.confirm_current_block().unwrap();
+
+        let middle_slot = svm.get_latest_absolute_slot();
+        svm.set_account(&payer, Account::new(4_000, 0, &system_program::id()))
+            .unwrap();
+        svm.set_account(
+            &closed_account,
+            Account::new(1_000, 0, &system_program::id()),
+        )
+        .unwrap();
+        let mut account = Account::new(6_000, 0, &program_id);
+        account.data = custom_account_data(1);
+        svm.set_account(&program_account, account.clone()).unwrap();
+        svm.confirm_current_block().unwrap();
+
+        let end_slot = svm.get_latest_absolute_slot();
+        account.data = custom_account_data(2);
+        svm.set_account(&program_account, account).unwrap();
+        svm.set_account(&closed_account, Account::default())
+            .unwrap();
+
+        // changes made at the start slot are not part of the diff
+        let diffs = svm
+            .get_account_diffs(start_slot, middle_slot, None, UiAccountEncoding::JsonParsed)
+            .unwrap();
+        assert_eq!(diffs.len(), 3);
+        let payer_diff = diffs
+            .iter()
+            .find(|d| d.pubkey == payer.to_string())
+            .unwrap();
+        assert_eq!(payer_diff.lamports_delta, -6_000);
+        assert!(!payer_diff.owner_changed);
+        assert!(matches!(payer_diff.change, UiAccountChange::Update(_, _)));
+
+        let program_account_diff = diffs
+            .iter()
+            .find(|d| d.pubkey == program_account.to_string())
+            .unwrap();
+        assert_eq!(program_account_diff.lamports_delta, 6_000);
+        assert!(program_account_diff.data_changed);
+        let UiAccountChange::Create(created) = &program_account_diff.change else {
+            panic!("expected the program account to be created");
+        };
+        let UiAccountData::Json(parsed) = &created.data else {
+            panic!("expected the program account data to be decoded with its IDL");
+        };
+        assert_eq!(parsed.parsed["my_custom_data"], 1);
+
+        // accounts set back to their initial state are left out
+        let diffs = svm
+            .get_account_diffs(
+                start_slot,
+                end_slot,
+                Some(&[closed_account]),
+                UiAccountEncoding::Base64,
+            )
+            .unwrap();
+        assert!(diffs.is_empty());
+
+        let diffs = svm
+            .get_account_diffs(
+                middle_slot,
+                end_slot,
+                Some(&[closed_account]),
+                UiAccountEncoding::Base64,
+            )
+            .unwrap();
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0].lamports_delta, -1_000);
+        assert!(matches!(diffs[0].change, UiAccountChange::Delete(_)));
+    }
+
+    #[test]
+    fn test_account_history_bounds() {
+        let (mut svm, _events_rx, _geyser_rx) = SurfnetSvm::new();
+        let pubkey = Pubkey::new_unique();
+
+        // only the last state written in a slot is kept
+        svm.set_account(&pubkey, Account::new(1, 0, &system_program::id()))
+            .unwrap();
+        svm.set_account(&pubkey, Account::new(2, 0, &system_program::id()))
+            .unwrap();
+        assert_eq!(svm.account_history[&pubkey].len(), 1);
+        assert_eq!(svm.account_history[&pubkey][0].1.lamports, 2);
+
+        for lamports in 3..MAX_ACCOUNT_HISTORY_LEN as u64 + 10 {
+            svm.confirm_current_block().unwrap();
+            svm.set_account(&pubkey, Account::new(lamports, 0, &system_program::id()))
+                .unwrap();
+        }
+        let history = &svm.account_history[&pubkey];
+        assert_eq!(history.len(), MAX_ACCOUNT_HISTORY_LEN);
+        assert_eq!(
+            history.back().unwrap().1.lamports,
+            MAX_ACCOUNT_HISTORY_LEN as u64 + 9
+        );
+        assert!(svm.truncated_account_histories.contains(&pubkey));
+
+        // ranges starting before the oldest retained state can't be diffed
+        let oldest_slot = history.front().unwrap().0;
+        let latest_slot = svm.get_latest_absolute_slot();
+        let err = svm
+            .get_account_changes(0, latest_slot, Some(&[pubkey]))
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            SurfpoolError::account_history_truncated(pubkey, oldest_slot, 0).to_string()
+        );
+        let changes = svm
+            .get_account_changes(oldest_slot, latest_slot, Some(&[pubkey]))
+            .unwrap();
+        assert_eq!(changes.len(), 1);
+        assert!(changes[0].1.is_some());
+
+        // the accounts holding program ELFs are not tracked
+        let program_data = Pubkey::new_unique();
+        svm.set_account(
+            &program_data,
+            Account::new(1_000_000, 64, &bpf_loader_upgradeable::id()),
+        )
+        .unwrap();
+        assert!(!svm.account_history.contains_key(&program_data));
+    }
+
+    #[test]
+    fn test_restore_pre_execution_state() {
+        let (mut svm, _events_rx, _geyser_rx) = SurfnetSvm::new();
+        let signature = Signature::new_unique();
+        let (payer, token_account_pubkey, created, read, mint) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+
+        // the state of the accounts right after the transaction
+        svm.set_account(&payer, Account::new(900, 0, &system_program::id()))
+            .unwrap();
+        let mut token_account_data = [0u8; TokenAccount::LEN];
+        TokenAccount {
+            mint,
+            owner: payer,
+            amount: 1000,
+            state: AccountState::Initialized,
+            ..Default::default()
+        }
+        .pack_into_slice(&mut token_account_data);
+        svm.set_account(
+            &token_account_pubkey,
+            Account {
+                lamports: 2_039_280,
+                data: token_account_data.to_vec(),
+                owner: spl_token::id(),
+                executable: false,
+                rent_epoch: 0,
+            },
+        )
+        .unwrap();
+        svm.set_account(&created, Account::new(1_000, 0, &system_program::id()))
+            .unwrap();
+        let read_account = Account::new(3_000, 8, &Pubkey::new_unique());
+        svm.set_account(&read, read_account.clone()).unwrap();
+
+        let account_keys = [payer, token_account_pubkey, created, read];
+        let written_accounts = [payer, token_account_pubkey, created];
+        let pre_token_balances: Vec<UiTransactionTokenBalance> =
+            serde_json::from_value(serde_json::json!([{
+                "accountIndex": 1,
+                "mint": mint.to_string(),
+                "uiTokenAmount": {
+                    "uiAmount": 500.0,
+                    "decimals": 0,
+                    "amount": "500",
+                    "uiAmountString": "500",
+                },
+                "owner": payer.to_string(),
+                "programId": spl_token::id().to_string(),
+            }]))
+            .unwrap();
+
+        // an account written by the transaction whose data is not recorded can't be restored
+        let error = svm
+            .restore_pre_execution_state(
+                &signature,
+                &account_keys,
+                &[payer, read],
+                &[1_000, 2_039_280, 0, 3_000],
+                &pre_token_balances,
+            )
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            SurfpoolError::unrecoverable_account_state(read, signature).to_string()
+        );
+
+        // neither can an account closed by the transaction
+        let closed = Pubkey::new_unique();
+        let error = svm
+            .restore_pre_execution_state(&signature, &[closed], &[closed], &[1_000], &[])
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            SurfpoolError::unrecoverable_account_state(closed, signature).to_string()
+        );
+
+        svm.restore_pre_execution_state(
+            &signature,
+            &account_keys,
+            &written_accounts,
+            &[1_000, 2_039_280, 0, 3_000],
+            &pre_token_balances,
+        )
+        .unwrap();
+        assert_eq!(svm.inner.get_account(&payer).unwrap().lamports, 1_000);
+        let token_account =
+            TokenAccount::unpack(&svm.inner.get_account(&token_account_pubkey).unwrap().data)
+                .unwrap();
+        assert_eq!(token_account.amount, 500);
+        assert!(
+            svm.inner
+                .get_account(&created)
+                .is_none_or(|account| account.lamports == 0)
+        );
+        assert_eq!(svm.inner.get_account(&read).unwrap(), read_account);
+    }
+
+    #[test]
+    fn test_deploy_program() {
+        let (mut svm, _events_rx, _geyser_rx) = SurfnetSvm::new();
+        let program_id = Pubkey::new_unique();
+        let programdata_address = get_program_data_address(&program_id);
+        let elf = include_bytes!("../tests/assets/metaplex_program.bin");
+        let authority = Pubkey::new_unique();
+
+        svm.deploy_program(&program_id, elf, Some(authority))
+            .unwrap();
+
+        let program_account = svm.inner.get_account(&program_id).unwrap();
+        assert!(program_account.executable);
+        assert_eq!(program_account.owner, bpf_loader_upgradeable::id());
+        assert_eq!(
+            bincode::deserialize::<UpgradeableLoaderState>(&program_account.data).unwrap(),
+            UpgradeableLoaderState::Program {
+                programdata_address
+            }
+        );
+
+        let programdata_account = svm.inner.get_account(&programdata_address).unwrap();
+        let metadata_len = UpgradeableLoaderState::size_of_programdata_metadata();
+        assert_eq!(&programdata_account.data[metadata_len..], elf);
+        assert_eq!(
+            bincode::deserialize::<UpgradeableLoaderState>(&programdata_account.data).unwrap(),
+            UpgradeableLoaderState::ProgramData {
+                slot: svm.get_latest_absolute_slot(),
+                upgrade_authority_address: Some(authority),
+            }
+        );
+
+        // Redeploying without an authority makes the program immutable, and keeps the metadata length
+        svm.deploy_program(&program_id, elf, None).unwrap();
+        let programdata_account = svm.inner.get_account(&programdata_address).unwrap();
+        assert_eq!(programdata_account.data.len(), metadata_len + elf.len());
+        assert_eq!(
+            bincode::deserialize::<UpgradeableLoaderState>(&programdata_account.data).unwrap(),
+            UpgradeableLoaderState::ProgramData {
+                slot: svm.get_latest_absolute_slot(),
+                upgrade_authority_address: None,
+            }
+        );
+
+        // A truncated ELF is rejected before any account is written
+        assert!(
+            svm.deploy_program(&program_id, &elf[..elf.len() / 2], None)
+                .is_err()
+        );
+        assert_eq!(
+            svm.inner.get_account(&programdata_address).unwrap(),
+            programdata_account
+        );
+        let other_program_id = Pubkey::new_unique();
+        assert!(
+            svm.deploy_program(&other_program_id, &elf[..elf.len() / 2], None)
+                .is_err()
+        );
+        assert!(svm.inner.get_account(&other_program_id).is_none());
+        assert!(
+            svm.inner
+                .get_account(&get_program_data_address(&other_program_id))
+                .is_none()
+        );
+    }
 }
@@ -1,4 +1,4 @@
-use std::{str::FromStr, sync::Arc, time::Duration};
+use std::{path::PathBuf, str::FromStr, sync::Arc, time::Duration};
 
 use base64::Engine;
 use crossbeam_channel::{unbounded, unbounded as crossbeam_unbounded};
@@ -20,7 +20,12 @@ use solana_message::{
 use solana_native_token::LAMPORTS_PER_SOL;
 use solana_pubkey::{Pubkey, pubkey};
 use solana_rpc_client_api::response::Response as RpcResponse;
-use solana_sdk::{system_instruction::transfer, system_program, transaction::Transaction};
+use solana_sdk::{
+    bpf_loader_upgradeable::{UpgradeableLoaderState, get_program_data_address},
+    system_instruction::transfer,
+    system_program,
+    transaction::Transaction,
+};
 use solana_signer::Signer;
 use solana_system_interface::instruction as system_instruction;
 use solana_transaction::versioned::VersionedTransaction;
@@ -104,6 +109,72 @@ async fn test_simnet_ready() {
     }
 }
 
+fn start_simnet_with_geyser_plugin(plugin_config_path: PathBuf) -> Result<(), String> {
+    let config = SurfpoolConfig {
+        simnets: vec![SimnetConfig {
+            block_production_mode: BlockProductionMode::Manual, // Prevent ticks
+            ..SimnetConfig::default()
+        }],
+        rpc: RpcConfig {
+            bind_port: get_free_port().unwrap(),
+            ws_port: get_free_port().unwrap(),
+            ..Default::default()
+        },
+        plugin_config_path: vec![plugin_config_path],
+        ..SurfpoolConfig::default()
+    };
+
+    let (surfnet_svm, simnet_events_rx, geyser_events_rx) = SurfnetSvm::new();
+    let (simnet_commands_tx, simnet_commands_rx) = unbounded();
+    let (subgraph_commands_tx, _subgraph_commands_rx) = unbounded();
+    let svm_locker = SurfnetSvmLocker::new(surfnet_svm);
+
+    let result = hiro_system_kit::thread_named("test")
+        .spawn(move || {
+            let future = start_local_surfnet_runloop(
+                svm_locker,
+                config,
+                subgraph_commands_tx,
+                simnet_commands_tx,
+                simnet_commands_rx,
+                geyser_events_rx,
+            );
+            hiro_system_kit::nestable_block_on(future).map_err(|e| e.to_string())
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+
+    assert!(
+        !simnet_events_rx
+            .try_iter()
+            .any(|event| matches!(event, SimnetEvent::Ready)),
+        "surfnet should not be ready when a geyser plugin fails to load"
+    );
+    result
+}
+
+#[cfg_attr(feature = "ignore_tests_ci", ignore = "flaky CI tests")]
+#[tokio::test]
+async fn test_simnet_fails_to_start_with_missing_geyser_plugin() {
+    let err = start_simnet_with_geyser_plugin(PathBuf::from("missing_geyser_plugin.json"))
+        .expect_err("surfnet should fail to start");
+    assert!(err.starts_with("Geyser plugin failed"), "{err}");
+}
+
+#[cfg_attr(feature = "ignore_tests_ci", ignore = "flaky CI tests")]
+#[tokio::test]
+async fn test_simnet_fails_to_start_with_invalid_geyser_plugin_manifest() {
+    let manifest_path = std::env::temp_dir().join(format!("geyser_plugin_{}.json", Uuid::new_v4()));
+    std::fs::write(&manifest_path, r#"{ "name": "invalid-plugin" }"#).unwrap();
+
+    let result = start_simnet_with_geyser_plugin(manifest_path.clone());
+    let _ = std::fs::remove_file(&manifest_path);
+
+    let err = result.expect_err("surfnet should fail to start");
+    assert!(err.starts_with("Geyser plugin failed"), "{err}");
+}
+
 #[cfg_attr(feature = "ignore_tests_ci", ignore = "flaky CI tests")]
 #[tokio::test]
 async fn test_simnet_ticks() {
@@ -3413,3 +3484,64 @@ fn test_time_travel_absolute_epoch() {
 
     println!("Time travel to absolute epoch test passed successfully!");
 }
+
+#[test]
+fn test_reload_program_keeps_program_owned_accounts() {
+    let (svm_locker, simnet_cmd_tx, simnet_events_rx) =
+        boot_simnet(BlockProductionMode::Manual, None);
+    let program_id = Pubkey::new_unique();
+    let programdata_address = get_program_data_address(&program_id);
+    let authority = Pubkey::new_unique();
+    let elf = include_bytes!("assets/metaplex_program.bin");
+    svm_locker
+        .with_svm_writer(|svm_writer| svm_writer.deploy_program(&program_id, elf, Some(authority)))
+        .unwrap();
+
+    let owned_pubkey = Pubkey::new_unique();
+    let owned_account = solana_account::Account {
+        lamports: LAMPORTS_PER_SOL,
+        data: vec![7; 64],
+        owner: program_id,
+        executable: false,
+        rent_epoch: 0,
+    };
+    svm_locker
+        .with_svm_writer(|svm_writer| svm_writer.set_account(&owned_pubkey, owned_account.clone()))
+        .unwrap();
+
+    simnet_cmd_tx
+        .send(SimnetCommand::ReloadProgram(program_id, elf.to_vec()))
+        .unwrap();
+    loop {
+        match simnet_events_rx.recv_timeout(Duration::from_secs(5)) {
+            Ok(SimnetEvent::InfoLog(_, message))
+                if message.starts_with("Deployed") && message.contains(&program_id.to_string()) =>
+            {
+                break;
+            }
+            Ok(SimnetEvent::ErrorLog(_, message)) => panic!("{message}"),
+            Ok(_) => continue,
+            Err(e) => panic!("program was not reloaded: {e:?}"),
+        }
+    }
+
+    svm_locker.with_svm_reader(|svm_reader| {
+        assert_eq!(
+            svm_reader.inner.get_account(&owned_pubkey),
+            Some(owned_account)
+        );
+        let programdata_account = svm_reader.inner.get_account(&programdata_address).unwrap();
+        assert_eq!(
+            &programdata_account.data[UpgradeableLoaderState::size_of_programdata_metadata()..],
+            elf
+        );
+        // The upgrade authority of the reloaded program is kept
+        assert!(matches!(
+            bincode::deserialize::<UpgradeableLoaderState>(&programdata_account.data).unwrap(),
+            UpgradeableLoaderState::ProgramData {
+                upgrade_authority_address: Some(upgrade_authority),
+                ..
+            } if upgrade_authority == authority
+        ));
+    });
+}
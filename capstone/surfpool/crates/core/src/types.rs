@@ -4,6 +4,7 @@ use litesvm::types::TransactionMetadata;
 use solana_account::Account;
 use solana_account_decoder::parse_token::UiTokenAmount;
 use solana_clock::{Epoch, Slot};
+use solana_hash::Hash;
 use solana_message::{
     AccountKeys, VersionedMessage,
     v0::{LoadedAddresses, LoadedMessage},
@@ -14,6 +15,7 @@ use solana_sdk::{
     program_pack::Pack,
     reserved_account_keys::ReservedAccountKeys,
     transaction::{SanitizedTransaction, TransactionVersion, VersionedTransaction},
+    transaction_context::TransactionReturnData,
 };
 use solana_transaction_status::{
     Encodable, EncodableWithMeta, EncodeError, EncodedTransaction,
@@ -29,7 +31,10 @@ use spl_token_2022::extension::StateWithExtensions;
 
 use crate::{
     error::{SurfpoolError, SurfpoolResult},
-    surfnet::locker::{format_ui_amount, format_ui_amount_string},
+    surfnet::{
+        fees::{FeeDetails, LAMPORTS_PER_SIGNATURE},
+        locker::{format_ui_amount, format_ui_amount_string},
+    },
 };
 
 #[derive(Debug, Clone)]
@@ -79,13 +84,14 @@ impl TransactionWithStatusMeta {
         token_program_ids: &[Pubkey],
         loaded_addresses: LoadedAddresses,
     ) -> Self {
-        let signatures_len = transaction.signatures.len();
+        let fee =
+            FeeDetails::from_message(&transaction.message, LAMPORTS_PER_SIGNATURE).total_fee();
         Self {
             slot,
             transaction,
             meta: TransactionStatusMeta {
                 status: Ok(()),
-                fee: 5000 * signatures_len as u64,
+                fee,
                 pre_balances: accounts_before
                     .iter()
                     .map(|a| a.clone().map(|a| a.lamports).unwrap_or(0))
@@ -164,7 +170,7 @@ impl TransactionWithStatusMeta {
                 ),
                 rewards: Some(vec![]),
                 loaded_addresses,
-                return_data: Some(transaction_meta.return_data),
+                return_data: non_empty_return_data(transaction_meta.return_data),
                 compute_units_consumed: Some(transaction_meta.compute_units_consumed),
             },
         }
@@ -318,7 +324,8 @@ impl TransactionWithStatusMeta {
             .map(|a| a.as_ref().map(|a| a.lamports).unwrap_or(0))
             .collect();
 
-        let fee = 5000 * transaction.signatures.len() as u64;
+        let fee =
+            FeeDetails::from_message(&transaction.message, LAMPORTS_PER_SIGNATURE).total_fee();
 
         let post_balances: Vec<u64> = accounts_after
             .iter()
@@ -380,13 +387,22 @@ impl TransactionWithStatusMeta {
                 post_token_balances: Some(balances),
                 rewards: Some(vec![]),
                 loaded_addresses,
-                return_data: Some(failure.meta.return_data.clone()),
+                return_data: non_empty_return_data(failure.meta.return_data.clone()),
                 compute_units_consumed: Some(failure.meta.compute_units_consumed),
             },
         }
     }
 }
 
+/// Programs that don't set return data leave it empty, in which case validators omit it.
+fn non_empty_return_data(return_data: TransactionReturnData) -> Option<TransactionReturnData> {
+    if return_data.data.is_empty() {
+        None
+    } else {
+        Some(return_data)
+    }
+}
+
 fn parse_ui_transaction_status_meta_with_account_keys(
     meta: TransactionStatusMeta,
     static_keys: &[Pubkey],
@@ -723,7 +739,8 @@ pub struct GeyserAccountUpdate {
     pub pubkey: Pubkey,
     pub account: Account,
     pub slot: u64,
-    pub sanitized_transaction: SanitizedTransaction,
+    /// The transaction that wrote the account, if any
+    pub sanitized_transaction: Option<SanitizedTransaction>,
     pub write_version: u64,
 }
 impl GeyserAccountUpdate {
@@ -731,7 +748,7 @@ impl GeyserAccountUpdate {
         pubkey: Pubkey,
         account: Account,
         slot: u64,
-        sanitized_transaction: SanitizedTransaction,
+        sanitized_transaction: Option<SanitizedTransaction>,
         write_version: u64,
     ) -> Self {
         Self {
@@ -744,6 +761,33 @@ impl GeyserAccountUpdate {
     }
 }
 
+/// The metadata of a confirmed block, sent to the Geyser plugins.
+#[derive(Debug, Clone)]
+pub struct GeyserBlockMetadata {
+    pub slot: Slot,
+    pub blockhash: String,
+    pub parent_slot: Slot,
+    pub parent_blockhash: String,
+    /// The block time, in seconds
+    pub block_time: Option<i64>,
+    pub block_height: Option<u64>,
+    pub executed_transaction_count: u64,
+    pub entry_count: u64,
+}
+
+/// An entry of a confirmed block, sent to the Geyser plugins.
+///
+/// Surfnet blocks are made of a single entry holding all the transactions of the block.
+#[derive(Debug, Clone)]
+pub struct GeyserEntryInfo {
+    pub slot: Slot,
+    pub index: usize,
+    pub num_hashes: u64,
+    pub hash: Hash,
+    pub executed_transaction_count: u64,
+    pub starting_transaction_index: usize,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub enum TimeTravelConfig {
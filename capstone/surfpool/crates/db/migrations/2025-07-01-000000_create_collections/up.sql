@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS collections (
+    id TEXT PRIMARY KEY NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL,
+    table_name TEXT NOT NULL,
+    schema TEXT NOT NULL
+);
@@ -1,3 +1,325 @@
+use std::collections::HashMap;
+
 pub use diesel;
+use diesel::{
+    MultiConnection, QueryableByName,
+    r2d2::{ConnectionManager, Pool, PooledConnection},
+    sql_types::Text,
+};
 pub use diesel_dynamic_schema;
+use diesel_migrations::{EmbeddedMigrations, embed_migrations};
+
+/// Applies the filters of a dynamic entries query.
+///
+/// Shared by the backends, whose boxed query types differ.
+macro_rules! apply_entry_filters {
+    ($query:expr, $dynamic_table:expr, $filters:expr) => {{
+        let mut query = $query;
+        for filter in $filters.iter() {
+            let column = filter.column.as_str();
+            query = match &filter.value {
+                EntryValue::Text(s) => {
+                    let col = $dynamic_table.column::<Text, _>(column);
+                    match filter.predicate {
+                        FilterPredicate::Equals => query.filter(col.eq(s)),
+                        FilterPredicate::NotEquals => query.filter(col.ne(s)),
+                        FilterPredicate::Contains => query.filter(col.like(format!("%{}%", s))),
+                        FilterPredicate::NotContains => {
+                            query.filter(col.not_like(format!("%{}%", s)))
+                        }
+                        FilterPredicate::StartsWith => query.filter(col.like(format!("{}%", s))),
+                        FilterPredicate::EndsWith => query.filter(col.like(format!("%{}", s))),
+                        predicate => {
+                            return Err(format!("Unsupported string predicate: {:?}", predicate));
+                        }
+                    }
+                }
+                EntryValue::Integer(i) => {
+                    let col = $dynamic_table.column::<BigInt, _>(column);
+                    match filter.predicate {
+                        FilterPredicate::Equals => query.filter(col.eq(*i)),
+                        FilterPredicate::NotEquals => query.filter(col.ne(*i)),
+                        FilterPredicate::GreaterThan => query.filter(col.gt(*i)),
+                        FilterPredicate::GreaterOrEqual => query.filter(col.ge(*i)),
+                        FilterPredicate::LowerThan => query.filter(col.lt(*i)),
+                        FilterPredicate::LowerOrEqual => query.filter(col.le(*i)),
+                        predicate => {
+                            return Err(format!("Unsupported integer predicate: {:?}", predicate));
+                        }
+                    }
+                }
+                EntryValue::Real(f) => {
+                    let col = $dynamic_table.column::<Double, _>(column);
+                    match filter.predicate {
+                        FilterPredicate::Equals => query.filter(col.eq(*f)),
+                        FilterPredicate::NotEquals => query.filter(col.ne(*f)),
+                        FilterPredicate::GreaterThan => query.filter(col.gt(*f)),
+                        FilterPredicate::GreaterOrEqual => query.filter(col.ge(*f)),
+                        FilterPredicate::LowerThan => query.filter(col.lt(*f)),
+                        FilterPredicate::LowerOrEqual => query.filter(col.le(*f)),
+                        predicate => {
+                            return Err(format!("Unsupported float predicate: {:?}", predicate));
+                        }
+                    }
+                }
+                EntryValue::Bool(b) => {
+                    let col = $dynamic_table.column::<Bool, _>(column);
+                    match filter.predicate {
+                        FilterPredicate::Equals => query.filter(col.eq(*b)),
+                        FilterPredicate::NotEquals => query.filter(col.ne(*b)),
+                        predicate => {
+                            return Err(format!("Unsupported boolean predicate: {:?}", predicate));
+                        }
+                    }
+                }
+                EntryValue::Null => {
+                    return Err(format!("Unsupported null value for filter on {}", column));
+                }
+            };
+        }
+        query
+    }};
+}
+
 pub mod schema;
+pub mod types;
+
+#[cfg(feature = "postgres")]
+pub mod postgres;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+use types::{CollectionStore, CollectionTable, ColumnType, EntryFilter, EntryRow, EntryValue};
+
+pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
+
+pub const IN_MEMORY_DATABASE_URL: &str = ":memory:";
+
+#[derive(MultiConnection)]
+pub enum DatabaseConnection {
+    // Postgres must be attempted first: SQLite accepts any connection url as a database path.
+    #[cfg(feature = "postgres")]
+    Postgresql(diesel::pg::PgConnection),
+    #[cfg(feature = "sqlite")]
+    Sqlite(diesel::sqlite::SqliteConnection),
+}
+
+impl CollectionStore for DatabaseConnection {
+    fn run_migrations(&mut self) -> Result<(), String> {
+        match self {
+            #[cfg(feature = "postgres")]
+            DatabaseConnection::Postgresql(conn) => conn.run_migrations(),
+            #[cfg(feature = "sqlite")]
+            DatabaseConnection::Sqlite(conn) => conn.run_migrations(),
+        }
+    }
+
+    fn register_collection(&mut self, collection: &CollectionTable) -> Result<(), String> {
+        match self {
+            #[cfg(feature = "postgres")]
+            DatabaseConnection::Postgresql(conn) => conn.register_collection(collection),
+            #[cfg(feature = "sqlite")]
+            DatabaseConnection::Sqlite(conn) => conn.register_collection(collection),
+        }
+    }
+
+    fn insert_entries(
+        &mut self,
+        collection: &CollectionTable,
+        entries: &[EntryRow],
+    ) -> Result<(), String> {
+        match self {
+            #[cfg(feature = "postgres")]
+            DatabaseConnection::Postgresql(conn) => conn.insert_entries(collection, entries),
+            #[cfg(feature = "sqlite")]
+            DatabaseConnection::Sqlite(conn) => conn.insert_entries(collection, entries),
+        }
+    }
+
+    fn fetch_entries(
+        &mut self,
+        table_name: &str,
+        columns: &[String],
+        filters: &[EntryFilter],
+    ) -> Result<Vec<EntryRow>, String> {
+        match self {
+            #[cfg(feature = "postgres")]
+            DatabaseConnection::Postgresql(conn) => {
+                conn.fetch_entries(table_name, columns, filters)
+            }
+            #[cfg(feature = "sqlite")]
+            DatabaseConnection::Sqlite(conn) => conn.fetch_entries(table_name, columns, filters),
+        }
+    }
+}
+
+pub struct SqlStore {
+    pub connection_url: String,
+    pub pool: Pool<ConnectionManager<DatabaseConnection>>,
+}
+
+impl SqlStore {
+    pub fn new_in_memory() -> Result<SqlStore, String> {
+        Self::new(IN_MEMORY_DATABASE_URL)
+    }
+
+    /// Connects to a SQLite database path or a `postgres://` url, and applies pending migrations.
+    pub fn new(connection_url: &str) -> Result<SqlStore, String> {
+        let manager = ConnectionManager::<DatabaseConnection>::new(connection_url);
+        // Each connection to `:memory:` opens a distinct database, so the pool is limited to one.
+        let max_size = if connection_url == IN_MEMORY_DATABASE_URL {
+            1
+        } else {
+            10
+        };
+        let pool = Pool::builder()
+            .max_size(max_size)
+            .build(manager)
+            .map_err(|e| format!("Failed to connect to database {connection_url}: {e}"))?;
+        let store = SqlStore {
+            connection_url: connection_url.to_string(),
+            pool,
+        };
+        store.get_conn()?.run_migrations()?;
+        Ok(store)
+    }
+
+    pub fn get_conn(
+        &self,
+    ) -> Result<PooledConnection<ConnectionManager<DatabaseConnection>>, String> {
+        self.pool
+            .get()
+            .map_err(|e| format!("Failed to get database connection: {e}"))
+    }
+}
+
+/// Quotes an identifier, so that table and column names keep their case and can't inject SQL.
+pub(crate) fn quote_identifier(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+/// Builds the `CREATE TABLE` statement of the entries table of a collection.
+pub(crate) fn create_entries_table_sql(
+    collection: &CollectionTable,
+    column_sql_type: fn(ColumnType) -> &'static str,
+) -> String {
+    let mut columns = vec![format!("{} TEXT PRIMARY KEY", quote_identifier("id"))];
+    for column in collection.columns.iter() {
+        columns.push(format!(
+            "{} {}",
+            quote_identifier(&column.name),
+            column_sql_type(column.column_type)
+        ));
+    }
+    format!(
+        "CREATE TABLE IF NOT EXISTS {} (\n    {}\n)",
+        quote_identifier(&collection.table_name),
+        columns.join(",\n    ")
+    )
+}
+
+/// The name of a column of an existing entries table.
+#[derive(QueryableByName)]
+pub(crate) struct ColumnName {
+    #[diesel(sql_type = Text)]
+    pub name: String,
+}
+
+/// Builds the `ALTER TABLE` statements adding the columns of a collection missing from its existing
+/// entries table, e.g. after fields were added to its schema.
+///
+/// Columns whose type changed are left as is.
+pub(crate) fn add_entries_columns_sql(
+    collection: &CollectionTable,
+    existing_columns: &[ColumnName],
+    column_sql_type: fn(ColumnType) -> &'static str,
+) -> Vec<String> {
+    collection
+        .columns
+        .iter()
+        .filter(|column| {
+            !existing_columns
+                .iter()
+                .any(|existing| existing.name == column.name)
+        })
+        .map(|column| {
+            format!(
+                "ALTER TABLE {} ADD COLUMN {} {}",
+                quote_identifier(&collection.table_name),
+                quote_identifier(&column.name),
+                column_sql_type(column.column_type)
+            )
+        })
+        .collect()
+}
+
+/// Builds the upsert statement of an entry, along with the values to bind to its placeholders and
+/// the types of their columns.
+///
+/// Columns missing from the entry are left out, so they default to `NULL` for a new entry and keep
+/// their value for an entry already stored.
+pub(crate) fn insert_entry_statement(
+    collection: &CollectionTable,
+    entry: &EntryRow,
+    placeholder: fn(usize) -> String,
+) -> Result<(String, Vec<(ColumnType, EntryValue)>), String> {
+    let mut columns = vec![quote_identifier("id")];
+    let mut values = vec![(ColumnType::Text, EntryValue::Text(entry.id.clone()))];
+    for column in collection.columns.iter() {
+        let Some(value) = entry.values.get(&column.name) else {
+            continue;
+        };
+        let value = value
+            .clone()
+            .coerce(column.column_type)
+            .map_err(|e| format!("Invalid value for column {}: {e}", column.name))?;
+        columns.push(quote_identifier(&column.name));
+        values.push((column.column_type, value));
+    }
+    let placeholders = (1..=values.len()).map(placeholder).collect::<Vec<_>>();
+    let updates = columns
+        .iter()
+        .skip(1)
+        .map(|column| format!("{column} = excluded.{column}"))
+        .collect::<Vec<_>>();
+    let on_conflict = if updates.is_empty() {
+        "DO NOTHING".to_string()
+    } else {
+        format!("DO UPDATE SET {}", updates.join(", "))
+    };
+    let sql = format!(
+        "INSERT INTO {} ({}) VALUES ({}) ON CONFLICT ({}) {}",
+        quote_identifier(&collection.table_name),
+        columns.join(", "),
+        placeholders.join(", "),
+        columns[0],
+        on_conflict
+    );
+    Ok((sql, values))
+}
+
+/// Returns the columns to select when fetching entries, starting with `id`.
+pub(crate) fn fetched_columns(columns: &[String]) -> Vec<String> {
+    let mut fetched = vec!["id".to_string()];
+    for column in columns.iter() {
+        if !fetched.contains(column) {
+            fetched.push(column.clone());
+        }
+    }
+    fetched
+}
+
+pub(crate) fn entry_row_from_values(columns: &[String], values: Vec<EntryValue>) -> EntryRow {
+    let mut row = EntryRow {
+        id: String::new(),
+        values: HashMap::new(),
+    };
+    for (column, value) in columns.iter().zip(values) {
+        if column == "id" {
+            row.id = value.to_string();
+        } else {
+            row.values.insert(column.clone(), value);
+        }
+    }
+    row
+}
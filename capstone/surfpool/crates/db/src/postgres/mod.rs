@@ -0,0 +1,282 @@
+use std::num::NonZeroU32;
+
+use diesel::{
+    deserialize::{self, FromSql},
+    pg::{Pg, PgConnection, PgValue},
+    prelude::*,
+    query_builder::{BoxedSqlQuery, SqlQuery},
+    sql_query,
+    sql_types::{BigInt, Bool, Double, Float, Integer, Nullable, Text, Untyped},
+    upsert::excluded,
+};
+use diesel_dynamic_schema::{
+    DynamicSelectClause,
+    dynamic_value::{Any, DynamicRow, NamedField},
+    table,
+};
+use diesel_migrations::MigrationHarness;
+
+use crate::{
+    ColumnName, MIGRATIONS, add_entries_columns_sql, create_entries_table_sql,
+    entry_row_from_values, fetched_columns, insert_entry_statement,
+    schema::collections,
+    types::{
+        CollectionStore, CollectionTable, ColumnType, EntryFilter, EntryRow, EntryValue,
+        FilterPredicate,
+    },
+};
+
+const BOOL_OID: NonZeroU32 = NonZeroU32::new(16).unwrap();
+const BIGINT_OID: NonZeroU32 = NonZeroU32::new(20).unwrap();
+const INTEGER_OID: NonZeroU32 = NonZeroU32::new(23).unwrap();
+const TEXT_OID: NonZeroU32 = NonZeroU32::new(25).unwrap();
+const FLOAT_OID: NonZeroU32 = NonZeroU32::new(700).unwrap();
+const DOUBLE_OID: NonZeroU32 = NonZeroU32::new(701).unwrap();
+const VARCHAR_OID: NonZeroU32 = NonZeroU32::new(1043).unwrap();
+
+struct PgDynamicValue(EntryValue);
+
+impl FromSql<Any, Pg> for PgDynamicValue {
+    fn from_sql(value: PgValue) -> deserialize::Result<Self> {
+        let value = match value.get_oid() {
+            VARCHAR_OID | TEXT_OID => {
+                EntryValue::Text(<String as FromSql<Text, Pg>>::from_sql(value)?)
+            }
+            INTEGER_OID => {
+                EntryValue::Integer(<i32 as FromSql<Integer, Pg>>::from_sql(value)?.into())
+            }
+            BIGINT_OID => EntryValue::Integer(<i64 as FromSql<BigInt, Pg>>::from_sql(value)?),
+            FLOAT_OID => EntryValue::Real(<f32 as FromSql<Float, Pg>>::from_sql(value)?.into()),
+            DOUBLE_OID => EntryValue::Real(<f64 as FromSql<Double, Pg>>::from_sql(value)?),
+            BOOL_OID => EntryValue::Bool(<bool as FromSql<Bool, Pg>>::from_sql(value)?),
+            e => return Err(format!("Unknown type: {e}").into()),
+        };
+        Ok(PgDynamicValue(value))
+    }
+
+    fn from_nullable_sql(value: Option<PgValue>) -> deserialize::Result<Self> {
+        match value {
+            Some(value) => Self::from_sql(value),
+            None => Ok(PgDynamicValue(EntryValue::Null)),
+        }
+    }
+}
+
+fn column_sql_type(column_type: ColumnType) -> &'static str {
+    match column_type {
+        ColumnType::Text => "TEXT",
+        ColumnType::Integer => "BIGINT",
+        ColumnType::Real => "DOUBLE PRECISION",
+        ColumnType::Bool => "BOOLEAN",
+    }
+}
+
+fn bind_entry_value(
+    query: BoxedSqlQuery<'static, Pg, SqlQuery>,
+    (column_type, value): (ColumnType, EntryValue),
+) -> BoxedSqlQuery<'static, Pg, SqlQuery> {
+    match value {
+        EntryValue::Null => match column_type {
+            ColumnType::Text => query.bind::<Nullable<Text>, _>(None::<String>),
+            ColumnType::Integer => query.bind::<Nullable<BigInt>, _>(None::<i64>),
+            ColumnType::Real => query.bind::<Nullable<Double>, _>(None::<f64>),
+            ColumnType::Bool => query.bind::<Nullable<Bool>, _>(None::<bool>),
+        },
+        EntryValue::Text(s) => query.bind::<Text, _>(s),
+        EntryValue::Integer(i) => query.bind::<BigInt, _>(i),
+        EntryValue::Real(f) => query.bind::<Double, _>(f),
+        EntryValue::Bool(b) => query.bind::<Bool, _>(b),
+    }
+}
+
+impl CollectionStore for PgConnection {
+    fn run_migrations(&mut self) -> Result<(), String> {
+        self.run_pending_migrations(MIGRATIONS)
+            .map(|_| ())
+            .map_err(|e| format!("Failed to run postgres migrations: {e}"))
+    }
+
+    fn register_collection(&mut self, collection: &CollectionTable) -> Result<(), String> {
+        sql_query(create_entries_table_sql(collection, column_sql_type))
+            .execute(&mut *self)
+            .map_err(|e| format!("Failed to create entries table: {e}"))?;
+        let existing_columns = sql_query("SELECT column_name AS name FROM information_schema.columns WHERE table_schema = current_schema() AND table_name = $1")
+            .bind::<Text, _>(collection.table_name.as_str())
+            .load::<ColumnName>(&mut *self)
+            .map_err(|e| format!("Failed to read entries table columns: {e}"))?;
+        for sql in add_entries_columns_sql(collection, &existing_columns, column_sql_type) {
+            sql_query(sql)
+                .execute(&mut *self)
+                .map_err(|e| format!("Failed to migrate entries table: {e}"))?;
+        }
+
+        let now = chrono::Utc::now().naive_utc();
+        diesel::insert_into(collections::table)
+            .values((
+                collections::id.eq(&collection.id),
+                collections::created_at.eq(now),
+                collections::updated_at.eq(now),
+                collections::table_name.eq(&collection.table_name),
+                collections::schema.eq(&collection.schema),
+            ))
+            .on_conflict(collections::id)
+            .do_update()
+            .set((
+                collections::updated_at.eq(excluded(collections::updated_at)),
+                collections::schema.eq(excluded(collections::schema)),
+            ))
+            .execute(&mut *self)
+            .map_err(|e| format!("Failed to register collection: {e}"))?;
+
+        Ok(())
+    }
+
+    fn insert_entries(
+        &mut self,
+        collection: &CollectionTable,
+        entries: &[EntryRow],
+    ) -> Result<(), String> {
+        let statements = entries
+            .iter()
+            .map(|entry| insert_entry_statement(collection, entry, |i| format!("${i}")))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.transaction::<_, diesel::result::Error, _>(|conn| {
+            for (sql, values) in statements {
+                let query = values
+                    .into_iter()
+                    .fold(sql_query(sql).into_boxed::<Pg>(), bind_entry_value);
+                query.execute(conn)?;
+            }
+            Ok(())
+        })
+        .map_err(|e| format!("Failed to insert entry: {e}"))
+    }
+
+    fn fetch_entries(
+        &mut self,
+        table_name: &str,
+        columns: &[String],
+        filters: &[EntryFilter],
+    ) -> Result<Vec<EntryRow>, String> {
+        let columns = fetched_columns(columns);
+        let dynamic_table = table(table_name);
+        let mut select = DynamicSelectClause::new();
+        for column in columns.iter() {
+            select.add_field(dynamic_table.column::<Untyped, _>(column.as_str()));
+        }
+
+        let query = apply_entry_filters!(
+            dynamic_table.clone().select(select).into_boxed(),
+            dynamic_table,
+            filters
+        );
+
+        let rows = query
+            .load::<DynamicRow<NamedField<PgDynamicValue>>>(&mut *self)
+            .map_err(|e| format!("Failed to fetch entries from {table_name}: {e}"))?;
+
+        let entries = rows
+            .into_iter()
+            .map(|row| {
+                let values = (0..columns.len()).map(|i| row[i].value.0.clone()).collect();
+                entry_row_from_values(&columns, values)
+            })
+            .collect();
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::types::CollectionColumn;
+
+    /// Connects to the database at `SURFPOOL_TEST_POSTGRES_URL`.
+    fn test_connection() -> PgConnection {
+        let url = std::env::var("SURFPOOL_TEST_POSTGRES_URL")
+            .expect("SURFPOOL_TEST_POSTGRES_URL must be set to run the postgres tests");
+        let mut conn = PgConnection::establish(&url).unwrap();
+        conn.run_migrations().unwrap();
+        conn
+    }
+
+    fn test_entry(id: &str, amount: i64, decimals: Option<i64>) -> EntryRow {
+        let mut values = HashMap::new();
+        values.insert("amount".to_string(), EntryValue::Integer(amount));
+        if let Some(decimals) = decimals {
+            values.insert("decimals".to_string(), EntryValue::Integer(decimals));
+        }
+        EntryRow {
+            id: id.to_string(),
+            values,
+        }
+    }
+
+    #[test]
+    #[ignore = "requires a postgres database at SURFPOOL_TEST_POSTGRES_URL"]
+    fn test_upsert_entries_and_migrate_table() {
+        let mut conn = test_connection();
+        let mut collection = CollectionTable {
+            id: "8b0d6a54-3f7e-4c51-9a0e-2d4f1c6b7e90".to_string(),
+            table_name: "entries_pg_test".to_string(),
+            columns: vec![CollectionColumn::new("amount", ColumnType::Integer)],
+            schema: "{}".to_string(),
+        };
+        sql_query(format!(
+            "DROP TABLE IF EXISTS \"{}\"",
+            collection.table_name
+        ))
+        .execute(&mut conn)
+        .unwrap();
+        conn.register_collection(&collection).unwrap();
+        conn.insert_entries(&collection, &[test_entry("a", 10, None)])
+            .unwrap();
+
+        // an entry stored again is updated, rather than failing on its primary key
+        conn.insert_entries(&collection, &[test_entry("a", 15, None)])
+            .unwrap();
+
+        // columns added to the collection are added to its existing entries table
+        collection.columns.extend([
+            CollectionColumn::new("decimals", ColumnType::Integer),
+            CollectionColumn::new("price", ColumnType::Real),
+            CollectionColumn::new("settled", ColumnType::Bool),
+        ]);
+        conn.register_collection(&collection).unwrap();
+        conn.insert_entries(&collection, &[test_entry("b", 20, Some(6))])
+            .unwrap();
+
+        // null values are bound with the type of their column
+        let mut null_entry = test_entry("c", 30, None);
+        for column in ["decimals", "price", "settled"] {
+            null_entry
+                .values
+                .insert(column.to_string(), EntryValue::Null);
+        }
+        conn.insert_entries(&collection, &[null_entry]).unwrap();
+
+        let columns = vec![
+            "amount".to_string(),
+            "decimals".to_string(),
+            "price".to_string(),
+            "settled".to_string(),
+        ];
+        let entries = conn
+            .fetch_entries(&collection.table_name, &columns, &[])
+            .unwrap();
+        assert_eq!(entries.len(), 3);
+        let a = entries.iter().find(|entry| entry.id == "a").unwrap();
+        assert_eq!(a.values.get("amount"), Some(&EntryValue::Integer(15)));
+        assert_eq!(a.values.get("decimals"), Some(&EntryValue::Null));
+        let b = entries.iter().find(|entry| entry.id == "b").unwrap();
+        assert_eq!(b.values.get("decimals"), Some(&EntryValue::Integer(6)));
+        let c = entries.iter().find(|entry| entry.id == "c").unwrap();
+        assert_eq!(c.values.get("amount"), Some(&EntryValue::Integer(30)));
+        for column in ["decimals", "price", "settled"] {
+            assert_eq!(c.values.get(column), Some(&EntryValue::Null));
+        }
+    }
+}
@@ -1,8 +1,8 @@
 diesel::table! {
     collections (id) {
         id -> Text,
-        created_at -> Date,
-        updated_at -> Date,
+        created_at -> Timestamp,
+        updated_at -> Timestamp,
         table_name -> Text,
         schema -> Text,
     }
@@ -0,0 +1,309 @@
+use diesel::{
+    deserialize::{self, FromSql},
+    prelude::*,
+    query_builder::{BoxedSqlQuery, SqlQuery},
+    sql_query,
+    sql_types::{BigInt, Bool, Double, Nullable, Text, Untyped},
+    sqlite::{Sqlite, SqliteConnection, SqliteType, SqliteValue},
+    upsert::excluded,
+};
+use diesel_dynamic_schema::{
+    DynamicSelectClause,
+    dynamic_value::{Any, DynamicRow, NamedField},
+    table,
+};
+use diesel_migrations::MigrationHarness;
+
+use crate::{
+    ColumnName, MIGRATIONS, add_entries_columns_sql, create_entries_table_sql,
+    entry_row_from_values, fetched_columns, insert_entry_statement,
+    schema::collections,
+    types::{
+        CollectionStore, CollectionTable, ColumnType, EntryFilter, EntryRow, EntryValue,
+        FilterPredicate,
+    },
+};
+
+struct SqliteDynamicValue(EntryValue);
+
+impl FromSql<Any, Sqlite> for SqliteDynamicValue {
+    fn from_sql(value: SqliteValue) -> deserialize::Result<Self> {
+        let value = match value.value_type() {
+            Some(SqliteType::Text) => {
+                EntryValue::Text(<String as FromSql<Text, Sqlite>>::from_sql(value)?)
+            }
+            Some(SqliteType::Long) => {
+                EntryValue::Integer(<i64 as FromSql<BigInt, Sqlite>>::from_sql(value)?)
+            }
+            Some(SqliteType::Double) => {
+                EntryValue::Real(<f64 as FromSql<Double, Sqlite>>::from_sql(value)?)
+            }
+            None => EntryValue::Null,
+            _ => return Err("Unknown data type".into()),
+        };
+        Ok(SqliteDynamicValue(value))
+    }
+
+    fn from_nullable_sql(value: Option<SqliteValue>) -> deserialize::Result<Self> {
+        match value {
+            Some(value) => Self::from_sql(value),
+            None => Ok(SqliteDynamicValue(EntryValue::Null)),
+        }
+    }
+}
+
+fn column_sql_type(column_type: ColumnType) -> &'static str {
+    match column_type {
+        ColumnType::Text => "TEXT",
+        ColumnType::Integer => "INTEGER",
+        ColumnType::Real => "REAL",
+        ColumnType::Bool => "BOOLEAN",
+    }
+}
+
+fn bind_entry_value(
+    query: BoxedSqlQuery<'static, Sqlite, SqlQuery>,
+    (column_type, value): (ColumnType, EntryValue),
+) -> BoxedSqlQuery<'static, Sqlite, SqlQuery> {
+    match value {
+        EntryValue::Null => match column_type {
+            ColumnType::Text => query.bind::<Nullable<Text>, _>(None::<String>),
+            ColumnType::Integer => query.bind::<Nullable<BigInt>, _>(None::<i64>),
+            ColumnType::Real => query.bind::<Nullable<Double>, _>(None::<f64>),
+            ColumnType::Bool => query.bind::<Nullable<Bool>, _>(None::<bool>),
+        },
+        EntryValue::Text(s) => query.bind::<Text, _>(s),
+        EntryValue::Integer(i) => query.bind::<BigInt, _>(i),
+        EntryValue::Real(f) => query.bind::<Double, _>(f),
+        EntryValue::Bool(b) => query.bind::<Bool, _>(b),
+    }
+}
+
+impl CollectionStore for SqliteConnection {
+    fn run_migrations(&mut self) -> Result<(), String> {
+        self.run_pending_migrations(MIGRATIONS)
+            .map(|_| ())
+            .map_err(|e| format!("Failed to run sqlite migrations: {e}"))
+    }
+
+    fn register_collection(&mut self, collection: &CollectionTable) -> Result<(), String> {
+        sql_query(create_entries_table_sql(collection, column_sql_type))
+            .execute(&mut *self)
+            .map_err(|e| format!("Failed to create entries table: {e}"))?;
+        let existing_columns = sql_query("SELECT name FROM pragma_table_info(?)")
+            .bind::<Text, _>(collection.table_name.as_str())
+            .load::<ColumnName>(&mut *self)
+            .map_err(|e| format!("Failed to read entries table columns: {e}"))?;
+        for sql in add_entries_columns_sql(collection, &existing_columns, column_sql_type) {
+            sql_query(sql)
+                .execute(&mut *self)
+                .map_err(|e| format!("Failed to migrate entries table: {e}"))?;
+        }
+
+        let now = chrono::Utc::now().naive_utc();
+        diesel::insert_into(collections::table)
+            .values((
+                collections::id.eq(&collection.id),
+                collections::created_at.eq(now),
+                collections::updated_at.eq(now),
+                collections::table_name.eq(&collection.table_name),
+                collections::schema.eq(&collection.schema),
+            ))
+            .on_conflict(collections::id)
+            .do_update()
+            .set((
+                collections::updated_at.eq(excluded(collections::updated_at)),
+                collections::schema.eq(excluded(collections::schema)),
+            ))
+            .execute(&mut *self)
+            .map_err(|e| format!("Failed to register collection: {e}"))?;
+
+        Ok(())
+    }
+
+    fn insert_entries(
+        &mut self,
+        collection: &CollectionTable,
+        entries: &[EntryRow],
+    ) -> Result<(), String> {
+        let statements = entries
+            .iter()
+            .map(|entry| insert_entry_statement(collection, entry, |_| "?".to_string()))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.transaction::<_, diesel::result::Error, _>(|conn| {
+            for (sql, values) in statements {
+                let query = values
+                    .into_iter()
+                    .fold(sql_query(sql).into_boxed::<Sqlite>(), bind_entry_value);
+                query.execute(conn)?;
+            }
+            Ok(())
+        })
+        .map_err(|e| format!("Failed to insert entry: {e}"))
+    }
+
+    fn fetch_entries(
+        &mut self,
+        table_name: &str,
+        columns: &[String],
+        filters: &[EntryFilter],
+    ) -> Result<Vec<EntryRow>, String> {
+        let columns = fetched_columns(columns);
+        let dynamic_table = table(table_name);
+        let mut select = DynamicSelectClause::new();
+        for column in columns.iter() {
+            select.add_field(dynamic_table.column::<Untyped, _>(column.as_str()));
+        }
+
+        let query = apply_entry_filters!(
+            dynamic_table.clone().select(select).into_boxed(),
+            dynamic_table,
+            filters
+        );
+
+        let rows = query
+            .load::<DynamicRow<NamedField<SqliteDynamicValue>>>(&mut *self)
+            .map_err(|e| format!("Failed to fetch entries from {table_name}: {e}"))?;
+
+        let entries = rows
+            .into_iter()
+            .map(|row| {
+                let values = (0..columns.len()).map(|i| row[i].value.0.clone()).collect();
+                entry_row_from_values(&columns, values)
+            })
+            .collect();
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::types::CollectionColumn;
+
+    fn test_collection() -> CollectionTable {
+        CollectionTable {
+            id: "3f1c4b0e-0a4e-4c3e-9a52-6c1f0e8d2b11".to_string(),
+            table_name: "entries_test".to_string(),
+            columns: vec![
+                CollectionColumn::new("owner", ColumnType::Text),
+                CollectionColumn::new("amount", ColumnType::Integer),
+                CollectionColumn::new("isFrozen", ColumnType::Bool),
+            ],
+            schema: "{}".to_string(),
+        }
+    }
+
+    fn test_entry(id: &str, owner: &str, amount: &str) -> EntryRow {
+        let mut values = HashMap::new();
+        values.insert("owner".to_string(), EntryValue::Text(owner.to_string()));
+        values.insert("amount".to_string(), EntryValue::Text(amount.to_string()));
+        EntryRow {
+            id: id.to_string(),
+            values,
+        }
+    }
+
+    #[test]
+    fn test_register_insert_fetch_with_filters() {
+        let mut conn = SqliteConnection::establish(":memory:").unwrap();
+        conn.run_migrations().unwrap();
+        let collection = test_collection();
+        conn.register_collection(&collection).unwrap();
+        // registering twice is a no-op for the entries table
+        conn.register_collection(&collection).unwrap();
+
+        conn.insert_entries(
+            &collection,
+            &[
+                test_entry("a", "alice", "10"),
+                test_entry("b", "bob", "20"),
+                test_entry("c", "o'brien", "30"),
+            ],
+        )
+        .unwrap();
+
+        let columns = vec!["owner".to_string(), "amount".to_string()];
+        let all = conn
+            .fetch_entries(&collection.table_name, &columns, &[])
+            .unwrap();
+        assert_eq!(all.len(), 3);
+
+        let filtered = conn
+            .fetch_entries(
+                &collection.table_name,
+                &columns,
+                &[
+                    EntryFilter::new(
+                        "amount",
+                        FilterPredicate::GreaterThan,
+                        EntryValue::Integer(10),
+                    ),
+                    EntryFilter::new(
+                        "owner",
+                        FilterPredicate::Contains,
+                        EntryValue::Text("'".to_string()),
+                    ),
+                ],
+            )
+            .unwrap();
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].id, "c");
+        assert_eq!(
+            filtered[0].values.get("amount"),
+            Some(&EntryValue::Integer(30))
+        );
+
+        let error = conn
+            .fetch_entries(
+                &collection.table_name,
+                &columns,
+                &[EntryFilter::new(
+                    "owner",
+                    FilterPredicate::GreaterThan,
+                    EntryValue::Text("a".to_string()),
+                )],
+            )
+            .unwrap_err();
+        assert!(error.contains("Unsupported string predicate"));
+    }
+
+    #[test]
+    fn test_upsert_entries_and_migrate_table() {
+        let mut conn = SqliteConnection::establish(":memory:").unwrap();
+        conn.run_migrations().unwrap();
+        let mut collection = test_collection();
+        conn.register_collection(&collection).unwrap();
+        conn.insert_entries(&collection, &[test_entry("a", "alice", "10")])
+            .unwrap();
+
+        // an entry stored again is updated, rather than failing on its primary key
+        conn.insert_entries(&collection, &[test_entry("a", "alice", "15")])
+            .unwrap();
+
+        // columns added to the collection are added to its existing entries table
+        collection
+            .columns
+            .push(CollectionColumn::new("decimals", ColumnType::Integer));
+        conn.register_collection(&collection).unwrap();
+        let mut entry = test_entry("b", "bob", "20");
+        entry
+            .values
+            .insert("decimals".to_string(), EntryValue::Integer(6));
+        conn.insert_entries(&collection, &[entry]).unwrap();
+
+        let columns = vec!["amount".to_string(), "decimals".to_string()];
+        let entries = conn
+            .fetch_entries(&collection.table_name, &columns, &[])
+            .unwrap();
+        assert_eq!(entries.len(), 2);
+        let a = entries.iter().find(|entry| entry.id == "a").unwrap();
+        assert_eq!(a.values.get("amount"), Some(&EntryValue::Integer(15)));
+        assert_eq!(a.values.get("decimals"), Some(&EntryValue::Null));
+        let b = entries.iter().find(|entry| entry.id == "b").unwrap();
+        assert_eq!(b.values.get("decimals"), Some(&EntryValue::Integer(6)));
+    }
+}
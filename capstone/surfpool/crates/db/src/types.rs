@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+
+/// The storage type of a collection column.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColumnType {
+    Text,
+    Integer,
+    Real,
+    Bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CollectionColumn {
+    pub name: String,
+    pub column_type: ColumnType,
+}
+
+impl CollectionColumn {
+    pub fn new(name: &str, column_type: ColumnType) -> Self {
+        Self {
+            name: name.to_string(),
+            column_type,
+        }
+    }
+}
+
+/// Describes the entries table backing a collection.
+#[derive(Clone, Debug)]
+pub struct CollectionTable {
+    /// The UUID of the collection
+    pub id: String,
+    /// The name of the table holding the entries of the collection
+    pub table_name: String,
+    /// The columns of the entries table, in addition to the `id` primary key
+    pub columns: Vec<CollectionColumn>,
+    /// The serialized schema of the collection, stored in the `collections` table
+    pub schema: String,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum EntryValue {
+    Null,
+    Text(String),
+    Integer(i64),
+    Real(f64),
+    Bool(bool),
+}
+
+impl EntryValue {
+    /// Converts the value to the storage type of a column, parsing text values if needed.
+    pub fn coerce(self, column_type: ColumnType) -> Result<EntryValue, String> {
+        let value = match (self, column_type) {
+            (EntryValue::Null, _) => EntryValue::Null,
+            (EntryValue::Text(s), ColumnType::Text) => EntryValue::Text(s),
+            (EntryValue::Integer(i), ColumnType::Integer) => EntryValue::Integer(i),
+            (EntryValue::Real(f), ColumnType::Real) => EntryValue::Real(f),
+            (EntryValue::Bool(b), ColumnType::Bool) => EntryValue::Bool(b),
+            (EntryValue::Integer(i), ColumnType::Real) => EntryValue::Real(i as f64),
+            (EntryValue::Integer(i), ColumnType::Bool) => EntryValue::Bool(i != 0),
+            (EntryValue::Text(s), ColumnType::Integer) => s
+                .parse::<i64>()
+                .map(EntryValue::Integer)
+                .map_err(|e| format!("unable to store '{s}' as an integer: {e}"))?,
+            (EntryValue::Text(s), ColumnType::Real) => s
+                .parse::<f64>()
+                .map(EntryValue::Real)
+                .map_err(|e| format!("unable to store '{s}' as a float: {e}"))?,
+            (EntryValue::Text(s), ColumnType::Bool) => s
+                .parse::<bool>()
+                .map(EntryValue::Bool)
+                .map_err(|e| format!("unable to store '{s}' as a boolean: {e}"))?,
+            (value, ColumnType::Text) => EntryValue::Text(value.to_string()),
+            (value, column_type) => {
+                return Err(format!(
+                    "unable to store {value:?} in a {column_type:?} column"
+                ));
+            }
+        };
+        Ok(value)
+    }
+}
+
+impl std::fmt::Display for EntryValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EntryValue::Null => write!(f, "null"),
+            EntryValue::Text(s) => write!(f, "{s}"),
+            EntryValue::Integer(i) => write!(f, "{i}"),
+            EntryValue::Real(r) => write!(f, "{r}"),
+            EntryValue::Bool(b) => write!(f, "{b}"),
+        }
+    }
+}
+
+/// A row of an entries table.
+#[derive(Clone, Debug, PartialEq)]
+pub struct EntryRow {
+    pub id: String,
+    pub values: HashMap<String, EntryValue>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FilterPredicate {
+    Equals,
+    NotEquals,
+    GreaterThan,
+    GreaterOrEqual,
+    LowerThan,
+    LowerOrEqual,
+    Contains,
+    NotContains,
+    StartsWith,
+    EndsWith,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EntryFilter {
+    pub column: String,
+    pub predicate: FilterPredicate,
+    pub value: EntryValue,
+}
+
+impl EntryFilter {
+    pub fn new(column: &str, predicate: FilterPredicate, value: EntryValue) -> Self {
+        Self {
+            column: column.to_string(),
+            predicate,
+            value,
+        }
+    }
+}
+
+/// Storage operations of a subgraph database backend.
+pub trait CollectionStore {
+    /// Applies the pending migrations of the `collections` table.
+    fn run_migrations(&mut self) -> Result<(), String>;
+    /// Creates the entries table of a collection and records it in the `collections` table.
+    fn register_collection(&mut self, collection: &CollectionTable) -> Result<(), String>;
+    /// Inserts entries in the entries table of a collection.
+    fn insert_entries(
+        &mut self,
+        collection: &CollectionTable,
+        entries: &[EntryRow],
+    ) -> Result<(), String>;
+    /// Fetches the `columns` of the entries of a collection matching all the `filters`.
+    ///
+    /// The `id` of each entry is always fetched.
+    fn fetch_entries(
+        &mut self,
+        table_name: &str,
+        columns: &[String],
+        filters: &[EntryFilter],
+    ) -> Result<Vec<EntryRow>, String>;
+}
@@ -1,7 +1,7 @@
-use juniper::{DefaultScalarValue, EmptyMutation, EmptySubscription, RootNode};
+use juniper::{DefaultScalarValue, EmptyMutation, RootNode};
 use query::{CollectionMetadataMap, DynamicQuery};
 
-use crate::query::DataloaderContext;
+use crate::{query::DataloaderContext, subscription::DynamicSubscription};
 
 pub mod mutation;
 pub mod query;
@@ -14,7 +14,7 @@ pub type DynamicSchema = RootNode<
     'static,
     DynamicQuery,
     EmptyMutation<DataloaderContext>,
-    EmptySubscription<DataloaderContext>,
+    DynamicSubscription,
     DefaultScalarValue,
 >;
 
@@ -22,7 +22,7 @@ pub fn new_dynamic_schema(subgraph_spec: CollectionMetadataMap) -> DynamicSchema
     let schema = DynamicSchema::new_with_info(
         DynamicQuery,
         EmptyMutation::<DataloaderContext>::new(),
-        EmptySubscription::<DataloaderContext>::new(),
+        DynamicSubscription,
         subgraph_spec,
         (),
         (),
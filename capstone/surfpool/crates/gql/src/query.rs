@@ -1,21 +1,22 @@
 use std::{collections::HashMap, pin::Pin};
 
 use convert_case::{Case, Casing};
-use diesel::prelude::*;
 use juniper::{
     Arguments, DefaultScalarValue, Executor, FieldError, GraphQLType, GraphQLValue,
     GraphQLValueAsync, Registry, meta::MetaType,
 };
-use surfpool_db::diesel::{
-    self, Connection, MultiConnection,
-    r2d2::{ConnectionManager, Pool, PooledConnection},
-};
+use surfpool_db::diesel::r2d2::{ConnectionManager, Pool};
+pub use surfpool_db::{DatabaseConnection, SqlStore};
+use tokio::sync::broadcast;
 
 use crate::types::{
-    CollectionEntry, CollectionEntryData, collections::CollectionMetadata,
-    filters::SubgraphFilterSpec,
+    CollectionEntry, CollectionEntryData, CollectionEntryDataUpdate,
+    collections::CollectionMetadata, filters::SubgraphFilterSpec,
 };
 
+/// Number of entry updates kept for subscribers lagging behind the indexer.
+const ENTRIES_BROADCAST_CAPACITY: usize = 1024;
+
 #[derive(Debug)]
 pub struct DynamicQuery;
 
@@ -63,8 +64,21 @@ pub trait Dataloader {
     ) -> Result<(), String>;
 }
 
+#[derive(Clone)]
 pub struct DataloaderContext {
     pub pool: Pool<ConnectionManager<DatabaseConnection>>,
+    /// Broadcasts the entries inserted in the collections to the GraphQL subscriptions
+    pub entries_broadcaster: broadcast::Sender<CollectionEntryDataUpdate>,
+}
+
+impl DataloaderContext {
+    pub fn new(pool: Pool<ConnectionManager<DatabaseConnection>>) -> Self {
+        let (entries_broadcaster, _) = broadcast::channel(ENTRIES_BROADCAST_CAPACITY);
+        Self {
+            pool,
+            entries_broadcaster,
+        }
+    }
 }
 
 impl juniper::Context for DataloaderContext {}
@@ -133,38 +147,6 @@ impl CollectionMetadataMap {
     }
 }
 
-#[derive(MultiConnection)]
-pub enum DatabaseConnection {
-    #[cfg(feature = "sqlite")]
-    Sqlite(SqliteConnection),
-    #[cfg(feature = "postgres")]
-    Postgresql(PgConnection),
-}
-
-pub struct SqlStore {
-    pub connection_url: String,
-    pub pool: Pool<ConnectionManager<DatabaseConnection>>,
-}
-
-impl SqlStore {
-    pub fn new_in_memory() -> SqlStore {
-        Self::new(":memory:")
-    }
-
-    pub fn new(connection_url: &str) -> SqlStore {
-        let manager = ConnectionManager::<DatabaseConnection>::new(connection_url);
-        let pool = Pool::new(manager).expect("unable to create connection pool");
-        SqlStore {
-            connection_url: connection_url.to_string(),
-            pool,
-        }
-    }
-
-    pub fn get_conn(&self) -> PooledConnection<ConnectionManager<DatabaseConnection>> {
-        self.pool.get().unwrap()
-    }
-}
-
 pub fn extract_graphql_features<'a>(
     executor: Option<&'a Executor<DataloaderContext>>,
 ) -> (Vec<(&'a str, &'a str, &'a DefaultScalarValue)>, Vec<String>) {
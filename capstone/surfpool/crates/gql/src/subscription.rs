@@ -1,7 +1,8 @@
 use std::pin::Pin;
 
-use futures::Stream;
+use futures::{Stream, stream};
 use juniper::{FieldError, graphql_subscription};
+use tokio::sync::broadcast::error::RecvError;
 
 use crate::{query::DataloaderContext, types::CollectionEntryDataUpdate};
 
@@ -15,18 +16,115 @@ pub struct DynamicSubscription;
     context = DataloaderContext,
 )]
 impl DynamicSubscription {
-    async fn entries_event(_context: &DataloaderContext) -> GqlEntriesStream {
-        // let entries_tx: tokio::sync::broadcast::Sender<CollectionEntryDataUpdate> =
-        //     context.entries_broadcaster.clone();
-        // let mut entries_tx = entries_tx.subscribe();
-        // let stream = async_stream::stream! {
-        //     loop {
-        //       if let Ok(entry_event) = entries_tx.recv().await {
-        //         yield Ok(entry_event)
-        //       }
-        //     }
-        // };
-        // Box::pin(stream)
-        unimplemented!()
+    /// Streams the entries indexed in the subgraph collections, optionally restricted to a single collection.
+    async fn entries_event(
+        context: &DataloaderContext,
+        collection: Option<String>,
+    ) -> GqlEntriesStream {
+        let entries_rx = context.entries_broadcaster.subscribe();
+        let stream = stream::unfold(entries_rx, move |mut entries_rx| {
+            let collection = collection.clone();
+            async move {
+                loop {
+                    match entries_rx.recv().await {
+                        Ok(entry_event) => {
+                            if let Some(collection) = &collection {
+                                if !entry_event.is_in_collection(collection) {
+                                    continue;
+                                }
+                            }
+                            return Some((Ok(entry_event), entries_rx));
+                        }
+                        // Slow subscribers skip the entries they missed rather than closing the stream
+                        Err(RecvError::Lagged(_)) => continue,
+                        Err(RecvError::Closed) => return None,
+                    }
+                }
+            }
+        });
+        Box::pin(stream)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use futures::StreamExt;
+    use juniper::{Value, Variables, graphql_value, resolve_into_stream};
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::{
+        new_dynamic_schema,
+        query::{CollectionMetadataMap, SqlStore},
+        types::{CollectionEntryData, scalars::bigint::BigInt},
+    };
+
+    #[tokio::test]
+    async fn test_entries_event_streams_structured_values() {
+        let store = SqlStore::new_in_memory().expect("new_in_memory");
+        let context = DataloaderContext::new(store.pool);
+        let schema = new_dynamic_schema(CollectionMetadataMap::new());
+
+        let query = r#"subscription {
+            entriesEvent(collection: "token_transfers") {
+                uuid
+                collection
+                values { name value }
+            }
+        }"#;
+        let (value, errors) =
+            resolve_into_stream(query, None, &schema, &Variables::new(), &context)
+                .await
+                .expect("resolve_into_stream");
+        assert!(errors.is_empty());
+        let Value::Object(fields) = value else {
+            panic!("Unexpected subscription response");
+        };
+        let Some((_, Value::Scalar(mut entries_stream))) =
+            fields.into_iter().find(|(name, _)| name == "entriesEvent")
+        else {
+            panic!("Missing entriesEvent stream");
+        };
+
+        // Entries of other collections are filtered out
+        let other_entry = CollectionEntryData::new(Uuid::new_v4(), HashMap::new());
+        context
+            .entries_broadcaster
+            .send(CollectionEntryDataUpdate::new(
+                "OtherCollection",
+                &other_entry,
+            ))
+            .unwrap();
+
+        let id = Uuid::new_v4();
+        let mut values = HashMap::new();
+        values.insert("memo".to_string(), Value::scalar("hello".to_string()));
+        values.insert("amount".to_string(), BigInt(42).to_output());
+        values.insert("confirmed".to_string(), Value::scalar(true));
+        let entry = CollectionEntryData::new(id, values);
+        context
+            .entries_broadcaster
+            .send(CollectionEntryDataUpdate::new("TokenTransfers", &entry))
+            .unwrap();
+
+        let event = entries_stream
+            .next()
+            .await
+            .expect("stream closed")
+            .expect("entry event");
+        assert_eq!(
+            event,
+            graphql_value!({
+                "uuid": (id.to_string()),
+                "collection": "tokenTransfers",
+                "values": [
+                    {"name": "amount", "value": "42"},
+                    {"name": "confirmed", "value": true},
+                    {"name": "memo", "value": "hello"},
+                ],
+            })
+        );
     }
 }
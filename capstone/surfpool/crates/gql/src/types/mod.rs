@@ -1,8 +1,9 @@
 use std::collections::HashMap;
 
+use convert_case::{Case, Casing};
 use juniper::{
-    Arguments, DefaultScalarValue, Executor, FieldError, GraphQLType, GraphQLValue, Registry,
-    Value, graphql_object,
+    Arguments, DefaultScalarValue, Executor, FieldError, GraphQLObject, GraphQLType, GraphQLValue,
+    Registry, Value, graphql_object,
     meta::{Field, MetaType},
 };
 use txtx_addon_kit::{
@@ -19,7 +20,9 @@ use crate::{
     query::DataloaderContext,
     types::{
         collections::CollectionMetadata,
-        scalars::{bigint::BigInt, pubkey::PublicKey, signature::Signature},
+        scalars::{
+            bigint::BigInt, entry_value::EntryValue, pubkey::PublicKey, signature::Signature,
+        },
     },
 };
 
@@ -99,6 +102,11 @@ impl CollectionEntryDataUpdate {
             name: name.to_owned(),
         }
     }
+
+    /// Whether the entry belongs to the collection `name`, as named in the query root or in the subgraph request.
+    pub fn is_in_collection(&self, name: &str) -> bool {
+        self.name.to_case(Case::Camel) == name.to_case(Case::Camel)
+    }
 }
 
 #[graphql_object(context = DataloaderContext)]
@@ -106,6 +114,35 @@ impl CollectionEntryDataUpdate {
     pub fn uuid(&self) -> String {
         self.entry.id.to_string()
     }
+
+    /// The name of the collection of the entry
+    pub fn collection(&self) -> String {
+        self.name.to_case(Case::Camel)
+    }
+
+    /// The values of the entry, sorted by field name
+    pub fn values(&self) -> Vec<CollectionEntryField> {
+        let mut values = self
+            .entry
+            .values
+            .iter()
+            .map(|(name, value)| CollectionEntryField {
+                name: name.clone(),
+                value: EntryValue(value.clone()),
+            })
+            .collect::<Vec<_>>();
+        values.sort_by(|a, b| a.name.cmp(&b.name));
+        values
+    }
+}
+
+#[derive(Debug, Clone, GraphQLObject)]
+#[graphql(context = DataloaderContext)]
+pub struct CollectionEntryField {
+    /// The name of the field
+    pub name: String,
+    /// The value of the field
+    pub value: EntryValue,
 }
 
 #[derive(Debug, Clone)]
@@ -0,0 +1,22 @@
+use juniper::{DefaultScalarValue, GraphQLScalar, InputValue, Value};
+
+#[derive(Clone, Debug, GraphQLScalar, PartialEq)]
+#[graphql(
+    parse_token(String, i32, f64, bool),
+    description = "A value of a collection entry, keeping the type it was indexed with",
+    name = "EntryValue",
+    scalar = DefaultScalarValue
+)]
+pub struct EntryValue(pub Value);
+
+impl EntryValue {
+    pub fn to_output(&self) -> Value {
+        self.0.clone()
+    }
+
+    fn from_input(v: &InputValue) -> Result<Self, String> {
+        v.as_scalar()
+            .map(|s| EntryValue(Value::Scalar(s.clone())))
+            .ok_or_else(|| format!("Expected a scalar, found: {v}"))
+    }
+}
@@ -1,4 +1,5 @@
 pub mod bigint;
+pub mod entry_value;
 pub mod hash;
 pub mod pubkey;
 pub mod signature;
@@ -1,236 +1,110 @@
 use std::collections::HashMap;
 
-use diesel::prelude::*;
 use juniper::{DefaultScalarValue, Executor, FieldError, Value, graphql_value};
 use surfpool_db::{
-    diesel::{
-        self, ExpressionMethods, QueryDsl, RunQueryDsl,
-        deserialize::{self, FromSql},
-        r2d2::{ConnectionManager, Pool},
-        sql_query,
-        sql_types::{Bool, Integer, Text, Untyped},
-    },
-    diesel_dynamic_schema::{
-        DynamicSelectClause,
-        dynamic_value::{Any, DynamicRow, NamedField},
-        table,
+    DatabaseConnection,
+    diesel::r2d2::{ConnectionManager, Pool},
+    types::{
+        CollectionColumn, CollectionStore, CollectionTable, ColumnType, EntryFilter, EntryRow,
+        EntryValue, FilterPredicate,
     },
 };
 use txtx_addon_kit::types::types::Type;
+use txtx_addon_network_svm_types::{SVM_I64, SVM_I128, SVM_U32, SVM_U64};
 use uuid::Uuid;
 
 use crate::{
-    query::{DatabaseConnection, Dataloader, DataloaderContext, extract_graphql_features},
-    types::{CollectionEntry, CollectionEntryData, collections::CollectionMetadata},
+    query::{Dataloader, DataloaderContext, extract_graphql_features},
+    types::{
+        CollectionEntry, CollectionEntryData,
+        collections::{CollectionMetadata, FieldMetadata},
+        scalars::bigint::BigInt,
+    },
 };
 
-#[derive(PartialEq, Debug)]
-pub struct DynamicValue(pub Value);
-
-#[cfg(feature = "sqlite")]
-impl FromSql<Any, diesel::sqlite::Sqlite> for DynamicValue {
-    fn from_sql(value: diesel::sqlite::SqliteValue) -> deserialize::Result<Self> {
-        use diesel::sqlite::{Sqlite, SqliteType};
-        match value.value_type() {
-            Some(SqliteType::Text) => {
-                <String as FromSql<diesel::sql_types::Text, Sqlite>>::from_sql(value)
-                    .map(|s| DynamicValue(Value::scalar(s)))
-            }
-            Some(SqliteType::Long) => {
-                use surfpool_db::diesel::deserialize::FromSql;
-
-                <i32 as FromSql<diesel::sql_types::Integer, Sqlite>>::from_sql(value)
-                    .map(|s| DynamicValue(Value::scalar(s)))
-            }
-            _ => Err("Unknown data type".into()),
+impl FieldMetadata {
+    /// The type of the column storing this field.
+    pub fn column_type(&self) -> ColumnType {
+        match &self.data.expected_type {
+            Type::String => ColumnType::Text,
+            Type::Integer => ColumnType::Integer,
+            Type::Float => ColumnType::Real,
+            Type::Bool => ColumnType::Bool,
+            _ => ColumnType::Text,
         }
     }
-}
-
-#[cfg(feature = "postgres")]
-impl FromSql<Any, diesel::pg::Pg> for DynamicValue {
-    fn from_sql(value: diesel::pg::PgValue) -> deserialize::Result<Self> {
-        use std::num::NonZeroU32;
 
-        use diesel::pg::Pg;
-
-        const VARCHAR_OID: NonZeroU32 = NonZeroU32::new(1043).unwrap();
-        const TEXT_OID: NonZeroU32 = NonZeroU32::new(25).unwrap();
-        const INTEGER_OID: NonZeroU32 = NonZeroU32::new(23).unwrap();
-
-        match value.get_oid() {
-            VARCHAR_OID | TEXT_OID => {
-                <String as FromSql<diesel::sql_types::Text, Pg>>::from_sql(value)
-                    .map(|s| DynamicValue(Value::scalar(s)))
+    /// Whether this field is exposed as a [BigInt], which is serialized as a string.
+    fn is_bigint(&self) -> bool {
+        match &self.data.expected_type {
+            Type::Integer => true,
+            Type::Addon(addon_id) => {
+                matches!(addon_id.as_str(), SVM_U32 | SVM_U64 | SVM_I64 | SVM_I128)
             }
-            INTEGER_OID => <i32 as FromSql<diesel::sql_types::Integer, Pg>>::from_sql(value)
-                .map(|s| DynamicValue(Value::scalar(s))),
-            e => Err(format!("Unknown type: {e}").into()),
+            _ => false,
         }
     }
 }
 
-#[cfg(feature = "postgres")]
-pub fn fetch_dynamic_entries_from_postres(
-    pg_conn: &mut diesel::pg::PgConnection,
-    metadata: &CollectionMetadata,
-    executor: Option<&Executor<DataloaderContext>>,
-) -> Result<(Vec<String>, Vec<DynamicRow<NamedField<DynamicValue>>>), FieldError> {
-    let mut select = DynamicSelectClause::new();
-    let dynamic_table = table(metadata.table_name.as_str());
-    let (filters_specs, fetched_fields) = extract_graphql_features(executor);
-    for field_name in fetched_fields.iter() {
-        select.add_field(dynamic_table.column::<Untyped, _>(field_name.to_string()));
-    }
-
-    let mut query = dynamic_table.clone().select(select).into_boxed();
-
-    for (field, predicate, value) in filters_specs {
-        match value {
-            DefaultScalarValue::String(s) => {
-                let col = dynamic_table.column::<Text, _>(field);
-                query = match predicate {
-                    "equals" => query.filter(col.eq(s)),
-                    "not" => query.filter(col.ne(s)),
-                    "_contains" => query.filter(col.like(format!("%{}%", s))),
-                    "_notContains" => query.filter(col.not_like(format!("%{}%", s))),
-                    "_endsWith" => query.filter(col.like(format!("%{}", s))),
-                    "_startsWith" => query.filter(col.like(format!("{}%", s))),
-                    _ => {
-                        return Err(FieldError::new(
-                            format!("Unsupported string predicate: {}", predicate),
-                            graphql_value!({"invalid_params": "Invalid string predicate"}),
-                        ));
-                    }
-                };
-            }
-            DefaultScalarValue::Int(i) => {
-                let col = dynamic_table.column::<Integer, _>(field);
-                query = match predicate {
-                    "equals" => query.filter(col.eq(*i)),
-                    "not" => query.filter(col.ne(*i)),
-                    "gt" => query.filter(col.gt(*i)),
-                    "gte" => query.filter(col.ge(*i)),
-                    "lt" => query.filter(col.lt(*i)),
-                    "lte" => query.filter(col.le(*i)),
-                    _ => {
-                        return Err(FieldError::new(
-                            format!("Unsupported integer predicate: {}", predicate),
-                            graphql_value!({"invalid_params": "Invalid integer predicate"}),
-                        ));
-                    }
-                };
-            }
-            DefaultScalarValue::Boolean(b) => {
-                let col = dynamic_table.column::<Bool, _>(field);
-                query = match predicate {
-                    "equals" => query.filter(col.eq(*b)),
-                    "not" => query.filter(col.ne(*b)),
-                    _ => {
-                        return Err(FieldError::new(
-                            format!("Unsupported boolean predicate: {}", predicate),
-                            graphql_value!({"invalid_params": "Invalid boolean predicate"}),
-                        ));
-                    }
-                };
-            }
-            _ => {
-                return Err(FieldError::new(
-                    format!("Unsupported predicate or value type: {}", predicate),
-                    graphql_value!({"invalid_params": "Invalid predicate or value type"}),
-                ));
-            }
-        };
+impl CollectionMetadata {
+    pub fn to_collection_table(&self) -> Result<CollectionTable, String> {
+        let schema = serde_json::to_string(self)
+            .map_err(|e| format!("Failed to serialize collection schema: {e}"))?;
+        Ok(CollectionTable {
+            id: self.id.to_string(),
+            table_name: self.table_name.clone(),
+            columns: self
+                .fields
+                .iter()
+                .map(|field| CollectionColumn::new(&field.data.display_name, field.column_type()))
+                .collect(),
+            schema,
+        })
     }
-
-    let fetched_data = query
-        .load::<DynamicRow<NamedField<DynamicValue>>>(&mut *pg_conn)
-        .unwrap();
-
-    Ok((fetched_fields, fetched_data))
 }
 
-#[cfg(feature = "sqlite")]
-pub fn fetch_dynamic_entries_from_sqlite(
-    sqlite_conn: &mut diesel::sqlite::SqliteConnection,
-    metadata: &CollectionMetadata,
-    executor: Option<&Executor<DataloaderContext>>,
-) -> Result<(Vec<String>, Vec<DynamicRow<NamedField<DynamicValue>>>), FieldError> {
-    // Isolate filters
-
-    let mut select = DynamicSelectClause::new();
-    let dynamic_table = table(metadata.table_name.as_str());
-    let (filters_specs, fetched_fields) = extract_graphql_features(executor);
-    for field_name in fetched_fields.iter() {
-        select.add_field(dynamic_table.column::<Untyped, _>(field_name.to_string()));
-    }
-
-    // Build the query and apply filters immediately to avoid borrow checker issues
-    let mut query = dynamic_table.clone().select(select).into_boxed();
+fn juniper_value_to_entry_value(value: &Value) -> Result<EntryValue, String> {
+    let value = match value {
+        Value::Null => EntryValue::Null,
+        Value::Scalar(DefaultScalarValue::String(s)) => EntryValue::Text(s.clone()),
+        Value::Scalar(DefaultScalarValue::Int(i)) => EntryValue::Integer(*i as i64),
+        Value::Scalar(DefaultScalarValue::Float(f)) => EntryValue::Real(*f),
+        Value::Scalar(DefaultScalarValue::Boolean(b)) => EntryValue::Bool(*b),
+        other => return Err(format!("Unsupported entry value: {:?}", other)),
+    };
+    Ok(value)
+}
 
-    for (field, predicate, value) in filters_specs {
-        match value {
-            DefaultScalarValue::String(s) => {
-                let col = dynamic_table.column::<Text, _>(field);
-                query = match predicate {
-                    "equals" => query.filter(col.eq(s)),
-                    "not" => query.filter(col.ne(s)),
-                    "contains" => query.filter(col.like(format!("%{}%", s))),
-                    "notContains" => query.filter(col.not_like(format!("%{}%", s))),
-                    "endsWith" => query.filter(col.like(format!("%{}", s))),
-                    "startsWith" => query.filter(col.like(format!("{}%", s))),
-                    _ => {
-                        return Err(FieldError::new(
-                            format!("Unsupported string predicate: {}", predicate),
-                            graphql_value!({"invalid_params": "Invalid string predicate"}),
-                        ));
-                    }
-                };
-            }
-            DefaultScalarValue::Int(i) => {
-                let col = dynamic_table.column::<Integer, _>(field);
-                query = match predicate {
-                    "equals" => query.filter(col.eq(*i)),
-                    "not" => query.filter(col.ne(*i)),
-                    "gt" => query.filter(col.gt(*i)),
-                    "gte" => query.filter(col.ge(*i)),
-                    "lt" => query.filter(col.lt(*i)),
-                    "lte" => query.filter(col.le(*i)),
-                    _ => {
-                        return Err(FieldError::new(
-                            format!("Unsupported integer predicate: {}", predicate),
-                            graphql_value!({"invalid_params": "Invalid integer predicate"}),
-                        ));
-                    }
-                };
-            }
-            DefaultScalarValue::Boolean(b) => {
-                let col = dynamic_table.column::<Bool, _>(field);
-                query = match predicate {
-                    "equals" => query.filter(col.eq(*b)),
-                    "not" => query.filter(col.ne(*b)),
-                    _ => {
-                        return Err(FieldError::new(
-                            format!("Unsupported boolean predicate: {}", predicate),
-                            graphql_value!({"invalid_params": "Invalid boolean predicate"}),
-                        ));
-                    }
-                };
-            }
-            _ => {
-                return Err(FieldError::new(
-                    format!("Unsupported predicate or value type: {}", predicate),
-                    graphql_value!({"invalid_params": "Invalid predicate or value type"}),
-                ));
-            }
-        };
+fn entry_value_to_juniper_value(value: EntryValue, field: Option<&FieldMetadata>) -> Value {
+    match (value, field) {
+        (EntryValue::Null, _) => Value::null(),
+        (EntryValue::Integer(i), Some(field)) if field.is_bigint() => BigInt(i as i128).to_output(),
+        (EntryValue::Integer(i), Some(field)) if field.is_bool() => Value::scalar(i != 0),
+        (EntryValue::Integer(i), _) => match i32::try_from(i) {
+            Ok(i) => Value::scalar(i),
+            Err(_) => BigInt(i as i128).to_output(),
+        },
+        (EntryValue::Text(s), _) => Value::scalar(s),
+        (EntryValue::Real(f), _) => Value::scalar(f),
+        (EntryValue::Bool(b), _) => Value::scalar(b),
     }
+}
 
-    let fetched_data = query
-        .load::<DynamicRow<NamedField<DynamicValue>>>(&mut *sqlite_conn)
-        .unwrap();
-
-    Ok((fetched_fields, fetched_data))
+fn filter_predicate(predicate: &str) -> Option<FilterPredicate> {
+    let predicate = match predicate {
+        "equals" => FilterPredicate::Equals,
+        "not" | "notEquals" => FilterPredicate::NotEquals,
+        "gt" => FilterPredicate::GreaterThan,
+        "gte" => FilterPredicate::GreaterOrEqual,
+        "lt" => FilterPredicate::LowerThan,
+        "lte" => FilterPredicate::LowerOrEqual,
+        "contains" => FilterPredicate::Contains,
+        "notContains" => FilterPredicate::NotContains,
+        "startsWith" => FilterPredicate::StartsWith,
+        "endsWith" => FilterPredicate::EndsWith,
+        _ => return None,
+    };
+    Some(predicate)
 }
 
 impl Dataloader for Pool<ConnectionManager<DatabaseConnection>> {
@@ -239,89 +113,51 @@ impl Dataloader for Pool<ConnectionManager<DatabaseConnection>> {
         executor: Option<&Executor<DataloaderContext>>,
         metadata: &CollectionMetadata,
     ) -> Result<Vec<CollectionEntry>, FieldError> {
-        let mut conn = self.get().expect("unable to connect to db");
-        // Use Diesel's query DSL to fetch the table_name
+        let mut conn = self.get().map_err(|e| {
+            FieldError::new(format!("Unable to connect to database: {e}"), Value::null())
+        })?;
+
+        let (filters_specs, fetched_fields) = extract_graphql_features(executor);
+        let mut filters = vec![];
+        for (field, predicate, value) in filters_specs {
+            let Some(filter_predicate) = filter_predicate(predicate) else {
+                return Err(FieldError::new(
+                    format!("Unsupported predicate: {}", predicate),
+                    graphql_value!({"invalid_params": "Invalid predicate"}),
+                ));
+            };
+            let value =
+                juniper_value_to_entry_value(&Value::Scalar(value.clone())).map_err(|e| {
+                    FieldError::new(e, graphql_value!({"invalid_params": "Invalid value type"}))
+                })?;
+            filters.push(EntryFilter::new(field, filter_predicate, value));
+        }
 
-        let (fetch_fields, fetched_data) = match &mut *conn {
-            #[cfg(feature = "sqlite")]
-            DatabaseConnection::Sqlite(sqlite_conn) => {
-                fetch_dynamic_entries_from_sqlite(sqlite_conn, metadata, executor)
-            }
-            #[cfg(feature = "postgres")]
-            DatabaseConnection::Postgresql(pg_conn) => {
-                fetch_dynamic_entries_from_postres(pg_conn, metadata, executor)
-            }
-        }?;
+        let rows = conn
+            .fetch_entries(&metadata.table_name, &fetched_fields, &filters)
+            .map_err(|e| FieldError::new(e, Value::null()))?;
 
         let mut results = Vec::new();
-        for row in fetched_data {
+        for row in rows {
             let mut values = HashMap::new();
-            for (i, field) in fetch_fields.iter().enumerate() {
-                values.insert(field.clone(), row[i].value.0.clone()); // FIXME
+            for (name, value) in row.values {
+                let field = metadata
+                    .fields
+                    .iter()
+                    .find(|field| field.data.display_name == name);
+                values.insert(name, entry_value_to_juniper_value(value, field));
             }
-            results.push(CollectionEntry(CollectionEntryData {
-                id: Uuid::new_v4(),
-                values,
-            }));
+            let id = Uuid::parse_str(&row.id).unwrap_or_else(|_| Uuid::new_v4());
+            results.push(CollectionEntry(CollectionEntryData { id, values }));
         }
         Ok(results)
     }
 
     fn register_collection(&self, metadata: &CollectionMetadata) -> Result<(), String> {
-        let mut conn = self.get().expect("unable to connect to db");
-
-        // 1. Ensure subgraphs table exists
-        sql_query(
-            "CREATE TABLE IF NOT EXISTS collections (
-                id TEXT PRIMARY KEY,
-                created_at TIMESTAMP NOT NULL,
-                updated_at TIMESTAMP NOT NULL,
-                table_name TEXT NOT NULL,
-                schema TEXT NOT NULL
-            )",
-        )
-        .execute(&mut *conn)
-        .map_err(|e| format!("Failed to create collections table: {e}"))?;
-
-        // 2. Create a new entries table for this subgraph, using the schema to determine the fields
-        // Build the SQL for the entries table using the schema fields
-        let mut columns = vec!["id TEXT PRIMARY KEY".to_string()];
-        for field in &metadata.fields {
-            // Map field types to SQLite types (expand as needed)
-            let sql_type = match &field.data.expected_type {
-                Type::String => "TEXT",
-                Type::Integer => "INTEGER",
-                Type::Float => "REAL",
-                Type::Bool => "BOOLEAN",
-                _ => "TEXT", // fallback for unknown types
-            };
-            let col = format!("{} {}", field.data.display_name, sql_type);
-            columns.push(col);
-        }
-        let create_entries_sql = format!(
-            "CREATE TABLE IF NOT EXISTS {} (\n    {}\n)",
-            metadata.table_name,
-            columns.join(",\n    ")
-        );
-
-        sql_query(&create_entries_sql)
-            .execute(&mut *conn)
-            .map_err(|e| format!("Failed to create entries table: {e}"))?;
-        // let schema_json = serde_json::to_string(request)
-        //     .map_err(|e| format!("Failed to serialize schema: {e}"))?;
-        let schema_json = String::new();
-        let now = chrono::Utc::now().naive_utc();
-
-        let sql = format!(
-            "INSERT INTO collections (id, created_at, updated_at, table_name, schema) VALUES ('{}', '{}', '{}', '{}', '{}')",
-            metadata.id, now, now, &metadata.table_name, schema_json
-        );
-
-        sql_query(&sql)
-            .execute(&mut *conn)
-            .map_err(|e| format!("Failed to insert subgraph: {e}"))?;
-
-        Ok(())
+        let mut conn = self
+            .get()
+            .map_err(|e| format!("Unable to connect to database: {e}"))?;
+        conn.register_collection(&metadata.to_collection_table()?)
     }
 
     fn insert_entries_into_collection(
@@ -329,48 +165,23 @@ impl Dataloader for Pool<ConnectionManager<DatabaseConnection>> {
         entries: Vec<CollectionEntryData>,
         metadata: &CollectionMetadata,
     ) -> Result<(), String> {
-        let mut conn = self.get().expect("unable to connect to db");
-
-        // 2. Prepare the insert statement using the schema for column order
-        // let CollectionEntry(data_entry) = entry;
-
-        let mut columns = vec![];
-        let mut values: Vec<String> = vec![];
+        let mut conn = self
+            .get()
+            .map_err(|e| format!("Unable to connect to database: {e}"))?;
 
+        let mut rows = Vec::with_capacity(entries.len());
         for entry in entries {
-            columns.push("id");
-            values.push(format!("'{}'", entry.id));
-
-            // Use the schema to determine the order and names of dynamic fields
-            // Insert null if the value is missing
-            for field in &metadata.fields {
-                let col = field.data.display_name.as_str();
-                if let Some(val) = entry.values.get(col) {
-                    let val_str = match val {
-                        juniper::Value::Scalar(DefaultScalarValue::String(value)) => {
-                            format!("'{}'", value)
-                        }
-                        juniper::Value::Scalar(value) => value.to_string(),
-                        _ => unimplemented!(),
-                    };
-                    values.push(val_str);
-                    columns.push(col);
-                }
+            let mut values = HashMap::new();
+            for (name, value) in entry.values.iter() {
+                values.insert(name.clone(), juniper_value_to_entry_value(value)?);
             }
-
-            // 3. Build and execute the insert
-            let sql: String = format!(
-                "INSERT INTO {} ({}) VALUES ({})",
-                metadata.table_name,
-                columns.join(", "),
-                values.join(", ")
-            );
-
-            sql_query(&sql)
-                .execute(&mut *conn)
-                .map_err(|e| format!("Failed to insert entry: {e}"))?;
+            rows.push(EntryRow {
+                id: entry.id.to_string(),
+                values,
+            });
         }
-        Ok(())
+
+        conn.insert_entries(&metadata.to_collection_table()?, &rows)
     }
 }
 
@@ -458,7 +269,7 @@ mod tests {
     #[test]
     fn test_register_insert_fetch() {
         // Prepare dataset
-        let store = SqlStore::new_in_memory();
+        let store = SqlStore::new_in_memory().expect("new_in_memory");
         let request = test_request();
         let uuid = Uuid::new_v4();
         let metadata = CollectionMetadata::from_request(&uuid, &request);
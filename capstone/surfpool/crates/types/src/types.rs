@@ -33,6 +33,18 @@ pub const DEFAULT_STUDIO_PORT: u16 = 8488;
 pub const CHANGE_TO_DEFAULT_STUDIO_PORT_ONCE_SUPERVISOR_MERGED: u16 = 18488;
 pub const DEFAULT_NETWORK_HOST: &str = "127.0.0.1";
 pub const DEFAULT_SLOT_TIME_MS: u64 = 400;
+pub const DEFAULT_SYNTHETIC_VALIDATORS: usize = 1;
+/// The tip accounts of the Jito block engine on mainnet.
+pub const JITO_TIP_ACCOUNTS: [Pubkey; 8] = [
+    Pubkey::from_str_const("96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5"),
+    Pubkey::from_str_const("HFqU5x63VTqvQss8hp11i4wVV8bD44PvwucfZ2bU7gRe"),
+    Pubkey::from_str_const("Cw8CFyM9FkoMi7K7Crf6HNQqf4uEMzpKw6QNghXLvLkY"),
+    Pubkey::from_str_const("ADaUMid9yfUytqMBgopwjb2DTLSokTSzL1zt6iGPaS49"),
+    Pubkey::from_str_const("DfXygSm4jCyNCybVYYK6DwvWqjKee8pbDmJGcLWNDXjh"),
+    Pubkey::from_str_const("ADuUkR4vqLUMWXxW9gh6D6L8pMSawimctcNZ5pGwDcEt"),
+    Pubkey::from_str_const("DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL"),
+    Pubkey::from_str_const("3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnizKZ6jT"),
+];
 pub type Idl = anchor_lang_idl::types::Idl;
 
 #[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
@@ -145,6 +157,7 @@ pub struct ProfileResult {
     pub compute_units_consumed: u64,
     pub log_messages: Option<Vec<String>>,
     pub error_message: Option<String>,
+    pub events: Vec<AnchorEvent>,
 }
 
 pub type ExecutionCapture = BTreeMap<Pubkey, Option<Account>>;
@@ -163,10 +176,55 @@ impl ProfileResult {
             compute_units_consumed,
             log_messages,
             error_message,
+            events: vec![],
         }
     }
 }
 
+/// An Anchor event emitted by a program, decoded with the IDL registered for the program.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AnchorEvent {
+    pub program_id: String,
+    pub name: String,
+    pub data: serde_json::Value,
+}
+
+/// A custom program error of a failed transaction, resolved with the IDL registered for the program.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct NamedProgramError {
+    /// The index of the failed instruction in the transaction
+    pub instruction_index: u8,
+    /// The name of the failed instruction, if its program has a registered IDL
+    pub instruction_name: Option<String>,
+    /// The program that returned the error, which is invoked by the failed instruction through CPIs
+    /// if it isn't the program of the instruction
+    pub program_id: String,
+    pub program_name: String,
+    pub code: u32,
+    pub name: String,
+    pub message: Option<String>,
+}
+
+impl fmt::Display for NamedProgramError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Instruction {}", self.instruction_index)?;
+        if let Some(instruction_name) = &self.instruction_name {
+            write!(f, " ({})", instruction_name)?;
+        }
+        write!(
+            f,
+            " failed with {}::{} (custom program error {:#x})",
+            self.program_name, self.name, self.code
+        )?;
+        if let Some(message) = &self.message {
+            write!(f, ": {}", message)?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum AccountProfileState {
     Readonly,
@@ -226,6 +284,25 @@ impl Default for RpcProfileResultConfig {
     }
 }
 
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcSendInstructionConfig {
+    /// The fee payer of the transaction, defaulting to the first signer of the instruction.
+    pub payer: Option<String>,
+    /// The encoding of the account states of the returned profile result.
+    pub encoding: Option<UiAccountEncoding>,
+    pub depth: Option<RpcProfileDepth>,
+}
+
+impl RpcSendInstructionConfig {
+    pub fn profile_result_config(&self) -> RpcProfileResultConfig {
+        RpcProfileResultConfig {
+            encoding: self.encoding.or(Some(UiAccountEncoding::JsonParsed)),
+            depth: self.depth.clone().or(Some(RpcProfileDepth::default())),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum RpcProfileDepth {
@@ -254,6 +331,8 @@ pub struct UiProfileResult {
     pub compute_units_consumed: u64,
     pub log_messages: Option<Vec<String>>,
     pub error_message: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<AnchorEvent>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -273,6 +352,57 @@ pub enum UiAccountChange {
     Unchanged(Option<UiAccount>),
 }
 
+/// The outcome of a transaction as it was executed by the datasource network.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UiOnchainTransactionResult {
+    pub slot: Slot,
+    pub err: Option<TransactionError>,
+    pub fee: u64,
+    pub compute_units_consumed: Option<u64>,
+    pub log_messages: Option<Vec<String>>,
+}
+
+/// A transaction of the datasource network re-executed in the surfnet.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UiReplayedTransaction {
+    pub onchain_result: UiOnchainTransactionResult,
+    pub profile: UiKeyedProfileResult,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RpcAccountDiffConfig {
+    /// The encoding of the account states. With `jsonParsed`, the data of accounts owned by
+    /// a program with a registered IDL is decoded.
+    pub encoding: Option<UiAccountEncoding>,
+    /// Restricts the diff to these accounts.
+    pub accounts: Option<Vec<String>>,
+}
+
+impl Default for RpcAccountDiffConfig {
+    fn default() -> Self {
+        Self {
+            encoding: Some(UiAccountEncoding::JsonParsed),
+            accounts: None,
+        }
+    }
+}
+
+/// The changes of an account between two slots.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct UiAccountDiff {
+    pub pubkey: String,
+    /// The difference between the lamports at the end slot and the lamports at the start slot.
+    pub lamports_delta: i64,
+    pub owner_changed: bool,
+    pub data_changed: bool,
+    /// The state of the account at the start slot and at the end slot.
+    pub change: UiAccountChange,
+}
+
 /// P starts with 300 lamports
 /// Ix 1 Transfers 100 lamports to P
 /// Ix 2 Transfers 100 lamports to P
@@ -346,6 +476,11 @@ pub enum SimnetEvent {
         tag: String,
         timestamp: DateTime<Local>,
     },
+    AnchorEventEmitted {
+        signature: Signature,
+        event: AnchorEvent,
+        timestamp: DateTime<Local>,
+    },
     RunbookStarted(String),
     RunbookCompleted(String),
 }
@@ -399,6 +534,28 @@ impl SimnetEvent {
         }
     }
 
+    pub fn anchor_event_emitted(signature: Signature, event: AnchorEvent) -> Self {
+        Self::AnchorEventEmitted {
+            signature,
+            event,
+            timestamp: Local::now(),
+        }
+    }
+
+    pub fn anchor_event_msg(&self) -> String {
+        match self {
+            SimnetEvent::AnchorEventEmitted {
+                signature, event, ..
+            } => {
+                format!(
+                    "Event {} emitted by {} in tx {}: {}",
+                    event.name, event.program_id, signature, event.data
+                )
+            }
+            _ => unreachable!("This function should only be called for AnchorEventEmitted events"),
+        }
+    }
+
     pub fn account_update_msg(&self) -> String {
         match self {
             SimnetEvent::AccountUpdate(_, pubkey) => {
@@ -449,9 +606,26 @@ pub enum TransactionStatusEvent {
     VerificationFailure(String),
 }
 
+/// The outcome of a bundle sent to the block production runloop.
+#[derive(Debug)]
+pub enum BundleStatusEvent {
+    /// Every transaction of the bundle was executed in this slot
+    Landed(Slot),
+    /// A transaction of the bundle failed, so that none of them was committed
+    Failed {
+        signature: Signature,
+        error: TransactionError,
+        logs: Vec<String>,
+    },
+    /// The bundle couldn't be executed
+    Dropped(String),
+}
+
 #[derive(Debug)]
 pub enum SimnetCommand {
     SlotForward(Option<Hash>),
+    /// Restores the latest snapshot taken before the current slot and switches to manual block production.
+    /// Snapshots are only taken through `surfnet_snapshot`, so without one this only emits a warning.
     SlotBackward(Option<Hash>),
     CommandClock(ClockCommand),
     UpdateInternalClock(Clock),
@@ -462,6 +636,10 @@ pub enum SimnetCommand {
         Sender<TransactionStatusEvent>,
         bool,
     ),
+    /// Swaps the bytecode of an upgradeable program in place, keeping its accounts and upgrade authority
+    ReloadProgram(Pubkey, Vec<u8>),
+    /// Executes the transactions of a bundle atomically and in order, within the current slot
+    BundleReceived(String, Vec<VersionedTransaction>, Sender<BundleStatusEvent>),
     Terminate(Option<(Hash, String)>),
 }
 
@@ -498,8 +676,33 @@ pub struct SurfpoolConfig {
     pub plugin_config_path: Vec<PathBuf>,
 }
 
+impl SurfpoolConfig {
+    /// The configuration of the surfnet at `index` in `simnets`, to start it with `start_local_surfnet`:
+    /// it only contains this simnet, and binds its own RPC servers if any.
+    ///
+    /// Geyser plugins are only loaded by the first surfnet.
+    pub fn surfnet_config(&self, index: usize) -> Option<SurfpoolConfig> {
+        let simnet = self.simnets.get(index)?;
+        Some(SurfpoolConfig {
+            simnets: vec![simnet.clone()],
+            rpc: simnet.rpc.clone().unwrap_or_else(|| self.rpc.clone()),
+            subgraph: self.subgraph.clone(),
+            studio: self.studio.clone(),
+            plugin_config_path: if index == 0 {
+                self.plugin_config_path.clone()
+            } else {
+                vec![]
+            },
+        })
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct SimnetConfig {
+    /// The name of the surfnet, identifying it when several surfnets are running
+    pub name: Option<String>,
+    /// The RPC servers of the surfnet, instead of the ones of the [SurfpoolConfig]
+    pub rpc: Option<RpcConfig>,
     pub offline_mode: bool,
     pub remote_rpc_url: Option<String>,
     pub slot_time: u64,
@@ -507,11 +710,36 @@ pub struct SimnetConfig {
     pub airdrop_addresses: Vec<Pubkey>,
     pub airdrop_token_amount: u64,
     pub expiry: Option<u64>,
+    pub state_dump_path: Option<PathBuf>,
+    /// The directory the `surfnet_dumpState` cheatcode writes to; the cheatcode is disabled if `None`
+    pub state_dump_dir: Option<PathBuf>,
+    pub remote_cache: Option<RemoteCacheConfig>,
+    /// The slot of the datasource that accounts are fetched at, instead of the latest one
+    pub fork_slot: Option<Slot>,
+    pub remote_traffic: Option<RemoteTrafficMode>,
+    /// Programs deployed from local ELFs on startup, overriding the programs of the datasource
+    pub program_deployments: Vec<ProgramDeployment>,
+    /// Accounts written from local files on startup
+    pub preloaded_accounts: Vec<AccountPreload>,
+    /// Token accounts set on startup, as with the `surfnet_setTokenAccount` cheatcode
+    pub token_accounts: Vec<TokenAccountPreset>,
+    /// Programs cloned on startup, as with the `surfnet_cloneProgramAccount` cheatcode
+    pub cloned_programs: Vec<ProgramClone>,
+    /// Paths of the Anchor IDLs registered on startup
+    pub idl_paths: Vec<PathBuf>,
+    /// The number of synthetic validators, holding stake and voting on every slot
+    pub validators: usize,
+    /// The accounts that bundles must transfer a tip to
+    pub tip_accounts: Vec<Pubkey>,
+    /// Paths of the keypairs signing the transactions built by `surfnet_sendInstruction`
+    pub signer_keypair_paths: Vec<PathBuf>,
 }
 
 impl Default for SimnetConfig {
     fn default() -> Self {
         Self {
+            name: None,
+            rpc: None,
             offline_mode: false,
             remote_rpc_url: Some(DEFAULT_RPC_URL.to_string()),
             slot_time: DEFAULT_SLOT_TIME_MS, // Default to 400ms to match CLI default
@@ -519,10 +747,89 @@ impl Default for SimnetConfig {
             airdrop_addresses: vec![],
             airdrop_token_amount: 0,
             expiry: None,
+            state_dump_path: None,
+            state_dump_dir: None,
+            remote_cache: None,
+            fork_slot: None,
+            remote_traffic: None,
+            program_deployments: vec![],
+            preloaded_accounts: vec![],
+            token_accounts: vec![],
+            cloned_programs: vec![],
+            idl_paths: vec![],
+            validators: DEFAULT_SYNTHETIC_VALIDATORS,
+            tip_accounts: JITO_TIP_ACCOUNTS.to_vec(),
+            signer_keypair_paths: vec![],
         }
     }
 }
 
+/// Configuration of the cache of the accounts fetched from the datasource RPC.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RemoteCacheConfig {
+    /// The number of seconds a fetched account is served from the cache; entries never expire if `None`
+    pub ttl: Option<u64>,
+    /// The file the cache is persisted to, so that it can be reused across runs
+    pub path: Option<PathBuf>,
+}
+
+/// A program deployed from a local ELF with the upgradeable loader, replacing any program at this address.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProgramDeployment {
+    pub program_id: Pubkey,
+    /// The path of the `.so` file of the program
+    pub path: PathBuf,
+    /// The upgrade authority of the program; the authority of the existing program is kept if `None`
+    pub authority: Option<Pubkey>,
+}
+
+/// An account written on startup from the content of a local file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AccountPreload {
+    /// The address of the account; JSON files provide their own address when `None`
+    pub pubkey: Option<Pubkey>,
+    pub path: PathBuf,
+    pub format: AccountFileFormat,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AccountFileFormat {
+    /// A keyed account, in the format of `solana account --output json`
+    Json,
+    /// The base64 encoded data of the account; the rent exempt minimum is used if no lamports are provided
+    Base64 {
+        owner: Pubkey,
+        lamports: Option<u64>,
+        executable: bool,
+    },
+}
+
+/// A token account of `owner` for `mint`, updated on startup.
+#[derive(Clone, Debug)]
+pub struct TokenAccountPreset {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    /// The token program of the mint; the SPL Token program is used if `None`
+    pub token_program: Option<Pubkey>,
+    pub update: TokenAccountUpdate,
+}
+
+/// A program cloned from `source` to `destination` on startup.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProgramClone {
+    pub source: Pubkey,
+    pub destination: Pubkey,
+}
+
+/// Whether the responses of the datasource RPC are recorded to, or replayed from, a fixture file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RemoteTrafficMode {
+    /// Forward requests to the datasource, and append every response to the fixture file
+    Record(PathBuf),
+    /// Serve requests from the fixture file, without any network access
+    Replay(PathBuf),
+}
+
 impl SimnetConfig {
     pub fn get_sanitized_datasource_url(&self) -> Option<String> {
         let Some(raw) = self.remote_rpc_url.as_ref() else {
@@ -834,6 +1141,41 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_surfnet_config() {
+        let devnet_rpc = RpcConfig {
+            bind_port: 8901,
+            ws_port: 8902,
+            ..RpcConfig::default()
+        };
+        let config = SurfpoolConfig {
+            simnets: vec![
+                SimnetConfig::default(),
+                SimnetConfig {
+                    name: Some("devnet".to_string()),
+                    rpc: Some(devnet_rpc.clone()),
+                    remote_rpc_url: Some("https://api.devnet.solana.com".to_string()),
+                    ..SimnetConfig::default()
+                },
+            ],
+            plugin_config_path: vec![PathBuf::from("plugin.json")],
+            ..SurfpoolConfig::default()
+        };
+
+        let mainnet = config.surfnet_config(0).unwrap();
+        assert_eq!(mainnet.simnets.len(), 1);
+        assert_eq!(mainnet.rpc.bind_port, DEFAULT_RPC_PORT);
+        assert_eq!(mainnet.plugin_config_path.len(), 1);
+
+        let devnet = config.surfnet_config(1).unwrap();
+        assert_eq!(devnet.simnets[0].name.as_deref(), Some("devnet"));
+        assert_eq!(devnet.rpc.get_rpc_base_url(), devnet_rpc.get_rpc_base_url());
+        assert_eq!(devnet.rpc.get_ws_base_url(), devnet_rpc.get_ws_base_url());
+        assert!(devnet.plugin_config_path.is_empty());
+
+        assert!(config.surfnet_config(2).is_none());
+    }
+
     #[test]
     fn print_ui_keyed_profile_result() {
         let pubkey = Pubkey::new_unique();
@@ -901,6 +1243,7 @@ mod tests {
                         "Log message: Account created".to_string(),
                     ]),
                     error_message: None,
+                    events: vec![],
                 },
                 UiProfileResult {
                     account_states: IndexMap::from_iter([
@@ -919,6 +1262,7 @@ mod tests {
                         "Log message: Account updated".to_string(),
                     ]),
                     error_message: None,
+                    events: vec![],
                 },
                 UiProfileResult {
                     account_states: IndexMap::from_iter([
@@ -934,6 +1278,7 @@ mod tests {
                         "Log message: Account deleted".to_string(),
                     ]),
                     error_message: None,
+                    events: vec![],
                 },
             ]),
             transaction_profile: UiProfileResult {
@@ -954,6 +1299,7 @@ mod tests {
                     "Log message: Account deleted".to_string(),
                 ]),
                 error_message: None,
+                events: vec![],
             },
             readonly_account_states: IndexMap::from_iter([(owner, readonly_account_state)]),
         };